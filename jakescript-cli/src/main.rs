@@ -1,6 +1,7 @@
 use fallible_iterator::FallibleIterator;
 use jakescript::ast::Script;
-use jakescript::interpreter::{self, Eval, Interpreter, Vm};
+use jakescript::bytecode;
+use jakescript::interpreter::{self, Eval, ExecutionState, Interpreter, Vm};
 use jakescript::lexer::{self, Lexer};
 use jakescript::parser::{self, Parser};
 use jakescript::token::{Element, SourceLocation};
@@ -19,27 +20,28 @@ fn main() -> Result<(), Error> {
     #[cfg(windows)]
     ansi_term::enable_ansi_support().ok();
 
-    let start_loc = SourceLocation::at_start_of("stdin");
     match Options::try_from(env::args())? {
-        Options(Mode::Eval, None, Some(ref source_path)) => {
+        Options(Mode::Eval, None, Some(backend), Some(ref source_path)) => {
             let source_file = fs::File::open(source_path)?;
             let mut buf = io::BufReader::new(source_file);
+            let start_loc = SourceLocation::at_start_of(source_path);
             let lexer =
                 Lexer::for_chars_fallible(fallible_iterator::convert(buf.chars()), start_loc);
 
             let (ast, parse_runtime) = parse(lexer)?;
             println!("Parsed in {parse_runtime:?}",);
 
-            let (value, eval_runtime) = eval(&ast)?;
+            let (value, eval_runtime) = eval(&ast, backend)?;
             println!(
                 "Evaluated in {eval_runtime:?} (total: {:?})",
                 parse_runtime + eval_runtime
             );
             eprintln!("{value:?}");
         }
-        Options(Mode::Parse, Some(format), Some(ref source_path)) => {
+        Options(Mode::Parse, Some(format), None, Some(ref source_path)) => {
             let source_file = fs::File::open(source_path)?;
             let mut buf = io::BufReader::new(source_file);
+            let start_loc = SourceLocation::at_start_of(source_path);
             let lexer =
                 Lexer::for_chars_fallible(fallible_iterator::convert(buf.chars()), start_loc);
 
@@ -52,9 +54,24 @@ fn main() -> Result<(), Error> {
                 Format::Yaml => serde_yaml::to_writer(stdout, &ast).unwrap(),
             }
         }
-        Options(Mode::Lex, None, Some(ref source_path)) => {
+        Options(Mode::Disasm, None, None, Some(ref source_path)) => {
             let source_file = fs::File::open(source_path)?;
             let mut buf = io::BufReader::new(source_file);
+            let start_loc = SourceLocation::at_start_of(source_path);
+            let lexer =
+                Lexer::for_chars_fallible(fallible_iterator::convert(buf.chars()), start_loc);
+
+            let (ast, parse_runtime) = parse(lexer)?;
+            println!("Parsed in {parse_runtime:?}",);
+
+            let (script, compile_runtime) = compile(&ast)?;
+            println!("Compiled in {compile_runtime:?}",);
+            print!("{script}");
+        }
+        Options(Mode::Lex, None, None, Some(ref source_path)) => {
+            let source_file = fs::File::open(source_path)?;
+            let mut buf = io::BufReader::new(source_file);
+            let start_loc = SourceLocation::at_start_of(source_path);
             let lexer =
                 Lexer::for_chars_fallible(fallible_iterator::convert(buf.chars()), start_loc);
 
@@ -65,14 +82,15 @@ fn main() -> Result<(), Error> {
                 elements.iter().map(Element::to_string).collect::<String>()
             );
         }
-        Options(Mode::Repl, None, None) => {
+        Options(Mode::Repl, None, None, None) => {
             let mut stdin = io::stdin().lock();
+            let start_loc = SourceLocation::at_start_of("stdin");
             let lexer =
                 Lexer::for_chars_fallible(fallible_iterator::convert(stdin.chars()), start_loc);
             let mut it = Interpreter::new(Vm::new().unwrap());
             Repl::new(lexer).execute(&mut it);
         }
-        Options(_, _, _) => unreachable!(),
+        Options(..) => unreachable!(),
     }
     Ok(())
 }
@@ -96,19 +114,33 @@ fn parse<I: FallibleIterator<Item = char, Error = io::Error>>(
     parser.execute().map(|ast| (ast, start_time.elapsed()))
 }
 
-fn eval(ast: &Script) -> interpreter::Result<(interpreter::Value, Duration)> {
+fn compile(ast: &Script) -> bytecode::Result<(bytecode::CompiledScript, Duration)> {
+    let start_time = Instant::now();
+    bytecode::compile(ast).map(|script| (script, start_time.elapsed()))
+}
+
+fn eval(ast: &Script, backend: Backend) -> Result<(interpreter::Value, Duration), Error> {
     let start_time = Instant::now();
     let mut it = Interpreter::new(Vm::new().unwrap());
-    ast.eval(&mut it).map(|value| (value, start_time.elapsed()))
+    let value = match backend {
+        Backend::TreeWalker => ast.eval(&mut it)?,
+        Backend::Bytecode => bytecode::compile(ast)?.eval(&mut it)?,
+    };
+    let runtime = start_time.elapsed();
+    if let ExecutionState::Exception(ex) = it.vm().execution_state().clone() {
+        return Err(Error::Uncaught(it.describe_exception(ex)));
+    }
+    Ok((value, runtime))
 }
 
 #[derive(Clone, Debug)]
-struct Options(Mode, Option<Format>, Option<PathBuf>);
+struct Options(Mode, Option<Format>, Option<Backend>, Option<PathBuf>);
 
 impl TryFrom<env::Args> for Options {
     type Error = ParseOptionsError;
 
-    fn try_from(mut args: env::Args) -> Result<Self, Self::Error> {
+    fn try_from(args: env::Args) -> Result<Self, Self::Error> {
+        let mut args = args.peekable();
         let executable_path = args
             .next()
             .filter(|it| !it.is_empty())
@@ -123,10 +155,18 @@ impl TryFrom<env::Args> for Options {
                     .and_then(|arg| Format::from_str(&arg).ok())
                     .ok_or_else(|| ParseOptionsError::new(executable_path.clone()))?,
             ),
-            Mode::Eval | Mode::Lex | Mode::Repl => None,
+            Mode::Eval | Mode::Disasm | Mode::Lex | Mode::Repl => None,
+        };
+        let backend = match mode {
+            Mode::Eval => Some(
+                args.next_if(|arg| Backend::from_str(arg).is_ok())
+                    .and_then(|arg| Backend::from_str(&arg).ok())
+                    .unwrap_or_default(),
+            ),
+            Mode::Parse | Mode::Disasm | Mode::Lex | Mode::Repl => None,
         };
         let source_path = match mode {
-            Mode::Eval | Mode::Parse | Mode::Lex => Some(
+            Mode::Eval | Mode::Parse | Mode::Disasm | Mode::Lex => Some(
                 args.next()
                     .and_then(|arg| PathBuf::from_str(&arg).ok())
                     .ok_or_else(|| ParseOptionsError::new(executable_path.clone()))?,
@@ -136,7 +176,7 @@ impl TryFrom<env::Args> for Options {
         if args.next().is_some() {
             return Err(ParseOptionsError::new(executable_path));
         }
-        Ok(Self(mode, format, source_path))
+        Ok(Self(mode, format, backend, source_path))
     }
 }
 
@@ -145,6 +185,7 @@ enum Mode {
     #[default]
     Eval,
     Parse,
+    Disasm,
     Lex,
     Repl,
 }
@@ -156,6 +197,7 @@ impl FromStr for Mode {
         match s {
             "--eval" => Ok(Self::Eval),
             "--parse" => Ok(Self::Parse),
+            "--disasm" => Ok(Self::Disasm),
             "--lex" => Ok(Self::Lex),
             "--repl" => Ok(Self::Repl),
             _ => Err(()),
@@ -181,11 +223,33 @@ impl FromStr for Format {
     }
 }
 
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+enum Backend {
+    #[default]
+    TreeWalker,
+    Bytecode,
+}
+
+impl FromStr for Backend {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "--bytecode" => Ok(Self::Bytecode),
+            _ => Err(()),
+        }
+    }
+}
+
 enum Error {
     Options(ParseOptionsError),
     Lex(lexer::Error),
     Parse(parser::Error),
+    Compile(bytecode::Error),
     Eval(interpreter::Error),
+    /// An exception thrown by the script which was never caught, described
+    /// along with its stack trace.
+    Uncaught(String),
     Io(io::Error),
 }
 
@@ -195,7 +259,9 @@ impl fmt::Display for Error {
             Self::Options(source) => write!(f, "{source}"),
             Self::Lex(source) => write!(f, "{source}"),
             Self::Parse(source) => write!(f, "{source}"),
+            Self::Compile(source) => write!(f, "{source}"),
             Self::Eval(source) => write!(f, "{source}"),
+            Self::Uncaught(description) => write!(f, "Uncaught {description}"),
             Self::Io(source) => write!(f, "{source}"),
         }
     }
@@ -213,7 +279,9 @@ impl std::error::Error for Error {
             Self::Options(source) => source,
             Self::Lex(source) => source,
             Self::Parse(source) => source,
+            Self::Compile(source) => source,
             Self::Eval(source) => source,
+            Self::Uncaught(..) => return None,
             Self::Io(source) => source,
         })
     }
@@ -237,6 +305,12 @@ impl From<parser::Error> for Error {
     }
 }
 
+impl From<bytecode::Error> for Error {
+    fn from(source: bytecode::Error) -> Self {
+        Self::Compile(source)
+    }
+}
+
 impl From<interpreter::Error> for Error {
     fn from(source: interpreter::Error) -> Self {
         Self::Eval(source)
@@ -272,11 +346,12 @@ impl fmt::Display for ParseOptionsError {
         write!(
             f,
             r#"Usage:
-    {}  --eval                      <source-path>  # Evaluate a file
+    {}  --eval   [--bytecode]       <source-path>  # Evaluate a file, optionally as bytecode
     {}  --parse  [--json | --yaml]  <source-path>  # Parse a file and output as JSON or YAML
+    {}  --disasm                    <source-path>  # Compile a file to bytecode and disassemble it
     {}  --lex                       <source-path>  # Lex (tokenise) a file
     {}  --repl                                     # Enter the interactive REPL"#,
-            exec_path, exec_path, exec_path, exec_path,
+            exec_path, exec_path, exec_path, exec_path, exec_path,
         )
     }
 }
@@ -32,7 +32,8 @@ where
             match it.vm().execution_state() {
                 ExecutionState::Advance => {}
                 ExecutionState::Exception(ex) => {
-                    eprintln!("Exception: {ex:?}");
+                    let ex = ex.clone();
+                    eprintln!("Uncaught {}", it.describe_exception(ex));
                     self.input_buf.clear();
                     return Result::ExitWithRuntimeError;
                 }
@@ -41,6 +42,11 @@ where
                     self.input_buf.clear();
                     return Result::ExitNormally;
                 }
+                ExecutionState::Interrupt => {
+                    eprintln!("Interrupted");
+                    self.input_buf.clear();
+                    it.vm_mut().reset_execution_state();
+                }
                 ExecutionState::Break | ExecutionState::Continue | ExecutionState::Return(..) => {
                     unreachable!()
                 }
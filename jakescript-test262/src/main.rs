@@ -1,13 +1,15 @@
 //! It is expected that the _[test262](https://github.com/tc39/test262)_ repository is checked out
 //! next to this repository.
 
-use jakescript::interpreter::{Eval, Interpreter, Vm};
+use jakescript::interpreter::{Eval, ExecutionState, Interpreter, InterruptHandle, Vm};
 use jakescript::lexer::Lexer;
 use jakescript::parser::Parser;
 use jakescript::token::SourceLocation;
 use jakescript::{interpreter, parser};
 use std::any::Any;
 use std::path::Path;
+use std::sync::mpsc;
+use std::time::Duration;
 use std::{fmt, thread};
 use test262_harness::Phase::{Early, Parse, Resolution, Runtime};
 use test262_harness::{Error, Harness, Negative, Test};
@@ -15,6 +17,10 @@ use test262_harness::{Error, Harness, Negative, Test};
 static TEST262_ROOT_DIR: &str = "../test262";
 static TEST262_TEST_DIR: &str = "../test262/test";
 
+/// Tests which fail by entering an infinite loop are interrupted after this
+/// long.
+const TEST_TIMEOUT: Duration = Duration::from_secs(10);
+
 fn main() {
     let harness = Harness::new(TEST262_ROOT_DIR).expect("failed to initialize harness");
 
@@ -37,8 +43,6 @@ fn main() {
         let expected = Expected::from(test.desc.negative.as_ref());
         let test_path = &test.path.display();
 
-        // TODO: Time out to protect against tests which fail by entering an infinite
-        // loop.
         let test_result = exec_test_suppressing_panic(&test);
 
         // TODO: For negative test cases, check that the name of the thrown exception
@@ -71,6 +75,11 @@ fn main() {
                 eprintln!("|{test_number}| FAIL {test_path}: {err}",);
             }
 
+            (Err(err @ FailureReason::Timeout), _) => {
+                fail_count += 1;
+                eprintln!("|{test_number}| FAIL {test_path}: {err}",);
+            }
+
             (Err(FailureReason::Panic(..)), _) => {
                 panic_count += 1;
                 eprintln!("|{test_number}| PANIC {test_path}");
@@ -93,23 +102,43 @@ fn is_test_we_care_about(path: impl AsRef<Path>) -> bool {
 
 fn exec_test_suppressing_panic(test: &Test) -> Result<(), FailureReason> {
     thread::scope(|s| {
-        s.spawn(|| exec_test(test))
+        let (interrupt_tx, interrupt_rx) = mpsc::channel();
+        let test_thread = s.spawn(move || exec_test(test, &interrupt_tx));
+        // The sender is dropped when the test finishes (or panics), which wakes this
+        // thread up early.
+        if let Ok(interrupt) = interrupt_rx.recv() {
+            if let Err(mpsc::RecvTimeoutError::Timeout) = interrupt_rx.recv_timeout(TEST_TIMEOUT) {
+                interrupt.interrupt();
+            }
+        }
+        test_thread
             .join()
             .unwrap_or_else(|payload| Err(FailureReason::from_panic_payload(payload)))
     })
 }
 
-fn exec_test(test: &Test) -> Result<(), FailureReason> {
+fn exec_test(
+    test: &Test,
+    interrupt_tx: &mpsc::Sender<InterruptHandle>,
+) -> Result<(), FailureReason> {
     let lexer = Lexer::for_str(&test.source, SourceLocation::at_start_of(&test.path));
     let parser = Parser::for_lexer(lexer);
 
     let vm = Vm::new().expect("failed to initialise a virtual machine");
+    interrupt_tx.send(vm.interrupt_handle()).unwrap();
     let mut it = Interpreter::new(vm);
 
     let script = parser.execute()?;
     script.eval(&mut it)?;
 
-    Ok(())
+    match it.vm().execution_state().clone() {
+        ExecutionState::Exception(ex) => Err(FailureReason::Eval(format!(
+            "uncaught exception: {}",
+            it.describe_exception(ex)
+        ))),
+        ExecutionState::Interrupt => Err(FailureReason::Timeout),
+        _ => Ok(()),
+    }
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -133,9 +162,12 @@ impl From<Option<&Negative>> for Expected {
     fn from(negative: Option<&Negative>) -> Self {
         match negative {
             None => Self::Pass,
-            Some(Negative { phase: Parse, .. }) => Self::ParserFail,
             Some(Negative {
-                phase: Early | Resolution | Runtime,
+                phase: Parse | Early,
+                ..
+            }) => Self::ParserFail,
+            Some(Negative {
+                phase: Resolution | Runtime,
                 ..
             }) => Self::RuntimeFail,
         }
@@ -148,6 +180,7 @@ impl From<Option<&Negative>> for Expected {
 enum FailureReason {
     Parse(String),
     Eval(String),
+    Timeout,
     Panic(Box<dyn Any + Send + 'static>),
 }
 
@@ -158,6 +191,7 @@ impl fmt::Display for FailureReason {
         match self {
             Self::Parse(source) => write!(f, "parse: {source}"),
             Self::Eval(source) => write!(f, "eval: {source}"),
+            Self::Timeout => write!(f, "timed out after {TEST_TIMEOUT:?}"),
             Self::Panic(_) => f.write_str("panic"),
         }
     }
@@ -0,0 +1,87 @@
+#![feature(test)]
+
+extern crate test;
+
+use common::{compile, eval, exec, parse};
+use test::Bencher;
+
+pub mod common;
+
+/// Small integers throughout, which never overflow into floats.
+const COLLATZ: &str = r#"
+let total = 0;
+for (let start = 1; start < 2000; start++) {
+    let n = start;
+    while (n !== 1) {
+        if (n % 2 === 0) {
+            n = n / 2;
+        } else {
+            n = 3 * n + 1;
+        }
+        total++;
+    }
+}
+console.assertEqual(total, 133988);
+"#;
+
+/// Integer arithmetic mixed with bitwise operators.
+const HASH: &str = r#"
+let hash = 7;
+for (let i = 0; i < 50000; i++) {
+    hash = ((hash * 31) + i) & 16777215;
+}
+console.assertEqual(hash, 6179759);
+"#;
+
+/// Floating point arithmetic and comparisons.
+const FLOAT: &str = r#"
+let factor = 3 / 2;
+let offset = 1 / 4;
+let limit = 2001 / 2;
+let x = 1 / 2;
+let count = 0;
+for (let i = 0; i < 50000; i++) {
+    x = x * factor + offset;
+    if (x > limit) {
+        x = x / 1024;
+        count++;
+    }
+}
+console.assert(count > 0);
+"#;
+
+#[bench]
+fn collatz_tree_walker(b: &mut Bencher) {
+    let ast = parse(COLLATZ);
+    b.iter(|| eval(&ast));
+}
+
+#[bench]
+fn collatz_bytecode(b: &mut Bencher) {
+    let script = compile(COLLATZ);
+    b.iter(|| exec(&script));
+}
+
+#[bench]
+fn hash_tree_walker(b: &mut Bencher) {
+    let ast = parse(HASH);
+    b.iter(|| eval(&ast));
+}
+
+#[bench]
+fn hash_bytecode(b: &mut Bencher) {
+    let script = compile(HASH);
+    b.iter(|| exec(&script));
+}
+
+#[bench]
+fn float_tree_walker(b: &mut Bencher) {
+    let ast = parse(FLOAT);
+    b.iter(|| eval(&ast));
+}
+
+#[bench]
+fn float_bytecode(b: &mut Bencher) {
+    let script = compile(FLOAT);
+    b.iter(|| exec(&script));
+}
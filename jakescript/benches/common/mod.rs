@@ -0,0 +1,29 @@
+use jakescript::ast::Script;
+use jakescript::bytecode::{self, CompiledScript};
+use jakescript::interpreter::{Eval, ExecutionState, Interpreter, Vm};
+use jakescript::lexer::Lexer;
+use jakescript::parser::Parser;
+use jakescript::token::SourceLocation;
+
+pub fn parse(source_code: &str) -> Script {
+    let lexer = Lexer::for_str(source_code, SourceLocation::at_start_of("bench"));
+    Parser::for_lexer(lexer).execute().unwrap()
+}
+
+pub fn compile(source_code: &str) -> CompiledScript {
+    bytecode::compile(&parse(source_code)).unwrap()
+}
+
+/// Run a script with the tree-walking interpreter.
+pub fn eval(ast: &Script) {
+    let mut it = Interpreter::new(Vm::new().unwrap());
+    ast.eval(&mut it).unwrap();
+    assert!(matches!(it.vm().execution_state(), ExecutionState::Advance));
+}
+
+/// Run a script with the bytecode interpreter.
+pub fn exec(script: &CompiledScript) {
+    let mut it = Interpreter::new(Vm::new().unwrap());
+    script.eval(&mut it).unwrap();
+    assert!(matches!(it.vm().execution_state(), ExecutionState::Advance));
+}
@@ -0,0 +1,87 @@
+#![feature(test)]
+
+extern crate test;
+
+use common::{compile, eval, exec, parse};
+use test::Bencher;
+
+pub mod common;
+
+const P004: &str = include_str!("../tests-js/project_euler/p004_largest_palindrome_product.js");
+const P008: &str = include_str!("../tests-js/project_euler/p008_largest_product_in_a_series.js");
+const P011: &str = include_str!("../tests-js/project_euler/p011_largest_product_in_a_grid.js");
+const P013: &str = include_str!("../tests-js/project_euler/p013_large_sum.js");
+const P017: &str = include_str!("../tests-js/project_euler/p017_number_letter_counts.js");
+const P067: &str = include_str!("../tests-js/project_euler/p067_maximum_path_sum_ii.js");
+
+#[bench]
+fn p004_tree_walker(b: &mut Bencher) {
+    let ast = parse(P004);
+    b.iter(|| eval(&ast));
+}
+
+#[bench]
+fn p004_bytecode(b: &mut Bencher) {
+    let script = compile(P004);
+    b.iter(|| exec(&script));
+}
+
+#[bench]
+fn p008_tree_walker(b: &mut Bencher) {
+    let ast = parse(P008);
+    b.iter(|| eval(&ast));
+}
+
+#[bench]
+fn p008_bytecode(b: &mut Bencher) {
+    let script = compile(P008);
+    b.iter(|| exec(&script));
+}
+
+#[bench]
+fn p011_tree_walker(b: &mut Bencher) {
+    let ast = parse(P011);
+    b.iter(|| eval(&ast));
+}
+
+#[bench]
+fn p011_bytecode(b: &mut Bencher) {
+    let script = compile(P011);
+    b.iter(|| exec(&script));
+}
+
+#[bench]
+fn p013_tree_walker(b: &mut Bencher) {
+    let ast = parse(P013);
+    b.iter(|| eval(&ast));
+}
+
+#[bench]
+fn p013_bytecode(b: &mut Bencher) {
+    let script = compile(P013);
+    b.iter(|| exec(&script));
+}
+
+#[bench]
+fn p017_tree_walker(b: &mut Bencher) {
+    let ast = parse(P017);
+    b.iter(|| eval(&ast));
+}
+
+#[bench]
+fn p017_bytecode(b: &mut Bencher) {
+    let script = compile(P017);
+    b.iter(|| exec(&script));
+}
+
+#[bench]
+fn p067_tree_walker(b: &mut Bencher) {
+    let ast = parse(P067);
+    b.iter(|| eval(&ast));
+}
+
+#[bench]
+fn p067_bytecode(b: &mut Bencher) {
+    let script = compile(P067);
+    b.iter(|| exec(&script));
+}
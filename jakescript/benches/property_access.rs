@@ -0,0 +1,84 @@
+#![feature(test)]
+
+extern crate test;
+
+use common::{compile, eval, exec, parse};
+use test::Bencher;
+
+pub mod common;
+
+/// Reads and writes of own properties, on objects which all have the same
+/// shape.
+const OWN: &str = r#"
+let points = [];
+for (let i = 0; i < 100; i++) {
+    points.push({ x: i, y: 2 * i });
+}
+let sum = 0;
+for (let round = 0; round < 200; round++) {
+    for (let i = 0; i < 100; i++) {
+        let p = points[i];
+        p.x = p.x + 1;
+        sum += p.x + p.y;
+    }
+}
+console.assertEqual(sum, 4980000);
+"#;
+
+/// Reads of a property which is found on the prototype.
+const PROTOTYPE: &str = r#"
+function Counter() {}
+Counter.prototype = { step: 3 };
+let counter = new Counter();
+let n = 0;
+for (let i = 0; i < 20000; i++) {
+    n += counter.step;
+}
+console.assertEqual(n, 60000);
+"#;
+
+/// Reads of a property of objects with a few different shapes.
+const POLYMORPHIC: &str = r#"
+let shapes = [{ a: 1 }, { b: 0, a: 2 }, { c: 0, a: 3 }, { d: 0, a: 4 }];
+let total = 0;
+for (let i = 0; i < 20000; i++) {
+    total += shapes[i % 4].a;
+}
+console.assertEqual(total, 50000);
+"#;
+
+#[bench]
+fn own_tree_walker(b: &mut Bencher) {
+    let ast = parse(OWN);
+    b.iter(|| eval(&ast));
+}
+
+#[bench]
+fn own_bytecode(b: &mut Bencher) {
+    let script = compile(OWN);
+    b.iter(|| exec(&script));
+}
+
+#[bench]
+fn prototype_tree_walker(b: &mut Bencher) {
+    let ast = parse(PROTOTYPE);
+    b.iter(|| eval(&ast));
+}
+
+#[bench]
+fn prototype_bytecode(b: &mut Bencher) {
+    let script = compile(PROTOTYPE);
+    b.iter(|| exec(&script));
+}
+
+#[bench]
+fn polymorphic_tree_walker(b: &mut Bencher) {
+    let ast = parse(POLYMORPHIC);
+    b.iter(|| eval(&ast));
+}
+
+#[bench]
+fn polymorphic_bytecode(b: &mut Bencher) {
+    let script = compile(POLYMORPHIC);
+    b.iter(|| exec(&script));
+}
@@ -0,0 +1,40 @@
+#![feature(test)]
+
+extern crate test;
+
+use common::{eval, parse};
+use test::Bencher;
+
+pub mod common;
+
+/// Build up a long string by appending to it one character at a time.
+#[bench]
+fn append_loop(b: &mut Bencher) {
+    let ast = parse(
+        r#"
+let s = "";
+for (let i = 0; i < 100000; i++) {
+    s = s + "x";
+}
+console.assertEqual(s.length, 100000);
+console.assertEqual(s[99999], "x");
+"#,
+    );
+    b.iter(|| eval(&ast));
+}
+
+/// Build up a long string by appending to it, reading the string back after
+/// each append.
+#[bench]
+fn append_and_index_loop(b: &mut Bencher) {
+    let ast = parse(
+        r#"
+let s = "";
+for (let i = 0; i < 1000; i++) {
+    s = s + "x";
+    console.assertEqual(s[i], "x");
+}
+"#,
+    );
+    b.iter(|| eval(&ast));
+}
@@ -40,6 +40,7 @@ ast_node!(
         pub binding: Identifier,
         pub parameters: Vec<Identifier>,
         pub body: Block,
+        pub strict: bool,
     }
 );
 
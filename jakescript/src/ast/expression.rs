@@ -36,6 +36,17 @@ ast_node!(
     }
 );
 
+impl Expression {
+    /// The expression inside any parentheses, e.g. `a` for `((a))`.
+    pub fn strip_groupings(&self) -> &Self {
+        let mut expr = self;
+        while let Self::Grouping(node) = expr {
+            expr = &node.inner;
+        }
+        expr
+    }
+}
+
 impl From<FunctionExpression> for Expression {
     fn from(inner: FunctionExpression) -> Self {
         Self::from(Box::new(inner))
@@ -162,6 +173,7 @@ ast_node!(
         pub binding: Option<Identifier>,
         pub parameters: Vec<Identifier>,
         pub body: Block,
+        pub strict: bool,
     }
 );
 
@@ -17,6 +17,9 @@ macro_rules! ident {
 pub enum Identifier {
     Custom(Box<str>),
     WellKnown(WellKnownIdentifier),
+    /// Never produced by the parser, but allows well-known symbols to be used
+    /// as property keys at runtime without colliding with any string.
+    Symbol(WellKnownSymbol),
 }
 
 impl Identifier {
@@ -24,6 +27,7 @@ impl Identifier {
         match self {
             Self::Custom(v) => v,
             Self::WellKnown(v) => v.as_str(),
+            Self::Symbol(v) => v.as_str(),
         }
     }
 
@@ -31,6 +35,7 @@ impl Identifier {
         match self {
             Self::Custom(v) => v,
             Self::WellKnown(v) => Box::from(v.as_str()),
+            Self::Symbol(v) => Box::from(v.as_str()),
         }
     }
 }
@@ -77,6 +82,12 @@ impl From<char> for Identifier {
     }
 }
 
+impl From<WellKnownSymbol> for Identifier {
+    fn from(symbol: WellKnownSymbol) -> Self {
+        Self::Symbol(symbol)
+    }
+}
+
 impl From<&str> for Identifier {
     fn from(s: &str) -> Self {
         WellKnownIdentifier::from_str(s)
@@ -138,6 +149,12 @@ simple_enumeration!(
     }
 );
 
+simple_enumeration!(
+    pub WellKnownSymbol {
+        ToPrimitive => "Symbol.toPrimitive",
+    }
+);
+
 #[derive(Debug)]
 pub struct ParseIdentifierError(Box<str>);
 
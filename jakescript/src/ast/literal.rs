@@ -5,7 +5,7 @@ pub enum Literal {
     Boolean(bool),
     Null,
     Numeric(NumericLiteral),
-    // TODO: Store string literals in the constant pool.
+    RegEx(RegExLiteral),
     String(StringLiteral),
 }
 
@@ -22,3 +22,11 @@ pub enum NumericLiteral {
 pub struct StringLiteral {
     pub value: Box<str>,
 }
+
+/// The pattern is kept in its raw (unescaped) form, and is compiled at runtime
+/// each time the literal is evaluated.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct RegExLiteral {
+    pub pattern: Box<str>,
+    pub flags: Box<str>,
+}
@@ -105,17 +105,23 @@ ast_node!(
     pub struct Script {
         loc: SourceLocation,
         body: Block,
+        strict: bool,
     }
 );
 
 impl Script {
-    pub fn new(loc: SourceLocation, body: Block) -> Self {
-        Self { loc, body }
+    pub fn new(loc: SourceLocation, body: Block, strict: bool) -> Self {
+        Self { loc, body, strict }
     }
 
     pub fn body(&self) -> &Block {
         &self.body
     }
+
+    /// Whether the script begins with a `"use strict"` directive.
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
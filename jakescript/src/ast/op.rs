@@ -158,22 +158,27 @@ pub enum UnaryOperator {
     NumericNegation,
     BitwiseNot,
     LogicalNot,
+    TypeOf,
 }
 
 impl UnaryOperator {
     pub fn associativity(&self) -> Associativity {
         match self {
-            Self::NumericPlus | Self::NumericNegation | Self::BitwiseNot | Self::LogicalNot => {
-                Associativity::RightToLeft
-            }
+            Self::NumericPlus
+            | Self::NumericNegation
+            | Self::BitwiseNot
+            | Self::LogicalNot
+            | Self::TypeOf => Associativity::RightToLeft,
         }
     }
 
     pub fn precedence(&self) -> Precedence {
         match self {
-            Self::NumericPlus | Self::NumericNegation | Self::BitwiseNot | Self::LogicalNot => {
-                Precedence(17)
-            }
+            Self::NumericPlus
+            | Self::NumericNegation
+            | Self::BitwiseNot
+            | Self::LogicalNot
+            | Self::TypeOf => Precedence(17),
         }
     }
 }
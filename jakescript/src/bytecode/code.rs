@@ -0,0 +1,235 @@
+use super::instruction::Instruction;
+use crate::ast::{Identifier, RegExLiteral};
+use crate::interpreter::{InlineCache, PropertyKey, Value, Variable, VariableKind};
+use crate::token::SourceLocation;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// A script which has been compiled to bytecode, which can be evaluated in
+/// place of its syntax tree. See [`compile()`][super::compile()].
+#[derive(Clone, Debug)]
+pub struct CompiledScript {
+    code: Rc<CodeBlock>,
+}
+
+impl CompiledScript {
+    pub(super) fn new(code: CodeBlock) -> Self {
+        Self {
+            code: Rc::new(code),
+        }
+    }
+
+    pub fn code(&self) -> &CodeBlock {
+        &self.code
+    }
+}
+
+/// The compiled body of a script or of a function, and the tables which its
+/// instructions refer to.
+#[derive(Debug)]
+pub struct CodeBlock {
+    pub(super) name: Option<Identifier>,
+    pub(super) strict: bool,
+    pub(super) instructions: Box<[Instruction]>,
+    pub(super) constants: Box<[Value]>,
+    pub(super) names: Box<[Identifier]>,
+    pub(super) functions: Box<[FunctionTemplate]>,
+    pub(super) regexps: Box<[RegExLiteral]>,
+    pub(super) property_caches: Box<[PropertyCache]>,
+    pub(super) scopes: Box<[Rc<ScopeLayout>]>,
+    /// The scope which each call creates for the parameters and top-level
+    /// declarations of the function. Always empty for scripts, as their
+    /// top-level declarations are global.
+    pub(super) function_scope: Rc<ScopeLayout>,
+    pub(super) register_count: usize,
+    /// The source location of each run of instructions which were compiled
+    /// from the same node, keyed by the index of the first instruction.
+    pub(super) locations: Box<[(usize, SourceLocation)]>,
+}
+
+impl CodeBlock {
+    /// The name of the function, or `None` for scripts and anonymous
+    /// functions.
+    pub fn name(&self) -> Option<&Identifier> {
+        self.name.as_ref()
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+
+    pub fn constant(&self, idx: u32) -> &Value {
+        &self.constants[idx as usize]
+    }
+
+    pub fn name_at(&self, idx: u16) -> &Identifier {
+        &self.names[usize::from(idx)]
+    }
+
+    pub fn function(&self, idx: u32) -> &FunctionTemplate {
+        &self.functions[idx as usize]
+    }
+
+    pub fn functions(&self) -> &[FunctionTemplate] {
+        &self.functions
+    }
+
+    pub fn regexp(&self, idx: u32) -> &RegExLiteral {
+        &self.regexps[idx as usize]
+    }
+
+    pub fn property_cache(&self, idx: u16) -> &PropertyCache {
+        &self.property_caches[usize::from(idx)]
+    }
+
+    pub fn scope(&self, idx: u32) -> &ScopeLayout {
+        &self.scopes[idx as usize]
+    }
+
+    pub fn function_scope(&self) -> &ScopeLayout {
+        &self.function_scope
+    }
+
+    /// The number of registers which need to be allocated for each call.
+    pub fn register_count(&self) -> usize {
+        self.register_count
+    }
+
+    /// The source location which the instruction at `pc` was compiled from.
+    pub fn source_location(&self, pc: usize) -> &SourceLocation {
+        let idx = self.locations.partition_point(|(start, _)| *start <= pc);
+        &self.locations[idx.saturating_sub(1)].1
+    }
+}
+
+/// A function literal or declaration, from which a new function object is
+/// created each time it's evaluated.
+#[derive(Debug)]
+pub struct FunctionTemplate {
+    pub(super) binds_own_name: bool,
+    pub(super) parameters: Vec<Identifier>,
+    pub(super) code: Rc<CodeBlock>,
+}
+
+impl FunctionTemplate {
+    /// See [`UserFunction::own_binding()`][crate::interpreter::UserFunction::own_binding()].
+    pub fn binds_own_name(&self) -> bool {
+        self.binds_own_name
+    }
+
+    pub fn parameters(&self) -> &[Identifier] {
+        &self.parameters
+    }
+
+    pub fn code(&self) -> &Rc<CodeBlock> {
+        &self.code
+    }
+}
+
+/// A property access with a constant key, e.g. `obj.key`, and the inline
+/// cache which remembers where the property was found the last few times it
+/// was evaluated. Each access has its own cache, even if another one in the
+/// same function has the same key.
+#[derive(Debug)]
+pub struct PropertyCache {
+    key: PropertyKey,
+    inline_cache: RefCell<InlineCache>,
+}
+
+impl PropertyCache {
+    pub(super) fn new(key: PropertyKey) -> Self {
+        Self {
+            key,
+            inline_cache: RefCell::default(),
+        }
+    }
+
+    pub fn key(&self) -> &PropertyKey {
+        &self.key
+    }
+
+    pub fn inline_cache(&self) -> &RefCell<InlineCache> {
+        &self.inline_cache
+    }
+}
+
+/// The variables of a scope, in slot order, which are known before the scope
+/// is created. The compiler resolves references to them to the slot they'll
+/// occupy, so they can be accessed without searching the scope chain by name.
+/// See [`Instruction::GetLocal`][super::Instruction::GetLocal].
+///
+/// Only scopes created by bytecode have a layout. Scopes created by the
+/// tree-walking interpreter are searched by name.
+#[derive(Debug, Default)]
+pub struct ScopeLayout {
+    pub(super) bindings: Vec<Binding>,
+}
+
+impl ScopeLayout {
+    pub fn bindings(&self) -> &[Binding] {
+        &self.bindings
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bindings.is_empty()
+    }
+
+    pub(super) fn slot(&self, name: &Identifier) -> Option<usize> {
+        self.bindings
+            .iter()
+            .position(|binding| *binding.name == *name)
+    }
+
+    /// Create the variables for a new scope with this layout, taking the
+    /// initial values of any parameters from `args`.
+    pub fn instantiate(&self, args: &[Value]) -> Vec<Variable> {
+        self.bindings
+            .iter()
+            .map(|binding| {
+                let name = Rc::clone(&binding.name);
+                match binding.initial_value {
+                    InitialValue::Argument(idx) => {
+                        let value = args.get(idx).cloned().unwrap_or_default();
+                        Variable::new(binding.kind, name, value)
+                    }
+                    InitialValue::Undefined => Variable::new_unassigned(binding.kind, name),
+                    InitialValue::Uninitialised => Variable::new_uninitialised(binding.kind, name),
+                }
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct Binding {
+    pub(super) name: Rc<Identifier>,
+    pub(super) kind: VariableKind,
+    pub(super) initial_value: InitialValue,
+}
+
+impl Binding {
+    pub fn name(&self) -> &Identifier {
+        &self.name
+    }
+
+    pub fn kind(&self) -> VariableKind {
+        self.kind
+    }
+
+    pub fn initial_value(&self) -> InitialValue {
+        self.initial_value
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum InitialValue {
+    /// The argument at this index, for a parameter.
+    Argument(usize),
+    Undefined,
+    /// In its temporal dead zone until it's initialised by its declaration.
+    Uninitialised,
+}
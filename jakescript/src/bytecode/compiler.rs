@@ -0,0 +1,1308 @@
+use super::code::{CodeBlock, CompiledScript, FunctionTemplate, PropertyCache, ScopeLayout};
+use super::error::{Error, ErrorKind, Result};
+use super::instruction::{Instruction, Register};
+use crate::ast::*;
+use crate::interpreter::{JsString, Number, PropertyKey, Value, VariableKind};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Compile a script to bytecode, which can be
+/// [evaluated][crate::interpreter::Eval] in place of its syntax tree.
+///
+/// The script evaluates to the value of its last top-level statement, as for
+/// the syntax tree, which is kept in a register of its own.
+pub fn compile(script: &Script) -> Result {
+    let loc = script.source_location();
+    let mut compiler = Compiler::new(None, script.is_strict(), Vec::default(), None);
+    let completion = compiler.alloc_register(loc)?;
+    compiler.emit(Instruction::LoadUndefined { dst: completion }, loc)?;
+    compiler.block(script.body(), Some(completion))?;
+    compiler.emit(Instruction::Return { src: completion }, loc)?;
+    Ok(CompiledScript::new(compiler.finish()))
+}
+
+struct Compiler<'a> {
+    name: Option<Identifier>,
+    strict: bool,
+    instructions: Vec<Instruction>,
+    constants: Vec<Value>,
+    names: Vec<Identifier>,
+    name_indices: HashMap<Identifier, u16>,
+    functions: Vec<FunctionTemplate>,
+    regexps: Vec<RegExLiteral>,
+    property_caches: Vec<PropertyCache>,
+    scopes: Vec<Rc<ScopeLayout>>,
+    /// `None` for the script, whose top-level declarations are global.
+    function_scope: Option<Rc<ScopeLayout>>,
+    /// The scopes outside of the function being compiled, outermost first,
+    /// ending with those which each call creates for it. Empty for the script,
+    /// as the only scope outside of it is the global scope.
+    enclosing_scopes: Vec<Rc<ScopeLayout>>,
+    locations: Vec<(usize, SourceLocation)>,
+    /// Registers are allocated like a stack, and freed by resetting this to
+    /// what it was before the allocation.
+    next_register: usize,
+    register_count: usize,
+    /// The statements enclosing the code being compiled, which `break`,
+    /// `continue` and `return` statements need to jump out of.
+    controls: Vec<Control<'a>>,
+}
+
+enum Control<'a> {
+    /// A scope which needs to be popped when jumping out of it.
+    Scope(Rc<ScopeLayout>),
+    /// A try block or catch block, which needs to be left when jumping out of
+    /// it, and whose finally block needs to run.
+    Try {
+        finally: Option<&'a Block>,
+    },
+    /// A finally block which runs because of an exception, which needs to be
+    /// discarded when jumping out of it rather than rethrown.
+    Rethrow,
+    /// The jumps to patch once the end of the loop is known.
+    Loop {
+        breaks: Vec<usize>,
+        continues: Vec<usize>,
+    },
+    Switch {
+        breaks: Vec<usize>,
+    },
+}
+
+/// Something which can be assigned to, with its object and key already
+/// evaluated into registers.
+#[derive(Copy, Clone)]
+enum Target<'a> {
+    /// A depth and slot.
+    Local(u16, u16),
+    Variable(u16),
+    Property(Register, &'a Identifier),
+    Computed(Register, Register),
+}
+
+impl<'a> Compiler<'a> {
+    fn new(
+        name: Option<Identifier>,
+        strict: bool,
+        enclosing_scopes: Vec<Rc<ScopeLayout>>,
+        function_scope: Option<Rc<ScopeLayout>>,
+    ) -> Self {
+        Self {
+            name,
+            strict,
+            instructions: Vec::default(),
+            constants: Vec::default(),
+            names: Vec::default(),
+            name_indices: HashMap::default(),
+            functions: Vec::default(),
+            regexps: Vec::default(),
+            property_caches: Vec::default(),
+            scopes: Vec::default(),
+            function_scope,
+            enclosing_scopes,
+            locations: Vec::default(),
+            next_register: 0,
+            register_count: 0,
+            controls: Vec::default(),
+        }
+    }
+
+    fn finish(self) -> CodeBlock {
+        CodeBlock {
+            name: self.name,
+            strict: self.strict,
+            instructions: self.instructions.into_boxed_slice(),
+            constants: self.constants.into_boxed_slice(),
+            names: self.names.into_boxed_slice(),
+            functions: self.functions.into_boxed_slice(),
+            regexps: self.regexps.into_boxed_slice(),
+            property_caches: self.property_caches.into_boxed_slice(),
+            scopes: self.scopes.into_boxed_slice(),
+            function_scope: self.function_scope.unwrap_or_default(),
+            register_count: self.register_count,
+            locations: self.locations.into_boxed_slice(),
+        }
+    }
+
+    fn emit(&mut self, instruction: Instruction, loc: &SourceLocation) -> Result<usize> {
+        let pc = self.instructions.len();
+        if u32::try_from(pc).is_err() {
+            return Err(Error::new(ErrorKind::CodeTooLarge, loc));
+        }
+        if self.locations.last().is_none_or(|(_, last)| last != loc) {
+            self.locations.push((pc, loc.clone()));
+        }
+        self.instructions.push(instruction);
+        Ok(pc)
+    }
+
+    /// The target of a jump to the next instruction to be emitted.
+    fn here(&self) -> u32 {
+        // Can't overflow, as `emit()` refuses to emit any more instructions than
+        // this can address.
+        u32::try_from(self.instructions.len()).unwrap()
+    }
+
+    /// Point the jump at `pc`, which was emitted before its target was known,
+    /// at `target`.
+    fn patch(&mut self, pc: usize, target: u32) {
+        match self.instructions[pc] {
+            Instruction::Jump {
+                target: ref mut placeholder,
+            }
+            | Instruction::JumpIfTrue {
+                target: ref mut placeholder,
+                ..
+            }
+            | Instruction::JumpIfFalse {
+                target: ref mut placeholder,
+                ..
+            }
+            | Instruction::EnterTry {
+                handler: ref mut placeholder,
+                ..
+            }
+            | Instruction::OnInterrupt {
+                target: ref mut placeholder,
+            } => *placeholder = target,
+            instruction => unreachable!("not a jump: {instruction:?}"),
+        }
+    }
+
+    fn patch_all(&mut self, pcs: impl IntoIterator<Item = usize>, target: u32) {
+        for pc in pcs {
+            self.patch(pc, target);
+        }
+    }
+
+    fn alloc_register(&mut self, loc: &SourceLocation) -> Result<Register> {
+        let register = u16::try_from(self.next_register)
+            .map(Register::new)
+            .map_err(|_| Error::new(ErrorKind::TooManyRegisters, loc))?;
+        self.next_register += 1;
+        self.register_count = self.register_count.max(self.next_register);
+        Ok(register)
+    }
+
+    /// Run `f` with a register which is freed again afterwards, along with any
+    /// registers allocated by `f`.
+    fn with_temp<T>(
+        &mut self,
+        loc: &SourceLocation,
+        f: impl FnOnce(&mut Self, Register) -> Result<T>,
+    ) -> Result<T> {
+        let mark = self.next_register;
+        let register = self.alloc_register(loc)?;
+        let result = f(self, register);
+        self.next_register = mark;
+        result
+    }
+
+    fn name(&mut self, identifier: &Identifier, loc: &SourceLocation) -> Result<u16> {
+        if let Some(&idx) = self.name_indices.get(identifier) {
+            return Ok(idx);
+        }
+        let idx = u16::try_from(self.names.len())
+            .map_err(|_| Error::new(ErrorKind::TooManyNames, loc))?;
+        self.names.push(identifier.clone());
+        self.name_indices.insert(identifier.clone(), idx);
+        Ok(idx)
+    }
+
+    fn property_cache(&mut self, key: &Identifier, loc: &SourceLocation) -> Result<u16> {
+        let idx = u16::try_from(self.property_caches.len())
+            .map_err(|_| Error::new(ErrorKind::CodeTooLarge, loc))?;
+        self.property_caches
+            .push(PropertyCache::new(PropertyKey::from(key.clone())));
+        Ok(idx)
+    }
+
+    fn constant(&mut self, value: Value, loc: &SourceLocation) -> Result<u32> {
+        let idx = u32::try_from(self.constants.len())
+            .map_err(|_| Error::new(ErrorKind::CodeTooLarge, loc))?;
+        self.constants.push(value);
+        Ok(idx)
+    }
+
+    /// Only the body of the script itself passes a `completion` register, which
+    /// receives the value of each top-level statement in turn.
+    fn block(&mut self, block: &'a Block, completion: Option<Register>) -> Result<()> {
+        // Declarations in scopes with a layout already exist by the time the block
+        // runs, but global ones need to be declared.
+        if self.is_global_scope() {
+            for node in block.body() {
+                if let Statement::Declaration(Declaration::Lexical(decl)) = node {
+                    let kind = VariableKind::from(decl.kind);
+                    for entry in &decl.bindings {
+                        let loc = entry.source_location();
+                        let name = self.name(&entry.identifier, loc)?;
+                        self.emit(Instruction::DeclareLexical { kind, name }, loc)?;
+                    }
+                }
+            }
+        }
+        for decl in block.hoisted_declarations() {
+            match decl {
+                Declaration::Function(node) => self.function_declaration(node)?,
+                Declaration::Variable(node) if self.function_scope.is_none() => {
+                    for entry in &node.bindings {
+                        let name = self.name(&entry.identifier, entry.source_location())?;
+                        self.emit(Instruction::DeclareVar { name }, entry.source_location())?;
+                    }
+                }
+                Declaration::Variable(..) => {}
+                Declaration::Lexical(..) => unreachable!("lexical declarations aren't hoisted"),
+            }
+        }
+        for node in block.body() {
+            let loc = node.source_location();
+            if let Some(completion) = completion {
+                self.emit(Instruction::SafePoint, loc)?;
+                if let Statement::Expression(expr) = node {
+                    self.expression(expr, completion)?;
+                } else {
+                    self.statement(node)?;
+                    self.emit(Instruction::LoadUndefined { dst: completion }, loc)?;
+                }
+            } else {
+                self.statement(node)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Run `f` within a new scope, which is popped again afterwards. Creating
+    /// the scope is skipped if nothing is declared in it.
+    fn scoped(
+        &mut self,
+        layout: ScopeLayout,
+        loc: &SourceLocation,
+        f: impl FnOnce(&mut Self) -> Result<()>,
+    ) -> Result<()> {
+        if layout.is_empty() {
+            return f(self);
+        }
+        self.push_scope(layout, loc)?;
+        f(self)?;
+        self.pop_scope(loc)
+    }
+
+    fn push_scope(&mut self, layout: ScopeLayout, loc: &SourceLocation) -> Result<()> {
+        let scope = u32::try_from(self.scopes.len())
+            .map_err(|_| Error::new(ErrorKind::CodeTooLarge, loc))?;
+        let layout = Rc::new(layout);
+        self.scopes.push(Rc::clone(&layout));
+        self.emit(Instruction::PushScope { scope }, loc)?;
+        self.controls.push(Control::Scope(layout));
+        Ok(())
+    }
+
+    fn pop_scope(&mut self, loc: &SourceLocation) -> Result<()> {
+        let Some(Control::Scope(..)) = self.controls.pop() else {
+            unreachable!()
+        };
+        self.emit(Instruction::PopScope, loc)?;
+        Ok(())
+    }
+
+    /// A block which isn't the body of a function, in a scope of its own.
+    fn scoped_block(&mut self, block: &'a Block, loc: &SourceLocation) -> Result<()> {
+        self.scoped(ScopeLayout::for_block(block), loc, |this| {
+            this.block(block, None)
+        })
+    }
+
+    /// The body of an `if` statement or a loop, in a scope of its own.
+    fn scoped_statement(&mut self, node: &'a Statement) -> Result<()> {
+        let loc = node.source_location();
+        match node {
+            Statement::Block(node) => self.scoped_block(&node.block, loc),
+            node => self.scoped(ScopeLayout::for_statement(node), loc, |this| {
+                this.statement(node)
+            }),
+        }
+    }
+
+    /// Whether the code being compiled runs directly in the global scope, i.e.
+    /// at the top level of the script.
+    fn is_global_scope(&self) -> bool {
+        self.enclosing_scopes.is_empty()
+            && !self
+                .controls
+                .iter()
+                .any(|control| matches!(control, Control::Scope(..)))
+    }
+
+    /// The scopes which the code being compiled can see, innermost first.
+    fn scope_chain(&self) -> impl DoubleEndedIterator<Item = &Rc<ScopeLayout>> {
+        let inner = self
+            .controls
+            .iter()
+            .rev()
+            .filter_map(|control| match control {
+                Control::Scope(layout) => Some(layout),
+                _ => None,
+            });
+        inner.chain(self.enclosing_scopes.iter().rev())
+    }
+
+    /// The depth and slot of the variable which `name` refers to, or `None` if
+    /// it has to be looked up by name at runtime.
+    fn resolve(&self, name: &Identifier) -> Option<(u16, u16)> {
+        let (depth, slot) = self
+            .scope_chain()
+            .enumerate()
+            .find_map(|(depth, layout)| layout.slot(name).map(|slot| (depth, slot)))?;
+        // Anything too far away to address can still be found by name.
+        Some((u16::try_from(depth).ok()?, u16::try_from(slot).ok()?))
+    }
+
+    /// Initialise a declaration in the current scope with the value in `src`.
+    fn initialise(
+        &mut self,
+        identifier: &Identifier,
+        kind: VariableKind,
+        src: Register,
+        loc: &SourceLocation,
+    ) -> Result<()> {
+        match self.resolve(identifier) {
+            Some((0, slot)) if !self.is_global_scope() => {
+                self.emit(Instruction::InitialiseLocal { src, slot }, loc)?;
+            }
+            _ => {
+                let name = self.name(identifier, loc)?;
+                let instruction = match kind {
+                    VariableKind::Var => Instruction::DeclareFunction { src, name },
+                    VariableKind::Const | VariableKind::Let => {
+                        Instruction::InitialiseLexical { kind, src, name }
+                    }
+                };
+                self.emit(instruction, loc)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn statement(&mut self, node: &'a Statement) -> Result<()> {
+        let loc = node.source_location();
+        match node {
+            Statement::Declaration(Declaration::Lexical(node)) => self.lexical_declaration(node),
+            // Declarations are only left in place when they're the body of another
+            // statement, rather than in a block.
+            Statement::Declaration(Declaration::Variable(node)) => self.variable_declaration(node),
+            Statement::Declaration(Declaration::Function(node)) => self.function_declaration(node),
+            Statement::Expression(node) => {
+                self.with_temp(loc, |this, dst| this.expression(node, dst))
+            }
+
+            Statement::Block(node) => self.scoped_block(&node.block, loc),
+            Statement::Empty(..) => Ok(()),
+
+            Statement::If(node) => self.if_statement(node),
+            Statement::Switch(node) => self.switch_statement(node),
+            Statement::Try(node) => self.try_statement(node),
+
+            Statement::Do(node) => self.do_statement(node),
+            Statement::For(node) => self.for_statement(node),
+            Statement::While(node) => self.while_statement(node),
+
+            Statement::Break(..) => {
+                let depth = self
+                    .controls
+                    .iter()
+                    .rposition(|control| {
+                        matches!(control, Control::Loop { .. } | Control::Switch { .. })
+                    })
+                    .expect("break outside of loop or switch");
+                self.unwind_to(depth + 1, loc)?;
+                let jump = self.emit(Instruction::Jump { target: 0 }, loc)?;
+                match self.controls[depth] {
+                    Control::Loop { ref mut breaks, .. } | Control::Switch { ref mut breaks } => {
+                        breaks.push(jump);
+                    }
+                    Control::Scope(..) | Control::Try { .. } | Control::Rethrow => unreachable!(),
+                }
+                Ok(())
+            }
+            Statement::Continue(..) => {
+                let depth = self
+                    .controls
+                    .iter()
+                    .rposition(|control| matches!(control, Control::Loop { .. }))
+                    .expect("continue outside of loop");
+                self.unwind_to(depth + 1, loc)?;
+                let jump = self.emit(Instruction::Jump { target: 0 }, loc)?;
+                match self.controls[depth] {
+                    Control::Loop {
+                        ref mut continues, ..
+                    } => continues.push(jump),
+                    Control::Scope(..)
+                    | Control::Try { .. }
+                    | Control::Rethrow
+                    | Control::Switch { .. } => unreachable!(),
+                }
+                Ok(())
+            }
+            Statement::Return(node) => self.with_temp(loc, |this, src| {
+                if let Some(ref value) = node.value {
+                    this.expression(value, src)?;
+                } else {
+                    this.emit(Instruction::LoadUndefined { dst: src }, loc)?;
+                }
+                // Scopes and handlers are discarded by returning anyway, so it's only
+                // necessary to unwind if there are finally blocks to run or leave.
+                if let Some(depth) = this.controls.iter().position(|control| {
+                    matches!(
+                        control,
+                        Control::Try { finally: Some(_) } | Control::Rethrow
+                    )
+                }) {
+                    this.unwind_to(depth, loc)?;
+                }
+                this.emit(Instruction::Return { src }, loc)?;
+                Ok(())
+            }),
+            Statement::Throw(node) => self.with_temp(loc, |this, src| {
+                this.expression(&node.exception, src)?;
+                this.emit(Instruction::Throw { src }, loc)?;
+                Ok(())
+            }),
+        }
+    }
+
+    /// Leave each of the enclosing statements above `depth`, innermost first,
+    /// ahead of jumping out of them.
+    fn unwind_to(&mut self, depth: usize, loc: &SourceLocation) -> Result<()> {
+        for idx in (depth..self.controls.len()).rev() {
+            match self.controls[idx] {
+                Control::Scope(..) => {
+                    self.emit(Instruction::PopScope, loc)?;
+                }
+                Control::Try { finally } => {
+                    self.emit(Instruction::LeaveTry, loc)?;
+                    if let Some(finally) = finally {
+                        // The finally block is outside of the try statement, so any jumps
+                        // within it mustn't leave the statements which it's nested in.
+                        let inner = self.controls.split_off(idx);
+                        let result = self.scoped_block(finally, loc);
+                        self.controls.extend(inner);
+                        result?;
+                    }
+                }
+                Control::Rethrow => {
+                    self.emit(Instruction::DiscardException, loc)?;
+                }
+                Control::Loop { .. } | Control::Switch { .. } => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn lexical_declaration(&mut self, node: &'a LexicalDeclaration) -> Result<()> {
+        let kind = VariableKind::from(node.kind);
+        for entry in &node.bindings {
+            let loc = entry.source_location();
+            self.with_temp(loc, |this, src| {
+                if let Some(ref initialiser) = entry.initialiser {
+                    this.expression(initialiser, src)?;
+                } else {
+                    this.emit(Instruction::LoadUndefined { dst: src }, loc)?;
+                }
+                this.initialise(&entry.identifier, kind, src, loc)
+            })?;
+        }
+        Ok(())
+    }
+
+    fn variable_declaration(&mut self, node: &'a VariableDeclaration) -> Result<()> {
+        for entry in &node.bindings {
+            let loc = entry.source_location();
+            // Variables in functions already exist by the time the function runs.
+            if self.function_scope.is_none() {
+                let name = self.name(&entry.identifier, loc)?;
+                self.emit(Instruction::DeclareVar { name }, loc)?;
+            }
+            if let Some(ref initialiser) = entry.initialiser {
+                self.with_temp(loc, |this, src| {
+                    this.expression(initialiser, src)?;
+                    let target = this.variable_target(&entry.identifier, loc)?;
+                    this.set_target(target, src, loc)
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    fn function_declaration(&mut self, node: &'a FunctionDeclaration) -> Result<()> {
+        let loc = node.source_location();
+        let function = self.function(
+            Some(&node.binding),
+            false,
+            &node.parameters,
+            &node.body,
+            node.strict,
+            loc,
+        )?;
+        self.with_temp(loc, |this, src| {
+            this.emit(Instruction::MakeFunction { dst: src, function }, loc)?;
+            this.initialise(&node.binding, VariableKind::Var, src, loc)
+        })
+    }
+
+    fn if_statement(&mut self, node: &'a IfStatement) -> Result<()> {
+        let loc = node.source_location();
+        let skip_body = self.with_temp(loc, |this, cond| {
+            this.expression(&node.condition, cond)?;
+            this.emit(Instruction::JumpIfFalse { cond, target: 0 }, loc)
+        })?;
+        self.scoped_statement(&node.body)?;
+        if let Some(ref else_body) = node.else_body {
+            let skip_else_body = self.emit(Instruction::Jump { target: 0 }, loc)?;
+            self.patch(skip_body, self.here());
+            self.scoped_statement(else_body)?;
+            self.patch(skip_else_body, self.here());
+        } else {
+            self.patch(skip_body, self.here());
+        }
+        Ok(())
+    }
+
+    /// The cases are tested in order, and then the default case is jumped to
+    /// if none of them matched. The bodies fall through to one another in the
+    /// same order, with the default case last.
+    fn switch_statement(&mut self, node: &'a SwitchStatement) -> Result<()> {
+        let loc = node.source_location();
+        self.with_temp(loc, |this, value| {
+            this.expression(&node.value, value)?;
+            let mut case_jumps = Vec::with_capacity(node.cases.len());
+            for case in &node.cases {
+                let case_loc = case.source_location();
+                let jump = this.with_temp(case_loc, |this, cond| {
+                    this.expression(&case.pattern, cond)?;
+                    this.emit(
+                        Instruction::StrictEqual {
+                            dst: cond,
+                            lhs: cond,
+                            rhs: value,
+                        },
+                        case_loc,
+                    )?;
+                    this.emit(Instruction::JumpIfTrue { cond, target: 0 }, case_loc)
+                })?;
+                case_jumps.push(jump);
+            }
+            let default_jump = this.emit(Instruction::Jump { target: 0 }, loc)?;
+
+            this.controls.push(Control::Switch { breaks: vec![] });
+            for (case, jump) in node.cases.iter().zip(case_jumps) {
+                this.patch(jump, this.here());
+                for stmt in &case.body {
+                    this.statement(stmt)?;
+                }
+            }
+            if let Some(ref default_case) = node.default_case {
+                this.patch(default_jump, this.here());
+                for stmt in &default_case.body {
+                    this.statement(stmt)?;
+                }
+            } else {
+                this.patch(default_jump, this.here());
+            }
+            let Some(Control::Switch { breaks }) = this.controls.pop() else {
+                unreachable!()
+            };
+            this.patch_all(breaks, this.here());
+            Ok(())
+        })
+    }
+
+    /// The finally block is compiled once for each way out of the try
+    /// statement, i.e. falling off the end, throwing, or jumping out with
+    /// `break`, `continue` or `return`. Being interrupted shares the way out
+    /// for throwing, but skips the catch block.
+    fn try_statement(&mut self, node: &'a TryStatement) -> Result<()> {
+        let loc = node.source_location();
+        let finally = node.finally.as_ref().map(|finally| &finally.body);
+        self.with_temp(loc, |this, exception| {
+            let enter_try = this.emit(
+                Instruction::EnterTry {
+                    exception,
+                    handler: 0,
+                },
+                loc,
+            )?;
+            let mut on_interrupts = Vec::new();
+            if finally.is_some() {
+                on_interrupts.push(this.emit(Instruction::OnInterrupt { target: 0 }, loc)?);
+            }
+            this.controls.push(Control::Try { finally });
+            this.scoped_block(&node.body, loc)?;
+            this.controls.pop();
+            this.emit(Instruction::LeaveTry, loc)?;
+            if let Some(finally) = finally {
+                this.scoped_block(finally, loc)?;
+            }
+            let mut exits = vec![this.emit(Instruction::Jump { target: 0 }, loc)?];
+
+            this.patch(enter_try, this.here());
+            if let Some(ref catch) = node.catch {
+                let catch_loc = catch.source_location();
+                let enter_catch = if finally.is_some() {
+                    let enter_catch = this.emit(
+                        Instruction::EnterTry {
+                            exception,
+                            handler: 0,
+                        },
+                        catch_loc,
+                    )?;
+                    on_interrupts
+                        .push(this.emit(Instruction::OnInterrupt { target: 0 }, catch_loc)?);
+                    this.controls.push(Control::Try { finally });
+                    Some(enter_catch)
+                } else {
+                    None
+                };
+                if let Some(ref exception_binding) = catch.exception_binding {
+                    let layout = ScopeLayout::for_binding(exception_binding, VariableKind::Let);
+                    this.scoped(layout, catch_loc, |this| {
+                        let src = exception;
+                        this.emit(Instruction::InitialiseLocal { src, slot: 0 }, catch_loc)?;
+                        this.scoped_block(&catch.body, catch_loc)
+                    })?;
+                } else {
+                    this.scoped_block(&catch.body, catch_loc)?;
+                }
+                if let (Some(enter_catch), Some(finally)) = (enter_catch, finally) {
+                    this.controls.pop();
+                    this.emit(Instruction::LeaveTry, catch_loc)?;
+                    this.scoped_block(finally, catch_loc)?;
+                    exits.push(this.emit(Instruction::Jump { target: 0 }, catch_loc)?);
+
+                    this.patch(enter_catch, this.here());
+                    let on_interrupt = this.rethrow_after(finally, exception, catch_loc)?;
+                    this.patch_all(on_interrupts, on_interrupt);
+                }
+            } else {
+                let finally = finally.expect("try statement without catch or finally");
+                let on_interrupt = this.rethrow_after(finally, exception, loc)?;
+                this.patch_all(on_interrupts, on_interrupt);
+            }
+            this.patch_all(exits, this.here());
+            Ok(())
+        })
+    }
+
+    /// Run a finally block on the way out of a try or catch block which threw,
+    /// then rethrow the exception. Like the tree-walker, the exception is lost
+    /// if another one is caught within the finally block.
+    ///
+    /// Returns the start of the finally block, which is where to go if the
+    /// script is interrupted instead. See [`Instruction::OnInterrupt`].
+    fn rethrow_after(
+        &mut self,
+        finally: &'a Block,
+        exception: Register,
+        loc: &SourceLocation,
+    ) -> Result<u32> {
+        self.emit(Instruction::HideException { src: exception }, loc)?;
+        let start = self.here();
+        self.controls.push(Control::Rethrow);
+        self.scoped_block(finally, loc)?;
+        self.controls.pop();
+        self.emit(Instruction::RestoreException, loc)?;
+        Ok(start)
+    }
+
+    fn do_statement(&mut self, node: &'a DoStatement) -> Result<()> {
+        let loc = node.source_location();
+        self.controls.push(Control::Loop {
+            breaks: vec![],
+            continues: vec![],
+        });
+        let top = self.here();
+        self.emit(Instruction::LoopHint, loc)?;
+        self.scoped_statement(&node.body)?;
+        self.with_temp(loc, |this, cond| {
+            this.expression(&node.condition, cond)?;
+            this.emit(Instruction::JumpIfTrue { cond, target: top }, loc)
+        })?;
+        // Like the tree-walker, `continue` starts the next iteration without
+        // checking the condition.
+        self.end_loop(top, vec![]);
+        Ok(())
+    }
+
+    fn for_statement(&mut self, node: &'a ForStatement) -> Result<()> {
+        let loc = node.source_location();
+        let scoped_initialiser = match node.initialiser {
+            Some(ForInitialiser::LexicalDeclaration(ref decl)) => {
+                self.push_scope(ScopeLayout::for_lexical_declaration(decl), loc)?;
+                true
+            }
+            _ => false,
+        };
+        if let Some(ref initialiser) = node.initialiser {
+            match initialiser {
+                ForInitialiser::Expression(expr) => {
+                    self.with_temp(loc, |this, dst| this.expression(expr, dst))?;
+                }
+                ForInitialiser::LexicalDeclaration(decl) => self.lexical_declaration(decl)?,
+                ForInitialiser::VariableDeclaration(decl) => self.variable_declaration(decl)?,
+            }
+        }
+        self.controls.push(Control::Loop {
+            breaks: vec![],
+            continues: vec![],
+        });
+        let top = self.here();
+        self.emit(Instruction::LoopHint, loc)?;
+        let mut exits = vec![];
+        if let Some(ref condition) = node.condition {
+            exits.push(self.with_temp(loc, |this, cond| {
+                this.expression(condition, cond)?;
+                this.emit(Instruction::JumpIfFalse { cond, target: 0 }, loc)
+            })?);
+        }
+        self.scoped_statement(&node.body)?;
+        let incrementor = self.here();
+        if let Some(ref expr) = node.incrementor {
+            self.with_temp(loc, |this, dst| this.expression(expr, dst))?;
+        }
+        self.emit(Instruction::Jump { target: top }, loc)?;
+        self.end_loop(incrementor, exits);
+        if scoped_initialiser {
+            self.pop_scope(loc)?;
+        }
+        Ok(())
+    }
+
+    fn while_statement(&mut self, node: &'a WhileStatement) -> Result<()> {
+        let loc = node.source_location();
+        self.controls.push(Control::Loop {
+            breaks: vec![],
+            continues: vec![],
+        });
+        let top = self.here();
+        self.emit(Instruction::LoopHint, loc)?;
+        let exit = self.with_temp(loc, |this, cond| {
+            this.expression(&node.condition, cond)?;
+            this.emit(Instruction::JumpIfFalse { cond, target: 0 }, loc)
+        })?;
+        self.scoped_statement(&node.body)?;
+        self.emit(Instruction::Jump { target: top }, loc)?;
+        self.end_loop(top, vec![exit]);
+        Ok(())
+    }
+
+    /// Pop the innermost loop, and point its `continue` jumps at `continue_to`
+    /// and its `break` jumps (and `exits`) at the next instruction.
+    fn end_loop(&mut self, continue_to: u32, exits: Vec<usize>) {
+        let Some(Control::Loop { breaks, continues }) = self.controls.pop() else {
+            unreachable!()
+        };
+        self.patch_all(continues, continue_to);
+        let end = self.here();
+        self.patch_all(breaks.into_iter().chain(exits), end);
+    }
+
+    fn function(
+        &mut self,
+        name: Option<&Identifier>,
+        binds_own_name: bool,
+        parameters: &'a [Identifier],
+        body: &'a Block,
+        strict: bool,
+        loc: &SourceLocation,
+    ) -> Result<u32> {
+        let mut enclosing_scopes: Vec<_> = self.scope_chain().rev().cloned().collect();
+        if binds_own_name && let Some(name) = name {
+            // See `Interpreter::call_user_fn()`.
+            let own_scope = ScopeLayout::for_binding(name, VariableKind::Var);
+            enclosing_scopes.push(Rc::new(own_scope));
+        }
+        let function_scope = Rc::new(ScopeLayout::for_function(parameters, body));
+        enclosing_scopes.push(Rc::clone(&function_scope));
+        let mut compiler = Compiler::new(
+            name.cloned(),
+            strict,
+            enclosing_scopes,
+            Some(function_scope),
+        );
+        compiler.block(body, None)?;
+        let src = compiler.alloc_register(loc)?;
+        compiler.emit(Instruction::LoadUndefined { dst: src }, loc)?;
+        compiler.emit(Instruction::Return { src }, loc)?;
+        let idx = u32::try_from(self.functions.len())
+            .map_err(|_| Error::new(ErrorKind::CodeTooLarge, loc))?;
+        self.functions.push(FunctionTemplate {
+            binds_own_name,
+            parameters: parameters.to_vec(),
+            code: Rc::new(compiler.finish()),
+        });
+        Ok(idx)
+    }
+
+    /// Compile `expr` so that its value ends up in `dst`. Any other registers
+    /// it needs are allocated above `dst`, so `dst` may be used as scratch
+    /// space along the way.
+    fn expression(&mut self, expr: &'a Expression, dst: Register) -> Result<()> {
+        let loc = expr.source_location();
+        match expr {
+            Expression::IdentifierReference(node) => {
+                let target = self.variable_target(&node.identifier, loc)?;
+                self.get_target(target, dst, loc)?;
+            }
+            Expression::This(..) => {
+                self.emit(Instruction::LoadThis { dst }, loc)?;
+            }
+
+            Expression::ComputedMemberAccess(node) => {
+                self.expression(&node.base, dst)?;
+                self.with_temp(loc, |this, key| {
+                    this.expression(&node.index, key)?;
+                    let instruction = Instruction::GetComputedProperty { dst, obj: dst, key };
+                    this.emit(instruction, loc)
+                })?;
+            }
+            Expression::MemberAccess(node) => {
+                self.expression(&node.base, dst)?;
+                let cache = self.property_cache(&node.member, loc)?;
+                self.emit(
+                    Instruction::GetProperty {
+                        dst,
+                        obj: dst,
+                        cache,
+                    },
+                    loc,
+                )?;
+            }
+
+            Expression::FunctionCall(node) => self.call(node, dst)?,
+            Expression::New(node) => {
+                let mark = self.next_register;
+                let ctor = self.alloc_register(loc)?;
+                self.expression(&node.constructor, ctor)?;
+                let argc = self.arguments(&node.arguments, loc)?;
+                self.emit(Instruction::New { dst, ctor, argc }, loc)?;
+                self.next_register = mark;
+            }
+
+            Expression::Assignment(node) => self.assignment(node, dst)?,
+            Expression::Binary(node) => self.binary(node, dst)?,
+            Expression::Grouping(node) => self.expression(&node.inner, dst)?,
+            Expression::Relational(node) => {
+                self.expression(&node.lhs, dst)?;
+                self.with_temp(loc, |this, rhs| {
+                    this.expression(&node.rhs, rhs)?;
+                    this.emit(relational_instruction(node.op, dst, dst, rhs), loc)
+                })?;
+            }
+            Expression::Ternary(node) => {
+                self.expression(&node.condition, dst)?;
+                let skip_true_value = self.emit(
+                    Instruction::JumpIfFalse {
+                        cond: dst,
+                        target: 0,
+                    },
+                    loc,
+                )?;
+                self.expression(&node.true_value, dst)?;
+                let skip_false_value = self.emit(Instruction::Jump { target: 0 }, loc)?;
+                self.patch(skip_true_value, self.here());
+                self.expression(&node.false_value, dst)?;
+                self.patch(skip_false_value, self.here());
+            }
+            Expression::Unary(node) => {
+                if let (UnaryOperator::TypeOf, Expression::IdentifierReference(operand)) =
+                    (node.op, node.operand.as_ref())
+                    && self.resolve(&operand.identifier).is_none()
+                {
+                    let name = self.name(&operand.identifier, loc)?;
+                    self.emit(Instruction::TypeOfVariable { dst, name }, loc)?;
+                } else {
+                    self.expression(&node.operand, dst)?;
+                    let src = dst;
+                    let instruction = match node.op {
+                        UnaryOperator::NumericPlus => Instruction::ToNumber { dst, src },
+                        UnaryOperator::NumericNegation => Instruction::Negate { dst, src },
+                        UnaryOperator::BitwiseNot => Instruction::BitwiseNot { dst, src },
+                        UnaryOperator::LogicalNot => Instruction::LogicalNot { dst, src },
+                        UnaryOperator::TypeOf => Instruction::TypeOf { dst, src },
+                    };
+                    self.emit(instruction, loc)?;
+                }
+            }
+            Expression::Update(node) => {
+                let mark = self.next_register;
+                let target = self.target(&node.operand)?;
+                self.get_target(target, dst, loc)?;
+                let src = dst;
+                match node.op {
+                    UpdateOperator::IncrementAndGet => {
+                        self.emit(Instruction::Increment { dst, src }, loc)?;
+                        self.set_target(target, dst, loc)?;
+                    }
+                    UpdateOperator::DecrementAndGet => {
+                        self.emit(Instruction::Decrement { dst, src }, loc)?;
+                        self.set_target(target, dst, loc)?;
+                    }
+                    UpdateOperator::GetAndIncrement | UpdateOperator::GetAndDecrement => {
+                        self.emit(Instruction::ToNumber { dst, src }, loc)?;
+                        let new_value = self.alloc_register(loc)?;
+                        self.emit(
+                            if node.op == UpdateOperator::GetAndIncrement {
+                                Instruction::Increment {
+                                    dst: new_value,
+                                    src,
+                                }
+                            } else {
+                                Instruction::Decrement {
+                                    dst: new_value,
+                                    src,
+                                }
+                            },
+                            loc,
+                        )?;
+                        self.set_target(target, new_value, loc)?;
+                    }
+                }
+                self.next_register = mark;
+            }
+
+            Expression::Array(node) => {
+                let mark = self.next_register;
+                let start = Register::new(
+                    u16::try_from(self.next_register)
+                        .map_err(|_| Error::new(ErrorKind::TooManyRegisters, loc))?,
+                );
+                for elem in &node.declared_elements {
+                    let register = self.alloc_register(elem.source_location())?;
+                    self.expression(elem, register)?;
+                }
+                let count = u16::try_from(node.declared_elements.len())
+                    .map_err(|_| Error::new(ErrorKind::TooManyRegisters, loc))?;
+                self.emit(Instruction::NewArray { dst, start, count }, loc)?;
+                self.next_register = mark;
+            }
+            Expression::Function(node) => {
+                let function = self.function(
+                    node.binding.as_ref(),
+                    true,
+                    &node.parameters,
+                    &node.body,
+                    node.strict,
+                    loc,
+                )?;
+                self.emit(Instruction::MakeFunction { dst, function }, loc)?;
+            }
+            Expression::Literal(node) => self.literal(&node.value, dst, loc)?,
+            Expression::Object(node) => {
+                self.emit(Instruction::NewObject { dst }, loc)?;
+                for prop in &node.declared_properties {
+                    self.with_temp(loc, |this, src| {
+                        let name = match prop.name {
+                            ObjectPropertyName::Identifier(ref name) => name.clone(),
+                            ObjectPropertyName::StringLiteral(ref name) => {
+                                Identifier::from(name.value.as_ref())
+                            }
+                            ObjectPropertyName::NumericLiteral(name) => {
+                                Identifier::from(Number::from(name).to_string())
+                            }
+                            ObjectPropertyName::Computed(ref key_expr) => {
+                                return this.with_temp(loc, |this, key| {
+                                    this.expression(key_expr, key)?;
+                                    this.expression(&prop.initialiser, src)?;
+                                    let instruction =
+                                        Instruction::DefineComputedProperty { obj: dst, key, src };
+                                    this.emit(instruction, loc)
+                                });
+                            }
+                        };
+                        let name = this.name(&name, loc)?;
+                        this.expression(&prop.initialiser, src)?;
+                        this.emit(
+                            Instruction::DefineProperty {
+                                obj: dst,
+                                name,
+                                src,
+                            },
+                            loc,
+                        )
+                    })?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn literal(&mut self, literal: &Literal, dst: Register, loc: &SourceLocation) -> Result<()> {
+        let instruction = match literal {
+            Literal::Boolean(value) => Instruction::LoadBoolean { dst, value: *value },
+            Literal::Null => Instruction::LoadNull { dst },
+            Literal::Numeric(value) => match Number::from(*value) {
+                Number::Int(value) if let Ok(value) = i32::try_from(value) => {
+                    Instruction::LoadInt { dst, value }
+                }
+                value => Instruction::LoadConstant {
+                    dst,
+                    constant: self.constant(Value::Number(value), loc)?,
+                },
+            },
+            Literal::RegEx(value) => {
+                let regexp = u32::try_from(self.regexps.len())
+                    .map_err(|_| Error::new(ErrorKind::CodeTooLarge, loc))?;
+                self.regexps.push(value.clone());
+                Instruction::LoadRegExp { dst, regexp }
+            }
+            Literal::String(value) => Instruction::LoadConstant {
+                dst,
+                constant: self
+                    .constant(Value::String(JsString::from(value.value.as_ref())), loc)?,
+            },
+        };
+        self.emit(instruction, loc)?;
+        Ok(())
+    }
+
+    fn binary(&mut self, node: &'a BinaryExpression, dst: Register) -> Result<()> {
+        let loc = node.source_location();
+        match node.op {
+            BinaryOperator::LogicalAnd | BinaryOperator::LogicalOr => {
+                self.expression(&node.lhs, dst)?;
+                let cond = dst;
+                let short_circuit = self.emit(
+                    if node.op == BinaryOperator::LogicalAnd {
+                        Instruction::JumpIfFalse { cond, target: 0 }
+                    } else {
+                        Instruction::JumpIfTrue { cond, target: 0 }
+                    },
+                    loc,
+                )?;
+                self.expression(&node.rhs, dst)?;
+                self.patch(short_circuit, self.here());
+                Ok(())
+            }
+            op => self.with_temp(loc, |this, rhs| {
+                // The operands are evaluated in the order of the operator's associativity.
+                match op.associativity() {
+                    Associativity::LeftToRight => {
+                        this.expression(&node.lhs, dst)?;
+                        this.expression(&node.rhs, rhs)?;
+                    }
+                    Associativity::RightToLeft => {
+                        this.expression(&node.rhs, rhs)?;
+                        this.expression(&node.lhs, dst)?;
+                    }
+                }
+                this.emit(binary_instruction(op, dst, dst, rhs), loc)?;
+                Ok(())
+            }),
+        }
+    }
+
+    fn assignment(&mut self, node: &'a AssignmentExpression, dst: Register) -> Result<()> {
+        let loc = node.source_location();
+        let mark = self.next_register;
+        let target = self.target(&node.lhs)?;
+        match node.op {
+            AssignmentOperator::Assign => self.expression(&node.rhs, dst)?,
+            AssignmentOperator::ComputeAssign(
+                op @ (BinaryOperator::LogicalAnd | BinaryOperator::LogicalOr),
+            ) => {
+                self.get_target(target, dst, loc)?;
+                let cond = dst;
+                let short_circuit = self.emit(
+                    if op == BinaryOperator::LogicalAnd {
+                        Instruction::JumpIfFalse { cond, target: 0 }
+                    } else {
+                        Instruction::JumpIfTrue { cond, target: 0 }
+                    },
+                    loc,
+                )?;
+                self.expression(&node.rhs, dst)?;
+                self.patch(short_circuit, self.here());
+            }
+            AssignmentOperator::ComputeAssign(op) => {
+                self.get_target(target, dst, loc)?;
+                self.with_temp(loc, |this, rhs| {
+                    this.expression(&node.rhs, rhs)?;
+                    this.emit(binary_instruction(op, dst, dst, rhs), loc)
+                })?;
+            }
+        }
+        self.set_target(target, dst, loc)?;
+        self.next_register = mark;
+        Ok(())
+    }
+
+    /// Evaluate the object and key of an assignment target into registers,
+    /// which the caller is responsible for freeing. Parentheses are ignored, so
+    /// `(a) = 1` assigns to `a`.
+    fn target(&mut self, expr: &'a Expression) -> Result<Target<'a>> {
+        let expr = expr.strip_groupings();
+        let loc = expr.source_location();
+        Ok(match expr {
+            Expression::IdentifierReference(node) => self.variable_target(&node.identifier, loc)?,
+            Expression::MemberAccess(node) => {
+                let obj = self.alloc_register(loc)?;
+                self.expression(&node.base, obj)?;
+                Target::Property(obj, &node.member)
+            }
+            Expression::ComputedMemberAccess(node) => {
+                let obj = self.alloc_register(loc)?;
+                self.expression(&node.base, obj)?;
+                let key = self.alloc_register(loc)?;
+                self.expression(&node.index, key)?;
+                Target::Computed(obj, key)
+            }
+            _ => unreachable!("invalid assignment target: {expr:#?}"),
+        })
+    }
+
+    fn variable_target(
+        &mut self,
+        identifier: &Identifier,
+        loc: &SourceLocation,
+    ) -> Result<Target<'a>> {
+        Ok(match self.resolve(identifier) {
+            Some((depth, slot)) => Target::Local(depth, slot),
+            None => Target::Variable(self.name(identifier, loc)?),
+        })
+    }
+
+    fn get_target(
+        &mut self,
+        target: Target<'a>,
+        dst: Register,
+        loc: &SourceLocation,
+    ) -> Result<()> {
+        let instruction = match target {
+            Target::Local(depth, slot) => Instruction::GetLocal { dst, depth, slot },
+            Target::Variable(name) => Instruction::GetVariable { dst, name },
+            Target::Property(obj, key) => {
+                let cache = self.property_cache(key, loc)?;
+                Instruction::GetProperty { dst, obj, cache }
+            }
+            Target::Computed(obj, key) => Instruction::GetComputedProperty { dst, obj, key },
+        };
+        self.emit(instruction, loc)?;
+        Ok(())
+    }
+
+    fn set_target(
+        &mut self,
+        target: Target<'a>,
+        src: Register,
+        loc: &SourceLocation,
+    ) -> Result<()> {
+        let instruction = match target {
+            Target::Local(depth, slot) => Instruction::SetLocal { src, depth, slot },
+            Target::Variable(name) => Instruction::SetVariable { src, name },
+            Target::Property(obj, key) => {
+                let cache = self.property_cache(key, loc)?;
+                Instruction::SetProperty { obj, cache, src }
+            }
+            Target::Computed(obj, key) => Instruction::SetComputedProperty { obj, key, src },
+        };
+        self.emit(instruction, loc)?;
+        Ok(())
+    }
+
+    /// Member accesses are called as methods, i.e. with their base as the
+    /// receiver, even when wrapped in parentheses.
+    fn call(&mut self, node: &'a FunctionCallExpression, dst: Register) -> Result<()> {
+        let loc = node.source_location();
+        let mark = self.next_register;
+        let callee = self.alloc_register(loc)?;
+        let receiver = self.alloc_register(loc)?;
+        let function = node.function.strip_groupings();
+        let function_loc = function.source_location();
+        match function {
+            Expression::MemberAccess(function) => {
+                self.expression(&function.base, receiver)?;
+                let cache = self.property_cache(&function.member, function_loc)?;
+                let instruction = Instruction::GetProperty {
+                    dst: callee,
+                    obj: receiver,
+                    cache,
+                };
+                self.emit(instruction, function_loc)?;
+            }
+            Expression::ComputedMemberAccess(function) => {
+                self.expression(&function.base, receiver)?;
+                self.with_temp(function_loc, |this, key| {
+                    this.expression(&function.index, key)?;
+                    let instruction = Instruction::GetComputedProperty {
+                        dst: callee,
+                        obj: receiver,
+                        key,
+                    };
+                    this.emit(instruction, function_loc)
+                })?;
+            }
+            function => {
+                self.expression(function, callee)?;
+                self.emit(Instruction::LoadUndefined { dst: receiver }, function_loc)?;
+            }
+        }
+        let argc = self.arguments(&node.arguments, loc)?;
+        self.emit(Instruction::Call { dst, callee, argc }, loc)?;
+        self.next_register = mark;
+        Ok(())
+    }
+
+    /// Evaluate each argument into the next free register, which the caller is
+    /// responsible for freeing.
+    fn arguments(&mut self, arguments: &'a [Expression], loc: &SourceLocation) -> Result<u16> {
+        for arg in arguments {
+            let register = self.alloc_register(arg.source_location())?;
+            self.expression(arg, register)?;
+        }
+        u16::try_from(arguments.len()).map_err(|_| Error::new(ErrorKind::TooManyRegisters, loc))
+    }
+}
+
+fn binary_instruction(
+    op: BinaryOperator,
+    dst: Register,
+    lhs: Register,
+    rhs: Register,
+) -> Instruction {
+    match op {
+        BinaryOperator::Addition => Instruction::Add { dst, lhs, rhs },
+        BinaryOperator::Subtraction => Instruction::Subtract { dst, lhs, rhs },
+        BinaryOperator::Multiplication => Instruction::Multiply { dst, lhs, rhs },
+        BinaryOperator::Division => Instruction::Divide { dst, lhs, rhs },
+        BinaryOperator::Modulus => Instruction::Modulus { dst, lhs, rhs },
+        BinaryOperator::Exponentiation => Instruction::Exponentiate { dst, lhs, rhs },
+        BinaryOperator::BitwiseAnd => Instruction::BitwiseAnd { dst, lhs, rhs },
+        BinaryOperator::BitwiseOr => Instruction::BitwiseOr { dst, lhs, rhs },
+        BinaryOperator::BitwiseXOr => Instruction::BitwiseXOr { dst, lhs, rhs },
+        BinaryOperator::BitwiseLeftShift => Instruction::ShiftLeft { dst, lhs, rhs },
+        BinaryOperator::BitwiseRightShift => Instruction::ShiftRight { dst, lhs, rhs },
+        BinaryOperator::BitwiseRightShiftUnsigned => {
+            Instruction::ShiftRightUnsigned { dst, lhs, rhs }
+        }
+        BinaryOperator::LogicalAnd | BinaryOperator::LogicalOr => {
+            unreachable!("short-circuiting operator: {op:?}")
+        }
+    }
+}
+
+fn relational_instruction(
+    op: RelationalOperator,
+    dst: Register,
+    lhs: Register,
+    rhs: Register,
+) -> Instruction {
+    match op {
+        RelationalOperator::Equality => Instruction::Equal { dst, lhs, rhs },
+        RelationalOperator::Inequality => Instruction::NotEqual { dst, lhs, rhs },
+        RelationalOperator::StrictEquality => Instruction::StrictEqual { dst, lhs, rhs },
+        RelationalOperator::StrictInequality => Instruction::StrictNotEqual { dst, lhs, rhs },
+        RelationalOperator::GreaterThan => Instruction::GreaterThan { dst, lhs, rhs },
+        RelationalOperator::GreaterThanOrEqual => Instruction::GreaterThanOrEqual { dst, lhs, rhs },
+        RelationalOperator::LessThan => Instruction::LessThan { dst, lhs, rhs },
+        RelationalOperator::LessThanOrEqual => Instruction::LessThanOrEqual { dst, lhs, rhs },
+    }
+}
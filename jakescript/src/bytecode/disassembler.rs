@@ -0,0 +1,230 @@
+use super::code::{CodeBlock, CompiledScript, ScopeLayout};
+use super::instruction::{Instruction, Register};
+use crate::interpreter::{Value, VariableKind};
+use std::fmt;
+
+/// A listing of the script's instructions, followed by those of each function
+/// it contains.
+impl fmt::Display for CompiledScript {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+/// A listing with one instruction per line, with the names and constants they
+/// refer to resolved, and the source position of each run of instructions
+/// which were compiled from the same node.
+impl fmt::Display for CodeBlock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.name() {
+            Some(name) => write!(f, "function {name}")?,
+            None => write!(f, "<script>")?,
+        }
+        write!(
+            f,
+            " ({}registers: {})",
+            if self.is_strict() { "strict, " } else { "" },
+            self.register_count()
+        )?;
+        if self.function_scope().is_empty() {
+            writeln!(f)?;
+        } else {
+            writeln!(f, " {}", layout(self.function_scope()))?;
+        }
+        let mut prev_loc = None;
+        for (pc, instruction) in self.instructions().iter().enumerate() {
+            let (mnemonic, operands) = decode(*instruction);
+            let operands = operands
+                .iter()
+                .map(|operand| self.resolve(*operand))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let line = format!("{pc:>6}  {mnemonic:<22}{operands}");
+            let loc = self.source_location(pc);
+            if prev_loc == Some(loc) {
+                writeln!(f, "{line}")?;
+            } else {
+                writeln!(f, "{line:<55} ; {}", loc.position())?;
+                prev_loc = Some(loc);
+            }
+        }
+        for (idx, function) in self.functions().iter().enumerate() {
+            let parameters = function
+                .parameters()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(f)?;
+            writeln!(f, "#{idx} ({parameters}):")?;
+            write!(f, "{}", function.code())?;
+        }
+        Ok(())
+    }
+}
+
+impl CodeBlock {
+    fn resolve(&self, operand: Operand) -> String {
+        match operand {
+            Operand::Register(register) => register.to_string(),
+            Operand::Name(idx) => format!("`{}`", self.name_at(idx)),
+            Operand::Key(idx) => format!("`{}`", self.property_cache(idx).key()),
+            Operand::Local(depth, slot) => format!("{depth}:{slot}"),
+            Operand::Scope(idx) => layout(self.scope(idx)),
+            Operand::Constant(idx) => match self.constant(idx) {
+                Value::String(s) => format!("{:?}", s.to_string_lossy()),
+                Value::Number(n) => n.to_string(),
+                value => format!("{value:?}"),
+            },
+            Operand::Function(idx) => format!("#{idx}"),
+            Operand::RegExp(idx) => {
+                let regexp = self.regexp(idx);
+                format!("/{}/{}", regexp.pattern, regexp.flags)
+            }
+            Operand::Target(target) => format!("@{target}"),
+            Operand::Int(value) => value.to_string(),
+            Operand::Boolean(value) => value.to_string(),
+            Operand::Count(count) => count.to_string(),
+            Operand::Kind(kind) => match kind {
+                VariableKind::Const => "const",
+                VariableKind::Let => "let",
+                VariableKind::Var => "var",
+            }
+            .to_owned(),
+        }
+    }
+}
+
+/// The name of each slot, e.g. ``[`a`, `b`]``.
+fn layout(layout: &ScopeLayout) -> String {
+    let names = layout
+        .bindings()
+        .iter()
+        .map(|binding| format!("`{}`", binding.name()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("[{names}]")
+}
+
+#[derive(Copy, Clone)]
+enum Operand {
+    Register(Register),
+    Name(u16),
+    /// The key of a property cache.
+    Key(u16),
+    /// A depth and slot.
+    Local(u16, u16),
+    Scope(u32),
+    Constant(u32),
+    Function(u32),
+    RegExp(u32),
+    Target(u32),
+    Int(i32),
+    Boolean(bool),
+    Count(u16),
+    Kind(VariableKind),
+}
+
+fn decode(instruction: Instruction) -> (&'static str, Vec<Operand>) {
+    use Instruction as I;
+    use Operand::{
+        Boolean, Constant, Count, Function, Int, Key, Kind, Local, Name, RegExp, Register as R,
+        Scope, Target,
+    };
+    match instruction {
+        I::LoadUndefined { dst } => ("load_undefined", vec![R(dst)]),
+        I::LoadNull { dst } => ("load_null", vec![R(dst)]),
+        I::LoadBoolean { dst, value } => ("load_boolean", vec![R(dst), Boolean(value)]),
+        I::LoadInt { dst, value } => ("load_int", vec![R(dst), Int(value)]),
+        I::LoadConstant { dst, constant } => ("load_constant", vec![R(dst), Constant(constant)]),
+        I::LoadRegExp { dst, regexp } => ("load_regexp", vec![R(dst), RegExp(regexp)]),
+        I::LoadThis { dst } => ("load_this", vec![R(dst)]),
+
+        I::MakeFunction { dst, function } => ("make_function", vec![R(dst), Function(function)]),
+        I::NewArray { dst, start, count } => ("new_array", vec![R(dst), R(start), Count(count)]),
+        I::NewObject { dst } => ("new_object", vec![R(dst)]),
+        I::DefineProperty { obj, name, src } => {
+            ("define_property", vec![R(obj), Name(name), R(src)])
+        }
+        I::DefineComputedProperty { obj, key, src } => {
+            ("define_computed_property", vec![R(obj), R(key), R(src)])
+        }
+
+        I::GetLocal { dst, depth, slot } => ("get_local", vec![R(dst), Local(depth, slot)]),
+        I::SetLocal { src, depth, slot } => ("set_local", vec![R(src), Local(depth, slot)]),
+        I::InitialiseLocal { src, slot } => ("initialise_local", vec![R(src), Local(0, slot)]),
+        I::GetVariable { dst, name } => ("get_variable", vec![R(dst), Name(name)]),
+        I::SetVariable { src, name } => ("set_variable", vec![R(src), Name(name)]),
+        I::TypeOfVariable { dst, name } => ("type_of_variable", vec![R(dst), Name(name)]),
+        I::DeclareVar { name } => ("declare_var", vec![Name(name)]),
+        I::DeclareFunction { src, name } => ("declare_function", vec![R(src), Name(name)]),
+        I::DeclareLexical { kind, name } => ("declare_lexical", vec![Kind(kind), Name(name)]),
+        I::InitialiseLexical { kind, src, name } => {
+            ("initialise_lexical", vec![Kind(kind), R(src), Name(name)])
+        }
+        I::PushScope { scope } => ("push_scope", vec![Scope(scope)]),
+        I::PopScope => ("pop_scope", vec![]),
+
+        I::GetProperty { dst, obj, cache } => ("get_property", vec![R(dst), R(obj), Key(cache)]),
+        I::SetProperty { obj, cache, src } => ("set_property", vec![R(obj), Key(cache), R(src)]),
+        I::GetComputedProperty { dst, obj, key } => {
+            ("get_computed_property", vec![R(dst), R(obj), R(key)])
+        }
+        I::SetComputedProperty { obj, key, src } => {
+            ("set_computed_property", vec![R(obj), R(key), R(src)])
+        }
+
+        I::Add { dst, lhs, rhs } => ("add", vec![R(dst), R(lhs), R(rhs)]),
+        I::Subtract { dst, lhs, rhs } => ("subtract", vec![R(dst), R(lhs), R(rhs)]),
+        I::Multiply { dst, lhs, rhs } => ("multiply", vec![R(dst), R(lhs), R(rhs)]),
+        I::Divide { dst, lhs, rhs } => ("divide", vec![R(dst), R(lhs), R(rhs)]),
+        I::Modulus { dst, lhs, rhs } => ("modulus", vec![R(dst), R(lhs), R(rhs)]),
+        I::Exponentiate { dst, lhs, rhs } => ("exponentiate", vec![R(dst), R(lhs), R(rhs)]),
+        I::BitwiseAnd { dst, lhs, rhs } => ("bitwise_and", vec![R(dst), R(lhs), R(rhs)]),
+        I::BitwiseOr { dst, lhs, rhs } => ("bitwise_or", vec![R(dst), R(lhs), R(rhs)]),
+        I::BitwiseXOr { dst, lhs, rhs } => ("bitwise_xor", vec![R(dst), R(lhs), R(rhs)]),
+        I::ShiftLeft { dst, lhs, rhs } => ("shift_left", vec![R(dst), R(lhs), R(rhs)]),
+        I::ShiftRight { dst, lhs, rhs } => ("shift_right", vec![R(dst), R(lhs), R(rhs)]),
+        I::ShiftRightUnsigned { dst, lhs, rhs } => {
+            ("shift_right_unsigned", vec![R(dst), R(lhs), R(rhs)])
+        }
+
+        I::Equal { dst, lhs, rhs } => ("equal", vec![R(dst), R(lhs), R(rhs)]),
+        I::NotEqual { dst, lhs, rhs } => ("not_equal", vec![R(dst), R(lhs), R(rhs)]),
+        I::StrictEqual { dst, lhs, rhs } => ("strict_equal", vec![R(dst), R(lhs), R(rhs)]),
+        I::StrictNotEqual { dst, lhs, rhs } => ("strict_not_equal", vec![R(dst), R(lhs), R(rhs)]),
+        I::LessThan { dst, lhs, rhs } => ("less_than", vec![R(dst), R(lhs), R(rhs)]),
+        I::LessThanOrEqual { dst, lhs, rhs } => {
+            ("less_than_or_equal", vec![R(dst), R(lhs), R(rhs)])
+        }
+        I::GreaterThan { dst, lhs, rhs } => ("greater_than", vec![R(dst), R(lhs), R(rhs)]),
+        I::GreaterThanOrEqual { dst, lhs, rhs } => {
+            ("greater_than_or_equal", vec![R(dst), R(lhs), R(rhs)])
+        }
+
+        I::ToNumber { dst, src } => ("to_number", vec![R(dst), R(src)]),
+        I::Negate { dst, src } => ("negate", vec![R(dst), R(src)]),
+        I::BitwiseNot { dst, src } => ("bitwise_not", vec![R(dst), R(src)]),
+        I::LogicalNot { dst, src } => ("logical_not", vec![R(dst), R(src)]),
+        I::TypeOf { dst, src } => ("type_of", vec![R(dst), R(src)]),
+        I::Increment { dst, src } => ("increment", vec![R(dst), R(src)]),
+        I::Decrement { dst, src } => ("decrement", vec![R(dst), R(src)]),
+
+        I::Call { dst, callee, argc } => ("call", vec![R(dst), R(callee), Count(argc)]),
+        I::New { dst, ctor, argc } => ("new", vec![R(dst), R(ctor), Count(argc)]),
+
+        I::Jump { target } => ("jump", vec![Target(target)]),
+        I::JumpIfTrue { cond, target } => ("jump_if_true", vec![R(cond), Target(target)]),
+        I::JumpIfFalse { cond, target } => ("jump_if_false", vec![R(cond), Target(target)]),
+        I::LoopHint => ("loop_hint", vec![]),
+        I::SafePoint => ("safe_point", vec![]),
+        I::EnterTry { exception, handler } => ("enter_try", vec![R(exception), Target(handler)]),
+        I::LeaveTry => ("leave_try", vec![]),
+        I::OnInterrupt { target } => ("on_interrupt", vec![Target(target)]),
+        I::HideException { src } => ("hide_exception", vec![R(src)]),
+        I::RestoreException => ("restore_exception", vec![]),
+        I::DiscardException => ("discard_exception", vec![]),
+        I::Throw { src } => ("throw", vec![R(src)]),
+        I::Return { src } => ("return", vec![R(src)]),
+    }
+}
@@ -0,0 +1,60 @@
+use super::code::CompiledScript;
+use crate::token::SourceLocation;
+use std::fmt;
+
+pub type Result<T = CompiledScript> = std::result::Result<T, Error>;
+
+/// A script which is valid, but which can't be compiled to bytecode because it
+/// exceeds the limits of the instruction encoding.
+#[derive(Clone, Debug)]
+pub struct Error {
+    kind: ErrorKind,
+    loc: SourceLocation,
+}
+
+impl Error {
+    pub fn new(kind: ErrorKind, loc: &SourceLocation) -> Self {
+        Self {
+            kind,
+            loc: loc.clone(),
+        }
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    pub fn source_location(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at {:#}", self.kind(), self.source_location())
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ErrorKind {
+    /// A function needs more registers than can be addressed, e.g. because an
+    /// expression is too deeply nested or a call has too many arguments.
+    TooManyRegisters,
+    /// A function refers to more distinct names than can be addressed.
+    TooManyNames,
+    /// A function has more instructions, constants or property accesses than
+    /// can be addressed.
+    CodeTooLarge,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::TooManyRegisters => "too many registers",
+            Self::TooManyNames => "too many names",
+            Self::CodeTooLarge => "code too large",
+        })
+    }
+}
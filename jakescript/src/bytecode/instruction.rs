@@ -0,0 +1,375 @@
+use crate::interpreter::VariableKind;
+use std::fmt;
+
+/// A register of the function which is running, which holds a temporary value
+/// such as the operand of an expression. Each call gets its own registers.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Register(u16);
+
+impl Register {
+    pub const MAX: Self = Self(u16::MAX);
+
+    pub fn new(index: u16) -> Self {
+        Self(index)
+    }
+
+    pub fn index(self) -> usize {
+        usize::from(self.0)
+    }
+
+    /// The register `n` places after this one, or `None` if there aren't that
+    /// many registers.
+    pub fn offset(self, n: usize) -> Option<Self> {
+        u16::try_from(self.index() + n).ok().map(Self)
+    }
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "r{}", self.0)
+    }
+}
+
+/// An instruction for the bytecode interpreter, which operates on the
+/// registers of the function which is running.
+///
+/// Operands which refer to a table of the [`CodeBlock`][super::CodeBlock]
+/// (i.e. `name`, `constant`, `function`, and `regexp`) are indices into that
+/// table, and `target`s are indices into its instructions. Instructions are
+/// kept to 8 bytes, so that they pack densely.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Instruction {
+    LoadUndefined {
+        dst: Register,
+    },
+    LoadNull {
+        dst: Register,
+    },
+    LoadBoolean {
+        dst: Register,
+        value: bool,
+    },
+    LoadInt {
+        dst: Register,
+        value: i32,
+    },
+    LoadConstant {
+        dst: Register,
+        constant: u32,
+    },
+    /// The regexp is compiled each time this is executed, as a new object is
+    /// created each time a regexp literal is evaluated.
+    LoadRegExp {
+        dst: Register,
+        regexp: u32,
+    },
+    LoadThis {
+        dst: Register,
+    },
+
+    /// Create a closure over the current scope.
+    MakeFunction {
+        dst: Register,
+        function: u32,
+    },
+    /// Create an array of the `count` values starting at `start`.
+    NewArray {
+        dst: Register,
+        start: Register,
+        count: u16,
+    },
+    NewObject {
+        dst: Register,
+    },
+    DefineProperty {
+        obj: Register,
+        name: u16,
+        src: Register,
+    },
+    DefineComputedProperty {
+        obj: Register,
+        key: Register,
+        src: Register,
+    },
+
+    /// Read the variable in `slot` of the scope `depth` scopes out from the
+    /// current one, as resolved by the compiler.
+    GetLocal {
+        dst: Register,
+        depth: u16,
+        slot: u16,
+    },
+    SetLocal {
+        src: Register,
+        depth: u16,
+        slot: u16,
+    },
+    /// Initialise a variable in the current scope by its declaration.
+    InitialiseLocal {
+        src: Register,
+        slot: u16,
+    },
+    /// Read a variable from the scope chain by name or, failing that, a
+    /// property of the global object. Only needed for variables which the
+    /// compiler couldn't resolve, e.g. global variables.
+    GetVariable {
+        dst: Register,
+        name: u16,
+    },
+    SetVariable {
+        src: Register,
+        name: u16,
+    },
+    /// The result of `typeof` for a variable, which unlike reading the
+    /// variable isn't an error if it hasn't been declared.
+    TypeOfVariable {
+        dst: Register,
+        name: u16,
+    },
+    /// Declare a hoisted `var` in the nearest function (or global) scope.
+    DeclareVar {
+        name: u16,
+    },
+    DeclareFunction {
+        src: Register,
+        name: u16,
+    },
+    /// Declare a `let` or `const` at the top of its block, in its temporal
+    /// dead zone until it's initialised.
+    DeclareLexical {
+        kind: VariableKind,
+        name: u16,
+    },
+    InitialiseLexical {
+        kind: VariableKind,
+        src: Register,
+        name: u16,
+    },
+    /// Create a scope with the layout at index `scope` of the
+    /// [`CodeBlock`][super::CodeBlock]'s table of scopes.
+    PushScope {
+        scope: u32,
+    },
+    PopScope,
+
+    /// Get the property with the key of the [`PropertyCache`][super::PropertyCache]
+    /// at index `cache`, which is used to skip looking up the key if the object
+    /// has the same shape as one seen before.
+    GetProperty {
+        dst: Register,
+        obj: Register,
+        cache: u16,
+    },
+    SetProperty {
+        obj: Register,
+        cache: u16,
+        src: Register,
+    },
+    GetComputedProperty {
+        dst: Register,
+        obj: Register,
+        key: Register,
+    },
+    SetComputedProperty {
+        obj: Register,
+        key: Register,
+        src: Register,
+    },
+
+    Add {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    Subtract {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    Multiply {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    Divide {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    Modulus {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    Exponentiate {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    BitwiseAnd {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    BitwiseOr {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    BitwiseXOr {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    ShiftLeft {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    ShiftRight {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    ShiftRightUnsigned {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+
+    Equal {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    NotEqual {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    StrictEqual {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    StrictNotEqual {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    LessThan {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    LessThanOrEqual {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    GreaterThan {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+    GreaterThanOrEqual {
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    },
+
+    ToNumber {
+        dst: Register,
+        src: Register,
+    },
+    Negate {
+        dst: Register,
+        src: Register,
+    },
+    BitwiseNot {
+        dst: Register,
+        src: Register,
+    },
+    LogicalNot {
+        dst: Register,
+        src: Register,
+    },
+    TypeOf {
+        dst: Register,
+        src: Register,
+    },
+    Increment {
+        dst: Register,
+        src: Register,
+    },
+    Decrement {
+        dst: Register,
+        src: Register,
+    },
+
+    /// Call the function in `callee`, with the receiver in the register after
+    /// it and the `argc` arguments in the registers after that.
+    Call {
+        dst: Register,
+        callee: Register,
+        argc: u16,
+    },
+    /// Construct an object with the constructor in `ctor`, with the `argc`
+    /// arguments in the registers after it.
+    New {
+        dst: Register,
+        ctor: Register,
+        argc: u16,
+    },
+
+    Jump {
+        target: u32,
+    },
+    JumpIfTrue {
+        cond: Register,
+        target: u32,
+    },
+    JumpIfFalse {
+        cond: Register,
+        target: u32,
+    },
+    /// Marks the start of each iteration of a loop. See
+    /// [`Vm::check_interrupt()`][crate::interpreter::Vm::check_interrupt()].
+    LoopHint,
+    /// Marks the start of each top-level statement, where garbage may be
+    /// collected. See
+    /// [`Vm::safe_point()`][crate::interpreter::Vm::safe_point()].
+    SafePoint,
+    /// Until the matching [`Instruction::LeaveTry`], jump to `handler` if an
+    /// exception is thrown, with the exception in `exception`.
+    EnterTry {
+        exception: Register,
+        handler: u32,
+    },
+    LeaveTry,
+    /// Follows an [`Instruction::EnterTry`] whose try statement has a finally
+    /// block. If the script is interrupted before the matching
+    /// [`Instruction::LeaveTry`], jump to `target` to run the finally block on
+    /// the way out, rather than to the handler, which might catch it. See
+    /// [`Vm::resume_interrupt()`][crate::interpreter::Vm::resume_interrupt()].
+    OnInterrupt {
+        target: u32,
+    },
+    /// Hide the exception in `src` while the finally block which follows runs,
+    /// as if it had been thrown. See
+    /// [`Vm::hide_current_exception()`][crate::interpreter::Vm::hide_current_exception()].
+    HideException {
+        src: Register,
+    },
+    /// Rethrow the hidden exception at the end of the finally block, unless
+    /// another exception was caught in the meantime.
+    RestoreException,
+    /// Discard the hidden exception when jumping out of the finally block.
+    DiscardException,
+    Throw {
+        src: Register,
+    },
+    Return {
+        src: Register,
+    },
+}
@@ -0,0 +1,13 @@
+pub use code::*;
+pub use compiler::*;
+pub use error::*;
+pub use instruction::*;
+
+mod code;
+mod compiler;
+mod disassembler;
+mod error;
+mod instruction;
+mod resolver;
+#[cfg(test)]
+mod test;
@@ -0,0 +1,180 @@
+//! Works out which variables each scope will contain before any code which
+//! runs in it is compiled, so that references to them can be resolved to a
+//! (depth, slot) coordinate. Only scopes created by compiled code are laid out
+//! like this. The global scope is shared with other scripts and the host, so
+//! references to anything which isn't declared in a scope with a layout fall
+//! back to looking up the variable by name at runtime.
+//!
+//! This only applies to the bytecode backend. The tree-walking interpreter
+//! doesn't use these layouts, and always looks variables up by name.
+
+use super::code::{Binding, InitialValue, ScopeLayout};
+use crate::ast::*;
+use crate::interpreter::VariableKind;
+use std::rc::Rc;
+use std::slice;
+
+impl ScopeLayout {
+    /// The scope created by each call, which holds the parameters, every `var`
+    /// declared anywhere in the body (other than in nested functions), and the
+    /// declarations at the top level of the body.
+    pub(super) fn for_function(parameters: &[Identifier], body: &Block) -> Self {
+        let mut layout = Self::default();
+        for (idx, parameter) in parameters.iter().enumerate() {
+            // Duplicate parameter names are allowed in sloppy mode, and the last one wins.
+            match layout.slot(parameter) {
+                Some(slot) => layout.bindings[slot].initial_value = InitialValue::Argument(idx),
+                None => layout.declare(parameter, VariableKind::Let, InitialValue::Argument(idx)),
+            }
+        }
+        layout.declare_block_scoped(body);
+        layout.declare_vars_in_block(body);
+        layout
+    }
+
+    /// The scope created for a block, which holds its lexical and function
+    /// declarations.
+    pub(super) fn for_block(block: &Block) -> Self {
+        let mut layout = Self::default();
+        layout.declare_block_scoped(block);
+        layout
+    }
+
+    /// The scope created for the body of an `if` statement or a loop, which
+    /// may be a single statement rather than a block.
+    pub(super) fn for_statement(node: &Statement) -> Self {
+        let mut layout = Self::default();
+        match node {
+            Statement::Block(node) => layout.declare_block_scoped(&node.block),
+            node => layout.declare_statements(slice::from_ref(node)),
+        }
+        layout
+    }
+
+    /// The scope created for a `let` or `const` declared in the initialiser of
+    /// a `for` loop.
+    pub(super) fn for_lexical_declaration(decl: &LexicalDeclaration) -> Self {
+        let mut layout = Self::default();
+        layout.declare_lexical(decl);
+        layout
+    }
+
+    /// A scope with nothing but `name` in it, e.g. the exception of a `catch`
+    /// block, or the name of a named function expression.
+    pub(super) fn for_binding(name: &Identifier, kind: VariableKind) -> Self {
+        let mut layout = Self::default();
+        layout.declare(name, kind, InitialValue::Uninitialised);
+        layout
+    }
+
+    /// A name which is declared more than once in the same scope (e.g. a `var`
+    /// with the same name as a parameter) shares the first slot.
+    fn declare(&mut self, name: &Identifier, kind: VariableKind, initial_value: InitialValue) {
+        if self.slot(name).is_none() {
+            self.bindings.push(Binding {
+                name: Rc::new(name.clone()),
+                kind,
+                initial_value,
+            });
+        }
+    }
+
+    fn declare_block_scoped(&mut self, block: &Block) {
+        for decl in block.hoisted_declarations() {
+            if let Declaration::Function(node) = decl {
+                self.declare(&node.binding, VariableKind::Var, InitialValue::Undefined);
+            }
+        }
+        self.declare_statements(block.body());
+    }
+
+    /// Declarations which are evaluated in the current scope, including those
+    /// in the cases of a `switch` statement, which don't get a scope of their
+    /// own.
+    fn declare_statements(&mut self, body: &[Statement]) {
+        for node in body {
+            match node {
+                Statement::Declaration(Declaration::Lexical(decl)) => self.declare_lexical(decl),
+                Statement::Declaration(Declaration::Function(node)) => {
+                    self.declare(&node.binding, VariableKind::Var, InitialValue::Undefined);
+                }
+                Statement::Switch(node) => {
+                    for case in &node.cases {
+                        self.declare_statements(&case.body);
+                    }
+                    if let Some(ref default_case) = node.default_case {
+                        self.declare_statements(&default_case.body);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn declare_lexical(&mut self, decl: &LexicalDeclaration) {
+        let kind = VariableKind::from(decl.kind);
+        for entry in &decl.bindings {
+            self.declare(&entry.identifier, kind, InitialValue::Uninitialised);
+        }
+    }
+
+    fn declare_vars_in_block(&mut self, block: &Block) {
+        for decl in block.hoisted_declarations() {
+            if let Declaration::Variable(node) = decl {
+                self.declare_vars(node);
+            }
+        }
+        for node in block.body() {
+            self.declare_vars_in(node);
+        }
+    }
+
+    fn declare_vars_in(&mut self, node: &Statement) {
+        match node {
+            Statement::Declaration(Declaration::Variable(node)) => self.declare_vars(node),
+            Statement::Block(node) => self.declare_vars_in_block(&node.block),
+            Statement::If(node) => {
+                self.declare_vars_in(&node.body);
+                if let Some(ref else_body) = node.else_body {
+                    self.declare_vars_in(else_body);
+                }
+            }
+            Statement::Switch(node) => {
+                let default_body = node.default_case.iter().map(|case| &case.body);
+                for body in node.cases.iter().map(|case| &case.body).chain(default_body) {
+                    for node in body {
+                        self.declare_vars_in(node);
+                    }
+                }
+            }
+            Statement::Try(node) => {
+                self.declare_vars_in_block(&node.body);
+                if let Some(ref catch) = node.catch {
+                    self.declare_vars_in_block(&catch.body);
+                }
+                if let Some(ref finally) = node.finally {
+                    self.declare_vars_in_block(&finally.body);
+                }
+            }
+            Statement::Do(node) => self.declare_vars_in(&node.body),
+            Statement::For(node) => {
+                if let Some(ForInitialiser::VariableDeclaration(ref decl)) = node.initialiser {
+                    self.declare_vars(decl);
+                }
+                self.declare_vars_in(&node.body);
+            }
+            Statement::While(node) => self.declare_vars_in(&node.body),
+            _ => {}
+        }
+    }
+
+    fn declare_vars(&mut self, decl: &VariableDeclaration) {
+        for entry in &decl.bindings {
+            self.declare(
+                &entry.identifier,
+                VariableKind::Var,
+                InitialValue::Undefined,
+            );
+        }
+    }
+}
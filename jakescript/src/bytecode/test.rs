@@ -0,0 +1,112 @@
+use super::*;
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+use crate::token::SourceLocation;
+use std::mem;
+
+fn compile_str(source_code: &str) -> CompiledScript {
+    let lexer = Lexer::for_str(source_code, SourceLocation::at_start_of("untitled"));
+    let ast = Parser::for_lexer(lexer).execute().unwrap();
+    compile(&ast).unwrap()
+}
+
+#[test]
+fn instructions_are_eight_bytes() {
+    assert_eq!(mem::size_of::<Instruction>(), 8);
+}
+
+#[test]
+fn disassemble_expression() {
+    let script = compile_str("let a = 1 + 2;");
+    assert_eq!(
+        script.to_string(),
+        "\
+<script> (registers: 3)
+     0  load_undefined        r0                        ; 1:1
+     1  declare_lexical       let, `a`                  ; 1:5
+     2  safe_point                                      ; 1:1
+     3  load_int              r1, 1                     ; 1:9
+     4  load_int              r2, 2                     ; 1:13
+     5  add                   r1, r1, r2                ; 1:11
+     6  initialise_lexical    let, r1, `a`              ; 1:5
+     7  load_undefined        r0                        ; 1:1
+     8  return                r0
+"
+    );
+}
+
+#[test]
+fn disassemble_nested_function() {
+    let script = compile_str("function f(x) { return x; }");
+    let listing = script.to_string();
+    assert!(
+        listing.contains("make_function         r1, #0"),
+        "{listing}"
+    );
+    assert!(
+        listing.contains("\n#0 (x):\nfunction f (registers: "),
+        "{listing}"
+    );
+    assert!(
+        listing.contains("get_local             r0, 0:0"),
+        "{listing}"
+    );
+}
+
+#[test]
+fn resolve_local_variables() {
+    let script = compile_str(
+        r#"
+let g = 1;
+function f(a) {
+    let b = a;
+    {
+        const c = g;
+        return function () { return a + b + c; };
+    }
+}
+"#,
+    );
+    let listing = script.to_string();
+    // Globals are looked up by name.
+    assert!(
+        listing.contains("get_variable          r0, `g`"),
+        "{listing}"
+    );
+    // The inner function's own scope is empty, and `c` is in the block which
+    // encloses it, inside of the scope of `f`.
+    assert!(listing.contains("push_scope            [`c`]"), "{listing}");
+    assert!(
+        listing.contains("get_local             r0, 2:0"),
+        "{listing}"
+    );
+    assert!(
+        listing.contains("get_local             r1, 1:0"),
+        "{listing}"
+    );
+}
+
+#[test]
+fn empty_scopes_are_skipped() {
+    let script = compile_str("function f() { if (true) { f(); } for (;;) { break; } }");
+    let listing = script.to_string();
+    assert!(!listing.contains("push_scope"), "{listing}");
+}
+
+#[test]
+fn names_and_constants_are_deduplicated() {
+    let script = compile_str(r#"let s = "abc"; s = "abc"; s = s + "abc";"#);
+    let listing = script.to_string();
+    assert_eq!(listing.matches("load_constant").count(), 3, "{listing}");
+    assert_eq!(listing.matches(r#""abc""#).count(), 3, "{listing}");
+}
+
+#[test]
+fn too_many_registers() {
+    let args = vec!["0"; usize::from(u16::MAX)].join(", ");
+    let source_code = format!("f({args});");
+    let lexer = Lexer::for_str(&source_code, SourceLocation::at_start_of("untitled"));
+    let ast = Parser::for_lexer(lexer).execute().unwrap();
+    let err = compile(&ast).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TooManyRegisters);
+}
@@ -0,0 +1,248 @@
+use super::heap::{Trace, Tracer};
+use super::object::{Property, PropertyKey};
+use super::value::{Number, Value};
+use crate::ast::Identifier;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// The elements of an array (i.e. its properties whose keys are array
+/// indices) and its `length`, which are stored apart from its other
+/// properties.
+///
+/// Elements are stored densely, as a plain `Vec` of values, for as long as
+/// there are no holes before the last element and every element has the
+/// attributes of one created by assignment. Otherwise they're stored sparsely,
+/// as a map from index to property, which the array never switches back from.
+/// Either way, the `length` may be greater than the index of the last element,
+/// in which case the indices after it are holes.
+pub struct ArrayElements {
+    storage: Storage,
+    length: u32,
+}
+
+enum Storage {
+    Dense(Vec<Value>),
+    Sparse(BTreeMap<u32, Property>),
+}
+
+impl ArrayElements {
+    /// # Panics
+    ///
+    /// Panics if there are more elements than an array can have.
+    pub fn new(elems: Vec<Value>) -> Self {
+        let length = u32::try_from(elems.len()).expect("too many elements for an array");
+        Self {
+            storage: Storage::Dense(elems),
+            length,
+        }
+    }
+
+    /// The value of the array's `length` property.
+    pub fn len(&self) -> u32 {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// The value of the array's `length` property, as a [`Number`].
+    pub fn len_value(&self) -> Value {
+        Value::Number(Number::from(i64::from(self.length)))
+    }
+
+    pub fn is_dense(&self) -> bool {
+        matches!(self.storage, Storage::Dense(_))
+    }
+
+    /// The element at `idx`, if the elements are stored densely and it isn't
+    /// a hole.
+    pub fn dense(&self, idx: usize) -> Option<&Value> {
+        match self.storage {
+            Storage::Dense(ref elems) => elems.get(idx),
+            Storage::Sparse(_) => None,
+        }
+    }
+
+    /// Overwrite the element at `idx` if the elements are stored densely and
+    /// it isn't a hole, or hand `value` back otherwise.
+    pub fn set_dense(&mut self, idx: usize, value: Value) -> Result<(), Value> {
+        if let Storage::Dense(ref mut elems) = self.storage
+            && let Some(elem) = elems.get_mut(idx)
+        {
+            *elem = value;
+            Ok(())
+        } else {
+            Err(value)
+        }
+    }
+
+    /// The element at `idx`, if the elements are stored sparsely and it isn't
+    /// a hole.
+    pub fn sparse(&self, idx: u32) -> Option<&Property> {
+        match self.storage {
+            Storage::Dense(_) => None,
+            Storage::Sparse(ref elems) => elems.get(&idx),
+        }
+    }
+
+    pub fn sparse_mut(&mut self, idx: u32) -> Option<&mut Property> {
+        match self.storage {
+            Storage::Dense(_) => None,
+            Storage::Sparse(ref mut elems) => elems.get_mut(&idx),
+        }
+    }
+
+    pub fn contains(&self, idx: u32) -> bool {
+        match self.storage {
+            Storage::Dense(ref elems) => (idx as usize) < elems.len(),
+            Storage::Sparse(ref elems) => elems.contains_key(&idx),
+        }
+    }
+
+    /// The indices which aren't holes, in ascending order.
+    pub fn indices(&self) -> impl Iterator<Item = u32> + '_ {
+        let (dense, sparse) = match self.storage {
+            Storage::Dense(ref elems) => (0..u32::try_from(elems.len()).unwrap(), None),
+            Storage::Sparse(ref elems) => (0..0, Some(elems.keys().copied())),
+        };
+        dense.chain(sparse.into_iter().flatten())
+    }
+
+    /// Add or replace the element at `idx`, extending the length past it if
+    /// necessary.
+    pub(super) fn define(&mut self, idx: u32, prop: Property) {
+        debug_assert_ne!(idx, u32::MAX);
+        let sparse_prop = match self.storage {
+            Storage::Dense(ref mut elems) => match prop.into_element_value() {
+                Ok(value) if (idx as usize) < elems.len() => {
+                    elems[idx as usize] = value;
+                    None
+                }
+                Ok(value) if idx as usize == elems.len() => {
+                    elems.push(value);
+                    None
+                }
+                // Leaving a hole, or an element with different attributes.
+                Ok(value) => Some(Property::new_enumerable(value)),
+                Err(prop) => Some(prop),
+            },
+            Storage::Sparse(_) => Some(prop),
+        };
+        if let Some(prop) = sparse_prop {
+            self.make_sparse().insert(idx, prop);
+        }
+        self.length = self.length.max(idx + 1);
+    }
+
+    /// Remove the element at `idx`, leaving a hole in its place. Returns
+    /// `false` if the element isn't configurable.
+    pub(super) fn delete(&mut self, idx: u32) -> bool {
+        match self.storage {
+            Storage::Dense(ref mut elems) if idx as usize + 1 == elems.len() => {
+                elems.pop();
+                true
+            }
+            Storage::Dense(ref elems) if idx as usize >= elems.len() => true,
+            _ => {
+                let elems = self.make_sparse();
+                if elems.get(&idx).is_some_and(|prop| !prop.deletable()) {
+                    return false;
+                }
+                elems.remove(&idx);
+                true
+            }
+        }
+    }
+
+    /// Set the length, deleting any elements at or after the new length, as in
+    /// [ArraySetLength](https://tc39.es/ecma262/#sec-arraysetlength). If an
+    /// element can't be deleted then the length is only reduced to just after
+    /// it, and `false` is returned.
+    pub(super) fn set_len(&mut self, length: u32) -> bool {
+        let deleted_all = match self.storage {
+            Storage::Dense(ref mut elems) => {
+                elems.truncate(length as usize);
+                true
+            }
+            Storage::Sparse(ref mut elems) => {
+                let removed = elems.split_off(&length);
+                let kept = removed
+                    .iter()
+                    .rev()
+                    .find(|(_, prop)| !prop.deletable())
+                    .map(|(&idx, _)| idx);
+                for (idx, prop) in removed {
+                    if kept.is_some_and(|kept| idx <= kept) {
+                        elems.insert(idx, prop);
+                    }
+                }
+                match kept {
+                    Some(kept) => {
+                        self.length = kept + 1;
+                        return false;
+                    }
+                    None => true,
+                }
+            }
+        };
+        self.length = length;
+        deleted_all
+    }
+
+    fn make_sparse(&mut self) -> &mut BTreeMap<u32, Property> {
+        if let Storage::Dense(ref mut elems) = self.storage {
+            let elems = (0..)
+                .zip(elems.drain(..))
+                .map(|(idx, value)| (idx, Property::new_enumerable(value)))
+                .collect();
+            self.storage = Storage::Sparse(elems);
+        }
+        match self.storage {
+            Storage::Dense(_) => unreachable!(),
+            Storage::Sparse(ref mut elems) => elems,
+        }
+    }
+}
+
+impl Trace for ArrayElements {
+    fn trace(&self, tracer: &mut Tracer) {
+        match self.storage {
+            Storage::Dense(ref elems) => {
+                for elem in elems {
+                    elem.trace(tracer);
+                }
+            }
+            Storage::Sparse(ref elems) => {
+                for prop in elems.values() {
+                    prop.trace(tracer);
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for ArrayElements {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ArrayElements")
+            .field("length", &self.length)
+            .field("dense", &self.is_dense())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The array index which `key` is the canonical string representation of, if
+/// any. Array indices are the integers from 0 up to, but not including,
+/// 2<sup>32</sup> - 1.
+pub fn array_index(key: &PropertyKey) -> Option<u32> {
+    let PropertyKey::Identifier(Identifier::Custom(ref s)) = *key else {
+        return None;
+    };
+    match s.as_bytes() {
+        [b'0'] => Some(0),
+        [b'1'..=b'9', rest @ ..] if rest.iter().all(u8::is_ascii_digit) => {
+            s.parse().ok().filter(|&idx| idx != u32::MAX)
+        }
+        _ => None,
+    }
+}
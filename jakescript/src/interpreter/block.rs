@@ -8,6 +8,7 @@ impl Eval for Script {
     type Output = Value;
 
     fn eval(&self, it: &mut Interpreter) -> Result<Self::Output> {
+        it.vm_mut().stack_mut().set_strict(self.is_strict());
         self.body().eval(it)
     }
 }
@@ -17,6 +18,11 @@ impl Eval for Block {
 
     fn eval(&self, it: &mut Interpreter) -> Result<Self::Output> {
         let mut result = Value::default();
+        for node in self.body() {
+            if let Statement::Declaration(Declaration::Lexical(decl)) = node {
+                decl.declare_uninitialised(it)?;
+            }
+        }
         for decl in self.hoisted_declarations() {
             if !matches!(it.vm().execution_state(), ExecutionState::Advance) {
                 break;
@@ -24,6 +30,7 @@ impl Eval for Block {
             assert!(decl.is_hoisted());
             decl.eval(it)?;
         }
+        let temporary_count = it.vm().stack().temporary_count();
         for node in self.body() {
             if let Statement::Declaration(decl) = node {
                 assert!(!decl.is_hoisted());
@@ -31,11 +38,21 @@ impl Eval for Block {
             if !matches!(it.vm().execution_state(), ExecutionState::Advance) {
                 break;
             }
-            result = match node {
-                Statement::Expression(expr) => expr.eval(it),
-                node => node.eval(it).map(|()| Value::default()),
-            }?;
+            // Nothing evaluated by previous statements is still needed.
+            it.vm_mut()
+                .stack_mut()
+                .truncate_temporaries(temporary_count);
+            it.vm_mut().safe_point();
+            result = it
+                .eval_or_throw(|it| match node {
+                    Statement::Expression(expr) => expr.eval(it),
+                    node => node.eval(it).map(|()| Value::default()),
+                })?
+                .unwrap_or_default();
         }
+        it.vm_mut()
+            .stack_mut()
+            .truncate_temporaries(temporary_count);
         Ok(result)
     }
 }
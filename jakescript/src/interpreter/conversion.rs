@@ -0,0 +1,333 @@
+//! The type conversion and comparison abstract operations, as described in
+//! [Abstract Operations](https://tc39.es/ecma262/#sec-abstract-operations).
+//!
+//! Converting an object may call back into user code (e.g. a `valueOf` method),
+//! so these operations are fallible and may leave an exception thrown.
+
+use super::error::{ErrorKind, NotCallableError, NotConvertibleError};
+use super::heap::Reference;
+use super::object::PropertyKey;
+use super::string::JsString;
+use super::value::{Number, Value};
+use super::{ExecutionState, Interpreter};
+use crate::ast::WellKnownSymbol;
+use crate::prop_key;
+
+/// The type which [`Interpreter::to_primitive()`] should prefer to convert an
+/// object to, which is passed to `@@toPrimitive` as a _hint_.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PreferredType {
+    Default,
+    Number,
+    String,
+}
+
+impl PreferredType {
+    fn hint(self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::Number => "number",
+            Self::String => "string",
+        }
+    }
+}
+
+/// The language types of
+/// [ECMAScript Language Types](https://tc39.es/ecma262/#sec-ecmascript-language-types),
+/// which don't quite match the variants of [`Value`] as symbols are allocated
+/// on the heap.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Type {
+    Undefined,
+    Null,
+    Boolean,
+    Number,
+    String,
+    Symbol,
+    Object,
+}
+
+impl Interpreter {
+    /// [ToPrimitive](https://tc39.es/ecma262/#sec-toprimitive)
+    pub fn to_primitive(
+        &mut self,
+        input: Value,
+        preferred_type: PreferredType,
+    ) -> Result<Value, ErrorKind> {
+        let Value::Object(input_ref) = input else {
+            return Ok(input);
+        };
+        if self.is_primitive(&input) {
+            return Ok(input);
+        }
+        let to_primitive = PropertyKey::from(WellKnownSymbol::ToPrimitive);
+        if let Some(exotic_to_prim) = self.get_method(input_ref, &to_primitive)? {
+            let hint = Value::String(self.vm_mut().intern_string(preferred_type.hint()));
+            let result = self.call_method(exotic_to_prim, input_ref, &[hint])?;
+            return if self.is_primitive(&result) || self.is_exception_thrown() {
+                Ok(result)
+            } else {
+                Err(ErrorKind::from(NotConvertibleError::new(
+                    "`Symbol.toPrimitive` returned an object".to_owned(),
+                )))
+            };
+        }
+        self.ordinary_to_primitive(
+            input_ref,
+            match preferred_type {
+                PreferredType::String => PreferredType::String,
+                PreferredType::Default | PreferredType::Number => PreferredType::Number,
+            },
+        )
+    }
+
+    /// [OrdinaryToPrimitive](https://tc39.es/ecma262/#sec-ordinarytoprimitive)
+    pub fn ordinary_to_primitive(
+        &mut self,
+        obj_ref: Reference,
+        hint: PreferredType,
+    ) -> Result<Value, ErrorKind> {
+        let method_names = match hint {
+            PreferredType::String => [prop_key!("toString"), prop_key!("valueOf")],
+            PreferredType::Default | PreferredType::Number => {
+                [prop_key!("valueOf"), prop_key!("toString")]
+            }
+        };
+        for method_name in method_names {
+            let method = self.get(obj_ref, &method_name)?;
+            if let Value::Object(method_ref) = method
+                && self.is_callable(&method)
+            {
+                let result = self.call_method(method_ref, obj_ref, &[])?;
+                if self.is_primitive(&result) || self.is_exception_thrown() {
+                    return Ok(result);
+                }
+            }
+        }
+        Err(ErrorKind::from(NotConvertibleError::new(
+            "cannot convert object to primitive value".to_owned(),
+        )))
+    }
+
+    /// [ToNumber](https://tc39.es/ecma262/#sec-tonumber)
+    pub fn to_number(&mut self, v: Value) -> Result<Number, ErrorKind> {
+        Ok(match self.type_of_value(&v) {
+            Type::Undefined => Number::NAN,
+            Type::Null => Number::ZERO,
+            Type::Boolean => Number::Int(i64::from(self.coerce_to_bool(&v))),
+            Type::Number => match v {
+                Value::Number(n) => n,
+                _ => unreachable!(),
+            },
+            Type::String => match v {
+                Value::String(ref s) => Number::from(s),
+                _ => unreachable!(),
+            },
+            Type::Symbol => {
+                return Err(ErrorKind::from(NotConvertibleError::new(
+                    "cannot convert a symbol to a number".to_owned(),
+                )))
+            }
+            Type::Object => {
+                let prim_value = self.to_primitive(v, PreferredType::Number)?;
+                self.to_number(prim_value)?
+            }
+        })
+    }
+
+    /// [ToString](https://tc39.es/ecma262/#sec-tostring)
+    pub fn to_string(&mut self, v: Value) -> Result<JsString, ErrorKind> {
+        Ok(match self.type_of_value(&v) {
+            Type::Undefined => self.vm_mut().intern_string("undefined"),
+            Type::Null => self.vm_mut().intern_string("null"),
+            Type::Boolean => {
+                let s = if self.coerce_to_bool(&v) {
+                    "true"
+                } else {
+                    "false"
+                };
+                self.vm_mut().intern_string(s)
+            }
+            Type::Number => match v {
+                Value::Number(n) => JsString::from(n.to_string()),
+                _ => unreachable!(),
+            },
+            Type::String => match v {
+                Value::String(s) => s,
+                _ => unreachable!(),
+            },
+            Type::Symbol => {
+                return Err(ErrorKind::from(NotConvertibleError::new(
+                    "cannot convert a symbol to a string".to_owned(),
+                )))
+            }
+            Type::Object => {
+                let prim_value = self.to_primitive(v, PreferredType::String)?;
+                self.to_string(prim_value)?
+            }
+        })
+    }
+
+    /// [ToPropertyKey](https://tc39.es/ecma262/#sec-topropertykey)
+    pub fn to_property_key(&mut self, v: Value) -> Result<PropertyKey, ErrorKind> {
+        let key = self.to_primitive(v, PreferredType::String)?;
+        if let Value::Object(key_ref) = key {
+            let key_obj = self.vm().heap().resolve(key_ref);
+            let symbol = key_obj.as_ref().symbol_data();
+            if let Some(symbol) = symbol {
+                return Ok(PropertyKey::from(symbol));
+            }
+        }
+        Ok(PropertyKey::from(&self.to_string(key)?))
+    }
+
+    /// [IsLooselyEqual](https://tc39.es/ecma262/#sec-islooselyequal), i.e. the
+    /// `==` operator.
+    pub fn is_loosely_equal(&mut self, x: Value, y: Value) -> Result<bool, ErrorKind> {
+        let x_type = self.type_of_value(&x);
+        let y_type = self.type_of_value(&y);
+        if x_type == y_type {
+            return Ok(self.is_strictly_equal(&x, &y));
+        }
+        Ok(match (x_type, y_type) {
+            (Type::Null, Type::Undefined) | (Type::Undefined, Type::Null) => true,
+            (Type::Number, Type::String) => {
+                let y = self.to_number(y)?;
+                self.is_strictly_equal(&x, &Value::Number(y))
+            }
+            (Type::String, Type::Number) => {
+                let x = self.to_number(x)?;
+                self.is_strictly_equal(&Value::Number(x), &y)
+            }
+            (Type::Boolean, _) => {
+                let x = self.to_number(x)?;
+                self.is_loosely_equal(Value::Number(x), y)?
+            }
+            (_, Type::Boolean) => {
+                let y = self.to_number(y)?;
+                self.is_loosely_equal(x, Value::Number(y))?
+            }
+            (Type::Number | Type::String | Type::Symbol, Type::Object) => {
+                let y = self.to_primitive(y, PreferredType::Default)?;
+                self.is_loosely_equal(x, y)?
+            }
+            (Type::Object, Type::Number | Type::String | Type::Symbol) => {
+                let x = self.to_primitive(x, PreferredType::Default)?;
+                self.is_loosely_equal(x, y)?
+            }
+            (_, _) => false,
+        })
+    }
+
+    /// [IsStrictlyEqual](https://tc39.es/ecma262/#sec-isstrictlyequal), i.e.
+    /// the `===` operator.
+    pub fn is_strictly_equal(&self, x: &Value, y: &Value) -> bool {
+        match (x, y) {
+            (Value::Boolean(x), Value::Boolean(y)) => x == y,
+            (Value::Number(x), Value::Number(y)) => x == y,
+            // Interned strings can usually be compared by pointer alone.
+            (Value::String(x), Value::String(y)) => x.ptr_eq(y) || x == y,
+            (Value::Object(x_ref), Value::Object(y_ref)) => x_ref == y_ref,
+            (Value::Null, Value::Null) | (Value::Undefined, Value::Undefined) => true,
+            (_, _) => false,
+        }
+    }
+
+    /// [IsLessThan](https://tc39.es/ecma262/#sec-islessthan), which returns
+    /// `None` (i.e. _undefined_) if either operand is `NaN`.
+    ///
+    /// `left_first` controls the order in which the operands are converted to
+    /// primitives, as that may run user code with side effects.
+    pub fn is_less_than(
+        &mut self,
+        x: Value,
+        y: Value,
+        left_first: bool,
+    ) -> Result<Option<bool>, ErrorKind> {
+        let (px, py) = if left_first {
+            let px = self.to_primitive(x, PreferredType::Number)?;
+            let py = self.to_primitive(y, PreferredType::Number)?;
+            (px, py)
+        } else {
+            let py = self.to_primitive(y, PreferredType::Number)?;
+            let px = self.to_primitive(x, PreferredType::Number)?;
+            (px, py)
+        };
+        if let (Value::String(px), Value::String(py)) = (&px, &py) {
+            // Strings are ordered by their UTF-16 code units, not by code points.
+            return Ok(Some(px < py));
+        }
+        let nx = self.to_number(px)?;
+        let ny = self.to_number(py)?;
+        Ok(nx.partial_cmp(&ny).map(|ord| ord.is_lt()))
+    }
+
+    pub fn is_callable(&self, v: &Value) -> bool {
+        match *v {
+            Value::Object(obj_ref) => {
+                let obj = self.vm().heap().resolve(obj_ref);
+                let callable = obj.as_ref().call_data().is_some();
+                callable
+            }
+            _ => false,
+        }
+    }
+
+    fn is_primitive(&self, v: &Value) -> bool {
+        !matches!(self.type_of_value(v), Type::Object)
+    }
+
+    fn is_exception_thrown(&self) -> bool {
+        matches!(self.vm().execution_state(), ExecutionState::Exception(_))
+    }
+
+    fn type_of_value(&self, v: &Value) -> Type {
+        match *v {
+            Value::Boolean(_) => Type::Boolean,
+            Value::Number(_) => Type::Number,
+            Value::String(_) => Type::String,
+            Value::Object(obj_ref) => {
+                let obj = self.vm().heap().resolve(obj_ref);
+                if obj.as_ref().symbol_data().is_some() {
+                    Type::Symbol
+                } else {
+                    Type::Object
+                }
+            }
+            Value::Null => Type::Null,
+            Value::Undefined => Type::Undefined,
+        }
+    }
+
+    fn get(&mut self, obj_ref: Reference, key: &PropertyKey) -> Result<Value, ErrorKind> {
+        let obj = self.vm().heap().resolve(obj_ref);
+        let value = obj.as_ref().get(self, key, obj_ref)?.unwrap_or_default();
+        Ok(value)
+    }
+
+    /// [GetMethod](https://tc39.es/ecma262/#sec-getmethod)
+    fn get_method(
+        &mut self,
+        obj_ref: Reference,
+        key: &PropertyKey,
+    ) -> Result<Option<Reference>, ErrorKind> {
+        match self.get(obj_ref, key)? {
+            Value::Null | Value::Undefined => Ok(None),
+            ref func @ Value::Object(func_ref) if self.is_callable(func) => Ok(Some(func_ref)),
+            _ => Err(ErrorKind::from(NotCallableError::new())),
+        }
+    }
+
+    fn call_method(
+        &mut self,
+        func_ref: Reference,
+        receiver: Reference,
+        args: &[Value],
+    ) -> Result<Value, ErrorKind> {
+        let func = self.vm().heap().resolve(func_ref);
+        let result = func
+            .as_ref()
+            .call(self, func_ref, Value::Object(receiver), args)?;
+        Ok(result)
+    }
+}
@@ -1,5 +1,5 @@
 use super::error::{Error, Result};
-use super::object::UserFunction;
+use super::object::{FunctionBody, UserFunction};
 use super::stack::{Variable, VariableKind};
 use super::value::Value;
 use super::{Eval, Interpreter};
@@ -21,10 +21,12 @@ impl Eval for FunctionDeclaration {
         let fn_obj_ref = it
             .vm_mut()
             .alloc_function(UserFunction::new(
-                None,
+                Some(self.binding.clone()),
+                false,
                 declared_scope,
                 self.parameters.clone(),
-                self.body.clone(),
+                FunctionBody::Ast(self.body.clone()),
+                self.strict,
             ))
             .map_err(|err| Error::new(err, self.source_location()))?;
         let variable = Variable::new(
@@ -52,13 +54,29 @@ impl Eval for LexicalDeclaration {
             };
             it.vm_mut()
                 .stack_mut()
-                .declare_variable(variable)
+                .initialise_variable(variable)
                 .map_err(|err| Error::new(err, self.source_location()))?;
         }
         Ok(())
     }
 }
 
+impl LexicalDeclaration {
+    /// Declare each binding at the top of its block, before the declaration
+    /// itself is evaluated to initialise them.
+    pub(super) fn declare_uninitialised(&self, it: &mut Interpreter) -> Result<()> {
+        let kind = VariableKind::from(self.kind);
+        for entry in &self.bindings {
+            let variable = Variable::new_uninitialised(kind, entry.identifier.clone());
+            it.vm_mut()
+                .stack_mut()
+                .declare_variable(variable)
+                .map_err(|err| Error::new(err, entry.source_location()))?;
+        }
+        Ok(())
+    }
+}
+
 impl Eval for VariableDeclaration {
     fn eval(&self, it: &mut Interpreter) -> Result<Self::Output> {
         for entry in &self.bindings {
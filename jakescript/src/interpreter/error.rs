@@ -1,3 +1,4 @@
+use super::object::PropertyKey;
 use super::value::Value;
 use crate::ast::{Identifier, SourceLocation};
 use std::fmt;
@@ -110,8 +111,8 @@ impl fmt::Display for Error {
     }
 }
 
-// TODO: Most of these variants should be thrown as exceptions within the
-// interpreter, and shouldn't cause it to exit.
+// Most of these variants are thrown as catchable exceptions within the
+// interpreter. See `ErrorKind::native_error_kind()`.
 error_kinds!(pub enum ErrorKind {
     Assertion(pub struct AssertionError {
         pub detail_msg: String,
@@ -128,20 +129,51 @@ error_kinds!(pub enum ErrorKind {
     VariableNotDefined(pub struct VariableNotDefinedError {
         pub name: Identifier,
     }) => "variable not defined",
+    VariableNotInitialised(pub struct VariableNotInitialisedError {
+        pub name: Identifier,
+    }) => "variable not initialised",
 
     FunctionNotDefined(pub struct FunctionNotDefinedError {
         pub name: Identifier,
     }) => "function not defined",
     NotCallable(#[derive(Default)] pub struct NotCallableError {
     }) => "object or primitive not callable",
+    NotIterable(#[derive(Default)] pub struct NotIterableError {
+    }) => "object or primitive not iterable",
+    IncompatibleReceiver(pub struct IncompatibleReceiverError {
+        pub detail_msg: String,
+    }) => "incompatible receiver",
+    OutOfRange(pub struct OutOfRangeError {
+        pub detail_msg: String,
+    }) => "value out of range",
+    NotConvertible(pub struct NotConvertibleError {
+        pub detail_msg: String,
+    }) => "value not convertible",
+
+    PropertyNotWritable(pub struct PropertyNotWritableError {
+        pub name: PropertyKey,
+    }) => "property not writable",
+    ObjectNotExtensible(pub struct ObjectNotExtensibleError {
+        pub name: PropertyKey,
+    }) => "object not extensible",
+    PropertyOfNullOrUndefined(pub struct PropertyOfNullOrUndefinedError {
+        pub name: PropertyKey,
+    }) => "property of null or undefined",
+
+    InvalidRegExp(pub struct InvalidRegExpError {
+        pub detail_msg: String,
+    }) => "invalid regular expression",
 
-    NumericOverflow(#[derive(Default)] pub struct NumericOverflowError {}) => "numeric overflow",
+    IncompatibleRegExp(pub struct IncompatibleRegExpError {
+        pub detail_msg: String,
+    }) => "incompatible regular expression",
 
     OutOfHeapSpace(#[derive(Default)] pub struct OutOfHeapSpaceError {}) => "out of heap space",
     OutOfStackSpace(#[derive(Default)] pub struct OutOfStackSpaceError {}) => "out of stack space",
 
-    /// Temporary hack to allow errors to be propagated outside of function calls while still
-    /// retaining source location information.
+    /// Allows errors which can't be caught by the script (e.g. assertion failures) to be
+    /// propagated outside of function calls while still retaining the source location where
+    /// they were raised. Exceptions record their call sites in `error.stack` instead.
     Boxed(pub struct BoxedError {
         inner: Box<Error>,
     }) => "function call",
@@ -151,6 +183,70 @@ impl ErrorKind {
     pub fn boxed(inner: Error) -> Self {
         Self::from(BoxedError::from(inner))
     }
+
+    /// The type of error object to throw for this error, or `None` if it's a
+    /// failure of the host which shouldn't be catchable by the script.
+    pub fn native_error_kind(&self) -> Option<NativeErrorKind> {
+        match self {
+            Self::AssignToConstVariable(_)
+            | Self::NotCallable(_)
+            | Self::NotIterable(_)
+            | Self::IncompatibleReceiver(_)
+            | Self::NotConvertible(_)
+            | Self::PropertyNotWritable(_)
+            | Self::ObjectNotExtensible(_)
+            | Self::PropertyOfNullOrUndefined(_)
+            | Self::IncompatibleRegExp(_) => Some(NativeErrorKind::Type),
+            Self::VariableNotDefined(_)
+            | Self::VariableNotInitialised(_)
+            | Self::FunctionNotDefined(_) => Some(NativeErrorKind::Reference),
+            Self::VariableAlreadyDefined(_) | Self::InvalidRegExp(_) => {
+                Some(NativeErrorKind::Syntax)
+            }
+            Self::OutOfRange(_) | Self::OutOfStackSpace(_) => Some(NativeErrorKind::Range),
+            Self::Assertion(_) | Self::OutOfHeapSpace(_) => None,
+            Self::Boxed(source) => source.inner().kind().native_error_kind(),
+        }
+    }
+
+    /// The message of the error object thrown for this error.
+    pub fn message(&self) -> String {
+        match self {
+            Self::Boxed(source) => source.inner().kind().message(),
+            kind => {
+                let detail_msg = format!("{kind:#}");
+                if detail_msg.is_empty() {
+                    kind.to_string()
+                } else {
+                    detail_msg
+                }
+            }
+        }
+    }
+}
+
+/// The types of error object which the interpreter itself may throw.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NativeErrorKind {
+    Eval,
+    Range,
+    Reference,
+    Syntax,
+    Type,
+    Uri,
+}
+
+impl NativeErrorKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Eval => "EvalError",
+            Self::Range => "RangeError",
+            Self::Reference => "ReferenceError",
+            Self::Syntax => "SyntaxError",
+            Self::Type => "TypeError",
+            Self::Uri => "URIError",
+        }
+    }
 }
 
 impl fmt::Display for AssertionError {
@@ -177,6 +273,16 @@ impl fmt::Display for VariableNotDefinedError {
     }
 }
 
+impl fmt::Display for VariableNotInitialisedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "`{}` cannot be accessed before initialisation",
+            self.name()
+        )
+    }
+}
+
 impl fmt::Display for FunctionNotDefinedError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "`{}` is not visible from the current scope", self.name())
@@ -189,9 +295,65 @@ impl fmt::Display for NotCallableError {
     }
 }
 
-impl fmt::Display for NumericOverflowError {
-    fn fmt(&self, _: &mut fmt::Formatter) -> fmt::Result {
-        Ok(())
+impl fmt::Display for NotIterableError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("object or primitive is not iterable")
+    }
+}
+
+impl fmt::Display for OutOfRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.detail_msg())
+    }
+}
+
+impl fmt::Display for NotConvertibleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.detail_msg())
+    }
+}
+
+impl fmt::Display for IncompatibleReceiverError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.detail_msg())
+    }
+}
+
+impl fmt::Display for PropertyNotWritableError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "`{}` is read-only", self.name())
+    }
+}
+
+impl fmt::Display for ObjectNotExtensibleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "`{}` can't be added because the object is not extensible",
+            self.name()
+        )
+    }
+}
+
+impl fmt::Display for PropertyOfNullOrUndefinedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "`{}` can't be accessed because the base is null or undefined",
+            self.name()
+        )
+    }
+}
+
+impl fmt::Display for InvalidRegExpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.detail_msg())
+    }
+}
+
+impl fmt::Display for IncompatibleRegExpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.detail_msg())
     }
 }
 
@@ -0,0 +1,546 @@
+use super::error::{Error, ErrorKind, InvalidRegExpError, NotCallableError, Result};
+use super::expression::{is_declared, numeric_binary_op};
+use super::object::{FunctionBody, Property, PropertyKey, UserFunction};
+use super::stack::{StackCheckpoint, Variable, VariableKind};
+use super::value::{Number, Value};
+use super::vm::ExecutionState;
+use super::{Eval, Interpreter};
+use crate::ast::{BinaryOperator, RelationalOperator};
+use crate::bytecode::{CodeBlock, CompiledScript, Instruction, Register};
+use crate::runtime::RegExp;
+use std::rc::Rc;
+
+impl Eval for CompiledScript {
+    type Output = Value;
+
+    fn eval(&self, it: &mut Interpreter) -> Result<Self::Output> {
+        it.vm_mut().stack_mut().set_strict(self.code().is_strict());
+        it.exec(self.code())
+    }
+}
+
+/// Where to resume if an exception is thrown within a try block.
+struct Handler {
+    target: usize,
+    exception: Register,
+    checkpoint: StackCheckpoint,
+    /// See [`Instruction::OnInterrupt`].
+    on_interrupt: Option<usize>,
+}
+
+enum Flow {
+    Next,
+    Jump(usize),
+    Return(Value),
+}
+
+impl Interpreter {
+    /// Run compiled code within the current frame, with a fresh set of
+    /// registers. Returns `Undefined` if the code doesn't return normally,
+    /// e.g. because of an uncaught exception, in which case the execution state
+    /// is left for the caller to handle.
+    pub(super) fn exec(&mut self, code: &CodeBlock) -> Result<Value> {
+        let checkpoint = self.vm().stack().checkpoint();
+        let base = self.vm().registers().len();
+        self.vm_mut()
+            .registers_mut()
+            .resize(base + code.register_count(), Value::Undefined);
+        let result = self.run(code, base);
+        // An exception hidden by a finally block which was exited abruptly is
+        // discarded, rather than rethrown by some unrelated finally block later.
+        if !matches!(self.vm().execution_state(), ExecutionState::Advance) {
+            self.vm_mut().restore_hidden_exception();
+        }
+        self.vm_mut().registers_mut().truncate(base);
+        self.vm_mut().stack_mut().unwind_to_checkpoint(checkpoint);
+        result
+    }
+
+    fn run(&mut self, code: &CodeBlock, base: usize) -> Result<Value> {
+        let mut handlers = Vec::new();
+        let mut pc = 0;
+        loop {
+            match self.step(code, base, pc, &mut handlers) {
+                Ok(Flow::Next) => pc += 1,
+                Ok(Flow::Jump(target)) => pc = target,
+                Ok(Flow::Return(value)) => return Ok(value),
+                Err(err) => {
+                    // Errors which can be caught are thrown as exceptions, and handled below.
+                    self.throw_error(Error::new(err, code.source_location(pc)))?;
+                }
+            }
+            match self.vm().execution_state() {
+                ExecutionState::Advance => {}
+                ExecutionState::Exception(_) => {
+                    let Some(handler) = handlers.pop() else {
+                        return Ok(Value::Undefined);
+                    };
+                    let Some(exception) = self.vm_mut().clear_exception() else {
+                        unreachable!()
+                    };
+                    self.vm_mut()
+                        .stack_mut()
+                        .unwind_to_checkpoint(handler.checkpoint);
+                    self.set_register(base, handler.exception, exception);
+                    pc = handler.target;
+                }
+                ExecutionState::Interrupt => {
+                    // Like the tree-walker, run the innermost finally block, which can't
+                    // catch the interrupt. Catch blocks, and the handlers of try
+                    // statements without a finally block, are skipped.
+                    let handler = loop {
+                        match handlers.pop() {
+                            Some(handler) if handler.on_interrupt.is_some() => break handler,
+                            Some(_) => {}
+                            None => return Ok(Value::Undefined),
+                        }
+                    };
+                    // Any exception hidden by a finally block being exited is discarded.
+                    self.vm_mut().restore_hidden_exception();
+                    self.vm_mut()
+                        .stack_mut()
+                        .unwind_to_checkpoint(handler.checkpoint);
+                    self.vm_mut().hide_current_exception();
+                    pc = handler.on_interrupt.unwrap();
+                }
+                ExecutionState::Exit => return Ok(Value::Undefined),
+                state @ (ExecutionState::Break
+                | ExecutionState::Continue
+                | ExecutionState::Return(_)) => {
+                    unreachable!("unexpected execution state: {state:?}")
+                }
+            }
+        }
+    }
+
+    fn register(&self, base: usize, register: Register) -> &Value {
+        &self.vm().registers()[base + register.index()]
+    }
+
+    fn set_register(&mut self, base: usize, register: Register, value: Value) {
+        self.vm_mut().registers_mut()[base + register.index()] = value;
+    }
+
+    /// The values of the `count` registers starting at index `start`.
+    fn registers(&self, base: usize, start: usize, count: usize) -> Vec<Value> {
+        let start = base + start;
+        self.vm().registers()[start..(start + count)].to_vec()
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn step(
+        &mut self,
+        code: &CodeBlock,
+        base: usize,
+        pc: usize,
+        handlers: &mut Vec<Handler>,
+    ) -> std::result::Result<Flow, ErrorKind> {
+        let set = |it: &mut Self, dst: Register, value: Value| it.set_register(base, dst, value);
+        match code.instructions()[pc] {
+            Instruction::LoadUndefined { dst } => set(self, dst, Value::Undefined),
+            Instruction::LoadNull { dst } => set(self, dst, Value::Null),
+            Instruction::LoadBoolean { dst, value } => set(self, dst, Value::Boolean(value)),
+            Instruction::LoadInt { dst, value } => {
+                set(self, dst, Value::Number(Number::from(i64::from(value))));
+            }
+            Instruction::LoadConstant { dst, constant } => {
+                set(self, dst, code.constant(constant).clone());
+            }
+            Instruction::LoadRegExp { dst, regexp } => {
+                let literal = code.regexp(regexp);
+                let regexp = RegExp::new(&literal.pattern, &literal.flags).map_err(|err| {
+                    InvalidRegExpError::new(format!(
+                        "/{}/{}: {err}",
+                        literal.pattern, literal.flags
+                    ))
+                })?;
+                let obj_ref = self.vm_mut().alloc_regexp(regexp)?;
+                set(self, dst, Value::Object(obj_ref));
+            }
+            Instruction::LoadThis { dst } => {
+                // TODO: In sloppy mode, primitive receivers should be wrapped in objects.
+                let this = match self.vm().stack().receiver() {
+                    Value::Null | Value::Undefined if !self.vm().stack().is_strict() => {
+                        Value::Object(self.vm().runtime().global_object_ref())
+                    }
+                    receiver => receiver,
+                };
+                set(self, dst, this);
+            }
+
+            Instruction::MakeFunction { dst, function } => {
+                let template = code.function(function);
+                let declared_scope = self.vm().stack().scope();
+                let fn_obj_ref = self.vm_mut().alloc_function(UserFunction::new(
+                    template.code().name().cloned(),
+                    template.binds_own_name(),
+                    declared_scope,
+                    template.parameters().to_vec(),
+                    FunctionBody::Bytecode(Rc::clone(template.code())),
+                    template.code().is_strict(),
+                ))?;
+                set(self, dst, Value::Object(fn_obj_ref));
+            }
+            Instruction::NewArray { dst, start, count } => {
+                let elems = self.registers(base, start.index(), usize::from(count));
+                let obj_ref = self.vm_mut().alloc_array(elems)?;
+                set(self, dst, Value::Object(obj_ref));
+            }
+            Instruction::NewObject { dst } => {
+                let obj_ref = self.vm_mut().alloc_object([])?;
+                set(self, dst, Value::Object(obj_ref));
+            }
+            Instruction::DefineProperty { obj, name, src } => {
+                let key = PropertyKey::from(code.name_at(name).clone());
+                self.define_property(base, obj, key, src);
+            }
+            Instruction::DefineComputedProperty { obj, key, src } => {
+                let key = self.to_property_key(self.register(base, key).clone())?;
+                self.define_property(base, obj, key, src);
+            }
+
+            Instruction::GetLocal { dst, depth, slot } => {
+                let variable = self
+                    .vm()
+                    .stack()
+                    .variable_at(usize::from(depth), usize::from(slot));
+                let value = variable.value()?;
+                set(self, dst, value);
+            }
+            Instruction::SetLocal { src, depth, slot } => {
+                let value = self.register(base, src).clone();
+                self.vm_mut()
+                    .stack_mut()
+                    .variable_at_mut(usize::from(depth), usize::from(slot))
+                    .set_value(value)?;
+            }
+            Instruction::InitialiseLocal { src, slot } => {
+                let value = self.register(base, src).clone();
+                self.vm_mut()
+                    .stack_mut()
+                    .variable_at_mut(0, usize::from(slot))
+                    .initialise(value);
+            }
+            Instruction::GetVariable { dst, name } => {
+                let value = self.get_binding_value(code.name_at(name))?;
+                set(self, dst, value);
+            }
+            Instruction::SetVariable { src, name } => {
+                let value = self.register(base, src).clone();
+                let strict = self.vm().stack().is_strict();
+                self.put_binding_value(code.name_at(name), value, strict)?;
+            }
+            Instruction::TypeOfVariable { dst, name } => {
+                let name = code.name_at(name);
+                // Referring to an undeclared variable isn't an error for `typeof`, but
+                // referring to one which hasn't been initialised yet still is.
+                let type_name = if is_declared(self, name) {
+                    let value = self.get_binding_value(name)?;
+                    self.type_of(&value)
+                } else {
+                    "undefined"
+                };
+                let type_name = self.vm_mut().intern_string(type_name);
+                set(self, dst, Value::String(type_name));
+            }
+            Instruction::DeclareVar { name } => {
+                let variable =
+                    Variable::new_unassigned(VariableKind::Var, code.name_at(name).clone());
+                self.vm_mut()
+                    .stack_mut()
+                    .declare_variable_within_escalation_boundary(variable)?;
+            }
+            Instruction::DeclareFunction { src, name } => {
+                let value = self.register(base, src).clone();
+                let variable = Variable::new(VariableKind::Var, code.name_at(name).clone(), value);
+                self.vm_mut().stack_mut().declare_variable(variable)?;
+            }
+            Instruction::DeclareLexical { kind, name } => {
+                let variable = Variable::new_uninitialised(kind, code.name_at(name).clone());
+                self.vm_mut().stack_mut().declare_variable(variable)?;
+            }
+            Instruction::InitialiseLexical { kind, src, name } => {
+                let value = self.register(base, src).clone();
+                let variable = Variable::new(kind, code.name_at(name).clone(), value);
+                self.vm_mut().stack_mut().initialise_variable(variable)?;
+            }
+            Instruction::PushScope { scope } => {
+                let variables = code.scope(scope).instantiate(&[]);
+                self.vm_mut().stack_mut().push_scope(false, variables)?;
+            }
+            Instruction::PopScope => self.vm_mut().stack_mut().pop_scope(),
+
+            Instruction::GetProperty { dst, obj, cache } => {
+                let obj = self.register(base, obj).clone();
+                let cache = code.property_cache(cache);
+                let value =
+                    self.get_cached_property_value(&obj, cache.key(), cache.inline_cache())?;
+                set(self, dst, value);
+            }
+            Instruction::SetProperty { obj, cache, src } => {
+                let obj = self.register(base, obj).clone();
+                let value = self.register(base, src).clone();
+                let strict = self.vm().stack().is_strict();
+                let cache = code.property_cache(cache);
+                self.put_cached_property_value(
+                    &obj,
+                    cache.key(),
+                    value,
+                    strict,
+                    cache.inline_cache(),
+                )?;
+            }
+            Instruction::GetComputedProperty { dst, obj, key } => {
+                let obj = self.register(base, obj).clone();
+                let key = self.register(base, key).clone();
+                let value = self.get_element_value(&obj, key)?;
+                set(self, dst, value);
+            }
+            Instruction::SetComputedProperty { obj, key, src } => {
+                let obj = self.register(base, obj).clone();
+                let key = self.register(base, key).clone();
+                let value = self.register(base, src).clone();
+                let strict = self.vm().stack().is_strict();
+                self.put_element_value(&obj, key, value, strict)?;
+            }
+
+            Instruction::Add { dst, lhs, rhs } => {
+                self.exec_binary_op(base, BinaryOperator::Addition, dst, lhs, rhs)?;
+            }
+            Instruction::Subtract { dst, lhs, rhs } => {
+                self.exec_binary_op(base, BinaryOperator::Subtraction, dst, lhs, rhs)?;
+            }
+            Instruction::Multiply { dst, lhs, rhs } => {
+                self.exec_binary_op(base, BinaryOperator::Multiplication, dst, lhs, rhs)?;
+            }
+            Instruction::Divide { dst, lhs, rhs } => {
+                self.exec_binary_op(base, BinaryOperator::Division, dst, lhs, rhs)?;
+            }
+            Instruction::Modulus { dst, lhs, rhs } => {
+                self.exec_binary_op(base, BinaryOperator::Modulus, dst, lhs, rhs)?;
+            }
+            Instruction::Exponentiate { dst, lhs, rhs } => {
+                self.exec_binary_op(base, BinaryOperator::Exponentiation, dst, lhs, rhs)?;
+            }
+            Instruction::BitwiseAnd { dst, lhs, rhs } => {
+                self.exec_binary_op(base, BinaryOperator::BitwiseAnd, dst, lhs, rhs)?;
+            }
+            Instruction::BitwiseOr { dst, lhs, rhs } => {
+                self.exec_binary_op(base, BinaryOperator::BitwiseOr, dst, lhs, rhs)?;
+            }
+            Instruction::BitwiseXOr { dst, lhs, rhs } => {
+                self.exec_binary_op(base, BinaryOperator::BitwiseXOr, dst, lhs, rhs)?;
+            }
+            Instruction::ShiftLeft { dst, lhs, rhs } => {
+                self.exec_binary_op(base, BinaryOperator::BitwiseLeftShift, dst, lhs, rhs)?;
+            }
+            Instruction::ShiftRight { dst, lhs, rhs } => {
+                self.exec_binary_op(base, BinaryOperator::BitwiseRightShift, dst, lhs, rhs)?;
+            }
+            Instruction::ShiftRightUnsigned { dst, lhs, rhs } => {
+                let op = BinaryOperator::BitwiseRightShiftUnsigned;
+                self.exec_binary_op(base, op, dst, lhs, rhs)?;
+            }
+
+            Instruction::Equal { dst, lhs, rhs } => {
+                self.exec_relational_op(base, RelationalOperator::Equality, dst, lhs, rhs)?;
+            }
+            Instruction::NotEqual { dst, lhs, rhs } => {
+                self.exec_relational_op(base, RelationalOperator::Inequality, dst, lhs, rhs)?;
+            }
+            Instruction::StrictEqual { dst, lhs, rhs } => {
+                let equal =
+                    self.is_strictly_equal(self.register(base, lhs), self.register(base, rhs));
+                set(self, dst, Value::Boolean(equal));
+            }
+            Instruction::StrictNotEqual { dst, lhs, rhs } => {
+                let equal =
+                    self.is_strictly_equal(self.register(base, lhs), self.register(base, rhs));
+                set(self, dst, Value::Boolean(!equal));
+            }
+            Instruction::LessThan { dst, lhs, rhs } => {
+                self.exec_relational_op(base, RelationalOperator::LessThan, dst, lhs, rhs)?;
+            }
+            Instruction::LessThanOrEqual { dst, lhs, rhs } => {
+                let op = RelationalOperator::LessThanOrEqual;
+                self.exec_relational_op(base, op, dst, lhs, rhs)?;
+            }
+            Instruction::GreaterThan { dst, lhs, rhs } => {
+                self.exec_relational_op(base, RelationalOperator::GreaterThan, dst, lhs, rhs)?;
+            }
+            Instruction::GreaterThanOrEqual { dst, lhs, rhs } => {
+                let op = RelationalOperator::GreaterThanOrEqual;
+                self.exec_relational_op(base, op, dst, lhs, rhs)?;
+            }
+
+            Instruction::ToNumber { dst, src } => {
+                let value = self.to_number(self.register(base, src).clone())?;
+                set(self, dst, Value::Number(value));
+            }
+            Instruction::Negate { dst, src } => {
+                let value = self.to_number(self.register(base, src).clone())?;
+                set(self, dst, Value::Number(-value));
+            }
+            Instruction::BitwiseNot { dst, src } => {
+                let value = self.to_number(self.register(base, src).clone())?;
+                set(self, dst, Value::Number(!value));
+            }
+            Instruction::LogicalNot { dst, src } => {
+                let value = self.coerce_to_bool(self.register(base, src));
+                set(self, dst, Value::Boolean(!value));
+            }
+            Instruction::TypeOf { dst, src } => {
+                let type_name = self.type_of(self.register(base, src));
+                let type_name = self.vm_mut().intern_string(type_name);
+                set(self, dst, Value::String(type_name));
+            }
+            Instruction::Increment { dst, src } => {
+                let value = self.to_number(self.register(base, src).clone())?;
+                set(self, dst, Value::Number(value + Number::ONE));
+            }
+            Instruction::Decrement { dst, src } => {
+                let value = self.to_number(self.register(base, src).clone())?;
+                set(self, dst, Value::Number(value - Number::ONE));
+            }
+
+            Instruction::Call { dst, callee, argc } => {
+                let Value::Object(fn_obj_ref) = *self.register(base, callee) else {
+                    return Err(ErrorKind::from(NotCallableError::new()));
+                };
+                let receiver = self.vm().registers()[base + callee.index() + 1].clone();
+                let args = self.registers(base, callee.index() + 2, usize::from(argc));
+                self.vm_mut()
+                    .stack_mut()
+                    .set_current_location(code.source_location(pc));
+                let fn_obj = self.vm().heap().resolve(fn_obj_ref);
+                let result = fn_obj.as_ref().call(self, fn_obj_ref, receiver, &args)?;
+                set(self, dst, result);
+            }
+            Instruction::New { dst, ctor, argc } => {
+                let Value::Object(ctor_ref) = *self.register(base, ctor) else {
+                    return Err(ErrorKind::from(NotCallableError::new()));
+                };
+                let args = self.registers(base, ctor.index() + 1, usize::from(argc));
+                self.vm_mut()
+                    .stack_mut()
+                    .set_current_location(code.source_location(pc));
+                let result = self.construct(ctor_ref, &args)?;
+                set(self, dst, result);
+            }
+
+            Instruction::Jump { target } => return Ok(Flow::Jump(target as usize)),
+            Instruction::JumpIfTrue { cond, target } => {
+                if self.is_truthy(self.register(base, cond)) {
+                    return Ok(Flow::Jump(target as usize));
+                }
+            }
+            Instruction::JumpIfFalse { cond, target } => {
+                if !self.is_truthy(self.register(base, cond)) {
+                    return Ok(Flow::Jump(target as usize));
+                }
+            }
+            Instruction::LoopHint => {
+                self.vm_mut().safe_point();
+                self.vm_mut().check_interrupt();
+            }
+            Instruction::SafePoint => self.vm_mut().safe_point(),
+            Instruction::EnterTry { exception, handler } => {
+                handlers.push(Handler {
+                    target: handler as usize,
+                    exception,
+                    checkpoint: self.vm().stack().checkpoint(),
+                    on_interrupt: None,
+                });
+            }
+            Instruction::OnInterrupt { target } => {
+                let handler = handlers.last_mut().expect("no try block entered");
+                handler.on_interrupt = Some(target as usize);
+            }
+            Instruction::LeaveTry => {
+                handlers.pop().expect("no try block to leave");
+            }
+            Instruction::HideException { src } => {
+                let exception = self.register(base, src).clone();
+                self.vm_mut()
+                    .set_execution_state(ExecutionState::Exception(exception));
+                self.vm_mut().hide_current_exception();
+            }
+            Instruction::RestoreException => self.vm_mut().restore_hidden_exception(),
+            Instruction::DiscardException => {
+                self.vm_mut().clear_exception();
+                self.vm_mut().resume_interrupt();
+            }
+            Instruction::Throw { src } => {
+                let exception = self.register(base, src).clone();
+                self.vm_mut()
+                    .set_execution_state(ExecutionState::Exception(exception));
+            }
+            Instruction::Return { src } => {
+                return Ok(Flow::Return(self.register(base, src).clone()));
+            }
+        }
+        Ok(Flow::Next)
+    }
+
+    fn define_property(&mut self, base: usize, obj: Register, key: PropertyKey, src: Register) {
+        let Value::Object(obj_ref) = *self.register(base, obj) else {
+            unreachable!("property defined on a non-object")
+        };
+        let value = self.register(base, src).clone();
+        self.vm_mut()
+            .heap_mut()
+            .resolve_mut(obj_ref)
+            .define_own_property(key, Property::new_user(value));
+    }
+
+    /// Arithmetic on two numbers is the common case, which doesn't need any
+    /// conversions.
+    fn exec_binary_op(
+        &mut self,
+        base: usize,
+        op: BinaryOperator,
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    ) -> std::result::Result<(), ErrorKind> {
+        let result = match (self.register(base, lhs), self.register(base, rhs)) {
+            (&Value::Number(lhs), &Value::Number(rhs)) => {
+                Value::Number(numeric_binary_op(op, lhs, rhs))
+            }
+            (lhs, rhs) => {
+                let (lhs, rhs) = (lhs.clone(), rhs.clone());
+                self.binary_op(op, lhs, rhs)?
+            }
+        };
+        self.set_register(base, dst, result);
+        Ok(())
+    }
+
+    fn exec_relational_op(
+        &mut self,
+        base: usize,
+        op: RelationalOperator,
+        dst: Register,
+        lhs: Register,
+        rhs: Register,
+    ) -> std::result::Result<(), ErrorKind> {
+        let result = match (op, self.register(base, lhs), self.register(base, rhs)) {
+            (RelationalOperator::LessThan, &Value::Number(lhs), &Value::Number(rhs)) => lhs < rhs,
+            (RelationalOperator::LessThanOrEqual, &Value::Number(lhs), &Value::Number(rhs)) => {
+                lhs <= rhs
+            }
+            (RelationalOperator::GreaterThan, &Value::Number(lhs), &Value::Number(rhs)) => {
+                lhs > rhs
+            }
+            (RelationalOperator::GreaterThanOrEqual, &Value::Number(lhs), &Value::Number(rhs)) => {
+                lhs >= rhs
+            }
+            (op, lhs, rhs) => {
+                let (lhs, rhs) = (lhs.clone(), rhs.clone());
+                self.relational_op(op, lhs, rhs)?
+            }
+        };
+        self.set_register(base, dst, Value::Boolean(result));
+        Ok(())
+    }
+}
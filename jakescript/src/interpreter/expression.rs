@@ -1,19 +1,18 @@
-use super::error::{
-    Error, ErrorKind, NotCallableError, NumericOverflowError, Result, VariableNotDefinedError,
-};
-use super::object::PropertyKey;
+use super::error::{Error, ErrorKind, NotCallableError, Result};
+use super::object::{Call, Extensible, Object, PropertyKey};
+use super::heap::Reference;
+use super::reference::ReferenceRecord;
 use super::value::{Number, Value};
-use super::{Eval, Interpreter};
+use super::{Eval, Interpreter, PreferredType};
 use crate::ast::*;
+use crate::prop_key;
 use std::assert_matches::assert_matches;
-use std::cmp;
-use std::ops::{BitAnd, BitOr, BitXor};
 
 impl Eval for Expression {
     type Output = Value;
 
     fn eval(&self, it: &mut Interpreter) -> Result<Self::Output> {
-        match self {
+        let value = match self {
             Self::IdentifierReference(ref node) => node.eval(it),
             Self::This(ref node) => node.eval(it),
 
@@ -35,7 +34,12 @@ impl Eval for Expression {
             Self::Function(ref node) => node.eval(it),
             Self::Literal(ref node) => node.eval(it),
             Self::Object(ref node) => node.eval(it),
-        }
+        }?;
+        // The value may be the only reference to an object (e.g. one which was
+        // just allocated), and the enclosing expression could call a function
+        // which collects garbage before it's done with the value.
+        it.vm_mut().stack_mut().push_temporary(&value);
+        Ok(value)
     }
 }
 
@@ -43,25 +47,9 @@ impl Eval for IdentifierReferenceExpression {
     type Output = Value;
 
     fn eval(&self, it: &mut Interpreter) -> Result<Self::Output> {
-        Ok(
-            if let Ok(variable) = it.vm().stack().lookup_variable(&self.identifier) {
-                variable.value()
-            } else {
-                let receiver = it.vm().runtime().global_object_ref();
-                let global_obj = it.vm().heap().resolve(receiver);
-                let value = global_obj
-                    .as_ref()
-                    .get(it, &self.identifier, receiver)
-                    .map_err(|err| Error::new(err, self.source_location()))?
-                    .ok_or_else(|| {
-                        Error::new(
-                            VariableNotDefinedError::new(self.identifier.clone()),
-                            self.source_location(),
-                        )
-                    })?;
-                value
-            },
-        )
+        self.eval_reference(it)?
+            .get_value(it)
+            .map_err(|err| Error::new(err, self.source_location()))
     }
 }
 
@@ -69,12 +57,13 @@ impl Eval for ThisExpression {
     type Output = Value;
 
     fn eval(&self, it: &mut Interpreter) -> Result<Self::Output> {
-        Ok(Value::Object(
-            it.vm()
-                .stack()
-                .receiver()
-                .unwrap_or_else(|| it.vm().runtime().global_object_ref()),
-        ))
+        // TODO: In sloppy mode, primitive receivers should be wrapped in objects.
+        Ok(match it.vm().stack().receiver() {
+            Value::Null | Value::Undefined if !it.vm().stack().is_strict() => {
+                Value::Object(it.vm().runtime().global_object_ref())
+            }
+            receiver => receiver,
+        })
     }
 }
 
@@ -82,22 +71,10 @@ impl Eval for ComputedMemberAccessExpression {
     type Output = Value;
 
     fn eval(&self, it: &mut Interpreter) -> Result<Self::Output> {
-        let base_value = self.base.eval(it)?;
-        let (base_refr, base_obj) = match base_value {
-            Value::Object(base_refr) => (base_refr, it.vm().heap().resolve(base_refr)),
-            base_value => todo!("ComputedPropertyExpression::eval: base={base_value:?}"),
-        };
-        let property_value = self.index.eval(it)?;
-        let property = match property_value {
-            Value::Number(Number::Int(n)) => PropertyKey::from(n),
-            property => todo!("ComputedPropertyExpression::eval: property={property:?}"),
-        };
-        let value = base_obj
-            .as_ref()
-            .get(it, &property, base_refr)
-            .map_err(|err| Error::new(err, self.source_location()))?
-            .unwrap_or_default();
-        Ok(value)
+        let base = self.base.eval(it)?;
+        let key = self.index.eval(it)?;
+        it.get_element_value(&base, key)
+            .map_err(|err| Error::new(err, self.source_location()))
     }
 }
 
@@ -105,19 +82,62 @@ impl Eval for MemberAccessExpression {
     type Output = Value;
 
     fn eval(&self, it: &mut Interpreter) -> Result<Self::Output> {
-        let base_value = self.base.eval(it)?;
-        match base_value {
-            Value::Object(base_refr) => {
-                let base_obj = it.vm().heap().resolve(base_refr);
-                let value = base_obj
-                    .as_ref()
-                    .get(it, &self.member, base_refr)
-                    .map_err(|err| Error::new(err, self.source_location()))?
-                    .unwrap_or_default();
-                Ok(value)
-            }
-            base_value => todo!("PropertyAccessExpression::eval: base={base_value:?}"),
-        }
+        self.eval_reference(it)?
+            .get_value(it)
+            .map_err(|err| Error::new(err, self.source_location()))
+    }
+}
+
+/// Evaluate an expression to a [`ReferenceRecord`] rather than to a value, so
+/// that the result can be assigned to or called as a method.
+trait EvalReference {
+    fn eval_reference(&self, it: &mut Interpreter) -> Result<ReferenceRecord>;
+}
+
+impl EvalReference for IdentifierReferenceExpression {
+    fn eval_reference(&self, it: &mut Interpreter) -> Result<ReferenceRecord> {
+        Ok(ReferenceRecord::Binding {
+            name: self.identifier.clone(),
+            strict: it.vm().stack().is_strict(),
+        })
+    }
+}
+
+impl EvalReference for ComputedMemberAccessExpression {
+    fn eval_reference(&self, it: &mut Interpreter) -> Result<ReferenceRecord> {
+        let base = self.base.eval(it)?;
+        let key = self.index.eval(it)?;
+        let key = it
+            .element_key(key)
+            .map_err(|err| Error::new(err, self.source_location()))?;
+        Ok(ReferenceRecord::Property {
+            base,
+            key,
+            strict: it.vm().stack().is_strict(),
+        })
+    }
+}
+
+impl EvalReference for MemberAccessExpression {
+    fn eval_reference(&self, it: &mut Interpreter) -> Result<ReferenceRecord> {
+        let base = self.base.eval(it)?;
+        Ok(ReferenceRecord::Property {
+            base,
+            key: PropertyKey::from(self.member.clone()),
+            strict: it.vm().stack().is_strict(),
+        })
+    }
+}
+
+/// Evaluate `expr` to a [`ReferenceRecord`] if it's an identifier or member
+/// access, or `None` otherwise. Parentheses are ignored, so `(obj.method)()`
+/// still passes `obj` as the receiver, and `(a) = 1` assigns to `a`.
+fn eval_reference(expr: &Expression, it: &mut Interpreter) -> Result<Option<ReferenceRecord>> {
+    match expr.strip_groupings() {
+        Expression::IdentifierReference(node) => node.eval_reference(it).map(Some),
+        Expression::ComputedMemberAccess(node) => node.eval_reference(it).map(Some),
+        Expression::MemberAccess(node) => node.eval_reference(it).map(Some),
+        _ => Ok(None),
     }
 }
 
@@ -125,25 +145,17 @@ impl Eval for FunctionCallExpression {
     type Output = Value;
 
     fn eval(&self, it: &mut Interpreter) -> Result<Self::Output> {
-        let (receiver, function) = match self.function.as_ref() {
-            Expression::ComputedMemberAccess(node) => {
-                // FIXME: Don't evaluate `node.base` twice!!
-                (Some(node.base.eval(it)?), node.eval(it)?)
-            }
-            Expression::MemberAccess(node) => {
-                // FIXME: Don't evaluate `node.base` twice!!
-                (Some(node.base.eval(it)?), node.eval(it)?)
+        let (receiver, function) = match eval_reference(&self.function, it)? {
+            Some(reference) => {
+                let function = reference
+                    .get_value(it)
+                    .map_err(|err| Error::new(err, self.function.source_location()))?;
+                // The function could be removed from the object while evaluating
+                // the arguments.
+                it.vm_mut().stack_mut().push_temporary(&function);
+                (reference.this_value(), function)
             }
-            Expression::FunctionCall(node) => {
-                // FIXME: Don't evaluate `node.function` twice!!
-                (Some(node.function.eval(it)?), node.eval(it)?)
-            }
-            node => (None, node.eval(it)?),
-        };
-        let receiver = match receiver {
-            Some(Value::Object(receiver)) => Some(receiver),
-            Some(receiver) => todo!("FunctionCallExpression: receiver={receiver:?}"),
-            None => None,
+            None => (Value::Undefined, self.function.eval(it)?),
         };
 
         let fn_obj_ref = match function {
@@ -157,6 +169,9 @@ impl Eval for FunctionCallExpression {
         for arg in &self.arguments {
             supplied_args.push(arg.eval(it)?);
         }
+        it.vm_mut()
+            .stack_mut()
+            .set_current_location(self.source_location());
         let fn_obj = it.vm().heap().resolve(fn_obj_ref);
         let result = fn_obj
             .as_ref()
@@ -169,8 +184,72 @@ impl Eval for FunctionCallExpression {
 impl Eval for NewExpression {
     type Output = Value;
 
-    fn eval(&self, _: &mut Interpreter) -> Result<Self::Output> {
-        todo!("NewExpression::eval: {self:#?}")
+    fn eval(&self, it: &mut Interpreter) -> Result<Self::Output> {
+        let map_err = |err: ErrorKind| Error::new(err, self.source_location());
+        let ctor_ref = match self.constructor.eval(it)? {
+            Value::Object(ctor_ref) => ctor_ref,
+            _ => return Err(map_err(ErrorKind::from(NotCallableError::new()))),
+        };
+        let mut supplied_args = Vec::with_capacity(self.arguments.len());
+        for arg in &self.arguments {
+            supplied_args.push(arg.eval(it)?);
+        }
+
+        it.vm_mut()
+            .stack_mut()
+            .set_current_location(self.source_location());
+        it.construct(ctor_ref, &supplied_args).map_err(map_err)
+    }
+}
+
+impl Interpreter {
+    /// Call `ctor_ref` as a constructor, i.e. as if by the `new` operator.
+    pub(super) fn construct(
+        &mut self,
+        ctor_ref: Reference,
+        args: &[Value],
+    ) -> std::result::Result<Value, ErrorKind> {
+        let ctor_obj = self.vm().heap().resolve(ctor_ref);
+        let native_fn = match ctor_obj.as_ref().call_data() {
+            Some(Call::Native(native_fn)) => Some(native_fn.clone()),
+            Some(Call::User(_)) => None,
+            None => return Err(ErrorKind::from(NotCallableError::new())),
+        };
+        if let Some(native_fn) = native_fn {
+            // Native constructors allocate the new object themselves, using the
+            // prototype of `new.target`.
+            drop(ctor_obj);
+            let result =
+                self.call_native_fn(&native_fn, Value::Undefined, args, Some(ctor_ref))?;
+            match result {
+                // TODO: Box other primitives once their wrapper objects exist.
+                Value::String(s) => self
+                    .vm_mut()
+                    .alloc_string_object(s)
+                    .map(Value::Object)
+                    .map_err(ErrorKind::from),
+                result => Ok(result),
+            }
+        } else {
+            let proto = match ctor_obj
+                .as_ref()
+                .get(self, &prop_key!("prototype"), ctor_ref)?
+            {
+                Some(Value::Object(proto)) => Some(proto),
+                _ => None,
+            };
+            let obj_ref = self
+                .vm_mut()
+                .heap_mut()
+                .allocate(Object::new_object(proto, [], Extensible::Yes))?;
+            let result = ctor_obj
+                .as_ref()
+                .call(self, ctor_ref, Value::Object(obj_ref), args)?;
+            Ok(match result {
+                Value::Object(result) => Value::Object(result),
+                _ => Value::Object(obj_ref),
+            })
+        }
     }
 }
 
@@ -179,50 +258,20 @@ impl Eval for AssignmentExpression {
 
     fn eval(&self, it: &mut Interpreter) -> Result<Self::Output> {
         let map_err = |err: ErrorKind| Error::new(err, self.source_location());
-        let compute_updated = |it: &mut Interpreter, lhs: Value| match self.op {
-            AssignmentOperator::Assign => {
-                let rhs = self.rhs.eval(it)?;
-                Ok((rhs, rhs))
-            }
+        assert_matches!(self.op.associativity(), Associativity::RightToLeft);
+        let Some(reference) = eval_reference(&self.lhs, it)? else {
+            unreachable!("invalid assignment target: {:#?}", self.lhs)
+        };
+        let value = match self.op {
+            AssignmentOperator::Assign => self.rhs.eval(it)?,
             AssignmentOperator::ComputeAssign(op) => {
-                eval_binary_op(it, op, |_| Ok(lhs), |it| self.rhs.eval(it))
-                    .map_err(map_err)?
-                    .map(|result_value| (result_value, result_value))
+                let lhs = reference.get_value(it).map_err(map_err)?;
+                it.vm_mut().stack_mut().push_temporary(&lhs);
+                eval_binary_op(it, op, |_| Ok(lhs), |it| self.rhs.eval(it)).map_err(map_err)??
             }
         };
-
-        assert_matches!(self.op.associativity(), Associativity::RightToLeft);
-        match self.lhs.as_ref() {
-            Expression::IdentifierReference(lhs_node) => it
-                .update_variable_or_global_object_property(
-                    &lhs_node.identifier,
-                    compute_updated,
-                    map_err,
-                ),
-            Expression::ComputedMemberAccess(lhs_node) => {
-                match lhs_node.base.eval(it)? {
-                    Value::Object(lhs_ref) => {
-                        let prop_value = lhs_node.index.eval(it)?;
-                        let prop_name = it.coerce_to_string(prop_value);
-                        let prop_key =
-                            PropertyKey::try_from(prop_name.as_ref()).unwrap_or_else(|_| {
-                                // FIXME: Remove this restriction as I think it's actually OK to key
-                                // an object property by the empty string.
-                                todo!("AssignmentExpression::eval: prop_name={prop_value:?}")
-                            });
-                        it.update_object_property(lhs_ref, &prop_key, compute_updated, map_err)
-                    }
-                    lhs => todo!("AssignmentExpression::eval: base_value={lhs:?}"),
-                }
-            }
-            Expression::MemberAccess(lhs_node) => match lhs_node.base.eval(it)? {
-                Value::Object(lhs_ref) => {
-                    it.update_object_property(lhs_ref, &lhs_node.member, compute_updated, map_err)
-                }
-                lhs => todo!("AssignmentExpression::eval: base_value={lhs:?}"),
-            },
-            lhs => todo!("AssignmentExpression::eval: lhs={lhs:#?}"),
-        }
+        reference.put_value(it, value.clone()).map_err(map_err)?;
+        Ok(value)
     }
 }
 
@@ -250,17 +299,37 @@ impl Eval for RelationalExpression {
         assert_matches!(self.op.associativity(), Associativity::LeftToRight);
         let lhs = self.lhs.eval(it)?;
         let rhs = self.rhs.eval(it)?;
+        it.relational_op(self.op, lhs, rhs)
+            .map(Value::Boolean)
+            .map_err(|err| Error::new(err, self.source_location()))
+    }
+}
 
-        Ok(Value::Boolean(match self.op {
-            RelationalOperator::Equality => it.equal(lhs, rhs),
-            RelationalOperator::Inequality => !it.equal(lhs, rhs),
-            RelationalOperator::StrictEquality => it.strictly_equal(lhs, rhs),
-            RelationalOperator::StrictInequality => !it.strictly_equal(lhs, rhs),
-            RelationalOperator::GreaterThan => it.compare(lhs, rhs, cmp::Ordering::is_gt),
-            RelationalOperator::GreaterThanOrEqual => it.compare(lhs, rhs, cmp::Ordering::is_ge),
-            RelationalOperator::LessThan => it.compare(lhs, rhs, cmp::Ordering::is_lt),
-            RelationalOperator::LessThanOrEqual => it.compare(lhs, rhs, cmp::Ordering::is_le),
-        }))
+impl Interpreter {
+    pub(super) fn relational_op(
+        &mut self,
+        op: RelationalOperator,
+        lhs: Value,
+        rhs: Value,
+    ) -> std::result::Result<bool, ErrorKind> {
+        match op {
+            RelationalOperator::Equality => self.is_loosely_equal(lhs, rhs),
+            RelationalOperator::Inequality => self.is_loosely_equal(lhs, rhs).map(|eq| !eq),
+            RelationalOperator::StrictEquality => Ok(self.is_strictly_equal(&lhs, &rhs)),
+            RelationalOperator::StrictInequality => Ok(!self.is_strictly_equal(&lhs, &rhs)),
+            RelationalOperator::GreaterThan => {
+                self.is_less_than(rhs, lhs, false).map(|lt| lt == Some(true))
+            }
+            RelationalOperator::GreaterThanOrEqual => {
+                self.is_less_than(lhs, rhs, true).map(|lt| lt == Some(false))
+            }
+            RelationalOperator::LessThan => {
+                self.is_less_than(lhs, rhs, true).map(|lt| lt == Some(true))
+            }
+            RelationalOperator::LessThanOrEqual => {
+                self.is_less_than(rhs, lhs, false).map(|lt| lt == Some(false))
+            }
+        }
     }
 }
 
@@ -269,7 +338,7 @@ impl Eval for TernaryExpression {
 
     fn eval(&self, it: &mut Interpreter) -> Result<Self::Output> {
         let condition = self.condition.eval(it)?;
-        if it.is_truthy(condition) {
+        if it.is_truthy(&condition) {
             self.true_value.eval(it)
         } else {
             self.false_value.eval(it)
@@ -281,71 +350,69 @@ impl Eval for UnaryExpression {
     type Output = Value;
 
     fn eval(&self, it: &mut Interpreter) -> Result<Self::Output> {
-        let operand = self.operand.eval(it)?;
+        let operand = match (self.op, self.operand.as_ref()) {
+            // Referring to an undeclared variable isn't an error for `typeof`, but referring to
+            // one which hasn't been initialised yet still is.
+            (UnaryOperator::TypeOf, Expression::IdentifierReference(node))
+                if !is_declared(it, &node.identifier) =>
+            {
+                Value::Undefined
+            }
+            (_, operand) => operand.eval(it)?,
+        };
+        let map_err = |err: ErrorKind| Error::new(err, self.source_location());
         Ok(match self.op {
-            UnaryOperator::NumericPlus => Value::Number(it.coerce_to_number(operand)),
-            UnaryOperator::NumericNegation => it
-                .coerce_to_number(operand)
-                .checked_neg()
-                .map(Value::Number)
-                .ok_or_else(NumericOverflowError::new)
-                .map_err(|err| Error::new(err, self.source_location()))?,
-            UnaryOperator::BitwiseNot => Value::Number(!it.coerce_to_number(operand)),
-            UnaryOperator::LogicalNot => Value::Boolean(!it.coerce_to_bool(operand)),
+            UnaryOperator::NumericPlus => Value::Number(it.to_number(operand).map_err(map_err)?),
+            UnaryOperator::NumericNegation => {
+                Value::Number(-it.to_number(operand).map_err(map_err)?)
+            }
+            UnaryOperator::BitwiseNot => Value::Number(!it.to_number(operand).map_err(map_err)?),
+            UnaryOperator::LogicalNot => Value::Boolean(!it.coerce_to_bool(&operand)),
+            UnaryOperator::TypeOf => {
+                let type_name = it.type_of(&operand);
+                Value::String(it.vm_mut().intern_string(type_name))
+            }
         })
     }
 }
 
+pub(super) fn is_declared(it: &Interpreter, identifier: &Identifier) -> bool {
+    it.vm().stack().lookup_variable(identifier).is_ok() || {
+        let global_obj_ref = it.vm().runtime().global_object_ref();
+        let global_obj = it.vm().heap().resolve(global_obj_ref);
+        let declared = global_obj
+            .as_ref()
+            .has_property(it, &PropertyKey::from(identifier.clone()));
+        declared
+    }
+}
+
 impl Eval for UpdateExpression {
     type Output = Value;
 
     fn eval(&self, it: &mut Interpreter) -> Result<Self::Output> {
         let map_err = |err: ErrorKind| Error::new(err, self.source_location());
-        let compute_updated =
-            |it: &mut Interpreter, operand: Value| -> std::result::Result<_, Error> {
-                let new_value = match self.op {
-                    UpdateOperator::GetAndIncrement | UpdateOperator::IncrementAndGet => it
-                        .coerce_to_number(operand)
-                        .checked_add(Number::ONE)
-                        .map(Value::Number)
-                        .ok_or_else(NumericOverflowError::new)
-                        .map_err(|err| Error::new(err, self.source_location()))?,
-                    UpdateOperator::GetAndDecrement | UpdateOperator::DecrementAndGet => it
-                        .coerce_to_number(operand)
-                        .checked_sub(Number::ONE)
-                        .map(Value::Number)
-                        .ok_or_else(NumericOverflowError::new)
-                        .map_err(|err| Error::new(err, self.source_location()))?,
-                };
-                Ok(match self.op {
-                    UpdateOperator::GetAndIncrement | UpdateOperator::GetAndDecrement => {
-                        (operand, new_value)
-                    }
-                    UpdateOperator::IncrementAndGet | UpdateOperator::DecrementAndGet => {
-                        (new_value, new_value)
-                    }
-                })
-            };
-
         assert_matches!(self.op.associativity(), Associativity::RightToLeft);
-        match self.operand.as_ref() {
-            Expression::IdentifierReference(operand_node) => it
-                .update_variable_or_global_object_property(
-                    &operand_node.identifier,
-                    compute_updated,
-                    map_err,
-                ),
-            Expression::MemberAccess(operand_node) => match operand_node.base.eval(it)? {
-                Value::Object(operand_ref) => it.update_object_property(
-                    operand_ref,
-                    &operand_node.member,
-                    compute_updated,
-                    map_err,
-                ),
-                operand => todo!("UpdateExpression::eval: operand={operand:?}"),
-            },
-            operand => todo!("UpdateExpression::eval: operand={operand:#?}"),
-        }
+        let Some(reference) = eval_reference(&self.operand, it)? else {
+            unreachable!("invalid update target: {:#?}", self.operand)
+        };
+        let old_value = reference.get_value(it).map_err(map_err)?;
+        let old_value = it.to_number(old_value).map_err(map_err)?;
+        let new_value = match self.op {
+            UpdateOperator::GetAndIncrement | UpdateOperator::IncrementAndGet => {
+                old_value + Number::ONE
+            }
+            UpdateOperator::GetAndDecrement | UpdateOperator::DecrementAndGet => {
+                old_value - Number::ONE
+            }
+        };
+        reference
+            .put_value(it, Value::Number(new_value))
+            .map_err(map_err)?;
+        Ok(Value::Number(match self.op {
+            UpdateOperator::GetAndIncrement | UpdateOperator::GetAndDecrement => old_value,
+            UpdateOperator::IncrementAndGet | UpdateOperator::DecrementAndGet => new_value,
+        }))
     }
 }
 
@@ -362,7 +429,7 @@ fn eval_binary_op(
                 Ok(lhs) => lhs,
                 Err(err) => return Ok(Err(err)),
             };
-            return Ok(if it.is_truthy(lhs) { rhs(it) } else { Ok(lhs) });
+            return Ok(if it.is_truthy(&lhs) { rhs(it) } else { Ok(lhs) });
         }
         BinaryOperator::LogicalOr => {
             assert_eq!(op_kind.associativity(), Associativity::LeftToRight);
@@ -370,7 +437,7 @@ fn eval_binary_op(
                 Ok(lhs) => lhs,
                 Err(err) => return Ok(Err(err)),
             };
-            return Ok(if it.is_truthy(lhs) { Ok(lhs) } else { rhs(it) });
+            return Ok(if it.is_truthy(&lhs) { Ok(lhs) } else { rhs(it) });
         }
         _ => {}
     }
@@ -400,31 +467,56 @@ fn eval_binary_op(
         }
     };
 
-    if matches!(op_kind, BinaryOperator::Addition) && (lhs.is_object() || rhs.is_object()) {
-        let lhs = it.coerce_to_string(lhs);
-        let rhs = it.coerce_to_string(rhs);
-        return Ok(Ok(Value::Object(it.concat(&lhs, &rhs)?)));
+    Ok(Ok(it.binary_op(op_kind, lhs, rhs)?))
+}
+
+impl Interpreter {
+    /// Apply a binary operator to operands which have already been evaluated,
+    /// so not including the short-circuiting logical operators.
+    pub(super) fn binary_op(
+        &mut self,
+        op: BinaryOperator,
+        lhs: Value,
+        rhs: Value,
+    ) -> std::result::Result<Value, ErrorKind> {
+        let (lhs, rhs) = if matches!(op, BinaryOperator::Addition) {
+            let lhs = self.to_primitive(lhs, PreferredType::Default)?;
+            let rhs = self.to_primitive(rhs, PreferredType::Default)?;
+            if matches!(lhs, Value::String(_)) || matches!(rhs, Value::String(_)) {
+                let lhs = self.to_string(lhs)?;
+                let rhs = self.to_string(rhs)?;
+                self.vm().check_string_length(lhs.len() + rhs.len())?;
+                return Ok(Value::String(lhs.concat(&rhs)));
+            }
+            (lhs, rhs)
+        } else {
+            (lhs, rhs)
+        };
+
+        let lhs = self.to_number(lhs)?;
+        let rhs = self.to_number(rhs)?;
+        Ok(Value::Number(numeric_binary_op(op, lhs, rhs)))
     }
+}
 
-    let lhs = it.coerce_to_number(lhs);
-    let rhs = it.coerce_to_number(rhs);
-    let result = match op_kind {
-        BinaryOperator::LogicalAnd | BinaryOperator::LogicalOr => unreachable!(),
-        BinaryOperator::Addition => lhs.checked_add(rhs),
-        BinaryOperator::Division => lhs.checked_div(rhs),
-        BinaryOperator::Modulus => lhs.checked_rem(rhs),
-        BinaryOperator::Multiplication => lhs.checked_mul(rhs),
-        BinaryOperator::Exponentiation => Some(lhs.pow(rhs)),
-        BinaryOperator::Subtraction => lhs.checked_sub(rhs),
-        BinaryOperator::BitwiseAnd => Some(lhs.bitand(rhs)),
-        BinaryOperator::BitwiseOr => Some(lhs.bitor(rhs)),
-        BinaryOperator::BitwiseXOr => Some(lhs.bitxor(rhs)),
-        BinaryOperator::BitwiseLeftShift => lhs.checked_shl(rhs),
-        BinaryOperator::BitwiseRightShift => lhs.checked_shr_signed(rhs),
-        BinaryOperator::BitwiseRightShiftUnsigned => lhs.checked_shr_unsigned(rhs),
-    };
-    result
-        .map(Value::Number)
-        .map(Ok)
-        .ok_or_else(|| ErrorKind::from(NumericOverflowError::new()))
+/// Apply a binary operator to operands which have already been converted to
+/// numbers.
+pub(super) fn numeric_binary_op(op: BinaryOperator, lhs: Number, rhs: Number) -> Number {
+    match op {
+        BinaryOperator::LogicalAnd | BinaryOperator::LogicalOr => {
+            unreachable!("short-circuiting operator: {op:?}")
+        }
+        BinaryOperator::Addition => lhs + rhs,
+        BinaryOperator::Division => lhs / rhs,
+        BinaryOperator::Modulus => lhs % rhs,
+        BinaryOperator::Multiplication => lhs * rhs,
+        BinaryOperator::Exponentiation => lhs.pow(rhs),
+        BinaryOperator::Subtraction => lhs - rhs,
+        BinaryOperator::BitwiseAnd => lhs & rhs,
+        BinaryOperator::BitwiseOr => lhs | rhs,
+        BinaryOperator::BitwiseXOr => lhs ^ rhs,
+        BinaryOperator::BitwiseLeftShift => lhs << rhs,
+        BinaryOperator::BitwiseRightShift => lhs >> rhs,
+        BinaryOperator::BitwiseRightShiftUnsigned => lhs.shr_unsigned(rhs),
+    }
 }
@@ -1,45 +1,176 @@
 use super::error::OutOfHeapSpaceError;
+use super::limits::Limits;
 use super::object::Object;
+use super::stack::ScopeId;
 use std::cell::{Ref, RefCell, RefMut};
-use std::fmt;
 use std::rc::Rc;
+use std::{fmt, mem};
 
 // TODO: Get rid of `Rc<RefCell<_>>` here.
-#[derive(Default)]
+/// Objects are freed by a mark-and-sweep garbage collector, which is run by
+/// the [`Vm`][super::Vm] as it knows what the roots are. The slots of freed
+/// objects are reused by later allocations, so each slot counts how many times
+/// it's been freed, and a [`Reference`] to an object which has been freed is
+/// caught even if the slot has been reused since.
 pub struct Heap {
-    slots: Vec<Rc<RefCell<Object>>>,
+    slots: Vec<Slot>,
+    free_slots: Vec<u32>,
+    /// See [`Limits::max_heap_objects()`].
+    max_objects: usize,
+    /// Number of objects allocated since the last collection.
+    allocated_since_collection: usize,
+    /// Number of objects which survived the last collection.
+    survived_collection: usize,
+    stats: HeapStats,
 }
 
 impl Heap {
+    /// The minimum number of objects which need to be allocated between
+    /// collections, so that small scripts don't collect needlessly often.
+    pub(super) const MIN_COLLECTION_THRESHOLD: usize = 1024;
+
+    pub fn new(limits: &Limits) -> Self {
+        Self {
+            slots: Vec::default(),
+            free_slots: Vec::default(),
+            max_objects: limits.max_heap_objects(),
+            allocated_since_collection: 0,
+            survived_collection: 0,
+            stats: HeapStats::default(),
+        }
+    }
+
     pub fn allocate(&mut self, obj: Object) -> Result<Reference, OutOfHeapSpaceError> {
-        let slot_idx = self.slots.len();
-        if slot_idx != usize::MAX {
-            self.slots.push(Rc::new(RefCell::new(obj)));
-            Ok(Reference(slot_idx))
-        } else {
-            Err(OutOfHeapSpaceError::new())
+        if self.stats.live_objects >= self.max_objects {
+            return Err(OutOfHeapSpaceError::new());
         }
+        let obj = Some(Rc::new(RefCell::new(obj)));
+        let index = if let Some(index) = self.free_slots.pop() {
+            self.slots[index as usize].obj = obj;
+            index
+        } else if let Ok(index) = u32::try_from(self.slots.len()) {
+            self.slots.push(Slot { obj, generation: 0 });
+            index
+        } else {
+            return Err(OutOfHeapSpaceError::new());
+        };
+        self.allocated_since_collection += 1;
+        self.stats.live_objects += 1;
+        self.stats.total_allocated += 1;
+        Ok(Reference {
+            index,
+            generation: self.slots[index as usize].generation,
+        })
     }
 
+    /// Get a handle to the object, which may be used to read it, or to change
+    /// it while calling back into the interpreter (e.g. to call a setter).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the object has been freed, which means that it wasn't
+    /// reachable from any of the roots the last time garbage was collected.
     pub fn resolve(&self, obj_ref: Reference) -> ObjectRef {
-        let obj = &self.slots[obj_ref.0];
-        ObjectRef { obj: obj.clone() }
+        ObjectRef {
+            obj: self.slot(obj_ref).clone(),
+        }
+    }
+
+    /// Borrow the object mutably, for changes which don't need the rest of
+    /// the interpreter. The heap can't be used until the borrow ends.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the object has been freed, as for [`Self::resolve()`], or if
+    /// it's already borrowed through an [`ObjectRef`].
+    pub fn resolve_mut(&mut self, obj_ref: Reference) -> RefMut<'_, Object> {
+        RefCell::borrow_mut(self.slot(obj_ref))
+    }
+
+    fn slot(&self, obj_ref: Reference) -> &Rc<RefCell<Object>> {
+        let slot = &self.slots[obj_ref.index as usize];
+        match slot.obj {
+            Some(ref obj) if slot.generation == obj_ref.generation => obj,
+            Some(_) | None => panic!("dangling reference: {obj_ref:?}"),
+        }
+    }
+
+    pub fn stats(&self) -> &HeapStats {
+        &self.stats
+    }
+
+    /// Whether enough has been allocated since the last collection that it's
+    /// worth collecting again. The threshold grows with the number of objects
+    /// which survived, so that the time spent collecting stays proportional to
+    /// the time spent allocating.
+    ///
+    /// The threshold is also capped at half of the room which was left under
+    /// [`Limits::max_heap_objects()`] after the last collection, so that
+    /// garbage is collected before it can use up the limit, leaving some
+    /// room for what's allocated between safe points.
+    pub fn should_collect(&self) -> bool {
+        let headroom = self.max_objects.saturating_sub(self.survived_collection);
+        let threshold = self
+            .survived_collection
+            .max(Self::MIN_COLLECTION_THRESHOLD)
+            .min(headroom / 2)
+            .max(1);
+        self.allocated_since_collection >= threshold
     }
 
-    pub fn resolve_mut(&mut self, obj_ref: Reference) -> ObjectRef {
-        let obj = &self.slots[obj_ref.0];
-        ObjectRef { obj: obj.clone() }
+    pub(super) fn capacity(&self) -> usize {
+        self.slots.len()
     }
+
+    /// Free every object which wasn't marked as reachable by the tracer. See
+    /// [`Vm::collect_garbage()`][super::Vm::collect_garbage()].
+    pub(super) fn sweep(&mut self, tracer: &Tracer) {
+        let mut freed = 0;
+        for (index, slot) in (0..).zip(self.slots.iter_mut()) {
+            if slot.obj.is_some() && !tracer.marked_objects[index as usize] {
+                slot.obj = None;
+                slot.generation = slot.generation.wrapping_add(1);
+                self.free_slots.push(index);
+                freed += 1;
+            }
+        }
+        self.stats.live_objects -= freed;
+        self.allocated_since_collection = 0;
+        self.survived_collection = self.stats.live_objects;
+        self.stats.total_freed += freed;
+        self.stats.collections += 1;
+    }
+}
+
+impl Default for Heap {
+    fn default() -> Self {
+        Self::new(&Limits::default())
+    }
+}
+
+struct Slot {
+    obj: Option<Rc<RefCell<Object>>>,
+    /// The number of times the object in this slot has been freed, wrapping
+    /// around, which tells references to it apart from references to objects
+    /// which used the slot before.
+    generation: u16,
 }
 
+/// The index of an object's slot in the [`Heap`], and the generation of the
+/// slot when the object was allocated. The generation wraps around after the
+/// slot has been reused 65536 times, which is rare enough that checking it
+/// still catches dangling references in practice.
 #[derive(Clone, Copy, Eq, PartialEq)]
-pub struct Reference(usize);
+pub struct Reference {
+    index: u32,
+    generation: u16,
+}
 
 impl fmt::Debug for Reference {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         // Note: 6 includes the 2 chars for the "0x" prefix, so only 4 actual digits are
         // displayed.
-        write!(f, "{:#06x}", self.0)
+        write!(f, "{:#06x}/{}", self.index, self.generation)
     }
 }
 
@@ -57,3 +188,96 @@ impl ObjectRef {
         RefCell::borrow_mut(&self.obj)
     }
 }
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct HeapStats {
+    live_objects: usize,
+    total_allocated: usize,
+    total_freed: usize,
+    collections: usize,
+}
+
+impl HeapStats {
+    /// The number of objects which haven't been freed, whether or not they're
+    /// still reachable.
+    pub fn live_objects(&self) -> usize {
+        self.live_objects
+    }
+
+    pub fn total_allocated(&self) -> usize {
+        self.total_allocated
+    }
+
+    pub fn total_freed(&self) -> usize {
+        self.total_freed
+    }
+
+    pub fn collections(&self) -> usize {
+        self.collections
+    }
+}
+
+/// Implemented by anything which may refer to objects on the heap, so that
+/// the garbage collector can find every object which is still reachable.
+pub trait Trace {
+    fn trace(&self, tracer: &mut Tracer);
+}
+
+impl<T: Trace> Trace for Option<T> {
+    fn trace(&self, tracer: &mut Tracer) {
+        if let Some(inner) = self {
+            inner.trace(tracer);
+        }
+    }
+}
+
+impl Trace for Reference {
+    fn trace(&self, tracer: &mut Tracer) {
+        tracer.visit(*self);
+    }
+}
+
+/// Marks the objects and scopes which are reachable, and records the ones
+/// which have been marked but not traced yet, so they can be traced in turn
+/// without recursing (which could overflow the stack for long chains of
+/// objects, such as linked lists).
+pub struct Tracer {
+    marked_objects: Vec<bool>,
+    marked_scopes: Vec<bool>,
+    pending: Vec<Pending>,
+}
+
+pub(super) enum Pending {
+    Object(Reference),
+    Scope(ScopeId),
+}
+
+impl Tracer {
+    pub(super) fn new(object_capacity: usize, scope_capacity: usize) -> Self {
+        Self {
+            marked_objects: vec![false; object_capacity],
+            marked_scopes: vec![false; scope_capacity],
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn visit(&mut self, obj_ref: Reference) {
+        if !mem::replace(&mut self.marked_objects[obj_ref.index as usize], true) {
+            self.pending.push(Pending::Object(obj_ref));
+        }
+    }
+
+    pub fn visit_scope(&mut self, scope: ScopeId) {
+        if !mem::replace(&mut self.marked_scopes[scope.index()], true) {
+            self.pending.push(Pending::Scope(scope));
+        }
+    }
+
+    pub(super) fn next_pending(&mut self) -> Option<Pending> {
+        self.pending.pop()
+    }
+
+    pub(super) fn is_scope_marked(&self, scope: ScopeId) -> bool {
+        self.marked_scopes[scope.index()]
+    }
+}
@@ -0,0 +1,32 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Allows another thread to stop a script which is running in a
+/// [`Vm`][super::Vm], e.g. because it's taking too long. See
+/// [`Vm::interrupt_handle()`][super::Vm::interrupt_handle()].
+///
+/// The interrupt is noticed the next time the script reaches a loop back-edge
+/// or calls a function, or on the next step of a built-in which loops (e.g.
+/// over the elements of an array, or while a regular expression backtracks),
+/// at which point the execution state is set to
+/// [`ExecutionState::Interrupt`][super::ExecutionState::Interrupt].
+#[derive(Clone, Debug, Default)]
+pub struct InterruptHandle(Arc<AtomicBool>);
+
+impl InterruptHandle {
+    /// Ask the script to stop. This may be called from any thread.
+    pub fn interrupt(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether an interrupt has been requested but not noticed by the
+    /// script yet.
+    pub fn is_interrupted(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Clear the interrupt, returning whether one had been requested.
+    pub(super) fn take(&self) -> bool {
+        self.0.swap(false, Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,96 @@
+/// Limits on the resources which a script may use, so that untrusted scripts
+/// can't exhaust the memory of the host or overflow its stack. See
+/// [`Vm::with_limits()`][super::Vm::with_limits()].
+///
+/// Exceeding the call depth, native stack size, or string length limits throws
+/// a `RangeError`, which the script may catch. Exceeding the heap limit fails
+/// with an [`OutOfHeapSpaceError`][super::OutOfHeapSpaceError] instead, which
+/// can't be caught by the script (throwing an error object would need another
+/// allocation) and is returned to the host.
+///
+/// # Examples
+///
+/// ```
+/// # use jakescript::interpreter::{Limits, Vm};
+/// let limits = Limits::default()
+///     .with_max_call_depth(100)
+///     .with_max_heap_objects(100_000);
+/// let vm = Vm::with_limits(limits).unwrap();
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Limits {
+    max_call_depth: usize,
+    max_native_stack_size: usize,
+    max_heap_objects: usize,
+    max_string_length: usize,
+}
+
+impl Limits {
+    /// The maximum number of function calls which may be in progress at once.
+    pub fn max_call_depth(&self) -> usize {
+        self.max_call_depth
+    }
+
+    #[must_use]
+    pub fn with_max_call_depth(mut self, max_call_depth: usize) -> Self {
+        self.max_call_depth = max_call_depth;
+        self
+    }
+
+    /// The maximum number of bytes of the native stack which may be used by
+    /// nested function calls, measured from the outermost call. The interpreter
+    /// recurses on the native stack, so this guards against overflowing it
+    /// regardless of how much each call uses (which varies between builds).
+    ///
+    /// This must be comfortably less than the size of the stack of the thread
+    /// which evaluates the script. The default suits threads with at least
+    /// 2 MiB of stack, which is the default for threads spawned by `std`.
+    pub fn max_native_stack_size(&self) -> usize {
+        self.max_native_stack_size
+    }
+
+    #[must_use]
+    pub fn with_max_native_stack_size(mut self, max_native_stack_size: usize) -> Self {
+        self.max_native_stack_size = max_native_stack_size;
+        self
+    }
+
+    /// The maximum number of objects which may be allocated at once. Objects
+    /// which are unreachable but haven't been collected yet still count.
+    pub fn max_heap_objects(&self) -> usize {
+        self.max_heap_objects
+    }
+
+    #[must_use]
+    pub fn with_max_heap_objects(mut self, max_heap_objects: usize) -> Self {
+        self.max_heap_objects = max_heap_objects;
+        self
+    }
+
+    /// The maximum length of a string, in UTF-16 code units.
+    ///
+    /// Strings don't count towards the heap limit, so this is what bounds the
+    /// memory used by each of them. The default of 2<sup>28</sup> - 1 code
+    /// units is 512 MiB, leaving room for an operation on a string of the
+    /// maximum length (e.g. replacing parts of it) to copy it a few times.
+    pub fn max_string_length(&self) -> usize {
+        self.max_string_length
+    }
+
+    #[must_use]
+    pub fn with_max_string_length(mut self, max_string_length: usize) -> Self {
+        self.max_string_length = max_string_length;
+        self
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_call_depth: 10_000,
+            max_native_stack_size: 1024 * 1024,
+            max_heap_objects: usize::MAX,
+            max_string_length: (1 << 28) - 1,
+        }
+    }
+}
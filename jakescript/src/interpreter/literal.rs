@@ -1,9 +1,9 @@
-use super::error::{Error, Result};
-use super::object::UserFunction;
+use super::error::{Error, InvalidRegExpError, Result};
+use super::object::{FunctionBody, PropertyKey, UserFunction};
 use super::value::{Number, Value};
 use super::{Eval, Interpreter};
 use crate::ast::*;
-use std::collections::HashMap;
+use crate::runtime::RegExp;
 
 impl Eval for ArrayExpression {
     type Output = Value;
@@ -30,9 +30,11 @@ impl Eval for FunctionExpression {
             .vm_mut()
             .alloc_function(UserFunction::new(
                 self.binding.clone(),
+                true,
                 declared_scope,
                 self.parameters.clone(),
-                self.body.clone(),
+                FunctionBody::Ast(self.body.clone()),
+                self.strict,
             ))
             .map_err(|err| Error::new(err, self.source_location()))?;
         Ok(Value::Object(fn_obj_ref))
@@ -45,15 +47,26 @@ impl Eval for LiteralExpression {
     fn eval(&self, it: &mut Interpreter) -> Result<Self::Output> {
         Ok(match self.value {
             Literal::Boolean(value) => Value::Boolean(value),
-            Literal::Numeric(value) => Value::Number(match value {
-                NumericLiteral::Int(value) => Number::Int(i64::try_from(value).unwrap()),
-                NumericLiteral::Float(value) => Number::Float(value),
-            }),
-            Literal::String(ref value) => Value::Object(
-                it.vm_mut()
-                    .alloc_string(value.value.clone())
-                    .map_err(|err| Error::new(err, self.source_location()))?,
-            ),
+            Literal::Numeric(value) => Value::Number(Number::from(value)),
+            Literal::RegEx(ref value) => {
+                let regexp = RegExp::new(&value.pattern, &value.flags).map_err(|err| {
+                    Error::new(
+                        InvalidRegExpError::new(format!(
+                            "/{}/{}: {err}",
+                            value.pattern, value.flags
+                        )),
+                        self.source_location(),
+                    )
+                })?;
+                Value::Object(
+                    it.vm_mut()
+                        .alloc_regexp(regexp)
+                        .map_err(|err| Error::new(err, self.source_location()))?,
+                )
+            }
+            // Interned so that evaluating the same literal repeatedly, e.g. in a loop,
+            // doesn't allocate a new string every time.
+            Literal::String(ref value) => Value::String(it.vm_mut().intern_string(&value.value)),
             Literal::Null => Value::Null,
         })
     }
@@ -63,10 +76,10 @@ impl Eval for ObjectExpression {
     type Output = Value;
 
     fn eval(&self, it: &mut Interpreter) -> Result<Self::Output> {
-        let mut resolved_props = HashMap::with_capacity(self.declared_properties.len());
+        let mut resolved_props = Vec::with_capacity(self.declared_properties.len());
         for prop in &self.declared_properties {
             let name = match prop.name {
-                ObjectPropertyName::Identifier(ref value) => value.clone(),
+                ObjectPropertyName::Identifier(ref value) => PropertyKey::from(value.clone()),
                 ObjectPropertyName::NumericLiteral(..)
                 | ObjectPropertyName::StringLiteral(..)
                 | ObjectPropertyName::Computed(..) => todo!(
@@ -75,7 +88,7 @@ impl Eval for ObjectExpression {
                 ),
             };
             let value = prop.initialiser.eval(it)?;
-            resolved_props.insert(name, value);
+            resolved_props.push((name, value));
         }
         let obj_ref = it
             .vm_mut()
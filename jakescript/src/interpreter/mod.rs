@@ -1,24 +1,37 @@
 use crate::ast::*;
+use crate::prop_key;
 use crate::runtime::NativeCall;
+pub use array::*;
+pub use conversion::*;
 pub use error::*;
 pub use heap::*;
+pub use interrupt::*;
+pub use limits::*;
 pub use object::*;
+pub use reference::*;
+pub use shape::*;
 pub use stack::*;
-use std::borrow::Cow;
-use std::cmp;
-use std::str::FromStr;
+pub use string::*;
 pub use value::*;
 pub use vm::*;
 
+mod array;
 mod block;
+mod conversion;
 mod declaration;
 mod error;
+mod exec;
 mod expression;
 mod heap;
+mod interrupt;
+mod limits;
 mod literal;
 mod object;
+mod reference;
+mod shape;
 mod stack;
 mod statement;
+mod string;
 mod value;
 mod vm;
 
@@ -45,87 +58,91 @@ impl Interpreter {
         &mut self.vm
     }
 
-    pub fn update_variable_or_global_object_property(
+    /// Evaluate `f`, and if it fails with an error which can be caught by the
+    /// script, unwind the stack and throw the error as an exception instead.
+    pub fn eval_or_throw<T>(
         &mut self,
-        key: &Identifier,
-        f: impl FnOnce(&mut Self, Value) -> Result<(Value, Value)>,
-        e: impl FnOnce(ErrorKind) -> Error,
-    ) -> Result {
-        // TODO: Performance: Avoid repeated variable lookup.
-        match self.vm().stack().lookup_variable(key) {
-            Ok(variable) => {
-                let curr_value = variable.value();
-                let (result_value, updated_value) = f(self, curr_value)?;
-                self.vm_mut()
-                    .stack_mut()
-                    .with_variable_mut(key, |variable| variable.set_value(updated_value))
-                    .expect("variable somehow disappeared while computing the new value")
-                    .map_err(ErrorKind::from)
-                    .map_err(e)?;
-                Ok(result_value)
-            }
-            Err(VariableNotDefinedError { .. }) => {
-                let global_obj_ref = self.vm().runtime().global_object_ref();
-                self.update_object_property(global_obj_ref, key, f, e)
-            }
-        }
+        f: impl FnOnce(&mut Self) -> Result<T>,
+    ) -> Result<Option<T>> {
+        let checkpoint = self.vm().stack().checkpoint();
+        let err = match f(self) {
+            Ok(value) => return Ok(Some(value)),
+            Err(err) => err,
+        };
+        self.throw_error(err)?;
+        self.vm_mut().stack_mut().unwind_to_checkpoint(checkpoint);
+        Ok(None)
     }
 
-    pub fn update_object_property(
-        &mut self,
-        base_ref: Reference,
-        key: &PropertyKey,
-        f: impl FnOnce(&mut Self, Value) -> Result<(Value, Value)>,
-        e: impl FnOnce(ErrorKind) -> Error,
-    ) -> Result {
-        let value = {
-            let base_obj = self.vm().heap().resolve(base_ref);
-            let value = match base_obj.as_ref().get(self, key, base_ref) {
-                Ok(value) => value.unwrap_or_default(),
-                Err(err) => return Err(e(err)),
-            };
-            value
+    /// If `err` can be caught by the script, throw it as an exception by
+    /// setting the execution state, or otherwise return it. The stack isn't
+    /// unwound, which is left to the caller.
+    pub fn throw_error(&mut self, err: Error) -> Result<()> {
+        let Some(native_error_kind) = err.kind().native_error_kind() else {
+            return Err(err);
         };
-        let (result_value, updated_value) = f(self, value)?;
-        self.vm_mut()
-            .heap_mut()
-            .resolve_mut(base_ref)
-            .as_ref_mut()
-            .set(self, key, base_ref, updated_value)
-            .map_err(e)?;
-        Ok(result_value)
+        // If an exception was already thrown (e.g. by a function called earlier
+        // in the same expression) then it takes precedence.
+        if matches!(self.vm().execution_state(), ExecutionState::Advance) {
+            // Capture the stack trace before unwinding, in case the error was
+            // raised from within a frame which is about to be popped.
+            self.vm_mut()
+                .stack_mut()
+                .set_current_location(err.source_location());
+            let ex = self
+                .vm_mut()
+                .alloc_error(native_error_kind, &err.kind().message())
+                .map_err(|alloc_err| Error::new(alloc_err, err.source_location()))?;
+            self.vm_mut()
+                .set_execution_state(ExecutionState::Exception(Value::Object(ex)));
+        }
+        Ok(())
     }
 
-    /// # Panics
-    ///
-    /// Panics if the a [`OutOfStackSpaceError`] occurs while trying to call the
-    /// function. TODO: Propagate the error to the caller instead.
+    /// Fails with an [`OutOfStackSpaceError`] if calling the function would
+    /// exceed the call depth or native stack size [limits][Limits], which is
+    /// thrown as a `RangeError` at the call site. Errors raised while
+    /// evaluating the body are [boxed][ErrorKind::boxed()] so they keep their
+    /// own source location.
     pub fn call_user_fn(
         &mut self,
         f: &UserFunction,
         fn_obj_ref: Reference,
-        receiver: Option<Reference>,
+        receiver: Value,
         args: &[Value],
-    ) -> Result {
-        let declared_params = f.declared_parameters();
-        let mut supplied_args = args.iter().copied();
-        let mut variables = Vec::with_capacity(declared_params.len());
-        for declared_param_name in declared_params.iter() {
-            let arg_value = supplied_args.next().unwrap_or_default();
-            variables.push(Variable::new(
-                VariableKind::Let,
-                declared_param_name.clone(),
-                arg_value,
-            ));
-        }
+    ) -> std::result::Result<Value, ErrorKind> {
+        let variables = match f.body() {
+            // Compiled functions know every variable declared in the body up front.
+            FunctionBody::Bytecode(code) => code.function_scope().instantiate(args),
+            FunctionBody::Ast(_) => {
+                let declared_params = f.declared_parameters();
+                let mut supplied_args = args.iter().cloned();
+                let mut variables = Vec::with_capacity(declared_params.len());
+                for declared_param_name in declared_params.iter() {
+                    let arg_value = supplied_args.next().unwrap_or_default();
+                    // Duplicate parameter names are allowed in sloppy mode, and the last one wins.
+                    variables.retain(|var: &Variable| var.name() != declared_param_name);
+                    variables.push(Variable::new(
+                        VariableKind::Let,
+                        declared_param_name.clone(),
+                        arg_value,
+                    ));
+                }
+                variables
+            }
+        };
 
         let declared_scope = f.declared_scope();
 
-        self.vm_mut()
-            .stack_mut()
-            .push_frame_with_existing_scope(declared_scope, receiver)
-            .map_err(|_| todo!())?;
-        if let Some(fn_name) = f.name() {
+        self.vm_mut().stack_mut().push_frame_with_existing_scope(
+            declared_scope,
+            receiver,
+            f.is_strict(),
+            f.name()
+                .cloned()
+                .unwrap_or_else(|| Identifier::from("<anonymous>")),
+        )?;
+        if let Some(fn_name) = f.own_binding() {
             // Create an outer scope with nothing but the function's name, which points to
             // itself, so that named function literals may recurse using their name without
             // making the name visible outside of the function body. It has its own outer
@@ -137,22 +154,26 @@ impl Interpreter {
             )];
             self.vm_mut()
                 .stack_mut()
-                .push_scope(false, outer_variables)
-                .map_err(|_| todo!())?;
+                .push_scope(false, outer_variables)?;
         }
-        self.vm_mut()
-            .stack_mut()
-            .push_scope(true, variables)
-            .map_err(|_| todo!())?;
-        f.body().eval(self)?;
+        self.vm_mut().stack_mut().push_scope(true, variables)?;
+        // The body won't be evaluated if the script should stop, as the execution
+        // state will have changed.
+        self.vm_mut().check_interrupt();
+        let result = match f.body() {
+            FunctionBody::Ast(body) => body.eval(self).map(|_| Value::Undefined),
+            FunctionBody::Bytecode(code) => self.exec(code),
+        }
+        .map_err(ErrorKind::boxed)?;
         self.vm_mut().stack_mut().pop_scope();
-        if f.name().is_some() {
+        if f.own_binding().is_some() {
             self.vm_mut().stack_mut().pop_scope();
         }
         self.vm_mut().stack_mut().pop_frame();
 
         Ok(match self.vm().execution_state() {
-            ExecutionState::Advance | ExecutionState::Exception(_) | ExecutionState::Exit => {
+            ExecutionState::Advance => result,
+            ExecutionState::Exception(_) | ExecutionState::Exit | ExecutionState::Interrupt => {
                 Value::Undefined
             }
             ExecutionState::Return(_) => {
@@ -168,132 +189,86 @@ impl Interpreter {
         })
     }
 
+    /// Call a native function, as a constructor if `new_target` is set. See
+    /// [`CallStack::new_target()`].
     pub fn call_native_fn(
         &mut self,
         f: &NativeCall,
-        receiver: Option<Reference>,
+        receiver: Value,
         args: &[Value],
+        new_target: Option<Reference>,
     ) -> std::result::Result<Value, ErrorKind> {
-        let receiver = receiver.unwrap_or_else(|| self.vm().runtime().global_object_ref());
-        f.call(self, receiver, args)
+        self.vm_mut().stack_mut().enter_native_call(new_target)?;
+        let result = f.call(self, receiver, args);
+        self.vm_mut().stack_mut().exit_native_call();
+        result
     }
 
-    fn concat(
-        &mut self,
-        lhs: &str,
-        rhs: &str,
-    ) -> std::result::Result<Reference, OutOfHeapSpaceError> {
-        let out = format!("{lhs}{rhs}");
-        self.vm_mut().alloc_string(out.into_boxed_str())
-    }
-
-    pub fn equal(&self, lhs: Value, rhs: Value) -> bool {
-        match lhs {
-            Value::Boolean(lhs) => lhs == self.coerce_to_bool(rhs),
-            Value::Number(lhs) => lhs == self.coerce_to_number(rhs),
-            Value::Object(lhs) => {
-                if let Value::Object(rhs) = rhs {
-                    lhs == rhs || {
-                        let lhs_obj = self.vm().heap().resolve(lhs);
-                        let rhs_obj = self.vm().heap().resolve(rhs);
-                        let value = if let Some(lhs_str) = lhs_obj.as_ref().string_data()
-                            && let Some(rhs_str) = rhs_obj.as_ref().string_data()
-                        {
-                            lhs_str == rhs_str
-                        } else {
-                            false
-                        };
-                        value
-                    }
+    /// The result of the `typeof` operator.
+    pub fn type_of(&self, v: &Value) -> &'static str {
+        match *v {
+            Value::Boolean(_) => "boolean",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Object(obj_ref) => {
+                let obj = self.vm().heap().resolve(obj_ref);
+                let value = if obj.as_ref().symbol_data().is_some() {
+                    "symbol"
+                } else if obj.as_ref().call_data().is_some() {
+                    "function"
                 } else {
-                    false
-                }
-            }
-            Value::Null | Value::Undefined => matches!(rhs, Value::Null | Value::Undefined),
-        }
-    }
-
-    pub fn strictly_equal(&self, lhs: Value, rhs: Value) -> bool {
-        match (lhs, rhs) {
-            (Value::Boolean(lhs), Value::Boolean(rhs)) => lhs == rhs,
-            (Value::Number(lhs), Value::Number(rhs)) => lhs == rhs,
-            (Value::Object(lhs), Value::Object(rhs)) => {
-                lhs == rhs || {
-                    let lhs_obj = self.vm().heap().resolve(lhs);
-                    let rhs_obj = self.vm().heap().resolve(rhs);
-                    if lhs_obj.as_ref().string_data().is_some()
-                        || rhs_obj.as_ref().string_data().is_some()
-                    {
-                        lhs_obj.as_ref().js_to_string() == rhs_obj.as_ref().js_to_string()
-                    } else {
-                        false
-                    }
-                }
+                    "object"
+                };
+                value
             }
-            (Value::Null, Value::Null) | (Value::Undefined, Value::Undefined) => true,
-            (_, _) => false,
+            Value::Null => "object",
+            Value::Undefined => "undefined",
         }
     }
 
-    pub fn compare(&self, lhs: Value, rhs: Value, op: impl FnOnce(cmp::Ordering) -> bool) -> bool {
-        let ord = if matches!(lhs, Value::Object(_)) || matches!(rhs, Value::Object(_)) {
-            Some(self.coerce_to_string(lhs).cmp(&self.coerce_to_string(rhs)))
-        } else {
-            self.coerce_to_number(lhs)
-                .partial_cmp(&self.coerce_to_number(rhs))
-        };
-        ord.map_or(false, op)
-    }
-
-    pub fn is_truthy(&self, v: Value) -> bool {
+    pub fn is_truthy(&self, v: &Value) -> bool {
         self.coerce_to_bool(v)
     }
 
-    pub fn coerce_to_bool(&self, v: Value) -> bool {
-        match v {
+    pub fn coerce_to_bool(&self, v: &Value) -> bool {
+        match *v {
             Value::Boolean(v) => v,
             Value::Number(v) => !v.is_zero() && !v.is_nan(),
-            Value::Object(obj_ref) => {
-                let obj = self.vm().heap().resolve(obj_ref);
-                let value = obj
-                    .as_ref()
-                    .string_data()
-                    .map_or(true, |string_data| !string_data.is_empty());
-                value
-            }
+            Value::String(ref v) => !v.is_empty(),
+            // Even a wrapper object around an empty string is truthy.
+            Value::Object(_) => true,
             Value::Null | Value::Undefined => false,
         }
     }
 
-    pub fn coerce_to_number(&self, v: Value) -> Number {
-        match v {
-            Value::Boolean(v) => Number::Int(i64::from(v)),
-            Value::Number(v) => v,
-            Value::Object(obj_ref) => {
-                let obj = self.vm().heap().resolve(obj_ref);
-                let value = obj
-                    .as_ref()
-                    .string_data()
-                    .and_then(|string_data| Number::from_str(string_data).ok())
-                    .unwrap_or(Number::NAN);
-                value
-            }
-            Value::Null => Number::Int(0),
-            Value::Undefined => Number::NAN,
-        }
+    /// Describe an uncaught exception for the user, including its stack trace
+    /// if it has one (see [`CallStack::stack_trace()`]).
+    pub fn describe_exception(&mut self, ex: Value) -> String {
+        // Converting the exception to a string may call user code, which can't run
+        // while the exception is still propagating.
+        let prev_state = self.vm_mut().reset_execution_state();
+        let description = self.describe_value(ex);
+        self.vm_mut().reset_execution_state();
+        self.vm_mut().set_execution_state(prev_state);
+        description
     }
 
-    pub fn coerce_to_string(&self, v: Value) -> Cow<'static, str> {
-        match v {
-            Value::Boolean(v) => if v { "true" } else { "false" }.into(),
-            Value::Number(v) => v.to_string().into(),
-            Value::Object(obj_ref) => {
-                let obj = self.vm().heap().resolve(obj_ref);
-                let value = obj.as_ref().js_to_string();
-                value
+    fn describe_value(&mut self, ex: Value) -> String {
+        if let Value::Object(ex_ref) = ex {
+            let ex_obj = self.vm().heap().resolve(ex_ref);
+            let stack = ex_obj.as_ref().get(self, &prop_key!("stack"), ex_ref);
+            drop(ex_obj);
+            if let Ok(Some(Value::String(stack))) = stack {
+                return stack.to_string_lossy();
+            }
+        }
+        // Converting the exception may itself fail, in which case there's nothing
+        // better to show than the raw value.
+        match self.to_string(ex.clone()) {
+            Ok(s) if matches!(self.vm().execution_state(), ExecutionState::Advance) => {
+                s.to_string_lossy()
             }
-            Value::Null => "null".into(),
-            Value::Undefined => "undefined".into(),
+            Ok(_) | Err(_) => format!("{ex:?}"),
         }
     }
 }
@@ -1,19 +1,22 @@
-use super::error::{ErrorKind, NotCallableError};
-use super::heap::Reference;
+use super::array::{array_index, ArrayElements};
+use super::error::{ErrorKind, NotCallableError, OutOfRangeError};
+use super::heap::{Reference, Trace, Tracer};
+use super::shape::Shape;
 use super::stack::ScopeId;
-use super::value::Value;
+use super::string::JsString;
+use super::value::{Number, Value};
 use super::Interpreter;
-use crate::ast::{Block, Identifier};
-use crate::runtime::NativeCall;
-use common_macros::hash_map;
-use std::borrow::Cow;
-use std::collections::{hash_map, HashMap};
+use crate::ast::{Block, Identifier, WellKnownSymbol};
+use crate::bytecode::CodeBlock;
+use crate::runtime::{NativeCall, RegExp, RegExpStringIterator};
+use std::fmt;
+use std::rc::Rc;
 
 #[macro_export]
 macro_rules! prop_key {
     ($lit:literal) => {{
         use $crate::ident;
-        ident!($lit)
+        $crate::interpreter::PropertyKey::from(ident!($lit))
     }};
 }
 
@@ -38,46 +41,72 @@ macro_rules! bool_enum {
 
 pub struct Object {
     proto: Option<Reference>,
-    props: HashMap<PropertyKey, Property>,
+    shape: Rc<Shape>,
+    /// The own properties, in the slots which the shape says they're in.
+    slots: Vec<Property>,
     data: ObjectData,
     extensible: Extensible,
 }
 
 impl Object {
-    pub fn new_string(proto: Reference, data: Box<str>, extensible: Extensible) -> Self {
-        Self::new(
-            Some(proto),
-            hash_map![],
-            ObjectData::String(data),
-            extensible,
-        )
+    /// A `String` wrapper object, which has a read-only property for each
+    /// code unit of the string as well as its length.
+    pub fn new_string(proto: Reference, data: JsString, extensible: Extensible) -> Self {
+        let props: Vec<_> = data
+            .as_utf16()
+            .iter()
+            .enumerate()
+            .map(|(idx, &code_unit)| {
+                let value = Value::String(JsString::from([code_unit].as_slice()));
+                let prop =
+                    Property::new_data(value, Writable::No, Enumerable::Yes, Configurable::No);
+                (PropertyKey::from(idx), prop)
+            })
+            .chain([(
+                prop_key!("length"),
+                Property::new_const(Value::Number(Number::try_from(data.len()).unwrap())),
+            )])
+            .collect();
+        Self::new(Some(proto), props, ObjectData::String(data), extensible)
+    }
+
+    pub fn new_symbol(symbol: WellKnownSymbol) -> Self {
+        Self::new(None, [], ObjectData::Symbol(symbol), Extensible::No)
+    }
+
+    pub fn new_regexp(proto: Reference, data: RegExp, extensible: Extensible) -> Self {
+        let props = [(
+            prop_key!("lastIndex"),
+            Property::new_data(
+                Value::Number(Number::ZERO),
+                Writable::Yes,
+                Enumerable::No,
+                Configurable::No,
+            ),
+        )];
+        Self::new(Some(proto), props, ObjectData::RegExp(data), extensible)
     }
 
     pub fn new_array(proto: Reference, elems: Vec<Value>, extensible: Extensible) -> Self {
-        let props = elems
-            .into_iter()
-            .enumerate()
-            .map(|(idx, value)| (PropertyKey::from(idx), Property::new_enumerable(value)))
-            .collect();
-        Self::new(Some(proto), props, ObjectData::None, extensible)
+        let data = ObjectData::Array(ArrayElements::new(elems));
+        Self::new(Some(proto), [], data, extensible)
     }
 
     pub fn new_object(
         proto: Option<Reference>,
-        props: HashMap<PropertyKey, Value>,
+        props: impl IntoIterator<Item = (PropertyKey, Value)>,
         extensible: Extensible,
     ) -> Self {
         let props = props
             .into_iter()
-            .map(|(key, value)| (key, Property::new_user(value)))
-            .collect();
+            .map(|(key, value)| (key, Property::new_user(value)));
         Self::new(proto, props, ObjectData::None, extensible)
     }
 
-    pub fn new_function(call: UserFunction, extensible: Extensible) -> Self {
+    pub fn new_function(proto: Reference, call: UserFunction, extensible: Extensible) -> Self {
         Self::new(
-            None,
-            hash_map![],
+            Some(proto),
+            [],
             ObjectData::Call(Call::User(call)),
             extensible,
         )
@@ -85,7 +114,7 @@ impl Object {
 
     pub fn new_native(
         proto: Option<Reference>,
-        props: HashMap<PropertyKey, Property>,
+        props: impl IntoIterator<Item = (PropertyKey, Property)>,
         call: impl Into<NativeCall>,
         extensible: Extensible,
     ) -> Self {
@@ -97,18 +126,26 @@ impl Object {
         )
     }
 
+    /// The properties are added in order, so if a key appears more than once
+    /// then the last property for it wins but it keeps its first position.
     pub fn new(
         proto: Option<Reference>,
-        props: HashMap<PropertyKey, Property>,
+        props: impl IntoIterator<Item = (PropertyKey, Property)>,
         data: ObjectData,
         extensible: Extensible,
     ) -> Self {
-        Self {
+        let mut obj = Self {
             proto,
-            props,
+            shape: Shape::root(),
+            slots: Vec::default(),
             data,
-            extensible,
+            extensible: Extensible::Yes,
+        };
+        for (key, prop) in props {
+            obj.define_own_property(key, prop);
         }
+        obj.extensible = extensible;
+        obj
     }
 
     pub fn prototype(&self) -> Option<Reference> {
@@ -125,39 +162,174 @@ impl Object {
         }
     }
 
-    pub fn own_property_keys(&self) -> impl Iterator<Item = &PropertyKey> {
-        self.props.keys()
+    pub fn shape(&self) -> &Rc<Shape> {
+        &self.shape
     }
 
+    /// Own property keys, with the indices of an array's elements first in
+    /// ascending order, followed by the other keys in the order in which they
+    /// were added.
+    pub fn own_property_keys(&self) -> impl Iterator<Item = PropertyKey> + '_ {
+        let elements = self.array_data();
+        let indices = elements
+            .into_iter()
+            .flat_map(ArrayElements::indices)
+            .map(|idx| PropertyKey::from(i64::from(idx)));
+        let length = elements.map(|_| prop_key!("length"));
+        indices
+            .chain(length)
+            .chain(self.shape.keys().iter().cloned())
+    }
+
+    /// An own property which is stored in one of the object's slots or, for an
+    /// array, as a sparse element. Elements which are stored densely and the
+    /// `length` of an array aren't stored as [`Property`]s, so they can only be
+    /// accessed through [`Object::get()`] and [`Object::set()`] (or through
+    /// [`Object::element()`] for the fast path).
     pub fn own_property(&self, key: &PropertyKey) -> Option<&Property> {
-        self.props.get(key)
+        match self.array_key(key) {
+            Some(ArrayKey::Index(idx)) => self.array_data()?.sparse(idx),
+            Some(ArrayKey::Length) => None,
+            None => self.shape.slot(key).map(|slot| &self.slots[slot]),
+        }
     }
 
     pub fn own_property_mut(&mut self, key: &PropertyKey) -> Option<&mut Property> {
-        self.props.get_mut(key)
+        match self.array_key(key) {
+            Some(ArrayKey::Index(idx)) => self.array_data_mut()?.sparse_mut(idx),
+            Some(ArrayKey::Length) => None,
+            None => self.shape.slot(key).map(|slot| &mut self.slots[slot]),
+        }
     }
 
+    pub fn has_own_property(&self, key: &PropertyKey) -> bool {
+        match (self.array_data(), self.array_key(key)) {
+            (Some(elements), Some(ArrayKey::Index(idx))) => elements.contains(idx),
+            (_, Some(ArrayKey::Length)) => true,
+            (_, _) => self.shape.slot(key).is_some(),
+        }
+    }
+
+    /// The element at `idx` of an array, if it's stored densely. This is the
+    /// fast path for indexing into an array with an integer, which doesn't
+    /// need to convert the index to a key.
+    pub fn element(&self, idx: usize) -> Option<&Value> {
+        self.array_data()?.dense(idx)
+    }
+
+    /// Overwrite the element at `idx` of an array if it's stored densely, or
+    /// hand `value` back otherwise. See [`Object::element()`].
+    pub fn set_element(&mut self, idx: usize, value: Value) -> Result<(), Value> {
+        match self.array_data_mut() {
+            Some(elements) => elements.set_dense(idx, value),
+            None => Err(value),
+        }
+    }
+
+    /// Whether `key` is the `length` or an array index, if the object is an
+    /// array.
+    fn array_key(&self, key: &PropertyKey) -> Option<ArrayKey> {
+        self.array_data()?;
+        if *key == prop_key!("length") {
+            Some(ArrayKey::Length)
+        } else {
+            array_index(key).map(ArrayKey::Index)
+        }
+    }
+
+    /// The property in `slot`, which must be a slot of the object's shape.
+    pub fn property_in_slot(&self, slot: usize) -> &Property {
+        &self.slots[slot]
+    }
+
+    pub fn property_in_slot_mut(&mut self, slot: usize) -> &mut Property {
+        &mut self.slots[slot]
+    }
+
+    /// Defining the `length` of an array sets it to the value of `value`,
+    /// which must be a valid length, and returns `false` if any elements past
+    /// the new length couldn't be deleted.
     pub fn define_own_property(&mut self, key: PropertyKey, value: Property) -> bool {
-        match (self.extensible(), self.props.entry(key)) {
-            (Extensible::Yes, hash_map::Entry::Occupied(mut entry)) => {
-                entry.insert(value);
+        match self.array_key(&key) {
+            Some(ArrayKey::Index(idx)) => return self.define_element(idx, value),
+            Some(ArrayKey::Length) => {
+                let length = match value.0 {
+                    PropertyInner::Data(DataProperty {
+                        value: Value::Number(length),
+                        ..
+                    }) => array_length(length),
+                    _ => None,
+                };
+                return match length {
+                    Some(length) => self.array_data_mut().unwrap().set_len(length),
+                    None => false,
+                };
+            }
+            None => {}
+        }
+        match (self.extensible(), self.shape.slot(&key)) {
+            (Extensible::Yes, Some(slot)) => {
+                self.slots[slot] = value;
+                true
+            }
+            (Extensible::Yes, None) => {
+                let shape = if self.shape.is_shared() {
+                    self.shape.transition(&key)
+                } else {
+                    None
+                };
+                match shape {
+                    Some(shape) => self.shape = shape,
+                    None => self.dictionary_shape().push(key),
+                }
+                self.slots.push(value);
                 true
             }
-            (Extensible::Yes, hash_map::Entry::Vacant(entry)) => {
-                entry.insert(value);
+            (Extensible::No, Some(slot)) => self.slots[slot] == value,
+            (Extensible::No, None) => false,
+        }
+    }
+
+    fn define_element(&mut self, idx: u32, value: Property) -> bool {
+        let extensible = self.extensible();
+        let elements = self.array_data_mut().unwrap();
+        match (extensible, elements.contains(idx)) {
+            (Extensible::Yes, _) => {
+                elements.define(idx, value);
                 true
             }
-            (Extensible::No, hash_map::Entry::Occupied(entry)) => *entry.get() == value,
-            (Extensible::No, hash_map::Entry::Vacant(_)) => false,
+            (Extensible::No, true) => match elements.dense(idx as usize) {
+                Some(elem) => Property::new_enumerable(elem.clone()) == value,
+                None => elements.sparse(idx) == Some(&value),
+            },
+            (Extensible::No, false) => false,
         }
     }
 
+    /// The object's own shape, which it's given instead of a shared one once
+    /// the layout of its properties is unlikely to be shared by other objects.
+    fn dictionary_shape(&mut self) -> &mut Shape {
+        if self.shape.is_shared() {
+            self.shape = Rc::new(self.shape.to_dictionary());
+        }
+        Rc::get_mut(&mut self.shape).expect("dictionary shapes aren't shared")
+    }
+
     pub fn get(
         &self,
         it: &mut Interpreter,
         key: &PropertyKey,
         receiver: Reference,
     ) -> Result<Option<Value>, ErrorKind> {
+        if let Some(elements) = self.array_data() {
+            match self.array_key(key) {
+                Some(ArrayKey::Length) => return Ok(Some(elements.len_value())),
+                Some(ArrayKey::Index(idx)) if let Some(elem) = elements.dense(idx as usize) => {
+                    return Ok(Some(elem.clone()));
+                }
+                Some(ArrayKey::Index(_)) | None => {}
+            }
+        }
         if let Some(prop) = self.own_property(key) {
             prop.get(it, receiver).map(Some)
         } else if let Some(proto_ref) = self.prototype() {
@@ -169,21 +341,94 @@ impl Object {
         }
     }
 
+    pub fn has_property(&self, it: &Interpreter, key: &PropertyKey) -> bool {
+        if self.has_own_property(key) {
+            true
+        } else if let Some(proto_ref) = self.prototype() {
+            let proto_obj = it.vm().heap().resolve(proto_ref);
+            let value = proto_obj.as_ref().has_property(it, key);
+            value
+        } else {
+            false
+        }
+    }
+
+    /// Returns `false` if the property couldn't be set, e.g. because it's
+    /// read-only or because the object isn't extensible.
     pub fn set(
         &mut self,
         it: &mut Interpreter,
         key: &PropertyKey,
         receiver: Reference,
         value: Value,
+    ) -> Result<bool, ErrorKind> {
+        match self.array_key(key) {
+            Some(ArrayKey::Length) => {
+                let length = it.to_number(value)?;
+                let length = array_length(length).ok_or_else(|| {
+                    ErrorKind::from(OutOfRangeError::new("invalid array length".to_owned()))
+                })?;
+                return Ok(self.array_data_mut().unwrap().set_len(length));
+            }
+            Some(ArrayKey::Index(idx)) => {
+                if let Err(value) = self.set_element(idx as usize, value) {
+                    return self.set_slow(it, key, receiver, value);
+                }
+                return Ok(true);
+            }
+            None => {}
+        }
+        self.set_slow(it, key, receiver, value)
+    }
+
+    fn set_slow(
+        &mut self,
+        it: &mut Interpreter,
+        key: &PropertyKey,
+        receiver: Reference,
+        value: Value,
     ) -> Result<bool, ErrorKind> {
         if let Some(prop) = self.own_property_mut(key) {
-            prop.set(it, receiver, value)
-        } else if let Some(proto_ref) = self.prototype() {
-            let mut proto_obj = it.vm_mut().heap_mut().resolve_mut(proto_ref);
-            let set = proto_obj.as_ref_mut().set(it, key, receiver, value)?;
-            Ok(set)
-        } else if matches!(self.extensible(), Extensible::Yes) {
-            self.define_own_property(key.clone(), Property::new_user(value));
+            return prop.set(it, receiver, value);
+        }
+        // Inherited properties which are read-only or have a setter take precedence
+        // over defining a new property on the object itself.
+        let mut next_proto_ref = self.prototype();
+        while let Some(proto_ref) = next_proto_ref {
+            let proto_obj = it.vm().heap().resolve(proto_ref);
+            // The `length` and densely stored elements of an array are writable.
+            if proto_obj.as_ref().own_property(key).is_none()
+                && proto_obj.as_ref().has_own_property(key)
+            {
+                break;
+            }
+            let setter = match proto_obj.as_ref().own_property(key).map(|prop| &prop.0) {
+                Some(PropertyInner::Data(inner)) => match inner.writable {
+                    Writable::Yes => break,
+                    Writable::No => return Ok(false),
+                },
+                Some(PropertyInner::Accessor(inner)) => match inner.set {
+                    Some(set) => Some(set),
+                    None => return Ok(false),
+                },
+                None => None,
+            };
+            if let Some(set) = setter {
+                let set_obj = it.vm().heap().resolve(set);
+                set_obj
+                    .as_ref()
+                    .call(it, set, Value::Object(receiver), &[value])?;
+                return Ok(true);
+            }
+            next_proto_ref = proto_obj.as_ref().prototype();
+        }
+        if matches!(self.extensible(), Extensible::Yes) {
+            // Elements created by assignment are enumerable, like those of array literals.
+            let prop = match self.array_key(key) {
+                Some(_) => Property::new_enumerable(value),
+                None => Property::new_user(value),
+            };
+            self.define_own_property(key.clone(), prop);
             Ok(true)
         } else {
             Ok(false)
@@ -191,13 +436,19 @@ impl Object {
     }
 
     pub fn delete(&mut self, key: &PropertyKey) -> Result<bool, ErrorKind> {
-        Ok(match self.props.entry(key.clone()) {
-            hash_map::Entry::Occupied(entry) if entry.get().deletable() => {
-                entry.remove();
+        match self.array_key(key) {
+            Some(ArrayKey::Index(idx)) => return Ok(self.array_data_mut().unwrap().delete(idx)),
+            Some(ArrayKey::Length) => return Ok(false),
+            None => {}
+        }
+        Ok(match self.shape.slot(key) {
+            Some(slot) if self.slots[slot].deletable() => {
+                self.dictionary_shape().remove(slot);
+                self.slots.remove(slot);
                 true
             }
-            hash_map::Entry::Occupied(_) => false,
-            hash_map::Entry::Vacant(_) => true,
+            Some(_) => false,
+            None => true,
         })
     }
 
@@ -205,41 +456,97 @@ impl Object {
         &self,
         it: &mut Interpreter,
         self_ref: Reference,
-        receiver: Option<Reference>,
+        receiver: Value,
         args: &[Value],
     ) -> Result<Value, ErrorKind> {
-        match self.call_data() {
-            Some(Call::User(ref user_fn)) => it
-                .call_user_fn(user_fn, self_ref, receiver, args)
-                .map_err(ErrorKind::boxed),
-            Some(Call::Native(ref native_fn)) => it.call_native_fn(native_fn, receiver, args),
-            None => Err(ErrorKind::from(NotCallableError::new())),
+        // The caller might be the only thing holding onto the function, receiver and
+        // arguments, so they're kept alive until the call returns.
+        let temporary_count = it.vm().stack().temporary_count();
+        let stack = it.vm_mut().stack_mut();
+        stack.push_temporary(&Value::Object(self_ref));
+        stack.push_temporary(&receiver);
+        for arg in args {
+            stack.push_temporary(arg);
         }
+        let result = match self.call_data() {
+            Some(Call::User(ref user_fn)) => it.call_user_fn(user_fn, self_ref, receiver, args),
+            Some(Call::Native(ref native_fn)) => it.call_native_fn(native_fn, receiver, args, None),
+            None => Err(ErrorKind::from(NotCallableError::new())),
+        };
+        it.vm_mut()
+            .stack_mut()
+            .truncate_temporaries(temporary_count);
+        result
     }
 
     pub fn call_data(&self) -> Option<&Call> {
         match self.data {
             ObjectData::Call(ref data) => Some(data),
-            ObjectData::None | ObjectData::String(_) => None,
+            _ => None,
         }
     }
 
-    pub fn string_data(&self) -> Option<&str> {
+    pub fn string_data(&self) -> Option<&JsString> {
         match self.data {
             ObjectData::String(ref data) => Some(data),
-            ObjectData::None | ObjectData::Call(_) => None,
+            _ => None,
+        }
+    }
+
+    pub fn symbol_data(&self) -> Option<WellKnownSymbol> {
+        match self.data {
+            ObjectData::Symbol(data) => Some(data),
+            _ => None,
+        }
+    }
+
+    pub fn array_data(&self) -> Option<&ArrayElements> {
+        match self.data {
+            ObjectData::Array(ref data) => Some(data),
+            _ => None,
+        }
+    }
+
+    fn array_data_mut(&mut self) -> Option<&mut ArrayElements> {
+        match self.data {
+            ObjectData::Array(ref mut data) => Some(data),
+            _ => None,
+        }
+    }
+
+    pub fn regexp_data(&self) -> Option<&RegExp> {
+        match self.data {
+            ObjectData::RegExp(ref data) => Some(data),
+            _ => None,
+        }
+    }
+
+    pub fn regexp_string_iterator_data(&self) -> Option<&RegExpStringIterator> {
+        match self.data {
+            ObjectData::RegExpStringIterator(ref data) => Some(data),
+            _ => None,
+        }
+    }
+
+    pub fn regexp_string_iterator_data_mut(&mut self) -> Option<&mut RegExpStringIterator> {
+        match self.data {
+            ObjectData::RegExpStringIterator(ref mut data) => Some(data),
+            _ => None,
         }
     }
 
     pub fn extensible(&self) -> Extensible {
         self.extensible
     }
+}
 
-    pub fn js_to_string(&self) -> Cow<'static, str> {
-        match self.data {
-            ObjectData::String(ref data) => Cow::Owned(data.to_string()),
-            ObjectData::None | ObjectData::Call(_) => "[object Object]".into(),
+impl Trace for Object {
+    fn trace(&self, tracer: &mut Tracer) {
+        self.proto.trace(tracer);
+        for prop in &self.slots {
+            prop.trace(tracer);
         }
+        self.data.trace(tracer);
     }
 }
 
@@ -247,14 +554,130 @@ impl Object {
 pub enum ObjectData {
     #[default]
     None,
+    /// The elements and `length` of an array.
+    Array(ArrayElements),
     Call(Call),
-    String(Box<str>),
+    /// The primitive value of a `String` wrapper object.
+    String(JsString),
+    Symbol(WellKnownSymbol),
+    RegExp(RegExp),
+    RegExpStringIterator(RegExpStringIterator),
+}
+
+impl Trace for ObjectData {
+    fn trace(&self, tracer: &mut Tracer) {
+        match self {
+            Self::None
+            | Self::Call(Call::Native(_))
+            | Self::String(_)
+            | Self::Symbol(_)
+            | Self::RegExp(_) => {}
+            Self::Call(Call::User(user_fn)) => tracer.visit_scope(user_fn.declared_scope()),
+            Self::Array(data) => data.trace(tracer),
+            Self::RegExpStringIterator(data) => data.trace(tracer),
+        }
+    }
+}
+
+/// The keys which an array stores in its [`ArrayElements`] rather than in
+/// its slots.
+#[derive(Copy, Clone, Debug)]
+enum ArrayKey {
+    Index(u32),
+    Length,
+}
+
+/// The length which `n` represents, if it's a valid length for an array, i.e.
+/// an integer from 0 up to and including 2<sup>32</sup> - 1.
+fn array_length(n: Number) -> Option<u32> {
+    let length = n.to_uint32();
+    (f64::from(length) == n.as_f64()).then_some(length)
 }
 
-pub type PropertyKey = Identifier;
+/// The key of a property, which is either a string or a symbol.
+///
+/// Strings which are valid UTF-16 are keyed by the equivalent Rust string, like
+/// identifiers in the source code, and strings which contain unpaired
+/// surrogates (which a Rust string can't hold) by their code units. Each string
+/// only has one representation, so distinct strings are always distinct keys.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum PropertyKey {
+    Identifier(Identifier),
+    Utf16(Rc<[u16]>),
+}
+
+impl PropertyKey {
+    /// The key as a Rust string, unless it contains unpaired surrogates.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Identifier(ident) => Some(ident.as_str()),
+            Self::Utf16(_) => None,
+        }
+    }
+
+    /// The key as a string, exactly, or the description of a symbol.
+    pub fn to_js_string(&self) -> JsString {
+        match self {
+            Self::Identifier(ident) => JsString::from(ident.as_str()),
+            Self::Utf16(code_units) => JsString::from(&code_units[..]),
+        }
+    }
+}
+
+impl fmt::Display for PropertyKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Identifier(ident) => f.write_str(ident.as_str()),
+            Self::Utf16(code_units) => f.write_str(&String::from_utf16_lossy(code_units)),
+        }
+    }
+}
+
+impl From<Identifier> for PropertyKey {
+    fn from(ident: Identifier) -> Self {
+        Self::Identifier(ident)
+    }
+}
+
+impl From<&str> for PropertyKey {
+    fn from(s: &str) -> Self {
+        Self::Identifier(Identifier::from(s))
+    }
+}
+
+impl From<String> for PropertyKey {
+    fn from(s: String) -> Self {
+        Self::Identifier(Identifier::from(s))
+    }
+}
+
+impl From<usize> for PropertyKey {
+    fn from(n: usize) -> Self {
+        Self::Identifier(Identifier::from(n))
+    }
+}
+
+impl From<i64> for PropertyKey {
+    fn from(n: i64) -> Self {
+        Self::Identifier(Identifier::from(n))
+    }
+}
+
+impl From<WellKnownSymbol> for PropertyKey {
+    fn from(symbol: WellKnownSymbol) -> Self {
+        Self::Identifier(Identifier::Symbol(symbol))
+    }
+}
+
+impl From<&JsString> for PropertyKey {
+    fn from(s: &JsString) -> Self {
+        String::from_utf16(s.as_utf16())
+            .map_or_else(|_| Self::Utf16(Rc::from(s.as_utf16())), Self::from)
+    }
+}
 
 /// [Table 4 — Default Attribute Values](https://262.ecma-international.org/6.0/#table-4)
-#[derive(PartialEq)]
+#[derive(Clone, PartialEq)]
 pub struct Property(PropertyInner);
 
 impl Property {
@@ -307,11 +730,13 @@ impl Property {
 
     pub fn get(&self, it: &mut Interpreter, receiver: Reference) -> Result<Value, ErrorKind> {
         match self.0 {
-            PropertyInner::Data(ref inner) => Ok(inner.value),
+            PropertyInner::Data(ref inner) => Ok(inner.value.clone()),
             PropertyInner::Accessor(ref inner) => match inner.get {
                 Some(get) => {
                     let get_obj = it.vm().heap().resolve(get);
-                    let result = get_obj.as_ref().call(it, get, Some(receiver), &[])?;
+                    let result = get_obj
+                        .as_ref()
+                        .call(it, get, Value::Object(receiver), &[])?;
                     Ok(result)
                 }
                 None => Ok(Value::Undefined),
@@ -331,12 +756,14 @@ impl Property {
                     inner.value = value;
                     true
                 }
-                Writable::No => inner.value == value,
+                Writable::No => false,
             },
             PropertyInner::Accessor(ref inner) => match inner.set {
                 Some(set) => {
                     let set_obj = it.vm().heap().resolve(set);
-                    set_obj.as_ref().call(it, set, Some(receiver), &[value])?;
+                    set_obj
+                        .as_ref()
+                        .call(it, set, Value::Object(receiver), &[value])?;
                     true
                 }
                 None => false,
@@ -344,6 +771,21 @@ impl Property {
         })
     }
 
+    /// The value of the property if it has the attributes of an element
+    /// created by assignment (see [`Property::new_enumerable()`]), which can be
+    /// stored densely, or the property itself otherwise.
+    pub(super) fn into_element_value(self) -> Result<Value, Self> {
+        match self.0 {
+            PropertyInner::Data(DataProperty {
+                value,
+                writable: Writable::Yes,
+                enumerable: Enumerable::Yes,
+                configurable: Configurable::Yes,
+            }) => Ok(value),
+            inner => Err(Self(inner)),
+        }
+    }
+
     pub fn deletable(&self) -> bool {
         match self.configurable() {
             Configurable::Yes => true,
@@ -373,14 +815,26 @@ impl Property {
     }
 }
 
-#[derive(PartialEq)]
+impl Trace for Property {
+    fn trace(&self, tracer: &mut Tracer) {
+        match self.0 {
+            PropertyInner::Data(ref inner) => inner.value.trace(tracer),
+            PropertyInner::Accessor(ref inner) => {
+                inner.get.trace(tracer);
+                inner.set.trace(tracer);
+            }
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
 enum PropertyInner {
     Data(DataProperty),
     Accessor(AccessorProperty),
 }
 
 /// [Table 2 — Attributes of a Data Property](https://262.ecma-international.org/6.0/#table-2)
-#[derive(PartialEq)]
+#[derive(Clone, PartialEq)]
 struct DataProperty {
     value: Value,
     writable: Writable,
@@ -389,7 +843,7 @@ struct DataProperty {
 }
 
 /// [Table 3 — Attributes of an Accessor Property](https://262.ecma-international.org/6.0/#table-3)
-#[derive(Eq, PartialEq)]
+#[derive(Clone, Eq, PartialEq)]
 struct AccessorProperty {
     get: Option<Reference>,
     set: Option<Reference>,
@@ -411,23 +865,29 @@ pub enum Call {
 #[derive(Clone, Debug)]
 pub struct UserFunction {
     name: Option<Identifier>,
+    binds_own_name: bool,
     declared_scope: ScopeId,
     declared_parameters: Vec<Identifier>,
-    body: Block,
+    body: FunctionBody,
+    strict: bool,
 }
 
 impl UserFunction {
     pub fn new(
         name: Option<Identifier>,
+        binds_own_name: bool,
         declared_scope: ScopeId,
         declared_parameters: Vec<Identifier>,
-        body: Block,
+        body: FunctionBody,
+        strict: bool,
     ) -> Self {
         Self {
             name,
+            binds_own_name,
             declared_scope,
             declared_parameters,
             body,
+            strict,
         }
     }
 
@@ -435,6 +895,13 @@ impl UserFunction {
         self.name.as_ref()
     }
 
+    /// The name which should be bound to the function itself within its body,
+    /// i.e. the name of a named function expression. Function declarations
+    /// don't need this, as their name is already declared in the outer scope.
+    pub fn own_binding(&self) -> Option<&Identifier> {
+        self.name.as_ref().filter(|_| self.binds_own_name)
+    }
+
     pub fn declared_scope(&self) -> ScopeId {
         self.declared_scope
     }
@@ -443,7 +910,19 @@ impl UserFunction {
         &self.declared_parameters
     }
 
-    pub fn body(&self) -> &Block {
+    pub fn body(&self) -> &FunctionBody {
         &self.body
     }
+
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+}
+
+/// The body of a user function, depending on which backend evaluated the code
+/// which created it.
+#[derive(Clone, Debug)]
+pub enum FunctionBody {
+    Ast(Block),
+    Bytecode(Rc<CodeBlock>),
 }
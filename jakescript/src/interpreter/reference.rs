@@ -0,0 +1,403 @@
+use super::error::{
+    ErrorKind, ObjectNotExtensibleError, PropertyNotWritableError, PropertyOfNullOrUndefinedError,
+    VariableNotDefinedError,
+};
+use super::heap::Reference;
+use super::object::{Object, PropertyKey};
+use super::shape::{CacheEntry, CachedLocation, InlineCache};
+use super::string::JsString;
+use super::value::{Number, Value};
+use super::Interpreter;
+use crate::ast::Identifier;
+use crate::runtime::Builtin;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// The result of evaluating an identifier or a member access expression, i.e.
+/// a _Reference Record_ in the spec.
+///
+/// Rather than the value itself, a reference records where the value lives, so
+/// that it can be read, written or called as a method while the expression is
+/// only evaluated once. For example, `getObj()` is only called once when
+/// evaluating `getObj().count += 1`.
+#[derive(Clone, Debug)]
+pub enum ReferenceRecord {
+    /// A variable in the scope chain or, failing that, a property of the global
+    /// object.
+    Binding { name: Identifier, strict: bool },
+    /// A property of an object or primitive value.
+    Property {
+        base: Value,
+        key: PropertyKey,
+        strict: bool,
+    },
+}
+
+impl ReferenceRecord {
+    /// The receiver to use when calling the referenced value as a function,
+    /// e.g. `obj` for `obj.method()`.
+    pub fn this_value(&self) -> Value {
+        match self {
+            Self::Property { base, .. } => base.clone(),
+            Self::Binding { .. } => Value::Undefined,
+        }
+    }
+
+    pub fn get_value(&self, it: &mut Interpreter) -> Result<Value, ErrorKind> {
+        match self {
+            Self::Binding { name, .. } => it.get_binding_value(name),
+            Self::Property { base, key, .. } => it.get_property_value(base, key),
+        }
+    }
+
+    pub fn put_value(&self, it: &mut Interpreter, value: Value) -> Result<(), ErrorKind> {
+        match self {
+            Self::Binding { name, strict } => it.put_binding_value(name, value, *strict),
+            Self::Property { base, key, strict } => {
+                it.put_property_value(base, key, value, *strict)
+            }
+        }
+    }
+}
+
+/// The semantics of [`ReferenceRecord`]s, for use by evaluators which know
+/// what kind of reference they have without needing to build one.
+impl Interpreter {
+    pub(super) fn get_binding_value(&mut self, name: &Identifier) -> Result<Value, ErrorKind> {
+        if let Ok(variable) = self.vm().stack().lookup_variable(name) {
+            return variable.value().map_err(ErrorKind::from);
+        }
+        let global_obj_ref = self.vm().runtime().global_object_ref();
+        get_property(self, global_obj_ref, &PropertyKey::from(name.clone()))?
+            .ok_or_else(|| ErrorKind::from(VariableNotDefinedError::new(name.clone())))
+    }
+
+    pub(super) fn put_binding_value(
+        &mut self,
+        name: &Identifier,
+        value: Value,
+        strict: bool,
+    ) -> Result<(), ErrorKind> {
+        if let Ok(result) = self
+            .vm_mut()
+            .stack_mut()
+            .with_variable_mut(name, |variable| variable.set_value(value.clone()))
+        {
+            return result;
+        }
+        let global_obj_ref = self.vm().runtime().global_object_ref();
+        let global_obj = self.vm().heap().resolve(global_obj_ref);
+        let key = PropertyKey::from(name.clone());
+        let declared = global_obj.as_ref().has_property(self, &key);
+        drop(global_obj);
+        if !declared && strict {
+            return Err(ErrorKind::from(VariableNotDefinedError::new(name.clone())));
+        }
+        set_property(self, global_obj_ref, &key, value, strict)
+    }
+
+    pub(super) fn get_property_value(
+        &mut self,
+        base: &Value,
+        key: &PropertyKey,
+    ) -> Result<Value, ErrorKind> {
+        match base {
+            &Value::Object(base_ref) => {
+                get_property(self, base_ref, key).map(Option::unwrap_or_default)
+            }
+            Value::Null | Value::Undefined => Err(ErrorKind::from(
+                PropertyOfNullOrUndefinedError::new(key.clone()),
+            )),
+            Value::Number(_) => {
+                // Primitives don't have their own properties, but inherit them from the
+                // prototype of their wrapper objects. TODO: Getters on the prototype should
+                // receive the primitive value rather than the prototype itself.
+                let proto_ref = self.vm().runtime().global_object().number_proto().obj_ref();
+                get_property(self, proto_ref, key).map(Option::unwrap_or_default)
+            }
+            Value::String(s) => {
+                if let Some(value) = string_own_property(s, key) {
+                    return Ok(value);
+                }
+                let proto_ref = self.vm().runtime().global_object().string_proto().obj_ref();
+                get_property(self, proto_ref, key).map(Option::unwrap_or_default)
+            }
+            // TODO: Look up properties on `Boolean.prototype` once it exists.
+            Value::Boolean(_) => Ok(Value::Undefined),
+        }
+    }
+
+    pub(super) fn put_property_value(
+        &mut self,
+        base: &Value,
+        key: &PropertyKey,
+        value: Value,
+        strict: bool,
+    ) -> Result<(), ErrorKind> {
+        match base {
+            &Value::Object(base_ref) => set_property(self, base_ref, key, value, strict),
+            Value::Null | Value::Undefined => Err(ErrorKind::from(
+                PropertyOfNullOrUndefinedError::new(key.clone()),
+            )),
+            // Primitives can't have properties of their own, so the assignment has no effect.
+            Value::Boolean(_) | Value::Number(_) | Value::String(_) if !strict => Ok(()),
+            Value::Boolean(_) | Value::Number(_) | Value::String(_) => {
+                Err(ErrorKind::from(ObjectNotExtensibleError::new(key.clone())))
+            }
+        }
+    }
+}
+
+/// Property accesses with an [`InlineCache`], which skip looking up the key for
+/// objects with a shape which the cache has seen before.
+impl Interpreter {
+    pub(super) fn get_cached_property_value(
+        &mut self,
+        base: &Value,
+        key: &PropertyKey,
+        cache: &RefCell<InlineCache>,
+    ) -> Result<Value, ErrorKind> {
+        let &Value::Object(base_ref) = base else {
+            return self.get_property_value(base, key);
+        };
+        let base_obj = self.vm().heap().resolve(base_ref);
+        let cached = lookup_cached(self, &cache.borrow(), base_ref, &base_obj.as_ref());
+        let location = cached.or_else(|| {
+            if cache.borrow().is_megamorphic() {
+                return None;
+            }
+            let entry = locate(self, &base_obj.as_ref(), key)?;
+            let location = match *entry.location() {
+                CachedLocation::Own(slot) => (base_ref, slot),
+                CachedLocation::Prototype { proto, slot, .. } => (proto, slot),
+            };
+            cache.borrow_mut().insert(entry);
+            Some(location)
+        });
+        drop(base_obj);
+        match location {
+            Some((holder_ref, slot)) => {
+                // The holder mustn't stay borrowed while a getter runs, in case it touches the
+                // holder.
+                let holder = self.vm().heap().resolve(holder_ref);
+                let prop = holder.as_ref().property_in_slot(slot).clone();
+                drop(holder);
+                prop.get(self, base_ref)
+            }
+            None => get_property(self, base_ref, key).map(Option::unwrap_or_default),
+        }
+    }
+
+    /// Only assignments to an object's own properties are cached, as adding a
+    /// property depends on the prototype chain too.
+    pub(super) fn put_cached_property_value(
+        &mut self,
+        base: &Value,
+        key: &PropertyKey,
+        value: Value,
+        strict: bool,
+        cache: &RefCell<InlineCache>,
+    ) -> Result<(), ErrorKind> {
+        let &Value::Object(base_ref) = base else {
+            return self.put_property_value(base, key, value, strict);
+        };
+        let base_obj = self.vm().heap().resolve(base_ref);
+        let base_obj = base_obj.as_ref();
+        let cached = match cache.borrow().lookup(base_obj.shape()) {
+            Some(entry) => match *entry.location() {
+                CachedLocation::Own(slot) => Some(slot),
+                CachedLocation::Prototype { .. } => None,
+            },
+            None => None,
+        };
+        let slot = cached.or_else(|| {
+            if cache.borrow().is_megamorphic() {
+                return None;
+            }
+            let shape = base_obj.shape();
+            let slot = shape.slot(key).filter(|_| shape.is_shared())?;
+            let entry = CacheEntry::new(Rc::clone(shape), CachedLocation::Own(slot));
+            cache.borrow_mut().insert(entry);
+            Some(slot)
+        });
+        drop(base_obj);
+        let Some(slot) = slot else {
+            return set_property(self, base_ref, key, value, strict);
+        };
+        let set = self
+            .vm()
+            .heap()
+            .resolve(base_ref)
+            .as_ref_mut()
+            .property_in_slot_mut(slot)
+            .set(self, base_ref, value)?;
+        // Failed assignments are silently ignored, except in strict mode code.
+        if set || !strict {
+            Ok(())
+        } else {
+            Err(ErrorKind::from(PropertyNotWritableError::new(key.clone())))
+        }
+    }
+}
+
+/// Property accesses with a computed key, which skip converting the key to a
+/// string when an integer is used to index into an array with dense elements.
+impl Interpreter {
+    pub(super) fn get_element_value(
+        &mut self,
+        base: &Value,
+        key: Value,
+    ) -> Result<Value, ErrorKind> {
+        if let (&Value::Object(base_ref), Some(idx)) = (base, element_index(&key)) {
+            let base_obj = self.vm().heap().resolve(base_ref);
+            let elem = base_obj.as_ref().element(idx).cloned();
+            if let Some(elem) = elem {
+                return Ok(elem);
+            }
+        }
+        let key = self.element_key(key)?;
+        self.get_property_value(base, &key)
+    }
+
+    pub(super) fn put_element_value(
+        &mut self,
+        base: &Value,
+        key: Value,
+        value: Value,
+        strict: bool,
+    ) -> Result<(), ErrorKind> {
+        let value = match (base, element_index(&key)) {
+            (&Value::Object(base_ref), Some(idx)) => {
+                let mut base_obj = self.vm_mut().heap_mut().resolve_mut(base_ref);
+                let set = base_obj.set_element(idx, value);
+                match set {
+                    Ok(()) => return Ok(()),
+                    Err(value) => value,
+                }
+            }
+            _ => value,
+        };
+        let key = self.element_key(key)?;
+        self.put_property_value(base, &key, value, strict)
+    }
+
+    /// Integers are the most common computed keys, and don't need the full
+    /// conversion.
+    pub(super) fn element_key(&mut self, key: Value) -> Result<PropertyKey, ErrorKind> {
+        match key {
+            Value::Number(Number::Int(n)) => Ok(PropertyKey::from(n)),
+            key => self.to_property_key(key),
+        }
+    }
+}
+
+fn element_index(key: &Value) -> Option<usize> {
+    match *key {
+        Value::Number(Number::Int(n)) => usize::try_from(n).ok(),
+        _ => None,
+    }
+}
+
+/// The object which has the property (either `obj` itself or its prototype)
+/// and the slot it's in, if `cache` knows where it is for objects of the same
+/// shape as `obj`.
+fn lookup_cached(
+    it: &Interpreter,
+    cache: &InlineCache,
+    obj_ref: Reference,
+    obj: &Object,
+) -> Option<(Reference, usize)> {
+    match *cache.lookup(obj.shape())?.location() {
+        CachedLocation::Own(slot) => Some((obj_ref, slot)),
+        CachedLocation::Prototype {
+            proto,
+            ref shape,
+            slot,
+        } => {
+            if obj.prototype() != Some(proto) {
+                return None;
+            }
+            let proto_obj = it.vm().heap().resolve(proto);
+            let valid = Rc::ptr_eq(proto_obj.as_ref().shape(), shape);
+            valid.then_some((proto, slot))
+        }
+    }
+}
+
+/// Where the property is for objects with the same shape as `obj`, if it's
+/// either an own property or a property of the prototype and the shapes
+/// involved can be cached.
+fn locate(it: &Interpreter, obj: &Object, key: &PropertyKey) -> Option<CacheEntry> {
+    let shape = obj.shape();
+    if !shape.is_shared() {
+        return None;
+    }
+    if let Some(slot) = shape.slot(key) {
+        return Some(CacheEntry::new(Rc::clone(shape), CachedLocation::Own(slot)));
+    }
+    let proto = obj.prototype()?;
+    let proto_obj = it.vm().heap().resolve(proto);
+    let proto_shape = proto_obj.as_ref().shape().clone();
+    if !proto_shape.is_shared() {
+        return None;
+    }
+    let slot = proto_shape.slot(key)?;
+    let location = CachedLocation::Prototype {
+        proto,
+        shape: proto_shape,
+        slot,
+    };
+    Some(CacheEntry::new(Rc::clone(shape), location))
+}
+
+/// The `length` and index properties which a string appears to have, as if
+/// it had been boxed in a `String` wrapper object.
+fn string_own_property(s: &JsString, key: &PropertyKey) -> Option<Value> {
+    let key = key.as_str()?;
+    if key == "length" {
+        return Some(Value::Number(Number::try_from(s.len()).unwrap()));
+    }
+    let idx = key.parse::<usize>().ok()?;
+    // Only canonical indices, e.g. not `"01"`, refer to characters.
+    if idx.to_string() != key {
+        return None;
+    }
+    s.code_unit_at(idx)
+        .map(|code_unit| Value::String(JsString::from([code_unit].as_slice())))
+}
+
+fn get_property(
+    it: &mut Interpreter,
+    base_ref: Reference,
+    key: &PropertyKey,
+) -> Result<Option<Value>, ErrorKind> {
+    let base_obj = it.vm().heap().resolve(base_ref);
+    let value = base_obj.as_ref().get(it, key, base_ref);
+    value
+}
+
+fn set_property(
+    it: &mut Interpreter,
+    base_ref: Reference,
+    key: &PropertyKey,
+    value: Value,
+    strict: bool,
+) -> Result<(), ErrorKind> {
+    let set = it
+        .vm()
+        .heap()
+        .resolve(base_ref)
+        .as_ref_mut()
+        .set(it, key, base_ref, value)?;
+    // Failed assignments are silently ignored, except in strict mode code.
+    if set || !strict {
+        return Ok(());
+    }
+    let base_obj = it.vm().heap().resolve(base_ref);
+    let err = if base_obj.as_ref().has_property(it, key) {
+        ErrorKind::from(PropertyNotWritableError::new(key.clone()))
+    } else {
+        ErrorKind::from(ObjectNotExtensibleError::new(key.clone()))
+    };
+    Err(err)
+}
@@ -0,0 +1,232 @@
+use super::heap::Reference;
+use super::object::PropertyKey;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::{Rc, Weak};
+
+/// The layout of an object's own properties, i.e. which keys it has and which
+/// slot the property for each key is stored in. Keys are kept in the order in
+/// which they were added.
+///
+/// Objects which have had the same keys added in the same order share the
+/// same shape, so checking that an object's shape is the same as one seen
+/// before is enough to know where a property is stored without looking up its
+/// key. See [`InlineCache`].
+///
+/// Objects which have too many properties or which have had a property
+/// deleted are given a shape of their own instead (a "dictionary" shape),
+/// which is updated in place and is never cached.
+pub struct Shape {
+    keys: Vec<PropertyKey>,
+    slots: HashMap<PropertyKey, usize>,
+    /// The shape which this one was created from by adding a key, which is
+    /// kept alive so that other objects which go through it on their way to
+    /// this shape can find it again.
+    parent: Option<Rc<Shape>>,
+    /// The shapes which have been created by adding a key to this one, so that
+    /// they can be shared with other objects which add the same key. They're
+    /// only kept alive by the objects which have them (or their descendants).
+    transitions: RefCell<HashMap<PropertyKey, Weak<Shape>>>,
+    shared: bool,
+}
+
+impl Shape {
+    /// The maximum number of properties an object can have before it's given
+    /// a dictionary shape, so that the cost of copying the keys into each new
+    /// shared shape stays bounded.
+    const MAX_SHARED_PROPERTIES: usize = 64;
+
+    /// The shared shape of objects without any properties.
+    pub fn root() -> Rc<Self> {
+        thread_local! {
+            static ROOT: Rc<Shape> = Rc::new(Shape::new(true));
+        }
+        ROOT.with(Rc::clone)
+    }
+
+    fn new(shared: bool) -> Self {
+        Self {
+            keys: Vec::default(),
+            slots: HashMap::default(),
+            parent: None,
+            transitions: RefCell::default(),
+            shared,
+        }
+    }
+
+    /// Own property keys in the order in which they were added.
+    pub fn keys(&self) -> &[PropertyKey] {
+        &self.keys
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn slot(&self, key: &PropertyKey) -> Option<usize> {
+        self.slots.get(key).copied()
+    }
+
+    /// Whether the shape may be shared by other objects, and therefore cached.
+    pub fn is_shared(&self) -> bool {
+        self.shared
+    }
+
+    /// The shared shape with the same keys as this one plus `key`, or `None`
+    /// if an object with it should be given a dictionary shape instead.
+    pub(super) fn transition(self: &Rc<Self>, key: &PropertyKey) -> Option<Rc<Self>> {
+        debug_assert!(self.is_shared() && self.slot(key).is_none());
+        if self.len() >= Self::MAX_SHARED_PROPERTIES {
+            return None;
+        }
+        let mut transitions = self.transitions.borrow_mut();
+        if let Some(shape) = transitions.get(key).and_then(Weak::upgrade) {
+            return Some(shape);
+        }
+        // Forget about shapes which have since been dropped, before growing the
+        // map to make space for another.
+        if transitions.len() == transitions.capacity() {
+            transitions.retain(|_, shape| shape.strong_count() > 0);
+        }
+        let mut shape = Self::new(true);
+        shape.keys.clone_from(&self.keys);
+        shape.slots.clone_from(&self.slots);
+        shape.parent = Some(Rc::clone(self));
+        shape.push(key.clone());
+        let shape = Rc::new(shape);
+        transitions.insert(key.clone(), Rc::downgrade(&shape));
+        Some(shape)
+    }
+
+    /// A dictionary shape with the same keys as this one.
+    pub(super) fn to_dictionary(&self) -> Self {
+        let mut shape = Self::new(false);
+        shape.keys.clone_from(&self.keys);
+        shape.slots.clone_from(&self.slots);
+        shape
+    }
+
+    pub(super) fn push(&mut self, key: PropertyKey) {
+        self.slots.insert(key.clone(), self.keys.len());
+        self.keys.push(key);
+    }
+
+    /// Remove the key in `slot`, moving the keys after it down a slot.
+    pub(super) fn remove(&mut self, slot: usize) {
+        debug_assert!(!self.is_shared());
+        let key = self.keys.remove(slot);
+        self.slots.remove(&key);
+        for (idx, key) in self.keys.iter().enumerate().skip(slot) {
+            *self.slots.get_mut(key).unwrap() = idx;
+        }
+    }
+}
+
+impl fmt::Debug for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Shape")
+            .field("keys", &self.keys)
+            .field("shared", &self.shared)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Remembers where a property was found for the last few shapes of object
+/// which a particular property access was evaluated for, so that evaluating
+/// it again for an object of one of those shapes can go straight to the slot.
+///
+/// Starts off empty, becomes monomorphic after the first lookup and then
+/// polymorphic once it has seen more than one shape, until it has seen too
+/// many to be worth checking and becomes megamorphic, after which it's no
+/// longer used.
+///
+/// Entries are never invalidated, as each one is checked whenever it's used
+/// instead. Only shared shapes are cached, and they never change once created
+/// (an object which gains a key moves to another shape, and one which loses a
+/// key moves to a dictionary shape), so an object with the cached shape has
+/// the property in the cached slot. A property found on the prototype is only
+/// used if the object still has that prototype, and the prototype still has
+/// the cached shape. See [`CachedLocation::Prototype`]. Changing the value of
+/// a property doesn't change the shape, but the value is read from the slot
+/// rather than cached, so there's nothing to invalidate.
+#[derive(Debug, Default)]
+pub enum InlineCache {
+    #[default]
+    Empty,
+    Monomorphic(CacheEntry),
+    Polymorphic(Vec<CacheEntry>),
+    Megamorphic,
+}
+
+impl InlineCache {
+    /// The number of shapes which a polymorphic cache can hold.
+    const MAX_POLYMORPHIC_ENTRIES: usize = 4;
+
+    pub fn lookup(&self, shape: &Rc<Shape>) -> Option<&CacheEntry> {
+        match self {
+            Self::Monomorphic(entry) if Rc::ptr_eq(&entry.shape, shape) => Some(entry),
+            Self::Polymorphic(entries) => {
+                entries.iter().find(|entry| Rc::ptr_eq(&entry.shape, shape))
+            }
+            Self::Empty | Self::Monomorphic(_) | Self::Megamorphic => None,
+        }
+    }
+
+    pub fn insert(&mut self, entry: CacheEntry) {
+        debug_assert!(entry.shape.is_shared());
+        *self = match std::mem::take(self) {
+            Self::Empty => Self::Monomorphic(entry),
+            Self::Monomorphic(first) => Self::Polymorphic(vec![first, entry]),
+            Self::Polymorphic(mut entries) if entries.len() < Self::MAX_POLYMORPHIC_ENTRIES => {
+                entries.push(entry);
+                Self::Polymorphic(entries)
+            }
+            Self::Polymorphic(_) | Self::Megamorphic => Self::Megamorphic,
+        };
+    }
+
+    pub fn is_megamorphic(&self) -> bool {
+        matches!(self, Self::Megamorphic)
+    }
+}
+
+/// Where a property was found for objects of a particular shape.
+#[derive(Debug)]
+pub struct CacheEntry {
+    shape: Rc<Shape>,
+    location: CachedLocation,
+}
+
+impl CacheEntry {
+    pub fn new(shape: Rc<Shape>, location: CachedLocation) -> Self {
+        Self { shape, location }
+    }
+
+    pub fn shape(&self) -> &Rc<Shape> {
+        &self.shape
+    }
+
+    pub fn location(&self) -> &CachedLocation {
+        &self.location
+    }
+}
+
+#[derive(Debug)]
+pub enum CachedLocation {
+    /// A slot of the object itself.
+    Own(usize),
+    /// A slot of the object's prototype, which is only valid while the object
+    /// still has that prototype and the prototype still has that shape. (The
+    /// object's shape means it doesn't have a property of its own which would
+    /// shadow it.)
+    Prototype {
+        proto: Reference,
+        shape: Rc<Shape>,
+        slot: usize,
+    },
+}
@@ -1,31 +1,64 @@
-use super::error::{AssignToConstVariableError, OutOfStackSpaceError, VariableAlreadyDefinedError};
+use super::error::{
+    AssignToConstVariableError, ErrorKind, OutOfStackSpaceError, VariableAlreadyDefinedError,
+    VariableNotInitialisedError,
+};
+use super::heap::{Heap, Reference, Trace, Tracer};
+use super::limits::Limits;
 use super::value::Value;
-use crate::ast::{Identifier, LexicalDeclarationKind};
-use crate::interpreter::{Reference, VariableNotDefinedError};
+use crate::ast::{Identifier, LexicalDeclarationKind, SourceLocation};
+use crate::interpreter::VariableNotDefinedError;
+use std::rc::Rc;
+use std::{fmt, hint, ptr};
 
 pub struct CallStack {
     root: CallFrame,
     frames: Vec<CallFrame>,
     scopes: ScopeStack,
+    /// Objects which the interpreter is holding onto outside of any variable,
+    /// e.g. the operands of an expression which is part way through being
+    /// evaluated, which need to survive garbage collection until it's done with
+    /// them.
+    temporaries: Vec<Reference>,
+    /// The `new.target` of each call to a native function in progress. They
+    /// don't get frames of their own, but still count towards the limits.
+    native_calls: Vec<Option<Reference>>,
+    /// See [`Limits::max_call_depth()`].
+    max_depth: usize,
+    /// See [`Limits::max_native_stack_size()`].
+    max_native_stack_size: usize,
+    /// The address of the native stack when the outermost frame was pushed.
+    native_stack_base: usize,
 }
 
 impl Default for CallStack {
     fn default() -> Self {
+        Self::new(&Limits::default())
+    }
+}
+
+impl CallStack {
+    pub fn new(limits: &Limits) -> Self {
         let mut scopes = ScopeStack::default();
         let root_scope = scopes.create_root(Vec::default()).unwrap();
         let root = CallFrame {
             scope: root_scope,
-            receiver: None,
+            receiver: Value::Undefined,
+            strict: false,
+            function_name: None,
+            current_loc: None,
         };
         Self {
             root,
             frames: Vec::default(),
             scopes,
+            temporaries: Vec::default(),
+            native_calls: Vec::default(),
+            max_depth: limits.max_call_depth(),
+            max_native_stack_size: limits.max_native_stack_size(),
+            native_stack_base: 0,
         }
     }
-}
 
-impl CallStack {
     fn frame(&self) -> &CallFrame {
         self.frames.last().unwrap_or(&self.root)
     }
@@ -36,7 +69,7 @@ impl CallStack {
 
     pub fn push_empty_frame(&mut self) -> Result<(), OutOfStackSpaceError> {
         let root_scope = self.scopes.create_root(Vec::default())?;
-        self.push_frame(root_scope, None)
+        self.push_frame(root_scope, Value::Undefined, false, None)
     }
 
     pub fn push_frame_with_variables_in_scope(
@@ -44,43 +77,172 @@ impl CallStack {
         variables: Vec<Variable>,
     ) -> Result<(), OutOfStackSpaceError> {
         let root_scope = self.scopes.create_root(variables)?;
-        self.push_frame(root_scope, None)
+        self.push_frame(root_scope, Value::Undefined, false, None)
     }
 
     pub fn push_frame_with_existing_scope(
         &mut self,
         existing_scope: ScopeId,
-        receiver: Option<Reference>,
+        receiver: Value,
+        strict: bool,
+        function_name: Identifier,
     ) -> Result<(), OutOfStackSpaceError> {
-        self.push_frame(existing_scope, receiver)
+        self.push_frame(existing_scope, receiver, strict, Some(function_name))
     }
 
     fn push_frame(
         &mut self,
         root_scope: ScopeId,
-        receiver: Option<Reference>,
+        receiver: Value,
+        strict: bool,
+        function_name: Option<Identifier>,
     ) -> Result<(), OutOfStackSpaceError> {
-        if self.frames.len() != usize::MAX {
-            self.frames.push(CallFrame {
-                scope: root_scope,
-                receiver,
-            });
-            Ok(())
-        } else {
-            Err(OutOfStackSpaceError::new())
-        }
+        self.check_space_for_call()?;
+        self.frames.push(CallFrame {
+            scope: root_scope,
+            receiver,
+            strict,
+            function_name,
+            current_loc: None,
+        });
+        Ok(())
     }
 
     pub fn pop_frame(&mut self) {
         self.frames.pop().expect("cannot pop the root call frame");
     }
 
+    /// Check that a native function may be called, in the same way as for
+    /// [pushing a frame][Self::push_frame()], as native functions can recurse
+    /// too (e.g. joining an array which contains arrays). Must be paired with
+    /// [`Self::exit_native_call()`] once it returns.
+    ///
+    /// `new_target` is the constructor which `new` was used with, if the
+    /// function is being called as a constructor. See [`Self::new_target()`].
+    pub fn enter_native_call(
+        &mut self,
+        new_target: Option<Reference>,
+    ) -> Result<(), OutOfStackSpaceError> {
+        self.check_space_for_call()?;
+        self.native_calls.push(new_target);
+        Ok(())
+    }
+
+    pub fn exit_native_call(&mut self) {
+        self.native_calls.pop().expect("no native call in progress");
+    }
+
+    /// The `new.target` of the native function which is running, i.e. the
+    /// constructor which `new` was used with, or `None` if it was called as a
+    /// plain function.
+    pub fn new_target(&self) -> Option<Reference> {
+        self.native_calls.last().copied().flatten()
+    }
+
+    fn check_space_for_call(&mut self) -> Result<(), OutOfStackSpaceError> {
+        let native_stack_address = native_stack_address();
+        let depth = self.frames.len() + self.native_calls.len();
+        if depth == 0 {
+            self.native_stack_base = native_stack_address;
+        }
+        let native_stack_size = self.native_stack_base.abs_diff(native_stack_address);
+        if depth >= self.max_depth || native_stack_size > self.max_native_stack_size {
+            return Err(OutOfStackSpaceError::new());
+        }
+        Ok(())
+    }
+
+    /// Whether any function calls are in progress, i.e. whether evaluation is
+    /// within anything but the root frame.
+    pub fn is_in_call(&self) -> bool {
+        !self.frames.is_empty()
+    }
+
+    /// Record where evaluation has got to within the current frame. This should
+    /// be called before each function call, so that the call site of each frame
+    /// is known, and before an error is thrown.
+    pub fn set_current_location(&mut self, loc: &SourceLocation) {
+        self.frame_mut().current_loc = Some(loc.clone());
+    }
+
+    pub fn current_location(&self) -> Option<&SourceLocation> {
+        self.frame().current_loc.as_ref()
+    }
+
+    /// The function name and current location of each frame, starting with the
+    /// innermost.
+    pub fn stack_trace(&self) -> StackTrace {
+        let frames = self
+            .frames
+            .iter()
+            .rev()
+            .chain([&self.root])
+            .filter_map(|frame| {
+                frame.current_loc.as_ref().map(|loc| StackTraceFrame {
+                    function_name: frame.function_name.clone(),
+                    loc: loc.clone(),
+                })
+            })
+            .collect();
+        StackTrace(frames)
+    }
+
+    /// Record the current frame and scope, so that the stack can later be
+    /// [unwound][Self::unwind_to_checkpoint()] back to this point.
+    pub fn checkpoint(&self) -> StackCheckpoint {
+        StackCheckpoint {
+            frame_count: self.frames.len(),
+            scope: self.frame().scope,
+            temporary_count: self.temporaries.len(),
+        }
+    }
+
+    /// Pop any frames and scopes which were pushed after the checkpoint was
+    /// taken, e.g. because an error interrupted evaluation before they could be
+    /// popped normally.
+    pub fn unwind_to_checkpoint(&mut self, checkpoint: StackCheckpoint) {
+        assert!(self.frames.len() >= checkpoint.frame_count);
+        self.frames.truncate(checkpoint.frame_count);
+        self.frame_mut().scope = checkpoint.scope;
+        self.truncate_temporaries(checkpoint.temporary_count);
+    }
+
+    /// Keep `value` alive across garbage collections while the interpreter
+    /// holds onto it, until the temporaries are
+    /// [truncated][Self::truncate_temporaries()] to fewer than there are
+    /// now. Only objects need to be kept alive, so other values are
+    /// ignored.
+    pub fn push_temporary(&mut self, value: &Value) {
+        if let Value::Object(obj_ref) = *value {
+            self.temporaries.push(obj_ref);
+        }
+    }
+
+    pub fn temporary_count(&self) -> usize {
+        self.temporaries.len()
+    }
+
+    /// Stop keeping alive the temporaries pushed since there were `count` of
+    /// them, e.g. at the end of a statement.
+    pub fn truncate_temporaries(&mut self, count: usize) {
+        self.temporaries.truncate(count);
+    }
+
     pub fn scope(&self) -> ScopeId {
         self.frame().scope
     }
 
-    pub fn receiver(&self) -> Option<Reference> {
-        self.frame().receiver
+    pub fn receiver(&self) -> Value {
+        self.frame().receiver.clone()
+    }
+
+    /// Whether the code running in the current frame is strict mode code.
+    pub fn is_strict(&self) -> bool {
+        self.frame().strict
+    }
+
+    pub fn set_strict(&mut self, strict: bool) {
+        self.frame_mut().strict = strict;
     }
 
     pub fn push_empty_scope(
@@ -119,6 +281,20 @@ impl CallStack {
         self.scopes.with_variable_mut(self.frame().scope, name, op)
     }
 
+    /// The variable in the given slot of the scope `depth` scopes out from the
+    /// current one, as resolved by the [bytecode compiler][crate::bytecode].
+    /// Code run by the tree-walking interpreter uses
+    /// [`lookup_variable`][Self::lookup_variable] instead.
+    pub fn variable_at(&self, depth: usize, slot: usize) -> &Variable {
+        let scope = self.scopes.ancestor(self.frame().scope, depth);
+        &self.scopes.lookup(scope).slots[slot]
+    }
+
+    pub fn variable_at_mut(&mut self, depth: usize, slot: usize) -> &mut Variable {
+        let scope = self.scopes.ancestor(self.frame().scope, depth);
+        &mut self.scopes.lookup_mut(scope).slots[slot]
+    }
+
     pub fn declare_variable(
         &mut self,
         variable: Variable,
@@ -126,6 +302,16 @@ impl CallStack {
         self.scopes.declare_variable(self.frame().scope, variable)
     }
 
+    /// Initialise a variable in the current scope which was previously declared
+    /// as uninitialised, or declare it if it wasn't.
+    pub fn initialise_variable(
+        &mut self,
+        variable: Variable,
+    ) -> Result<(), VariableAlreadyDefinedError> {
+        self.scopes
+            .initialise_variable(self.frame().scope, variable)
+    }
+
     pub fn declare_variable_within_escalation_boundary(
         &mut self,
         variable: Variable,
@@ -133,16 +319,155 @@ impl CallStack {
         self.scopes
             .declare_variable_within_escalation_boundary(self.frame().scope, variable)
     }
+
+    /// The number of scopes which haven't been freed by the garbage collector,
+    /// whether or not they're still reachable.
+    pub fn live_scopes(&self) -> usize {
+        self.scopes.scopes.len() - self.scopes.free_slots.len()
+    }
+
+    /// Whether enough scopes have been allocated since the last collection that
+    /// it's worth collecting again, as for [`Heap::should_collect()`].
+    pub fn should_collect(&self) -> bool {
+        let threshold = self
+            .scopes
+            .survived_collection
+            .max(Heap::MIN_COLLECTION_THRESHOLD);
+        self.scopes.allocated_since_collection >= threshold
+    }
+
+    pub(super) fn scope_capacity(&self) -> usize {
+        self.scopes.scopes.len()
+    }
+
+    pub(super) fn trace_scope(&self, scope: ScopeId, tracer: &mut Tracer) {
+        let scope = self.scopes.lookup(scope);
+        if let Some(parent) = scope.parent {
+            tracer.visit_scope(parent);
+        }
+        for variable in &scope.slots {
+            variable.value.trace(tracer);
+        }
+    }
+
+    /// Free every scope which wasn't marked as reachable by the tracer. See
+    /// [`Vm::collect_garbage()`][super::Vm::collect_garbage()].
+    pub(super) fn sweep_scopes(&mut self, tracer: &Tracer) {
+        for (idx, slot) in self.scopes.scopes.iter_mut().enumerate() {
+            if slot.is_some() && !tracer.is_scope_marked(ScopeId(idx)) {
+                *slot = None;
+                self.scopes.free_slots.push(idx);
+            }
+        }
+        self.scopes.allocated_since_collection = 0;
+        self.scopes.survived_collection = self.live_scopes();
+    }
+}
+
+/// The current scope of each frame is a root, which keeps its outer scopes
+/// alive as well. Other scopes are only reachable through the functions which
+/// were declared in them, i.e. closures.
+impl Trace for CallStack {
+    fn trace(&self, tracer: &mut Tracer) {
+        for frame in self.frames.iter().chain([&self.root]) {
+            tracer.visit_scope(frame.scope);
+            frame.receiver.trace(tracer);
+        }
+        for &obj_ref in self
+            .temporaries
+            .iter()
+            .chain(self.native_calls.iter().flatten())
+        {
+            tracer.visit(obj_ref);
+        }
+    }
+}
+
+/// The approximate address of the top of the native stack, for measuring how
+/// much of it is in use. The direction the stack grows in doesn't matter, as
+/// only the distance between two addresses is used.
+#[inline(never)]
+fn native_stack_address() -> usize {
+    let marker = 0_u8;
+    hint::black_box(ptr::addr_of!(marker)) as usize
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct StackCheckpoint {
+    frame_count: usize,
+    scope: ScopeId,
+    temporary_count: usize,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct StackTrace(Vec<StackTraceFrame>);
+
+impl StackTrace {
+    pub fn frames(&self) -> &[StackTraceFrame] {
+        &self.0
+    }
+}
+
+/// One line per frame, each preceded by a line terminator, so that the trace
+/// may be appended to a description of the error.
+impl fmt::Display for StackTrace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for frame in self.frames() {
+            write!(f, "\n    {frame}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct StackTraceFrame {
+    function_name: Option<Identifier>,
+    loc: SourceLocation,
+}
+
+impl StackTraceFrame {
+    /// The name of the function running in the frame, or `None` for the root
+    /// frame.
+    pub fn function_name(&self) -> Option<&Identifier> {
+        self.function_name.as_ref()
+    }
+
+    pub fn source_location(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+impl fmt::Display for StackTraceFrame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let location = self.loc.location().display();
+        let position = self.loc.position();
+        match self.function_name() {
+            Some(function_name) => write!(f, "at {function_name} ({location}:{position})"),
+            None => write!(f, "at {location}:{position}"),
+        }
+    }
 }
 
 struct CallFrame {
     scope: ScopeId,
-    receiver: Option<Reference>,
+    receiver: Value,
+    strict: bool,
+    function_name: Option<Identifier>,
+    /// See [`CallStack::set_current_location()`].
+    current_loc: Option<SourceLocation>,
 }
 
+/// Scopes are allocated separately from the [`CallStack`] frames, as closures
+/// may refer to them after they've been popped. They're freed by the garbage
+/// collector once they're no longer reachable, and their slots are reused.
 #[derive(Default)]
 struct ScopeStack {
-    scopes: Vec<Scope>,
+    scopes: Vec<Option<Scope>>,
+    free_slots: Vec<usize>,
+    /// Number of scopes allocated since the last collection.
+    allocated_since_collection: usize,
+    /// Number of scopes which survived the last collection.
+    survived_collection: usize,
 }
 
 impl ScopeStack {
@@ -205,6 +530,14 @@ impl ScopeStack {
         self.lookup_mut(scope).declare_variable(variable)
     }
 
+    fn initialise_variable(
+        &mut self,
+        scope: ScopeId,
+        variable: Variable,
+    ) -> Result<(), VariableAlreadyDefinedError> {
+        self.lookup_mut(scope).initialise_variable(variable)
+    }
+
     fn declare_variable_within_escalation_boundary(
         &mut self,
         scope: ScopeId,
@@ -222,28 +555,52 @@ impl ScopeStack {
         }
     }
 
+    fn ancestor(&self, mut id: ScopeId, depth: usize) -> ScopeId {
+        for _ in 0..depth {
+            id = self
+                .lookup(id)
+                .parent
+                .expect("scope is not nested that deeply");
+        }
+        id
+    }
+
     fn lookup(&self, id: ScopeId) -> &Scope {
-        &self.scopes[id.0]
+        self.scopes[id.0]
+            .as_ref()
+            .unwrap_or_else(|| panic!("dangling scope: {id:?}"))
     }
 
     fn lookup_mut(&mut self, id: ScopeId) -> &mut Scope {
-        &mut self.scopes[id.0]
+        self.scopes[id.0]
+            .as_mut()
+            .unwrap_or_else(|| panic!("dangling scope: {id:?}"))
     }
 
     fn allocate(&mut self, scope: Scope) -> Result<ScopeId, OutOfStackSpaceError> {
-        let idx = self.scopes.len();
-        if idx != usize::MAX {
-            self.scopes.push(scope);
-            Ok(ScopeId(idx))
+        let idx = if let Some(idx) = self.free_slots.pop() {
+            self.scopes[idx] = Some(scope);
+            idx
+        } else if self.scopes.len() != usize::MAX {
+            self.scopes.push(Some(scope));
+            self.scopes.len() - 1
         } else {
-            Err(OutOfStackSpaceError::new())
-        }
+            return Err(OutOfStackSpaceError::new());
+        };
+        self.allocated_since_collection += 1;
+        Ok(ScopeId(idx))
     }
 }
 
 #[derive(Clone, Copy, Debug)]
 pub struct ScopeId(usize);
 
+impl ScopeId {
+    pub(super) fn index(self) -> usize {
+        self.0
+    }
+}
+
 #[derive(Debug)]
 struct Scope {
     parent: Option<ScopeId>,
@@ -273,11 +630,11 @@ impl Scope {
     }
 
     fn lookup_variable(&self, name: &Identifier) -> Option<&Variable> {
-        self.slots.iter().find(|var| &var.name == name)
+        self.slots.iter().find(|var| *var.name == *name)
     }
 
     fn lookup_variable_mut(&mut self, name: &Identifier) -> Option<&mut Variable> {
-        self.slots.iter_mut().find(|var| &var.name == name)
+        self.slots.iter_mut().find(|var| *var.name == *name)
     }
 
     fn declare_variable(&mut self, variable: Variable) -> Result<(), VariableAlreadyDefinedError> {
@@ -288,25 +645,55 @@ impl Scope {
             Err(VariableAlreadyDefinedError::new(variable.name().clone()))
         }
     }
+
+    fn initialise_variable(
+        &mut self,
+        variable: Variable,
+    ) -> Result<(), VariableAlreadyDefinedError> {
+        match self.lookup_variable_mut(variable.name()) {
+            Some(existing) if !existing.is_initialised() => {
+                *existing = variable;
+                Ok(())
+            }
+            Some(_) => Err(VariableAlreadyDefinedError::new(variable.name().clone())),
+            None => {
+                self.slots.push(variable);
+                Ok(())
+            }
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct Variable {
     kind: VariableKind,
-    name: Identifier,
-    value: Value,
+    /// Shared with every other variable created for the same declaration, e.g.
+    /// on each iteration of a loop.
+    name: Rc<Identifier>,
+    /// `None` until the variable is initialised.
+    value: Option<Value>,
 }
 
 impl Variable {
-    pub fn new_unassigned(kind: VariableKind, name: Identifier) -> Self {
+    pub fn new_unassigned(kind: VariableKind, name: impl Into<Rc<Identifier>>) -> Self {
         Self::new(kind, name, Value::default())
     }
 
-    pub fn new(kind: VariableKind, name: Identifier, initial_value: Value) -> Self {
+    /// A variable which can't be accessed until it's initialised by its
+    /// declaration, i.e. which is in its _temporal dead zone_.
+    pub fn new_uninitialised(kind: VariableKind, name: impl Into<Rc<Identifier>>) -> Self {
+        Self {
+            kind,
+            name: name.into(),
+            value: None,
+        }
+    }
+
+    pub fn new(kind: VariableKind, name: impl Into<Rc<Identifier>>, initial_value: Value) -> Self {
         Self {
             kind,
-            name,
-            value: initial_value,
+            name: name.into(),
+            value: Some(initial_value),
         }
     }
 
@@ -318,17 +705,34 @@ impl Variable {
         &self.name
     }
 
-    pub fn value(&self) -> Value {
+    pub fn is_initialised(&self) -> bool {
+        self.value.is_some()
+    }
+
+    pub fn value(&self) -> Result<Value, VariableNotInitialisedError> {
         self.value
+            .clone()
+            .ok_or_else(|| VariableNotInitialisedError::new(self.name().clone()))
+    }
+
+    /// Initialise the variable by its declaration, which unlike
+    /// [`Self::set_value()`] is allowed for `const` variables.
+    pub fn initialise(&mut self, value: Value) {
+        self.value = Some(value);
     }
 
-    pub fn set_value(&mut self, value: Value) -> Result<(), AssignToConstVariableError> {
+    pub fn set_value(&mut self, value: Value) -> Result<(), ErrorKind> {
         match self.kind {
+            _ if !self.is_initialised() => Err(ErrorKind::from(VariableNotInitialisedError::new(
+                self.name().clone(),
+            ))),
             VariableKind::Let | VariableKind::Var => {
-                self.value = value;
+                self.value = Some(value);
                 Ok(())
             }
-            VariableKind::Const => Err(AssignToConstVariableError::new(self.name().clone())),
+            VariableKind::Const => Err(ErrorKind::from(AssignToConstVariableError::new(
+                self.name().clone(),
+            ))),
         }
     }
 }
@@ -38,14 +38,20 @@ impl Eval for EmptyStatement {
 
 impl Eval for BlockStatement {
     fn eval(&self, it: &mut Interpreter) -> Result<Self::Output> {
-        self.block.eval(it).map(|_| ())
+        it.vm_mut()
+            .stack_mut()
+            .push_empty_scope(false)
+            .map_err(|err| Error::new(err, self.source_location()))?;
+        self.block.eval(it)?;
+        it.vm_mut().stack_mut().pop_scope();
+        Ok(())
     }
 }
 
 impl Eval for IfStatement {
     fn eval(&self, it: &mut Interpreter) -> Result<Self::Output> {
         let condition = self.condition.eval(it)?;
-        if it.is_truthy(condition) {
+        if it.is_truthy(&condition) {
             it.vm_mut()
                 .stack_mut()
                 .push_empty_scope(false)
@@ -72,7 +78,7 @@ impl Eval for SwitchStatement {
         // Skip cases while `actual != expected`.
         while let Some(case) = cases.peek() {
             let expected = case.pattern.eval(it)?;
-            if it.equal(expected, value) {
+            if it.is_strictly_equal(&expected, &value) {
                 break;
             }
             cases.next().unwrap();
@@ -94,7 +100,8 @@ impl Eval for SwitchStatement {
             | ExecutionState::Continue
             | ExecutionState::Return(_)
             | ExecutionState::Exception(_)
-            | ExecutionState::Exit => {
+            | ExecutionState::Exit
+            | ExecutionState::Interrupt => {
                 // Don't reset the execution state just yet so that it can be
                 // handled/cleared by some calling AST node.
             }
@@ -172,7 +179,16 @@ impl Eval for FinallyStatement {
 
 impl Eval for DoStatement {
     fn eval(&self, it: &mut Interpreter) -> Result<Self::Output> {
+        let temporary_count = it.vm().stack().temporary_count();
         loop {
+            // Nothing evaluated by previous iterations is still needed.
+            it.vm_mut()
+                .stack_mut()
+                .truncate_temporaries(temporary_count);
+            it.vm_mut().safe_point();
+            if it.vm_mut().check_interrupt() {
+                break;
+            }
             it.vm_mut()
                 .stack_mut()
                 .push_empty_scope(false)
@@ -187,7 +203,7 @@ impl Eval for DoStatement {
             }
 
             let condition = self.condition.eval(it)?;
-            if !it.is_truthy(condition) {
+            if !it.is_truthy(&condition) {
                 break;
             }
         }
@@ -204,10 +220,19 @@ impl Eval for ForStatement {
                 .map_err(|err| Error::new(err, self.source_location()))?;
             initialiser.eval(it)?;
         }
+        let temporary_count = it.vm().stack().temporary_count();
         loop {
+            // Nothing evaluated by previous iterations is still needed.
+            it.vm_mut()
+                .stack_mut()
+                .truncate_temporaries(temporary_count);
+            it.vm_mut().safe_point();
+            if it.vm_mut().check_interrupt() {
+                break;
+            }
             if let Some(ref condition) = self.condition {
                 let condition = condition.eval(it)?;
-                if !it.is_truthy(condition) {
+                if !it.is_truthy(&condition) {
                     break;
                 }
             }
@@ -248,9 +273,18 @@ impl Eval for ForInitialiser {
 
 impl Eval for WhileStatement {
     fn eval(&self, it: &mut Interpreter) -> Result<Self::Output> {
+        let temporary_count = it.vm().stack().temporary_count();
         loop {
+            // Nothing evaluated by previous iterations is still needed.
+            it.vm_mut()
+                .stack_mut()
+                .truncate_temporaries(temporary_count);
+            it.vm_mut().safe_point();
+            if it.vm_mut().check_interrupt() {
+                break;
+            }
             let condition = self.condition.eval(it)?;
-            if !it.is_truthy(condition) {
+            if !it.is_truthy(&condition) {
                 break;
             }
 
@@ -292,8 +326,11 @@ impl Eval for ReturnStatement {
         } else {
             Value::Undefined
         };
-        it.vm_mut()
-            .set_execution_state(ExecutionState::Return(value));
+        // If evaluating the value threw an exception then it takes precedence.
+        if matches!(it.vm().execution_state(), ExecutionState::Advance) {
+            it.vm_mut()
+                .set_execution_state(ExecutionState::Return(value));
+        }
         Ok(())
     }
 }
@@ -301,8 +338,10 @@ impl Eval for ReturnStatement {
 impl Eval for ThrowStatement {
     fn eval(&self, it: &mut Interpreter) -> Result<Self::Output> {
         let ex = self.exception.eval(it)?;
-        it.vm_mut()
-            .set_execution_state(ExecutionState::Exception(ex));
+        if matches!(it.vm().execution_state(), ExecutionState::Advance) {
+            it.vm_mut()
+                .set_execution_state(ExecutionState::Exception(ex));
+        }
         Ok(())
     }
 }
@@ -0,0 +1,389 @@
+use std::borrow::Borrow;
+use std::cell::{Cell, OnceCell};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+use std::{char, fmt};
+
+/// An immutable string value, as a sequence of UTF-16 code units. Cloning is
+/// cheap, as the code units are reference counted rather than copied.
+///
+/// Like in other engines, the code units needn't be valid UTF-16, i.e. they may
+/// contain unpaired surrogates. They're only converted to Rust strings, which
+/// replaces any unpaired surrogates, when leaving the engine (e.g. when
+/// printed).
+///
+/// Concatenating long strings doesn't copy them, but creates a rope which
+/// refers to both halves. The rope is flattened the first time its code units
+/// are needed, e.g. when it's indexed or compared, so that building up a string
+/// by repeatedly appending to it takes linear rather than quadratic time.
+#[derive(Clone)]
+pub struct JsString(Rc<Repr>);
+
+struct Repr {
+    len: usize,
+    /// Always set for flat strings, and set for ropes once they're flattened.
+    code_units: OnceCell<Box<[u16]>>,
+    /// The halves of a rope which hasn't been flattened yet.
+    rope: Cell<Option<(JsString, JsString)>>,
+}
+
+impl JsString {
+    /// Concatenations shorter than this are copied eagerly, as a rope wouldn't
+    /// be any cheaper to create.
+    const MIN_ROPE_LEN: usize = 32;
+
+    fn flat(code_units: Box<[u16]>) -> Self {
+        Self(Rc::new(Repr {
+            len: code_units.len(),
+            code_units: OnceCell::from(code_units),
+            rope: Cell::new(None),
+        }))
+    }
+
+    /// The code units of the string, flattening it first if it's a rope.
+    pub fn as_utf16(&self) -> &[u16] {
+        self.0.code_units.get_or_init(|| self.flatten())
+    }
+
+    /// The length in UTF-16 code units, which is also the `length` property of
+    /// the string.
+    pub fn len(&self) -> usize {
+        self.0.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn code_unit_at(&self, idx: usize) -> Option<u16> {
+        self.as_utf16().get(idx).copied()
+    }
+
+    /// [CodePointAt](https://tc39.es/ecma262/#sec-codepointat), i.e. the code
+    /// point starting at the code unit index, which is either a surrogate pair
+    /// or a single code unit (including an unpaired surrogate).
+    pub fn code_point_at(&self, idx: usize) -> Option<u32> {
+        let first = self.code_unit_at(idx)?;
+        if is_leading_surrogate(first)
+            && let Some(second) = self.code_unit_at(idx + 1)
+            && is_trailing_surrogate(second)
+        {
+            let high = u32::from(first) - 0xD800;
+            let low = u32::from(second) - 0xDC00;
+            return Some((high << 10) + low + 0x10000);
+        }
+        Some(u32::from(first))
+    }
+
+    /// The code units from `start` up to, but not including, `end`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn substring(&self, start: usize, end: usize) -> Self {
+        Self::from(&self.as_utf16()[start..end])
+    }
+
+    pub fn concat(&self, other: &Self) -> Self {
+        if other.is_empty() {
+            return self.clone();
+        }
+        if self.is_empty() {
+            return other.clone();
+        }
+        let len = self.len() + other.len();
+        if len < Self::MIN_ROPE_LEN {
+            return Self::from([self.as_utf16(), other.as_utf16()].concat());
+        }
+        Self(Rc::new(Repr {
+            len,
+            code_units: OnceCell::new(),
+            rope: Cell::new(Some((self.clone(), other.clone()))),
+        }))
+    }
+
+    /// Whether the string is a rope which hasn't been flattened yet.
+    pub fn is_rope(&self) -> bool {
+        self.0.code_units.get().is_none()
+    }
+
+    /// [UTF16EncodeCodePoint](https://tc39.es/ecma262/#sec-utf16encodecodepoint),
+    /// which unlike [`char::encode_utf16()`] accepts lone surrogates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the code point is greater than `0x10FFFF`.
+    pub fn from_code_point(code_point: u32) -> Self {
+        assert!(
+            code_point <= 0x10FFFF,
+            "invalid code point: {code_point:#x}"
+        );
+        if let Ok(code_unit) = u16::try_from(code_point) {
+            return Self::from([code_unit].as_slice());
+        }
+        let code_point = code_point - 0x10000;
+        let high = 0xD800 + u16::try_from(code_point >> 10).unwrap();
+        let low = 0xDC00 + u16::try_from(code_point & 0x3FF).unwrap();
+        Self::from([high, low].as_slice())
+    }
+
+    /// Whether both strings share the same allocation, e.g. because they were
+    /// both interned by the same [`StringTable`].
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// Decode the string, replacing any unpaired surrogates with
+    /// [`char::REPLACEMENT_CHARACTER`].
+    pub fn to_string_lossy(&self) -> String {
+        self.chars_lossy().collect()
+    }
+
+    fn chars_lossy(&self) -> impl Iterator<Item = char> + '_ {
+        char::decode_utf16(self.as_utf16().iter().copied())
+            .map(|ch| ch.unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+
+    /// Copy the leaves of the rope into a single buffer. The rope may be
+    /// arbitrarily deep (e.g. after appending to a string in a loop), so it's
+    /// walked iteratively rather than recursively.
+    fn flatten(&self) -> Box<[u16]> {
+        let (lhs, rhs) = self
+            .0
+            .rope
+            .take()
+            .expect("string is neither flat nor a rope");
+        let mut out = Vec::with_capacity(self.len());
+        let mut pending = vec![rhs, lhs];
+        while let Some(s) = pending.pop() {
+            if let Some(code_units) = s.0.code_units.get() {
+                out.extend_from_slice(code_units);
+                continue;
+            }
+            // Other strings may still refer to this part of the rope, so it's
+            // left intact rather than flattened as well.
+            let (lhs, rhs) = s.0.rope.take().expect("string is neither flat nor a rope");
+            pending.push(rhs.clone());
+            pending.push(lhs.clone());
+            s.0.rope.set(Some((lhs, rhs)));
+        }
+        debug_assert_eq!(out.len(), self.len());
+        out.into_boxed_slice()
+    }
+}
+
+impl Default for JsString {
+    fn default() -> Self {
+        Self::from(Vec::new())
+    }
+}
+
+impl PartialEq for JsString {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other) || self.len() == other.len() && self.as_utf16() == other.as_utf16()
+    }
+}
+
+impl Eq for JsString {}
+
+impl PartialOrd for JsString {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for JsString {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_utf16().cmp(other.as_utf16())
+    }
+}
+
+impl Hash for JsString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Must match the hash of `[u16]`, for lookups through `Borrow<[u16]>`.
+        self.as_utf16().hash(state);
+    }
+}
+
+impl Borrow<[u16]> for JsString {
+    fn borrow(&self) -> &[u16] {
+        self.as_utf16()
+    }
+}
+
+impl fmt::Debug for JsString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.to_string_lossy(), f)
+    }
+}
+
+impl fmt::Display for JsString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.chars_lossy()
+            .try_for_each(|ch| fmt::Write::write_char(f, ch))
+    }
+}
+
+impl From<&[u16]> for JsString {
+    fn from(code_units: &[u16]) -> Self {
+        Self::flat(Box::from(code_units))
+    }
+}
+
+impl From<Vec<u16>> for JsString {
+    fn from(code_units: Vec<u16>) -> Self {
+        Self::flat(code_units.into_boxed_slice())
+    }
+}
+
+impl From<&str> for JsString {
+    fn from(s: &str) -> Self {
+        Self::from(s.encode_utf16().collect::<Vec<_>>())
+    }
+}
+
+impl From<String> for JsString {
+    fn from(s: String) -> Self {
+        Self::from(s.as_str())
+    }
+}
+
+impl From<Box<str>> for JsString {
+    fn from(s: Box<str>) -> Self {
+        Self::from(s.as_ref())
+    }
+}
+
+impl Drop for Repr {
+    fn drop(&mut self) {
+        // Dropping a deep rope recursively could overflow the stack, so any
+        // parts of the rope which aren't referred to elsewhere are unlinked
+        // and dropped one at a time instead.
+        let mut pending = Vec::new();
+        pending.extend(
+            self.rope
+                .take()
+                .into_iter()
+                .flat_map(|(lhs, rhs)| [lhs, rhs]),
+        );
+        while let Some(s) = pending.pop() {
+            if let Ok(repr) = Rc::try_unwrap(s.0) {
+                pending.extend(
+                    repr.rope
+                        .take()
+                        .into_iter()
+                        .flat_map(|(lhs, rhs)| [lhs, rhs]),
+                );
+            }
+        }
+    }
+}
+
+fn is_leading_surrogate(code_unit: u16) -> bool {
+    (0xD800..=0xDBFF).contains(&code_unit)
+}
+
+fn is_trailing_surrogate(code_unit: u16) -> bool {
+    (0xDC00..=0xDFFF).contains(&code_unit)
+}
+
+/// Deduplicates strings which are likely to be created repeatedly, e.g. by
+/// evaluating the same string literal in a loop, so that they share a single
+/// allocation.
+///
+/// Interned strings are reference counted like any other [`JsString`], and are
+/// evicted from the table once nothing else refers to them.
+#[derive(Default)]
+pub struct StringTable {
+    strings: HashSet<JsString>,
+    /// The size of the table after it was last purged of unused strings.
+    live_count: usize,
+}
+
+impl StringTable {
+    pub fn intern(&mut self, s: &str) -> JsString {
+        self.intern_utf16(&s.encode_utf16().collect::<Vec<_>>())
+    }
+
+    pub fn intern_utf16(&mut self, code_units: &[u16]) -> JsString {
+        if let Some(interned) = self.strings.get(code_units) {
+            return interned.clone();
+        }
+        // Amortise the cost of purging by waiting until the table has doubled in size.
+        if self.strings.len() >= 2 * self.live_count.max(64) {
+            self.purge();
+        }
+        let interned = JsString::from(code_units);
+        self.strings.insert(interned.clone());
+        interned
+    }
+
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    /// Evict strings which are only referenced by the table itself.
+    pub fn purge(&mut self) {
+        self.strings
+            .retain(|interned| Rc::strong_count(&interned.0) > 1);
+        self.live_count = self.strings.len();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::JsString;
+
+    #[test]
+    fn short_concatenations_are_flat() {
+        let s = JsString::from("foo").concat(&JsString::from("bar"));
+        assert!(!s.is_rope());
+        assert_eq!(s, JsString::from("foobar"));
+    }
+
+    #[test]
+    fn long_concatenations_are_ropes_until_flattened() {
+        let lhs = JsString::from("a".repeat(20));
+        let rhs = JsString::from("b".repeat(20));
+        let s = lhs.concat(&rhs);
+        assert!(s.is_rope());
+        assert_eq!(s.len(), 40);
+        assert!(s.is_rope());
+        assert_eq!(s.code_unit_at(20), Some(u16::from(b'b')));
+        assert!(!s.is_rope());
+        assert_eq!(s.to_string_lossy(), "a".repeat(20) + &"b".repeat(20));
+    }
+
+    #[test]
+    fn parts_of_a_rope_are_unaffected_by_flattening_it() {
+        let lhs = JsString::from("a".repeat(20)).concat(&JsString::from("b".repeat(20)));
+        let s = lhs.concat(&JsString::from("c".repeat(20)));
+        assert_eq!(s.len(), 60);
+        assert_eq!(s.code_unit_at(59), Some(u16::from(b'c')));
+        assert!(lhs.is_rope());
+        assert_eq!(lhs.to_string_lossy(), "a".repeat(20) + &"b".repeat(20));
+    }
+
+    #[test]
+    fn deep_ropes_are_flattened_and_dropped_without_overflowing_the_stack() {
+        let x = JsString::from("x");
+        let mut s = JsString::from("x".repeat(32));
+        for _ in 0..1_000_000 {
+            s = s.concat(&x);
+        }
+        let unflattened = s.clone();
+        assert_eq!(s.len(), 1_000_032);
+        assert!(s
+            .as_utf16()
+            .iter()
+            .all(|&code_unit| code_unit == u16::from(b'x')));
+        drop(s);
+        drop(unflattened);
+    }
+}
@@ -1,11 +1,15 @@
-use super::heap::Reference;
+use super::heap::{Reference, Trace, Tracer};
+use super::string::JsString;
+use crate::ast::NumericLiteral;
+use crate::token::symbol::{is_line_terminator, is_whitespace};
 use std::str::FromStr;
 use std::{cmp, fmt, num, ops};
 
-#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub enum Value {
     Boolean(bool),
     Number(Number),
+    String(JsString),
     Object(Reference),
     Null,
     #[default]
@@ -18,6 +22,14 @@ impl Value {
     }
 }
 
+impl Trace for Value {
+    fn trace(&self, tracer: &mut Tracer) {
+        if let Self::Object(obj_ref) = *self {
+            tracer.visit(obj_ref);
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub enum Number {
     Float(f64),
@@ -25,6 +37,10 @@ pub enum Number {
 }
 
 impl Number {
+    /// Integers outside of this range can't all be represented exactly by an
+    /// `f64`, so results are only stored as a [`Number::Int`] when they're
+    /// within it, to keep arithmetic identical to IEEE-754.
+    const MAX_SAFE_INT: i64 = (1 << 53) - 1;
     pub const NAN: Self = Self::Float(f64::NAN);
     pub const NEG_INF: Self = Self::Float(f64::NEG_INFINITY);
     pub const ONE: Self = Self::Int(1);
@@ -90,85 +106,303 @@ impl Number {
         }
     }
 
-    pub fn checked_neg(self) -> Option<Self> {
-        Some(match self {
-            Self::Float(value) => Self::Float(-value),
-            Self::Int(value) => Self::Int(value.checked_neg()?),
-        })
+    pub fn abs(self) -> Self {
+        match self {
+            Self::Float(value) => Self::Float(value.abs()),
+            Self::Int(value) => value
+                .checked_abs()
+                .map_or_else(|| Self::Float(self.as_f64().abs()), Self::from_int),
+        }
     }
 
-    pub fn checked_abs(self) -> Option<Self> {
-        Some(match self {
-            Self::Float(value) => Self::Float(value.abs()),
-            Self::Int(value) => Self::Int(value.checked_abs()?),
-        })
+    /// The `**` operator, as described in
+    /// [Number::exponentiate](https://tc39.es/ecma262/#sec-numeric-types-number-exponentiate).
+    pub fn pow(self, exp: Self) -> Self {
+        if exp.is_nan() {
+            return Self::NAN;
+        }
+        if exp.is_zero() {
+            return Self::ONE;
+        }
+        if self.is_nan() {
+            return Self::NAN;
+        }
+        // Unlike `powf()`, `1 ** Infinity` is NaN rather than 1.
+        if self.as_f64().abs() == 1.0 && exp.is_infinite() {
+            return Self::NAN;
+        }
+        if let (Self::Int(base), Self::Int(exp)) = (self, exp)
+            && let Ok(exp) = u32::try_from(exp)
+            && let Some(value) = base.checked_pow(exp)
+        {
+            return Self::from_int(value);
+        }
+        Self::Float(self.as_f64().powf(exp.as_f64()))
+    }
+
+    pub fn sqrt(self) -> Self {
+        Self::Float(self.as_f64().sqrt())
+    }
+
+    /// The `>>>` operator, which unlike `>>` treats the left operand as an
+    /// unsigned integer.
+    pub fn shr_unsigned(self, rhs: Self) -> Self {
+        Self::Int(i64::from(self.to_uint32() >> rhs.shift_count()))
+    }
+
+    /// [Number::toString](https://tc39.es/ecma262/#sec-numeric-types-number-tostring)
+    /// with any radix between 2 and 36. The digits of non-integers are
+    /// implementation-defined for radixes other than 10, so this generates as
+    /// many as are needed to round-trip the value, like V8 does.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radix` is out of range.
+    pub fn to_string_radix(self, radix: u32) -> String {
+        assert!((2..=36).contains(&radix), "radix out of range: {radix}");
+        if radix == 10 || !self.is_finite() || self.is_nan() {
+            return self.to_string();
+        }
+        if let Self::Int(value) = self {
+            let mut digits = Vec::new();
+            let mut magnitude = value.unsigned_abs();
+            loop {
+                let digit = u32::try_from(magnitude % u64::from(radix)).unwrap();
+                digits.push(char::from_digit(digit, radix).unwrap());
+                magnitude /= u64::from(radix);
+                if magnitude == 0 {
+                    break;
+                }
+            }
+            if value < 0 {
+                digits.push('-');
+            }
+            return digits.into_iter().rev().collect();
+        }
+
+        let value = self.as_f64();
+        let radix_f = f64::from(radix);
+        let mut integer = value.abs().floor();
+        let mut fraction = value.abs() - integer;
+        // Only generate as many fractional digits as needed to distinguish the value
+        // from the adjacent floats, i.e. half the distance to the next one.
+        let mut delta = f64::max(
+            0.5 * (f64::from_bits(value.abs().to_bits() + 1) - value.abs()),
+            f64::from_bits(1),
+        );
+        let mut frac_digits = Vec::new();
+        if fraction >= delta {
+            loop {
+                fraction *= radix_f;
+                delta *= radix_f;
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                let digit = fraction as u32;
+                frac_digits.push(digit);
+                fraction -= f64::from(digit);
+                if (fraction > 0.5 || (fraction == 0.5 && digit & 1 == 1)) && fraction + delta > 1.0
+                {
+                    // Round up, carrying into the integer part if necessary.
+                    loop {
+                        match frac_digits.pop() {
+                            Some(digit) if digit + 1 < radix => {
+                                frac_digits.push(digit + 1);
+                                break;
+                            }
+                            Some(_) => {}
+                            None => {
+                                integer += 1.0;
+                                break;
+                            }
+                        }
+                    }
+                    break;
+                }
+                if fraction < delta {
+                    break;
+                }
+            }
+        }
+
+        let mut int_digits = Vec::new();
+        // Digits below the precision of the float are always zero.
+        while integer / radix_f >= 2_f64.powi(53) {
+            integer /= radix_f;
+            int_digits.push('0');
+        }
+        loop {
+            let remainder = integer % radix_f;
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            int_digits.push(char::from_digit(remainder as u32, radix).unwrap());
+            integer = (integer - remainder) / radix_f;
+            if integer <= 0.0 {
+                break;
+            }
+        }
+        if value.is_sign_negative() {
+            int_digits.push('-');
+        }
+        let mut out: String = int_digits.into_iter().rev().collect();
+        if !frac_digits.is_empty() {
+            out.push('.');
+            out.extend(
+                frac_digits
+                    .into_iter()
+                    .map(|digit| char::from_digit(digit, radix).unwrap()),
+            );
+        }
+        out
+    }
+
+    /// [ToInt32](https://tc39.es/ecma262/#sec-toint32), which is applied to the
+    /// operands of the bitwise operators.
+    pub fn to_int32(self) -> i32 {
+        #[allow(clippy::cast_possible_truncation)]
+        let value = self.to_uint32() as i32;
+        value
+    }
+
+    /// [ToUint32](https://tc39.es/ecma262/#sec-touint32)
+    pub fn to_uint32(self) -> u32 {
+        match self {
+            // Truncating to the low 32 bits is the same as taking the value modulo 2^32.
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            Self::Int(value) => value as u32,
+            Self::Float(value) if !value.is_finite() => 0,
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            Self::Float(value) => value.trunc().rem_euclid(4_294_967_296.0) as u32,
+        }
+    }
+
+    fn from_int(value: i64) -> Self {
+        if (-Self::MAX_SAFE_INT..=Self::MAX_SAFE_INT).contains(&value) {
+            Self::Int(value)
+        } else {
+            #[allow(clippy::cast_precision_loss)]
+            Self::Float(value as f64)
+        }
+    }
+
+    /// The number of bits to shift by when `self` is the right operand of a
+    /// shift operator, which is always less than 32.
+    fn shift_count(self) -> u32 {
+        self.to_uint32() & 0x1F
     }
 
-    pub fn checked_add(self, rhs: Self) -> Option<Self> {
-        Some(match (self, rhs) {
-            (Self::Int(lhs), Self::Int(rhs)) => Self::Int(i64::checked_add(lhs, rhs)?),
+    fn negative_zero() -> Self {
+        Self::Float(-0.0)
+    }
+}
+
+impl ops::Neg for Number {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        match self {
+            Self::Float(value) => Self::Float(-value),
+            Self::Int(0) => Self::negative_zero(),
+            Self::Int(value) => value
+                .checked_neg()
+                .map_or_else(|| Self::Float(-self.as_f64()), Self::from_int),
+        }
+    }
+}
+
+impl ops::Add for Number {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        match (self, other) {
+            (Self::Int(lhs), Self::Int(rhs)) => lhs.checked_add(rhs).map_or_else(
+                || Self::Float(self.as_f64() + other.as_f64()),
+                Self::from_int,
+            ),
             (lhs, rhs) => Self::Float(lhs.as_f64() + rhs.as_f64()),
-        })
+        }
     }
+}
+
+impl ops::Sub for Number {
+    type Output = Self;
 
-    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
-        Some(match (self, rhs) {
-            (Self::Int(lhs), Self::Int(rhs)) => Self::Int(i64::checked_sub(lhs, rhs)?),
+    fn sub(self, other: Self) -> Self::Output {
+        match (self, other) {
+            (Self::Int(lhs), Self::Int(rhs)) => lhs.checked_sub(rhs).map_or_else(
+                || Self::Float(self.as_f64() - other.as_f64()),
+                Self::from_int,
+            ),
             (lhs, rhs) => Self::Float(lhs.as_f64() - rhs.as_f64()),
-        })
+        }
     }
+}
 
-    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
-        Some(match (self, rhs) {
-            (Self::Int(lhs), Self::Int(rhs)) => Self::Int(i64::checked_mul(lhs, rhs)?),
+impl ops::Mul for Number {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self::Output {
+        match (self, other) {
+            // The sign of zero depends on the signs of the operands, e.g. `0 * -1` is `-0`.
+            (Self::Int(0), Self::Int(other)) | (Self::Int(other), Self::Int(0)) if other < 0 => {
+                Self::negative_zero()
+            }
+            (Self::Int(lhs), Self::Int(rhs)) => lhs.checked_mul(rhs).map_or_else(
+                || Self::Float(self.as_f64() * other.as_f64()),
+                Self::from_int,
+            ),
             (lhs, rhs) => Self::Float(lhs.as_f64() * rhs.as_f64()),
-        })
+        }
     }
+}
+
+impl ops::Div for Number {
+    type Output = Self;
 
-    pub fn checked_div(self, rhs: Self) -> Option<Self> {
-        Some(match (self, rhs) {
-            (Self::Int(0), Self::Int(0)) => Self::NAN,
-            (Self::Int(lhs), Self::Int(0)) => Self::infinity(lhs),
-            (Self::Int(lhs), Self::Int(rhs)) => Self::Int(i64::checked_div(lhs, rhs)?),
+    fn div(self, rhs: Self) -> Self::Output {
+        match (self, rhs) {
+            (Self::Int(lhs), Self::Int(rhs)) if lhs.checked_rem(rhs) == Some(0) => {
+                if lhs == 0 && rhs < 0 {
+                    Self::negative_zero()
+                } else {
+                    Self::from_int(lhs / rhs)
+                }
+            }
             (lhs, rhs) => Self::Float(lhs.as_f64() / rhs.as_f64()),
-        })
+        }
     }
+}
 
-    pub fn checked_rem(self, rhs: Self) -> Option<Self> {
-        Some(match (self, rhs) {
-            (Self::Int(_), Self::Int(0)) => Self::NAN,
-            (Self::Int(lhs), Self::Int(rhs)) => Self::Int(i64::checked_rem(lhs, rhs)?),
+impl ops::Rem for Number {
+    type Output = Self;
+
+    /// The result has the same sign as the dividend, like `fmod()` in C.
+    fn rem(self, rhs: Self) -> Self::Output {
+        match (self, rhs) {
+            (Self::Int(lhs), Self::Int(rhs)) if let Some(value) = lhs.checked_rem(rhs) => {
+                if value == 0 && lhs < 0 {
+                    Self::negative_zero()
+                } else {
+                    Self::Int(value)
+                }
+            }
             (lhs, rhs) => Self::Float(lhs.as_f64() % rhs.as_f64()),
-        })
+        }
     }
+}
 
-    pub fn pow(self, exp: Self) -> Self {
-        Self::Float(self.as_f64().powf(exp.as_f64()))
-    }
+impl ops::Shl for Number {
+    type Output = Self;
 
-    pub fn sqrt(self) -> Self {
-        Self::Float(self.as_f64().sqrt())
+    fn shl(self, rhs: Self) -> Self::Output {
+        Self::Int(i64::from(self.to_int32() << rhs.shift_count()))
     }
+}
 
-    /// # Panics
-    ///
-    /// Always panics.
-    pub fn checked_shl(self, rhs: Self) -> Option<Self> {
-        todo!("Number::checked_shl: lhs={self}, rhs={rhs}")
-    }
+impl ops::Shr for Number {
+    type Output = Self;
 
-    /// # Panics
-    ///
-    /// Always panics.
-    pub fn checked_shr_signed(self, rhs: Self) -> Option<Self> {
-        todo!("Number::checked_shr_signed: lhs={self}, rhs={rhs}")
-    }
-
-    /// # Panics
-    ///
-    /// Always panics.
-    pub fn checked_shr_unsigned(self, rhs: Self) -> Option<Self> {
-        todo!("Number::checked_shr_unsigned: lhs={self}, rhs={rhs}")
+    /// The `>>` operator, which preserves the sign of the left operand. See
+    /// also [`Number::shr_unsigned()`].
+    fn shr(self, rhs: Self) -> Self::Output {
+        Self::Int(i64::from(self.to_int32() >> rhs.shift_count()))
     }
 }
 
@@ -176,13 +410,7 @@ impl ops::Not for Number {
     type Output = Self;
 
     fn not(self) -> Self::Output {
-        if self.is_nan() {
-            Self::NAN
-        } else if self.is_infinite() {
-            Self::Int(-1)
-        } else {
-            Self::Int(!self.as_i64())
-        }
+        Self::Int(i64::from(!self.to_int32()))
     }
 }
 
@@ -190,13 +418,7 @@ impl ops::BitAnd for Number {
     type Output = Self;
 
     fn bitand(self, rhs: Self) -> Self::Output {
-        if self.is_nan() || rhs.is_nan() {
-            Self::NAN
-        } else if self.is_infinite() || rhs.is_infinite() {
-            Self::Int(0)
-        } else {
-            Self::Int(self.as_i64() & self.as_i64())
-        }
+        Self::Int(i64::from(self.to_int32() & rhs.to_int32()))
     }
 }
 
@@ -204,17 +426,7 @@ impl ops::BitOr for Number {
     type Output = Self;
 
     fn bitor(self, rhs: Self) -> Self::Output {
-        if self.is_nan() || rhs.is_nan() {
-            Self::NAN
-        } else if self.is_infinite() && rhs.is_infinite() {
-            Self::Int(0)
-        } else if self.is_infinite() {
-            Self::Int(rhs.as_i64())
-        } else if rhs.is_infinite() {
-            Self::Int(self.as_i64())
-        } else {
-            Self::Int(self.as_i64() | rhs.as_i64())
-        }
+        Self::Int(i64::from(self.to_int32() | rhs.to_int32()))
     }
 }
 
@@ -222,17 +434,7 @@ impl ops::BitXor for Number {
     type Output = Self;
 
     fn bitxor(self, rhs: Self) -> Self::Output {
-        if self.is_nan() || rhs.is_nan() {
-            Self::NAN
-        } else if self.is_infinite() && rhs.is_infinite() {
-            Self::Int(0)
-        } else if self.is_infinite() {
-            Self::Int(rhs.as_i64())
-        } else if rhs.is_infinite() {
-            Self::Int(self.as_i64())
-        } else {
-            Self::Int(self.as_i64() ^ rhs.as_i64())
-        }
+        Self::Int(i64::from(self.to_int32() ^ rhs.to_int32()))
     }
 }
 
@@ -255,22 +457,53 @@ impl cmp::PartialOrd for Number {
 }
 
 impl fmt::Display for Number {
+    /// [Number::toString](https://tc39.es/ecma262/#sec-numeric-types-number-tostring)
+    /// with a radix of 10, which uses the fewest digits which still uniquely
+    /// identify the value.
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            Self::Float(value) => {
-                if value.is_nan() {
-                    f.write_str("NaN")
-                } else if value.is_infinite() {
-                    f.write_str(if value.is_sign_positive() {
-                        "Infinity"
-                    } else {
-                        "-Infinity"
-                    })
-                } else {
-                    write!(f, "{value}")
-                }
+        let value = match *self {
+            Self::Int(value) => return write!(f, "{value}"),
+            Self::Float(value) => value,
+        };
+        if value.is_nan() {
+            return f.write_str("NaN");
+        } else if value == 0.0 {
+            // Both `0` and `-0` are displayed without a sign.
+            return f.write_str("0");
+        } else if value.is_infinite() {
+            return f.write_str(if value.is_sign_positive() {
+                "Infinity"
+            } else {
+                "-Infinity"
+            });
+        }
+        if value.is_sign_negative() {
+            f.write_str("-")?;
+        }
+        // Rust's scientific notation also uses the shortest round-tripping digits, so
+        // it only needs to be rearranged into the format described by the spec.
+        let scientific = format!("{:e}", value.abs());
+        let (mantissa, exponent) = scientific.split_once('e').unwrap();
+        let digits = mantissa.replace('.', "");
+        let k = i64::try_from(digits.len()).unwrap();
+        let n = i64::from_str(exponent).unwrap() + 1;
+        let zeros = |count: i64| "0".repeat(usize::try_from(count).unwrap());
+        if k <= n && n <= 21 {
+            write!(f, "{digits}{}", zeros(n - k))
+        } else if 0 < n && n <= 21 {
+            let (int_part, frac_part) = digits.split_at(usize::try_from(n).unwrap());
+            write!(f, "{int_part}.{frac_part}")
+        } else if -6 < n && n <= 0 {
+            write!(f, "0.{}{digits}", zeros(-n))
+        } else {
+            let (first_digit, rest) = digits.split_at(1);
+            let sign = if n - 1 < 0 { '-' } else { '+' };
+            let exponent = (n - 1).abs();
+            if rest.is_empty() {
+                write!(f, "{first_digit}e{sign}{exponent}")
+            } else {
+                write!(f, "{first_digit}.{rest}e{sign}{exponent}")
             }
-            Self::Int(value) => write!(f, "{value}"),
         }
     }
 }
@@ -278,16 +511,35 @@ impl fmt::Display for Number {
 impl FromStr for Number {
     type Err = ();
 
-    // TODO: Parsing numbers at runtime should use the same logic as the lexer?
+    /// [StringToNumber](https://tc39.es/ecma262/#sec-stringtonumber), except
+    /// that it fails rather than returning `NaN` if the string isn't a valid
+    /// _StringNumericLiteral_.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if !s.is_empty() {
-            i64::from_str(s)
-                .map(Self::Int)
-                .map_err(|_| ())
-                .or_else(|_| f64::from_str(s).map(Self::Float).map_err(|_| ()))
-        } else {
-            Ok(Self::Int(0))
+        let s = s.trim_matches(|ch| is_whitespace(ch) || is_line_terminator(ch));
+        if s.is_empty() {
+            return Ok(Self::ZERO);
+        }
+        if let Some((radix, digits)) = strip_radix_prefix(s) {
+            return parse_int_radix(digits, radix).ok_or(());
         }
+        let unsigned = s.strip_prefix(['+', '-']).unwrap_or(s);
+        if unsigned == "Infinity" {
+            return Ok(if s.starts_with('-') {
+                Self::NEG_INF
+            } else {
+                Self::POS_INF
+            });
+        }
+        if !is_str_unsigned_decimal_literal(unsigned) {
+            return Err(());
+        }
+        // `-0` must be a float to keep its sign.
+        if let Ok(value) = i64::from_str(s)
+            && !(value == 0 && s.starts_with('-'))
+        {
+            return Ok(Self::from(value));
+        }
+        f64::from_str(s).map(Self::Float).map_err(|_| ())
     }
 }
 
@@ -297,9 +549,23 @@ impl From<&str> for Number {
     }
 }
 
+impl From<&JsString> for Number {
+    /// [StringToNumber](https://tc39.es/ecma262/#sec-stringtonumber). The
+    /// whitespace around a number is all in the BMP and the number itself is
+    /// ASCII, so a string which contains a surrogate can't be a number.
+    fn from(s: &JsString) -> Self {
+        let s: Option<String> = s
+            .as_utf16()
+            .iter()
+            .map(|&code_unit| char::from_u32(u32::from(code_unit)))
+            .collect();
+        s.map_or(Self::NAN, |s| Self::from(s.as_str()))
+    }
+}
+
 impl From<i64> for Number {
     fn from(v: i64) -> Self {
-        Self::Int(v)
+        Self::from_int(v)
     }
 }
 
@@ -309,6 +575,18 @@ impl From<f64> for Number {
     }
 }
 
+impl From<NumericLiteral> for Number {
+    fn from(literal: NumericLiteral) -> Self {
+        match literal {
+            #[allow(clippy::cast_precision_loss)]
+            NumericLiteral::Int(value) => {
+                i64::try_from(value).map_or(Self::Float(value as f64), Self::from)
+            }
+            NumericLiteral::Float(value) => Self::Float(value),
+        }
+    }
+}
+
 impl TryFrom<usize> for Number {
     type Error = num::TryFromIntError;
 
@@ -316,3 +594,54 @@ impl TryFrom<usize> for Number {
         i64::try_from(v).map(Self::from)
     }
 }
+
+/// Strip the prefix from a _NonDecimalIntegerLiteral_, e.g. `0x1F`.
+fn strip_radix_prefix(s: &str) -> Option<(u32, &str)> {
+    let mut chars = s.chars();
+    if chars.next() != Some('0') {
+        return None;
+    }
+    let radix = match chars.next()? {
+        'b' | 'B' => 2,
+        'o' | 'O' => 8,
+        'x' | 'X' => 16,
+        _ => return None,
+    };
+    Some((radix, &s[2..]))
+}
+
+fn parse_int_radix(digits: &str, radix: u32) -> Option<Number> {
+    if digits.is_empty() || !digits.chars().all(|ch| ch.is_digit(radix)) {
+        return None;
+    }
+    Some(match i64::from_str_radix(digits, radix) {
+        Ok(value) => Number::from(value),
+        // Too large for an integer, so accumulate as a float instead.
+        Err(_) => Number::Float(digits.chars().fold(0.0, |acc, ch| {
+            acc * f64::from(radix) + f64::from(ch.to_digit(radix).unwrap())
+        })),
+    })
+}
+
+/// Whether `s` matches _StrUnsignedDecimalLiteral_, other than `Infinity`.
+/// Rust's float parser is more lenient than this (e.g. it accepts `inf` and
+/// `nan`), so it can't be used to validate the string by itself.
+fn is_str_unsigned_decimal_literal(s: &str) -> bool {
+    let (mantissa, exponent) = match s.find(['e', 'E']) {
+        Some(idx) => (&s[..idx], Some(&s[(idx + 1)..])),
+        None => (s, None),
+    };
+    let (int_part, frac_part) = match mantissa.find('.') {
+        Some(idx) => (&mantissa[..idx], &mantissa[(idx + 1)..]),
+        None => (mantissa, ""),
+    };
+    let is_digits = |s: &str| s.chars().all(|ch| ch.is_ascii_digit());
+    let valid_mantissa = (!int_part.is_empty() || !frac_part.is_empty())
+        && is_digits(int_part)
+        && is_digits(frac_part);
+    let valid_exponent = exponent.is_none_or(|exponent| {
+        let digits = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+        !digits.is_empty() && is_digits(digits)
+    });
+    valid_mantissa && valid_exponent
+}
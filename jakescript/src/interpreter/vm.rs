@@ -1,11 +1,13 @@
-use super::error::{InitialisationError, OutOfHeapSpaceError};
-use super::heap::{Heap, ObjectRef, Reference};
-use super::object::{Extensible, Object, PropertyKey, UserFunction};
+use super::error::{InitialisationError, NativeErrorKind, OutOfHeapSpaceError, OutOfRangeError};
+use super::heap::{Heap, ObjectRef, Pending, Reference, Trace, Tracer};
+use super::interrupt::InterruptHandle;
+use super::limits::Limits;
+use super::object::{Extensible, Object, ObjectData, Property, PropertyKey, UserFunction};
 use super::stack::CallStack;
+use super::string::{JsString, StringTable};
 use super::value::Value;
-use crate::runtime::{Builtin, Runtime};
+use crate::runtime::{Builtin, RegExp, Runtime};
 use std::assert_matches::assert_matches;
-use std::collections::HashMap;
 use std::mem;
 
 pub struct Vm {
@@ -14,18 +16,49 @@ pub struct Vm {
     heap: Heap,
     runtime: Runtime,
     stack: CallStack,
+    /// The registers of each compiled function which is running, in the order
+    /// they were called. See [`crate::bytecode`].
+    registers: Vec<Value>,
+    strings: StringTable,
+    limits: Limits,
+    /// See [`Self::set_fuel()`].
+    fuel: Option<u64>,
+    interrupt: InterruptHandle,
+    /// Whether the script has been interrupted, which outlasts the execution
+    /// state while `finally` blocks run. See [`Self::resume_interrupt()`].
+    interrupted: bool,
+    /// Values which are kept alive by the host. See [`Self::new_handle()`].
+    handles: Vec<Option<Value>>,
+    /// See [`Self::begin_join()`].
+    joining: Vec<Reference>,
+    gc_requested: bool,
 }
 
 impl Vm {
     pub fn new() -> Result<Self, InitialisationError> {
-        let mut heap = Heap::default();
+        Self::with_limits(Limits::default())
+    }
+
+    /// Create a VM which restricts the resources scripts may use, e.g. because
+    /// they're untrusted. See [`Limits`].
+    pub fn with_limits(limits: Limits) -> Result<Self, InitialisationError> {
+        let mut heap = Heap::new(&limits);
         let runtime = Runtime::with_default_global_object(&mut heap)?;
         Ok(Self {
             execution_state: ExecutionState::default(),
             hidden_exception: Option::default(),
             heap,
             runtime,
-            stack: CallStack::default(),
+            stack: CallStack::new(&limits),
+            registers: Vec::default(),
+            strings: StringTable::default(),
+            limits,
+            fuel: None,
+            interrupt: InterruptHandle::default(),
+            interrupted: false,
+            handles: Vec::default(),
+            joining: Vec::default(),
+            gc_requested: false,
         })
     }
 
@@ -43,7 +76,16 @@ impl Vm {
     }
 
     pub fn reset_execution_state(&mut self) -> ExecutionState {
-        mem::take(&mut self.execution_state)
+        let execution_state = mem::take(&mut self.execution_state);
+        // Only the host resets the state after the script has stopped, at which
+        // point it may be run again.
+        if matches!(
+            execution_state,
+            ExecutionState::Exit | ExecutionState::Interrupt
+        ) {
+            self.interrupted = false;
+        }
+        execution_state
     }
 
     pub fn handle_loop_execution_state(&mut self) -> IterationDecision {
@@ -57,7 +99,10 @@ impl Vm {
                 self.reset_execution_state();
                 IterationDecision::Continue
             }
-            ExecutionState::Return(_) | ExecutionState::Exception(_) | ExecutionState::Exit => {
+            ExecutionState::Return(_)
+            | ExecutionState::Exception(_)
+            | ExecutionState::Exit
+            | ExecutionState::Interrupt => {
                 // Exit the loop, but don't reset the execution state just yet so that it can be
                 // handled/cleared by some calling AST node.
                 IterationDecision::Break
@@ -71,6 +116,10 @@ impl Vm {
     /// exception has already been hidden, discard the exception value taken
     /// from the execution state.
     ///
+    /// If the script was [interrupted][ExecutionState::Interrupt], the state is
+    /// reset too, so that the `finally` block runs, but the interrupt is
+    /// resumed at the end of it. See [`Self::resume_interrupt()`].
+    ///
     /// This is useful for allowing `finally` blocks to function properly.
     ///
     /// # JavaScript examples
@@ -112,7 +161,10 @@ impl Vm {
     /// }
     /// ```
     pub fn hide_current_exception(&mut self) {
-        if let ExecutionState::Exception(..) = self.execution_state() {
+        if let ExecutionState::Interrupt = self.execution_state() {
+            debug_assert!(self.interrupted);
+            self.execution_state = ExecutionState::Advance;
+        } else if let ExecutionState::Exception(..) = self.execution_state() {
             if let ExecutionState::Exception(ex) = self.reset_execution_state() {
                 if self.hidden_exception.is_none() {
                     self.hidden_exception = Some(ex);
@@ -125,14 +177,27 @@ impl Vm {
 
     /// If an exception was previously [hidden][Self::hide_current_exception()],
     /// restore it by putting it back into the execution state. If the
-    /// execution state already contains an exception, discard the hidden
-    /// exception.
+    /// execution state has changed since (e.g. because another exception was
+    /// thrown, or the script was [interrupted][ExecutionState::Interrupt]),
+    /// discard the hidden exception.
     pub fn restore_hidden_exception(&mut self) {
         if let Some(exception) = self.hidden_exception.take() {
-            if !matches!(self.execution_state(), ExecutionState::Exception(..)) {
+            if matches!(self.execution_state(), ExecutionState::Advance) {
                 self.set_execution_state(ExecutionState::Exception(exception));
             }
         }
+        self.resume_interrupt();
+    }
+
+    /// If the script was interrupted, and the interrupt was
+    /// [hidden][Self::hide_current_exception()] so that a `finally` block could
+    /// run, set the execution state back to [`ExecutionState::Interrupt`] once
+    /// the block has finished, however it finished. The script can't catch the
+    /// interrupt, or escape it with `break`, `continue` or `return`.
+    pub fn resume_interrupt(&mut self) {
+        if self.interrupted && !matches!(self.execution_state(), ExecutionState::Exit) {
+            self.execution_state = ExecutionState::Interrupt;
+        }
     }
 
     /// Reset the execution state to [`ExecutionState::Advance`] if it contains
@@ -174,6 +239,189 @@ impl Vm {
         &mut self.stack
     }
 
+    pub(super) fn registers(&self) -> &[Value] {
+        &self.registers
+    }
+
+    pub(super) fn registers_mut(&mut self) -> &mut Vec<Value> {
+        &mut self.registers
+    }
+
+    pub fn strings(&self) -> &StringTable {
+        &self.strings
+    }
+
+    /// Get a string which shares its allocation with any other equal strings
+    /// interned previously. See [`StringTable`].
+    pub fn intern_string(&mut self, s: &str) -> JsString {
+        self.strings.intern(s)
+    }
+
+    pub fn limits(&self) -> &Limits {
+        &self.limits
+    }
+
+    /// Check that a string of `len` code units wouldn't exceed
+    /// [`Limits::max_string_length()`]. This should be called before building
+    /// a string whose length depends on the script, e.g. by concatenation.
+    pub fn check_string_length(&self, len: usize) -> Result<(), OutOfRangeError> {
+        if len <= self.limits.max_string_length() {
+            Ok(())
+        } else {
+            Err(OutOfRangeError::new("invalid string length".to_owned()))
+        }
+    }
+
+    /// Record that `obj_ref` is being joined into a string, e.g. by
+    /// `Array.prototype.join()`, or return `false` if it already is, in which
+    /// case it contains itself and should be treated as empty rather than
+    /// recursing forever. Must be paired with [`Self::end_join()`] if it
+    /// returns `true`.
+    pub fn begin_join(&mut self, obj_ref: Reference) -> bool {
+        if self.joining.contains(&obj_ref) {
+            false
+        } else {
+            self.joining.push(obj_ref);
+            true
+        }
+    }
+
+    pub fn end_join(&mut self, obj_ref: Reference) {
+        assert_eq!(self.joining.pop(), Some(obj_ref));
+    }
+
+    pub fn fuel(&self) -> Option<u64> {
+        self.fuel
+    }
+
+    /// Limit how long scripts may run for, in units of fuel, or remove the
+    /// limit if `None`. A unit is consumed each time a loop iterates or a
+    /// function is called, and periodically while a regular expression
+    /// backtracks. Once there's none left the script is stopped as if it had
+    /// been [interrupted][Self::interrupt_handle()].
+    ///
+    /// `finally` blocks still run as the script is stopped, but with no fuel
+    /// to spare, so a loop or function call within one stops it again.
+    pub fn set_fuel(&mut self, fuel: Option<u64>) {
+        self.fuel = fuel;
+    }
+
+    /// Get a handle which can be used to stop the script from another thread.
+    pub fn interrupt_handle(&self) -> InterruptHandle {
+        self.interrupt.clone()
+    }
+
+    /// Called at loop back-edges, on entry to each function, on each step of
+    /// a built-in which loops over an array-like object, and while a regular
+    /// expression backtracks, which between them bound how long a script can
+    /// run for without reaching one. Consumes
+    /// a unit of [fuel][Self::set_fuel()], and if there's none left or an
+    /// interrupt has been requested, sets the execution state to
+    /// [`ExecutionState::Interrupt`] (unless it's already been changed). Once
+    /// the script has been interrupted, it's interrupted again each time this
+    /// is called while a `finally` block runs, until the host
+    /// [resets][Self::reset_execution_state()] the execution state.
+    ///
+    /// Returns whether the script should stop.
+    pub fn check_interrupt(&mut self) -> bool {
+        let out_of_fuel = match self.fuel {
+            Some(0) => true,
+            Some(ref mut fuel) => {
+                *fuel -= 1;
+                false
+            }
+            None => false,
+        };
+        if out_of_fuel || self.interrupt.take() {
+            self.interrupted = true;
+        }
+        if self.interrupted && matches!(self.execution_state, ExecutionState::Advance) {
+            self.execution_state = ExecutionState::Interrupt;
+        }
+        !matches!(self.execution_state, ExecutionState::Advance)
+    }
+
+    /// Free every object and scope which is no longer reachable, using a
+    /// mark-and-sweep collector. The roots are the global object (and the
+    /// built-in prototypes), the call stack and registers, the execution state,
+    /// and any [handles][Self::new_handle()] held by the host.
+    ///
+    /// References which are only held on the Rust stack aren't roots, and would
+    /// be left dangling, unless they've been pushed as
+    /// [temporaries][CallStack::push_temporary()]. The interpreter does so for
+    /// the operands of expressions which are still being evaluated, and for the
+    /// function, receiver and arguments of each call which is in progress, so
+    /// this is safe to call between statements. Native functions which call
+    /// back into the interpreter must push any other objects they hold.
+    pub fn collect_garbage(&mut self) {
+        self.gc_requested = false;
+        let Self {
+            execution_state,
+            hidden_exception,
+            heap,
+            runtime,
+            stack,
+            registers,
+            handles,
+            ..
+        } = self;
+        let mut tracer = Tracer::new(heap.capacity(), stack.scope_capacity());
+        runtime.global_object().trace(&mut tracer);
+        stack.trace(&mut tracer);
+        execution_state.trace(&mut tracer);
+        hidden_exception.trace(&mut tracer);
+        for register in registers.iter() {
+            register.trace(&mut tracer);
+        }
+        for handle in handles.iter() {
+            handle.trace(&mut tracer);
+        }
+        while let Some(pending) = tracer.next_pending() {
+            match pending {
+                Pending::Object(obj_ref) => heap.resolve(obj_ref).as_ref().trace(&mut tracer),
+                Pending::Scope(scope) => stack.trace_scope(scope, &mut tracer),
+            }
+        }
+        heap.sweep(&tracer);
+        stack.sweep_scopes(&tracer);
+    }
+
+    /// Collect garbage at the next [safe point][Self::safe_point()], whether or
+    /// not enough has been allocated since the last collection.
+    pub fn request_garbage_collection(&mut self) {
+        self.gc_requested = true;
+    }
+
+    /// Called between statements and loop iterations, including within function
+    /// calls, where every reference held by the interpreter is rooted. Collects
+    /// garbage if it's due.
+    pub fn safe_point(&mut self) {
+        let due = self.gc_requested || self.heap.should_collect() || self.stack.should_collect();
+        if due {
+            self.collect_garbage();
+        }
+    }
+
+    /// Keep a value alive across garbage collections while the host holds onto
+    /// it, until the handle is [released][Self::release_handle()].
+    pub fn new_handle(&mut self, value: Value) -> Handle {
+        if let Some(idx) = self.handles.iter().position(Option::is_none) {
+            self.handles[idx] = Some(value);
+            Handle(idx)
+        } else {
+            self.handles.push(Some(value));
+            Handle(self.handles.len() - 1)
+        }
+    }
+
+    pub fn handle_value(&self, handle: &Handle) -> &Value {
+        self.handles[handle.0].as_ref().unwrap()
+    }
+
+    pub fn release_handle(&mut self, handle: Handle) -> Value {
+        self.handles[handle.0].take().unwrap()
+    }
+
     pub fn global_object(&self) -> ObjectRef {
         let obj_ref = self.runtime().global_object_ref();
         self.heap().resolve(obj_ref)
@@ -186,24 +434,63 @@ impl Vm {
     }
 
     pub fn alloc_function(&mut self, f: UserFunction) -> Result<Reference, OutOfHeapSpaceError> {
+        let proto = self.runtime().global_object().function_proto().obj_ref();
         self.heap_mut()
-            .allocate(Object::new_function(f, Extensible::Yes))
+            .allocate(Object::new_function(proto, f, Extensible::Yes))
     }
 
     pub fn alloc_object(
         &mut self,
-        props: HashMap<PropertyKey, Value>,
+        props: impl IntoIterator<Item = (PropertyKey, Value)>,
     ) -> Result<Reference, OutOfHeapSpaceError> {
+        let proto = self.runtime().global_object().object_proto().obj_ref();
         self.heap_mut()
-            .allocate(Object::new_object(None, props, Extensible::Yes))
+            .allocate(Object::new_object(Some(proto), props, Extensible::Yes))
     }
 
-    pub fn alloc_string(&mut self, s: Box<str>) -> Result<Reference, OutOfHeapSpaceError> {
+    /// Allocate a `String` wrapper object for a primitive string value, i.e.
+    /// box it.
+    pub fn alloc_string_object(&mut self, s: JsString) -> Result<Reference, OutOfHeapSpaceError> {
         let proto = self.runtime().global_object().string_proto().obj_ref();
         self.heap_mut()
             .allocate(Object::new_string(proto, s, Extensible::Yes))
     }
 
+    pub fn alloc_regexp(&mut self, regexp: RegExp) -> Result<Reference, OutOfHeapSpaceError> {
+        let proto = self.runtime().global_object().regexp_proto().obj_ref();
+        self.heap_mut()
+            .allocate(Object::new_regexp(proto, regexp, Extensible::Yes))
+    }
+
+    pub fn alloc_error(
+        &mut self,
+        kind: NativeErrorKind,
+        message: &str,
+    ) -> Result<Reference, OutOfHeapSpaceError> {
+        let proto = self
+            .runtime()
+            .global_object()
+            .native_error_proto(kind)
+            .obj_ref();
+        let stack = format!("{}: {message}{}", kind.name(), self.stack().stack_trace());
+        let props = [
+            (
+                PropertyKey::from("message"),
+                Property::new_user(Value::String(JsString::from(message))),
+            ),
+            (
+                PropertyKey::from("stack"),
+                Property::new_user(Value::String(JsString::from(stack))),
+            ),
+        ];
+        self.heap_mut().allocate(Object::new(
+            Some(proto),
+            props,
+            ObjectData::None,
+            Extensible::Yes,
+        ))
+    }
+
     #[allow(clippy::unused_self)]
     pub fn write_message(&mut self, message: &str) {
         // Note: Print to stderr as stdout is swallowed when running in the REPL.
@@ -211,6 +498,10 @@ impl Vm {
     }
 }
 
+/// A value held by the host. See [`Vm::new_handle()`].
+#[derive(Debug, Eq, PartialEq)]
+pub struct Handle(usize);
+
 #[derive(Clone, Debug, Default)]
 pub enum ExecutionState {
     #[default]
@@ -219,9 +510,24 @@ pub enum ExecutionState {
     Continue,
     Exception(Value),
     Exit,
+    /// The script was stopped by the host, either through an
+    /// [`InterruptHandle`] or because it ran out of [fuel][Vm::set_fuel()].
+    /// Like [`ExecutionState::Exit`], this can't be caught by the script, but
+    /// `finally` blocks are still run on the way out. See
+    /// [`Vm::resume_interrupt()`].
+    Interrupt,
     Return(Value),
 }
 
+impl Trace for ExecutionState {
+    fn trace(&self, tracer: &mut Tracer) {
+        match self {
+            Self::Exception(value) | Self::Return(value) => value.trace(tracer),
+            Self::Advance | Self::Break | Self::Continue | Self::Exit | Self::Interrupt => {}
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum IterationDecision {
     Advance,
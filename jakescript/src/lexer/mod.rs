@@ -17,6 +17,9 @@ mod test;
 
 pub struct Lexer<I: FallibleIterator<Item = char, Error = io::Error>> {
     source: SourceCode<I>,
+    /// Whether a `/` at the current position should be lexed as the start of a
+    /// regular expression literal rather than as a division punctuator.
+    regex_allowed: bool,
 }
 
 impl<'a> Lexer<Fallible<Chars<'a>>> {
@@ -37,7 +40,10 @@ impl<I: FallibleIterator<Item = char, Error = io::Error>> Lexer<I> {
     }
 
     fn new(source: SourceCode<I>) -> Self {
-        Self { source }
+        Self {
+            source,
+            regex_allowed: true,
+        }
     }
 
     pub fn source_location(&self) -> &SourceLocation {
@@ -96,9 +102,11 @@ impl<I: FallibleIterator<Item = char, Error = io::Error>> Lexer<I> {
             Some(Literal::Numeric(value))
         } else if let Some(value) = self.parse_string_literal()? {
             Some(Literal::String(value))
-        } else {
+        } else if self.regex_allowed {
             self.parse_regex_literal()?
                 .map(|value| Literal::RegEx(Box::new(value)))
+        } else {
+            None
         })
     }
 
@@ -117,9 +125,12 @@ impl<I: FallibleIterator<Item = char, Error = io::Error>> Lexer<I> {
     }
 
     fn parse_numeric_literal(&mut self) -> Result<Option<NumericLiteral>> {
-        let value = match self.parse_non_decimal_int_literal()? {
-            Some(value) => Some(value),
-            None => self.parse_decimal_literal()?,
+        let value = if let Some(value) = self.parse_non_decimal_int_literal()? {
+            Some(value)
+        } else if let Some(value) = self.parse_legacy_octal_int_literal()? {
+            Some(value)
+        } else {
+            self.parse_decimal_literal()?
         };
         if let Some(value) = value {
             // Ensure the character following the numeric literal is valid
@@ -201,6 +212,42 @@ impl<I: FallibleIterator<Item = char, Error = io::Error>> Lexer<I> {
         }))
     }
 
+    /// ```plain
+    /// LegacyOctalIntegerLiteral::
+    ///     0 OctalDigit
+    ///     LegacyOctalIntegerLiteral OctalDigit
+    /// NonOctalDecimalIntegerLiteral::
+    ///     0 NonOctalDigit
+    ///     LegacyOctalLikeDecimalIntegerLiteral NonOctalDigit
+    ///     NonOctalDecimalIntegerLiteral DecimalDigit
+    /// ```
+    ///
+    /// Integers with a leading zero are read as octal, unless one of their
+    /// digits is 8 or 9, in which case they're read as decimal.
+    fn parse_legacy_octal_int_literal(&mut self) -> Result<Option<NumericLiteral>> {
+        if !matches!(self.source.peek()?, Some('0'))
+            || !matches!(self.source.peek_nth(1)?, Some(ch1) if ch1.is_ascii_digit())
+        {
+            return Ok(None);
+        }
+        let mut octal = true;
+        let mut offset = 1;
+        while let Some(ch) = self.source.peek_nth(offset)? {
+            match ch {
+                '0'..='7' => {}
+                '8' | '9' => octal = false,
+                _ => break,
+            }
+            offset += 1;
+        }
+        assert!(self.source.next_if_eq(&'0')?.is_some());
+        Ok(Some(if octal {
+            NumericLiteral::LegacyOctInt(self.parse_int_literal_part(8)?.unwrap())
+        } else {
+            NumericLiteral::NonOctalDecInt(self.parse_int_literal_part(10)?.unwrap())
+        }))
+    }
+
     fn parse_int_literal_part(&mut self, radix: u32) -> Result<Option<u64>> {
         let mut present = false;
         let mut value = 0;
@@ -296,7 +343,7 @@ impl<I: FallibleIterator<Item = char, Error = io::Error>> Lexer<I> {
         }
         debug_assert!(!escaped);
         debug_assert!(!content.is_empty());
-        debug_assert!(raw_content_len >= content.len());
+        debug_assert!(raw_content_len >= content.chars().count());
         assert!(self.source.next_if_eq(&qt)?.is_some());
         self.source.advance_by(raw_content_len)?.unwrap();
         assert!(self.source.next_if_eq(&qt)?.is_some());
@@ -368,8 +415,10 @@ impl<I: FallibleIterator<Item = char, Error = io::Error>> Lexer<I> {
                     return Ok(None);
                 }
                 (ch, true, _) => {
+                    // Escape sequences are interpreted by the regex engine, not the lexer.
                     escaped = false;
-                    content.push(into_escaped(*ch));
+                    content.push('\\');
+                    content.push(*ch);
                 }
                 (ch, false, _) => {
                     content.push(*ch);
@@ -380,13 +429,12 @@ impl<I: FallibleIterator<Item = char, Error = io::Error>> Lexer<I> {
         debug_assert!(!escaped);
         debug_assert!(!in_class);
         debug_assert!(!content.is_empty());
-        debug_assert!(raw_content_len >= content.len());
         assert!(self.source.next_if_eq(&'/')?.is_some());
         self.source.advance_by(raw_content_len)?.unwrap();
         assert!(self.source.next_if_eq(&'/')?.is_some());
 
         let mut flags = Vec::new();
-        while let Some(ch) = self.source.next_if(|ch| is_identifier_start(*ch))? {
+        while let Some(ch) = self.source.next_if(|ch| is_identifier_part(*ch))? {
             flags.push(ch);
         }
         Ok(Some(RegExLiteral {
@@ -515,9 +563,34 @@ impl<I: FallibleIterator<Item = char, Error = io::Error>> FallibleIterator for L
 
     fn next(&mut self) -> std::result::Result<Option<Self::Item>, Self::Error> {
         if self.source.peek()?.is_some() {
-            self.parse_element().map(Some)
+            let elem = self.parse_element()?;
+            if let Some(token) = elem.token() {
+                self.regex_allowed = regex_allowed_after(token);
+            }
+            Ok(Some(elem))
         } else {
             Ok(None)
         }
     }
 }
+
+/// A `/` can either be the start of a regular expression literal or a division
+/// punctuator, depending on the syntactic context. The lexer doesn't have
+/// access to that context, so instead make the decision based on the previous
+/// token: a regex can't directly follow anything which could end an
+/// expression.
+fn regex_allowed_after(token: &Token) -> bool {
+    match token {
+        Token::Identifier(_) | Token::Literal(_) | Token::Template(_) => false,
+        // Contextual keywords are far more likely to be used as identifiers here.
+        Token::Keyword(kw) => !kw.is_contextual() && !matches!(kw, Keyword::Super | Keyword::This),
+        Token::Punctuator(punc) => !matches!(
+            punc,
+            Punctuator::CloseBrace
+                | Punctuator::CloseBracket
+                | Punctuator::CloseParen
+                | Punctuator::MinusMinus
+                | Punctuator::PlusPlus
+        ),
+    }
+}
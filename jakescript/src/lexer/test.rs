@@ -91,3 +91,74 @@ fn tokenise_unclosed_multi_line_comment() {
     assert_matches!(lexer.next(), Err(err) if matches!(err.kind(), ErrorKind::UnclosedComment));
     assert_matches!(lexer.next(), Err(err) if matches!(err.kind(), ErrorKind::UnclosedComment));
 }
+
+#[test]
+fn tokenise_regex_literal_or_division() {
+    fn tokens(source_code: &str) -> Vec<Token> {
+        Lexer::for_str(source_code, SourceLocation::default())
+            .filter_map(|elem| Ok(elem.into_token()))
+            .collect()
+            .unwrap()
+    }
+    fn regex(content: &str, flags: &str) -> Token {
+        Token::Literal(Literal::RegEx(Box::new(RegExLiteral {
+            content: content.into(),
+            flags: flags.chars().collect(),
+        })))
+    }
+
+    assert_eq!(
+        tokens(r"x = /a\/b[/]/gi;"),
+        vec![
+            Token::Identifier("x".into()),
+            Token::Punctuator(Punctuator::Eq),
+            regex(r"a\/b[/]", "gi"),
+            Token::Punctuator(Punctuator::Semi),
+        ]
+    );
+    assert_eq!(
+        tokens("a / b / c"),
+        vec![
+            Token::Identifier("a".into()),
+            Token::Punctuator(Punctuator::Slash),
+            Token::Identifier("b".into()),
+            Token::Punctuator(Punctuator::Slash),
+            Token::Identifier("c".into()),
+        ]
+    );
+    assert_eq!(
+        tokens("(1) / 2"),
+        vec![
+            Token::Punctuator(Punctuator::OpenParen),
+            Token::Literal(Literal::Numeric(NumericLiteral::DecInt(1))),
+            Token::Punctuator(Punctuator::CloseParen),
+            Token::Punctuator(Punctuator::Slash),
+            Token::Literal(Literal::Numeric(NumericLiteral::DecInt(2))),
+        ]
+    );
+}
+
+#[test]
+fn tokenise_integers_with_a_leading_zero() {
+    fn token(source_code: &str) -> Token {
+        let mut lexer = Lexer::for_str(source_code, SourceLocation::default());
+        let token = lexer.next().unwrap().unwrap().into_token().unwrap();
+        assert_eq!(lexer.next().unwrap(), None);
+        token
+    }
+
+    for (source_code, expected) in [
+        ("0", NumericLiteral::DecInt(0)),
+        ("017", NumericLiteral::LegacyOctInt(15)),
+        ("08", NumericLiteral::NonOctalDecInt(8)),
+        ("019", NumericLiteral::NonOctalDecInt(19)),
+        ("0778", NumericLiteral::NonOctalDecInt(778)),
+        ("0o17", NumericLiteral::OctInt(15)),
+    ] {
+        assert_eq!(
+            token(source_code),
+            Token::Literal(Literal::Numeric(expected)),
+            "{source_code}"
+        );
+    }
+}
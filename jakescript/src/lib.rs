@@ -7,6 +7,7 @@
 #![feature(let_chains)]
 
 pub mod ast;
+pub mod bytecode;
 pub mod interpreter;
 pub mod lexer;
 pub mod parser;
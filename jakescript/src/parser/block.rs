@@ -1,4 +1,4 @@
-use super::error::Result;
+use super::error::{EarlyErrorKind, Error, Result};
 use super::Parser;
 use crate::ast::*;
 use crate::iter::peek_fallible::PeekableNthFallibleIterator;
@@ -17,8 +17,83 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
         Ok((loc, block))
     }
 
+    /// Parse the body of a function, which may begin with a directive prologue.
+    /// Returns whether the function is strict mode code, either because it's
+    /// nested inside strict mode code or because of its own `"use strict"`
+    /// directive.
+    pub(super) fn parse_function_body(
+        &mut self,
+        parameters: &[Identifier],
+    ) -> Result<(Block, bool)> {
+        let outer_strict = self.strict;
+        let outer_context = std::mem::replace(&mut self.context, StatementContext::FUNCTION);
+        self.expect_punctuator(OpenBrace)?;
+        self.skip_non_tokens()?;
+        let block = self.parse_block_body_with_directives(parameters);
+        let strict = self.strict;
+        self.strict = outer_strict;
+        self.context = outer_context;
+        let block = block?;
+        self.skip_non_tokens()?;
+        self.expect_punctuator(CloseBrace)?;
+        Ok((block, strict))
+    }
+
     /// - `loc` - Location of the opening brace.
     pub(super) fn parse_block_body(&mut self) -> Result<Block> {
+        self.parse_block_body_impl(false, DeclaredNames::default())
+    }
+
+    /// Parse the body of a script or function. If its directive prologue
+    /// contains a `"use strict"` directive, the parser is left in strict mode.
+    ///
+    /// - `parameters` - Names of the function's parameters, which can't be
+    ///   redeclared by lexical declarations in the function body.
+    pub(super) fn parse_block_body_with_directives(
+        &mut self,
+        parameters: &[Identifier],
+    ) -> Result<Block> {
+        let names = DeclaredNames {
+            function_boundary: true,
+            var: parameters.to_vec(),
+            ..DeclaredNames::default()
+        };
+        self.parse_block_body_impl(true, names)
+    }
+
+    /// Parse the body of a `catch` clause, whose exception binding can't be
+    /// redeclared by lexical declarations in the body (though it can by `var`
+    /// declarations, for compatibility with old code).
+    pub(super) fn parse_catch_body(
+        &mut self,
+        exception_binding: Option<&Identifier>,
+    ) -> Result<Block> {
+        self.expect_punctuator(OpenBrace)?;
+        self.skip_non_tokens()?;
+        let names = DeclaredNames {
+            var: exception_binding.into_iter().cloned().collect(),
+            ..DeclaredNames::default()
+        };
+        let block = self.parse_block_body_impl(false, names)?;
+        self.skip_non_tokens()?;
+        self.expect_punctuator(CloseBrace)?;
+        Ok(block)
+    }
+
+    /// - `directive_prologue` - Whether the block may begin with a directive
+    ///   prologue, i.e. a sequence of string literal expression statements.
+    fn parse_block_body_impl(
+        &mut self,
+        directive_prologue: bool,
+        names: DeclaredNames,
+    ) -> Result<Block> {
+        self.declared_names.push(names);
+        let block = self.parse_block_body_statements(directive_prologue);
+        self.declared_names.pop().unwrap();
+        block
+    }
+
+    fn parse_block_body_statements(&mut self, mut directive_prologue: bool) -> Result<Block> {
         let mut hoisted_decls = Vec::new();
         let mut body = Vec::new();
         loop {
@@ -28,7 +103,23 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
                 None => break,
                 _ => {}
             }
-            match self.parse_statement()? {
+            let statement = self.parse_statement()?;
+            // Only a string literal on its own is a directive. A parenthesised one
+            // is a grouping expression, which ends the directive prologue.
+            if directive_prologue {
+                match statement {
+                    Statement::Expression(Expression::Literal(LiteralExpression {
+                        value: Literal::String(ref directive),
+                        ..
+                    })) => {
+                        if directive.value.as_ref() == "use strict" {
+                            self.strict = true;
+                        }
+                    }
+                    _ => directive_prologue = false,
+                }
+            }
+            match statement {
                 Statement::Declaration(decl) if decl.is_hoisted() => {
                     let (decl, init_exprs) = decl.into_declaration_and_initialiser();
                     hoisted_decls.push(decl);
@@ -41,3 +132,124 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
         Ok(Block::new(hoisted_decls, body))
     }
 }
+
+impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
+    /// Run `f` within a new, nested scope for lexical declarations. Blocks
+    /// create their own scope automatically.
+    pub(super) fn with_declaration_scope<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T>,
+    ) -> Result<T> {
+        self.declared_names.push(DeclaredNames::default());
+        let result = f(self);
+        self.declared_names.pop().unwrap();
+        result
+    }
+
+    /// A lexical declaration can't share its name with any other declaration in
+    /// the same block.
+    pub(super) fn declare_lexical_name(
+        &mut self,
+        name: &Identifier,
+        loc: &SourceLocation,
+    ) -> Result<()> {
+        let names = self.declared_names.last_mut().unwrap();
+        if names.lexical.contains(name) || names.var.contains(name) {
+            return Err(Error::early(
+                EarlyErrorKind::DuplicateDeclaration(name.clone()),
+                loc.clone(),
+            ));
+        }
+        names.lexical.push(name.clone());
+        Ok(())
+    }
+
+    /// A `var` declaration is scoped to the enclosing function, so it can't
+    /// share its name with a lexical declaration in any block in between.
+    pub(super) fn declare_var_name(
+        &mut self,
+        name: &Identifier,
+        loc: &SourceLocation,
+    ) -> Result<()> {
+        for names in self.declared_names.iter_mut().rev() {
+            if names.lexical.contains(name) {
+                return Err(Error::early(
+                    EarlyErrorKind::DuplicateDeclaration(name.clone()),
+                    loc.clone(),
+                ));
+            }
+            names.var.push(name.clone());
+            if names.function_boundary {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Function declarations are treated like `var` declarations at the top
+    /// level of a function or script, but are lexical declarations within a
+    /// block. Outside of strict mode code, a function declaration in a block
+    /// can still be redeclared by another function declaration.
+    pub(super) fn declare_function_name(
+        &mut self,
+        name: &Identifier,
+        loc: &SourceLocation,
+    ) -> Result<()> {
+        let strict = self.strict;
+        let names = self.declared_names.last_mut().unwrap();
+        let duplicate = if names.function_boundary {
+            names.lexical.contains(name)
+        } else {
+            let redeclared_fn = !strict && names.functions.contains(name);
+            names.var.contains(name) || (names.lexical.contains(name) && !redeclared_fn)
+        };
+        if duplicate {
+            return Err(Error::early(
+                EarlyErrorKind::DuplicateDeclaration(name.clone()),
+                loc.clone(),
+            ));
+        }
+        if names.function_boundary {
+            names.var.push(name.clone());
+        } else {
+            names.lexical.push(name.clone());
+            names.functions.push(name.clone());
+        }
+        Ok(())
+    }
+}
+
+/// Names declared directly within a block, used to detect conflicting
+/// declarations.
+#[derive(Default)]
+pub(super) struct DeclaredNames {
+    /// Whether this is the body of a function or script, which `var`
+    /// declarations are scoped to.
+    function_boundary: bool,
+    /// Includes function declarations, unless this is the body of a function
+    /// or script.
+    lexical: Vec<Identifier>,
+    /// The function declarations among the `lexical` names.
+    functions: Vec<Identifier>,
+    /// Includes `var` declarations in nested blocks, function declarations in
+    /// the body of a function or script, and the parameters of the function or
+    /// `catch` clause.
+    var: Vec<Identifier>,
+}
+
+/// The kind of statement which the code currently being parsed is nested
+/// inside, used to check where `break`, `continue` and `return` are allowed.
+#[derive(Clone, Copy, Debug, Default)]
+pub(super) struct StatementContext {
+    pub(super) function: bool,
+    pub(super) iteration: bool,
+    pub(super) switch: bool,
+}
+
+impl StatementContext {
+    const FUNCTION: Self = Self {
+        function: true,
+        iteration: false,
+        switch: false,
+    };
+}
@@ -1,11 +1,11 @@
-use super::error::{Error, Result};
-use super::Parser;
+use super::error::{EarlyErrorKind, Error, Result};
+use super::{check_binding_identifier, Parser};
 use crate::ast::*;
 use crate::iter::peek_fallible::PeekableNthFallibleIterator;
 use crate::lexer;
 use crate::token::Keyword::{Const, Function, Let, Var};
-use crate::token::Punctuator::{CloseParen, Comma, Eq, OpenParen, Semi};
-use crate::token::{Element, Token};
+use crate::token::Punctuator::{CloseParen, Comma, Eq, OpenParen};
+use crate::token::{Element, SourceLocation, Token};
 use fallible_iterator::FallibleIterator;
 
 impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
@@ -17,14 +17,12 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
             }
             Some(Token::Keyword(Const | Let)) => {
                 let decl = self.parse_lexical_declaration()?;
-                self.skip_non_tokens()?;
-                self.expect_punctuator(Semi)?;
+                self.expect_semicolon()?;
                 Ok(Declaration::Lexical(decl))
             }
             Some(Token::Keyword(Var)) => {
                 let decl = self.parse_variable_declaration()?;
-                self.skip_non_tokens()?;
-                self.expect_punctuator(Semi)?;
+                self.expect_semicolon()?;
                 Ok(Declaration::Variable(decl))
             }
             _ => Err(Error::unexpected(
@@ -37,16 +35,19 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
     fn parse_function_declaration(&mut self) -> Result<FunctionDeclaration> {
         let loc = self.expect_keyword(Function)?;
         self.skip_non_tokens()?;
-        let (binding, _) = self.expect_identifier("function_name")?;
+        let (binding, binding_loc) = self.expect_identifier("function_name")?;
+        self.declare_function_name(&binding, &binding_loc)?;
         self.skip_non_tokens()?;
         let parameters = self.parse_fn_parameters()?;
         self.skip_non_tokens()?;
-        let (_, body) = self.parse_block()?;
+        let (body, strict) = self.parse_function_body(&parameters)?;
+        check_fn_signature(strict, &loc, Some(&binding), &parameters)?;
         Ok(FunctionDeclaration {
             loc,
             binding,
             parameters,
             body,
+            strict,
         })
     }
 
@@ -94,6 +95,15 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
         };
         self.skip_non_tokens()?;
         let bindings = self.parse_variable_bindings()?;
+        for binding in &bindings {
+            self.declare_lexical_name(&binding.identifier, &binding.loc)?;
+            if kind == LexicalDeclarationKind::Const && binding.initialiser.is_none() {
+                return Err(Error::early(
+                    EarlyErrorKind::ConstWithoutInitialiser(binding.identifier.clone()),
+                    binding.loc.clone(),
+                ));
+            }
+        }
         Ok(LexicalDeclaration {
             loc,
             kind,
@@ -105,6 +115,9 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
         let loc = self.expect_keyword(Var)?;
         self.skip_non_tokens()?;
         let bindings = self.parse_variable_bindings()?;
+        for binding in &bindings {
+            self.declare_var_name(&binding.identifier, &binding.loc)?;
+        }
         Ok(VariableDeclaration { loc, bindings })
     }
 
@@ -119,14 +132,16 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
                 Some(elem) if elem.punctuator() == Some(Comma) => {
                     self.source.next()?.unwrap();
                 }
-                Some(elem) if elem.punctuator() == Some(Semi) => break Ok(bindings),
-                elem => return Err(Error::unexpected((Comma, Semi), elem.cloned())),
+                // Whatever comes next must terminate the declaration, which is up to the
+                // caller to check.
+                _ => break Ok(bindings),
             }
         }
     }
 
     fn parse_variable_binding(&mut self) -> Result<Binding> {
         let (identifier, loc) = self.expect_identifier("variable_name")?;
+        self.check_binding_identifier(&identifier, &loc)?;
         self.skip_non_tokens()?;
         let initialiser = match self.source.peek()? {
             Some(elem) if elem.punctuator() == Some(Eq) => {
@@ -134,8 +149,7 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
                 self.skip_non_tokens()?;
                 Some(self.parse_expression()?)
             }
-            Some(elem) if matches!(elem.punctuator(), Some(Comma | Semi)) => None,
-            elem => return Err(Error::unexpected((Eq, Comma, Semi), elem.cloned())),
+            _ => None,
         };
         Ok(Binding {
             loc,
@@ -144,3 +158,27 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
         })
     }
 }
+
+/// The restrictions on a strict mode function's name and parameters apply even
+/// if it's only the function's own body which contains the `"use strict"`
+/// directive, so they can't be checked until after the body has been parsed.
+pub(super) fn check_fn_signature(
+    strict: bool,
+    loc: &SourceLocation,
+    binding: Option<&Identifier>,
+    parameters: &[Identifier],
+) -> Result<()> {
+    if let Some(binding) = binding {
+        check_binding_identifier(strict, binding, loc)?;
+    }
+    for (idx, param) in parameters.iter().enumerate() {
+        check_binding_identifier(strict, param, loc)?;
+        if strict && parameters[..idx].contains(param) {
+            return Err(Error::early(
+                EarlyErrorKind::StrictModeDuplicateParameter(param.clone()),
+                loc.clone(),
+            ));
+        }
+    }
+    Ok(())
+}
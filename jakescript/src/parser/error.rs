@@ -1,6 +1,6 @@
-use crate::ast::Script;
+use crate::ast::{Identifier, Script};
 use crate::lexer;
-use crate::token::{Element, Keyword, Punctuator};
+use crate::token::{Element, Keyword, Punctuator, SourceLocation};
 use ansi_term::Style;
 use std::borrow::Cow;
 use std::fmt;
@@ -27,6 +27,10 @@ impl Error {
         Self(ErrorKind::Parser(expected.into(), Actual::EndOfInput))
     }
 
+    pub fn early(kind: EarlyErrorKind, loc: SourceLocation) -> Self {
+        Self(ErrorKind::Early(kind, loc))
+    }
+
     pub fn kind(&self) -> &ErrorKind {
         &self.0
     }
@@ -54,13 +58,16 @@ impl From<lexer::Error> for Error {
 pub enum ErrorKind {
     Lexical(lexer::Error),
     Parser(Expected, Actual),
+    /// Syntactically valid source code which the spec nonetheless requires to
+    /// be rejected before it runs.
+    Early(EarlyErrorKind, SourceLocation),
 }
 
 impl ErrorKind {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             ErrorKind::Lexical(source) => Some(source),
-            ErrorKind::Parser(..) => None,
+            ErrorKind::Parser(..) | ErrorKind::Early(..) => None,
         }
     }
 }
@@ -77,6 +84,86 @@ impl fmt::Display for ErrorKind {
             Self::Parser(expected, Actual::EndOfInput) => {
                 write!(f, "expected {expected} but reached end of input")
             }
+            Self::Early(kind, loc) => write!(f, "{kind} at {loc}"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum EarlyErrorKind {
+    BreakOutsideLoopOrSwitch,
+    ConstWithoutInitialiser(Identifier),
+    ContinueOutsideLoop,
+    DuplicateDeclaration(Identifier),
+    DuplicateProtoProperty,
+    InvalidAssignmentTarget,
+    ReturnOutsideFunction,
+    UndefinedLabel(Identifier),
+    StrictModeDuplicateParameter(Identifier),
+    StrictModeEvalOrArguments(Identifier),
+    StrictModeLegacyOctalLiteral,
+    StrictModeNonOctalDecimalLiteral,
+    StrictModeWith,
+}
+
+impl fmt::Display for EarlyErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::BreakOutsideLoopOrSwitch => write!(
+                f,
+                "{} is only allowed inside a loop or {} statement",
+                highlight(Keyword::Break.as_str()),
+                highlight(Keyword::Switch.as_str())
+            ),
+            Self::ConstWithoutInitialiser(name) => write!(
+                f,
+                "{} declaration {} must be initialised",
+                highlight(Keyword::Const.as_str()),
+                highlight(name.as_str())
+            ),
+            Self::ContinueOutsideLoop => write!(
+                f,
+                "{} is only allowed inside a loop",
+                highlight(Keyword::Continue.as_str())
+            ),
+            Self::DuplicateDeclaration(name) => write!(
+                f,
+                "{} has already been declared in the current scope",
+                highlight(name.as_str())
+            ),
+            Self::DuplicateProtoProperty => write!(
+                f,
+                "duplicate {} property in object literal",
+                highlight("__proto__")
+            ),
+            Self::InvalidAssignmentTarget => f.write_str("invalid assignment target"),
+            Self::ReturnOutsideFunction => write!(
+                f,
+                "{} is only allowed inside a function",
+                highlight(Keyword::Return.as_str())
+            ),
+            Self::UndefinedLabel(name) => write!(f, "label {} not found", highlight(name.as_str())),
+            Self::StrictModeDuplicateParameter(name) => write!(
+                f,
+                "duplicate parameter {} is not allowed in strict mode",
+                highlight(name.as_str())
+            ),
+            Self::StrictModeEvalOrArguments(name) => write!(
+                f,
+                "{} cannot be assigned to or bound in strict mode",
+                highlight(name.as_str())
+            ),
+            Self::StrictModeLegacyOctalLiteral => {
+                f.write_str("legacy octal literals are not allowed in strict mode")
+            }
+            Self::StrictModeNonOctalDecimalLiteral => {
+                f.write_str("decimal literals with a leading zero are not allowed in strict mode")
+            }
+            Self::StrictModeWith => write!(
+                f,
+                "{} statements are not allowed in strict mode",
+                highlight(Keyword::With.as_str())
+            ),
         }
     }
 }
@@ -114,6 +201,9 @@ impl fmt::Display for Actual {
 #[derive(Debug)]
 pub enum Expected {
     AnyExpression,
+    /// An expression which isn't separated from the previous token by a line
+    /// terminator.
+    AnyExpressionOnSameLine,
     AnyStatement,
 
     Identifier(&'static str),
@@ -158,6 +248,7 @@ impl fmt::Display for Expected {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::AnyExpression => f.write_str("any expression"),
+            Self::AnyExpressionOnSameLine => f.write_str("any expression on the same line"),
             Self::AnyStatement => f.write_str("any statement"),
 
             Self::Identifier(placeholder) => write!(f, "{} identifier", emphasis(*placeholder)),
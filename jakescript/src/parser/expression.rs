@@ -1,12 +1,12 @@
-use super::error::{Error, Expected, Result};
+use super::error::{EarlyErrorKind, Error, Expected, Result};
 use super::op::{ParseOperator, Position};
-use super::Parser;
+use super::{as_identifier, Parser};
 use crate::ast::*;
 use crate::iter::peek_fallible::PeekableNthFallibleIterator;
 use crate::lexer;
-use crate::token::Keyword::{Function, New, This};
+use crate::token::Keyword::{Function, New, This, TypeOf};
 use crate::token::Punctuator::{
-    CloseBracket, CloseParen, Colon, Comma, OpenBrace, OpenBracket, OpenParen,
+    CloseBracket, CloseParen, Colon, Comma, Dot, OpenBrace, OpenBracket, OpenParen,
 };
 use crate::token::{Element, SourceLocation};
 use fallible_iterator::FallibleIterator;
@@ -20,7 +20,7 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
         let mut expression = self.parse_primary_expression()?;
         loop {
             self.skip_non_tokens()?;
-            if self.source.peek()?.map(Element::punctuator).is_none() {
+            if self.source.peek()?.and_then(Element::punctuator).is_none() {
                 break;
             }
             match self.parse_secondary_expression(expression, min_precedence)? {
@@ -38,7 +38,7 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
 
     fn parse_primary_expression(&mut self) -> Result<Expression> {
         Ok(match self.source.peek()? {
-            Some(elem) if elem.identifier().is_some() => self
+            Some(elem) if as_identifier(self.strict, elem).is_some() => self
                 .parse_identifier_reference_expression()
                 .map(Expression::IdentifierReference)?,
             Some(elem) if elem.keyword() == Some(This) => {
@@ -48,6 +48,12 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
             Some(elem) if elem.keyword() == Some(New) => {
                 self.parse_new_expression().map(Expression::New)?
             }
+            Some(elem) if elem.keyword() == Some(TypeOf) => {
+                let loc = self.expect_keyword(TypeOf)?;
+                self.skip_non_tokens()?;
+                self.parse_primary_unary_expression(loc, UnaryOperator::TypeOf)
+                    .map(Expression::Unary)?
+            }
 
             Some(elem) if elem.punctuator() == Some(OpenBracket) => {
                 self.parse_array_expression().map(Expression::Array)?
@@ -114,6 +120,11 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
         };
 
         let op_kind = match Operator::try_parse(punc, Position::PostfixOrInfix) {
+            // A postfix `++` or `--` must be on the same line as its operand, otherwise it's
+            // the prefix operator of the next statement (after automatic semicolon insertion).
+            Some(Operator::Update(_)) if self.line_terminator_before_next_token()? => {
+                return Ok(ParseSecondaryExpressionOutcome::NotSecondary(lhs));
+            }
             Some(op_kind) if op_kind.precedence() > min_precedence => op_kind,
             Some(_) | None => return Ok(ParseSecondaryExpressionOutcome::NotSecondary(lhs)),
         };
@@ -152,6 +163,7 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
                 Expression::Unary(Self::parse_unary_expression(loc, kind, lhs))
             }
             Operator::Update(kind) => {
+                self.check_assignment_target(&lhs)?;
                 Expression::Update(Self::parse_update_expression(loc, kind, lhs))
             }
         }))
@@ -187,13 +199,7 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
         loc: SourceLocation,
         base: Expression,
     ) -> Result<MemberAccessExpression> {
-        let member = match self.parse_expression_impl(Operator::MemberAccess.precedence())? {
-            Expression::IdentifierReference(member_expr) => member_expr.identifier,
-            member_expr => todo!(
-                "Unsupported member access expression (only simple `a.b` expressions are \
-                 supported): {member_expr:#?}"
-            ),
-        };
+        let (member, _) = self.expect_identifier_name("member")?;
         Ok(MemberAccessExpression {
             loc,
             base: Box::new(base),
@@ -219,7 +225,7 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
     fn parse_new_expression(&mut self) -> Result<NewExpression> {
         let loc = self.expect_keyword(New)?;
         self.skip_non_tokens()?;
-        let constructor = self.parse_expression()?;
+        let constructor = self.parse_new_constructor()?;
         self.skip_non_tokens()?;
         let arguments = if self
             .source
@@ -240,8 +246,35 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
         })
     }
 
+    /// The constructor of a `new` expression may contain member accesses, but
+    /// not function calls, as the first argument list belongs to the `new`
+    /// expression itself.
+    fn parse_new_constructor(&mut self) -> Result<Expression> {
+        let mut constructor = self.parse_primary_expression()?;
+        loop {
+            self.skip_non_tokens()?;
+            constructor = match self.source.peek()?.and_then(Element::punctuator) {
+                Some(Dot) => {
+                    let loc = self.expect_punctuator(Dot)?;
+                    self.skip_non_tokens()?;
+                    self.parse_member_access_expression(loc, constructor)
+                        .map(Expression::MemberAccess)?
+                }
+                Some(OpenBracket) => {
+                    let loc = self.expect_punctuator(OpenBracket)?;
+                    self.skip_non_tokens()?;
+                    self.parse_computed_member_access_expression(loc, constructor)
+                        .map(Expression::ComputedMemberAccess)?
+                }
+                _ => break Ok(constructor),
+            };
+        }
+    }
+
     fn parse_fn_arguments(&mut self) -> Result<Vec<Expression>> {
-        if let Some(elem) = self.source.peek()? && elem.punctuator() == Some(CloseParen) {
+        if let Some(elem) = self.source.peek()?
+            && elem.punctuator() == Some(CloseParen)
+        {
             return Ok(vec![]);
         }
 
@@ -267,6 +300,7 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
         op: AssignmentOperator,
         lhs: Expression,
     ) -> Result<AssignmentExpression> {
+        self.check_assignment_target(&lhs)?;
         let rhs = self.parse_expression_impl(op.precedence())?;
         Ok(AssignmentExpression {
             loc,
@@ -374,6 +408,7 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
     ) -> Result<UpdateExpression> {
         let operand = self.parse_expression_impl(op.precedence())?;
         self.skip_non_tokens()?;
+        self.check_assignment_target(&operand)?;
         Ok(Self::parse_update_expression(loc, op, operand))
     }
 
@@ -388,6 +423,19 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
             operand: Box::new(operand),
         }
     }
+
+    fn check_assignment_target(&self, target: &Expression) -> Result<()> {
+        match target.strip_groupings() {
+            Expression::IdentifierReference(target) => {
+                self.check_binding_identifier(&target.identifier, target.source_location())
+            }
+            Expression::MemberAccess(_) | Expression::ComputedMemberAccess(_) => Ok(()),
+            target => Err(Error::early(
+                EarlyErrorKind::InvalidAssignmentTarget,
+                target.source_location().clone(),
+            )),
+        }
+    }
 }
 
 enum ParseSecondaryExpressionOutcome {
@@ -1,5 +1,6 @@
-use super::error::{Error, Result};
-use super::Parser;
+use super::declaration::check_fn_signature;
+use super::error::{EarlyErrorKind, Error, Result};
+use super::{as_identifier, as_identifier_name, Parser};
 use crate::ast::{self, *};
 use crate::iter::peek_fallible::PeekableNthFallibleIterator;
 use crate::lexer;
@@ -25,7 +26,9 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
     }
 
     fn parse_array_elements(&mut self) -> Result<Vec<Expression>> {
-        if let Some(elem) = self.source.peek()? && elem.punctuator() == Some(CloseBracket) {
+        if let Some(elem) = self.source.peek()?
+            && elem.punctuator() == Some(CloseBracket)
+        {
             return Ok(vec![]);
         }
 
@@ -50,7 +53,7 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
         let loc = self.expect_keyword(Function)?;
         self.skip_non_tokens()?;
         let binding = match self.source.peek()? {
-            Some(elem) if elem.identifier().is_some() => {
+            Some(elem) if as_identifier(self.strict, elem).is_some() => {
                 let (binding, _) = self.expect_identifier("function_name")?;
                 Some(binding)
             }
@@ -65,12 +68,14 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
         self.skip_non_tokens()?;
         let parameters = self.parse_fn_parameters()?;
         self.skip_non_tokens()?;
-        let (_, body) = self.parse_block()?;
+        let (body, strict) = self.parse_function_body(&parameters)?;
+        check_fn_signature(strict, &loc, binding.as_ref(), &parameters)?;
         Ok(FunctionExpression {
             loc,
             binding,
             parameters,
             body,
+            strict,
         })
     }
 
@@ -78,9 +83,23 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
         let (value, loc) = self.expect_literal()?;
         let value = match value {
             token::Literal::Boolean(value) => ast::Literal::Boolean(value),
+            token::Literal::Numeric(token::NumericLiteral::LegacyOctInt(_)) if self.strict => {
+                return Err(Error::early(
+                    EarlyErrorKind::StrictModeLegacyOctalLiteral,
+                    loc,
+                ));
+            }
+            token::Literal::Numeric(token::NumericLiteral::NonOctalDecInt(_)) if self.strict => {
+                return Err(Error::early(
+                    EarlyErrorKind::StrictModeNonOctalDecimalLiteral,
+                    loc,
+                ));
+            }
             token::Literal::Numeric(
                 token::NumericLiteral::BinInt(value)
                 | token::NumericLiteral::OctInt(value)
+                | token::NumericLiteral::LegacyOctInt(value)
+                | token::NumericLiteral::NonOctalDecInt(value)
                 | token::NumericLiteral::DecInt(value)
                 | token::NumericLiteral::HexInt(value),
             ) => ast::Literal::Numeric(ast::NumericLiteral::Int(value)),
@@ -90,12 +109,10 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
             token::Literal::String(value) => {
                 ast::Literal::String(ast::StringLiteral { value: value.value })
             }
-            token::Literal::RegEx(value) => {
-                // FIXME: Support Literal::RegEx properly.
-                ast::Literal::String(ast::StringLiteral {
-                    value: value.to_string().into_boxed_str(),
-                })
-            }
+            token::Literal::RegEx(value) => ast::Literal::RegEx(ast::RegExLiteral {
+                pattern: value.content,
+                flags: value.flags.into_iter().collect::<String>().into_boxed_str(),
+            }),
             token::Literal::Null => ast::Literal::Null,
         };
         Ok(LiteralExpression { loc, value })
@@ -115,12 +132,13 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
 
     fn parse_object_properties(&mut self) -> Result<Vec<ObjectProperty>> {
         let mut props = Vec::new();
+        let mut has_proto = false;
         Ok(loop {
             self.skip_non_tokens()?;
             match self.source.peek()? {
                 Some(elem) if elem.punctuator() == Some(CloseBrace) => break props,
-                Some(elem) if elem.identifier().is_some() => {
-                    props.push(self.parse_object_property()?);
+                Some(elem) if as_identifier_name(elem).is_some() => {
+                    props.push(self.parse_object_property(&mut has_proto)?);
                 }
                 elem => {
                     return Err(Error::unexpected(
@@ -140,19 +158,16 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
         })
     }
 
-    fn parse_object_property(&mut self) -> Result<ObjectProperty> {
-        let name = match self.source.next()? {
-            // TODO: Parse non-identifier declared property names.
-            Some(elem) if elem.identifier().is_some() => {
-                ObjectPropertyName::Identifier(Identifier::from(elem.into_identifier().unwrap()))
+    fn parse_object_property(&mut self, has_proto: &mut bool) -> Result<ObjectProperty> {
+        // TODO: Parse non-identifier declared property names.
+        let (name, loc) = self.expect_identifier_name("property_key")?;
+        if name.as_str() == "__proto__" {
+            if *has_proto {
+                return Err(Error::early(EarlyErrorKind::DuplicateProtoProperty, loc));
             }
-            elem => {
-                return Err(Error::unexpected(
-                    Expected::Identifier("property_key"),
-                    elem,
-                ));
-            }
-        };
+            *has_proto = true;
+        }
+        let name = ObjectPropertyName::Identifier(name);
         self.skip_non_tokens()?;
         self.expect_punctuator(Colon)?;
         self.skip_non_tokens()?;
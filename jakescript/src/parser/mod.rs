@@ -3,7 +3,9 @@ use crate::iter::peek_fallible::{
     IntoPeekableNthFallible, PeekableNthFallible, PeekableNthFallibleIterator,
 };
 use crate::lexer::{self, Lexer};
+use crate::token::symbol::is_line_terminator;
 use crate::token::{self, Element, Keyword, Punctuator, SourceLocation};
+use block::{DeclaredNames, StatementContext};
 pub use error::*;
 use fallible_iterator::FallibleIterator;
 use std::{io, iter};
@@ -22,6 +24,15 @@ type Fallible<I> = fallible_iterator::Convert<iter::Map<I, fn(Element) -> lexer:
 
 pub struct Parser<I: FallibleIterator<Item = Element, Error = lexer::Error>> {
     source: PeekableNthFallible<I>,
+    /// Whether the code currently being parsed is strict mode code.
+    strict: bool,
+    /// Location of the most recent token to be preceded by a line terminator,
+    /// which is significant for automatic semicolon insertion.
+    line_terminator_before: Option<SourceLocation>,
+    /// Names declared in each block enclosing the code currently being parsed,
+    /// innermost last.
+    declared_names: Vec<DeclaredNames>,
+    context: StatementContext,
 }
 
 impl<I: FallibleIterator<Item = char, Error = io::Error>> Parser<Lexer<I>> {
@@ -40,6 +51,10 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
     pub fn for_elements_fallible(source: I) -> Self {
         Self {
             source: source.peekable_nth_fallible(),
+            strict: false,
+            line_terminator_before: None,
+            declared_names: Vec::new(),
+            context: StatementContext::default(),
         }
     }
 
@@ -54,15 +69,52 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
             .unwrap_or_default();
 
         self.skip_non_tokens()?;
-        let body = self.parse_block_body()?;
-        Ok(Script::new(loc, body))
+        let body = self.parse_block_body_with_directives(&[])?;
+        Ok(Script::new(loc, body, self.strict))
     }
 
     fn skip_non_tokens(&mut self) -> lexer::Result<()> {
-        self.source.advance_while(|elem| elem.token().is_none())?;
+        let mut line_terminator = false;
+        self.source.advance_while(|elem| {
+            line_terminator |= elem.line_terminator().is_some()
+                || elem
+                    .comment()
+                    .is_some_and(|comment| comment.value.contains(is_line_terminator));
+            elem.token().is_none()
+        })?;
+        if line_terminator {
+            self.line_terminator_before =
+                self.source.peek()?.map(Element::source_location).cloned();
+        }
         Ok(())
     }
 
+    /// Whether the next token is on a different line to the previous one.
+    fn line_terminator_before_next_token(&mut self) -> lexer::Result<bool> {
+        self.skip_non_tokens()?;
+        Ok(match (self.source.peek()?, &self.line_terminator_before) {
+            (Some(elem), Some(loc)) => elem.source_location() == loc,
+            (Some(_), None) | (None, _) => false,
+        })
+    }
+
+    /// Expect a `;` to terminate the current statement, or automatically insert
+    /// one if the next token is on a different line, is a `}`, or is the end of
+    /// input.
+    fn expect_semicolon(&mut self) -> Result<()> {
+        let line_terminator_before = self.line_terminator_before_next_token()?;
+        match self.source.peek()? {
+            Some(elem) if elem.punctuator() == Some(Punctuator::Semi) => {
+                self.source.next()?.unwrap();
+                Ok(())
+            }
+            Some(elem) if elem.punctuator() == Some(Punctuator::CloseBrace) => Ok(()),
+            Some(_) if line_terminator_before => Ok(()),
+            None => Ok(()),
+            elem => Err(Error::unexpected(Punctuator::Semi, elem.cloned())),
+        }
+    }
+
     fn expect_keyword(&mut self, expected: Keyword) -> Result<SourceLocation> {
         match self.source.next()? {
             Some(elem) if elem.keyword() == Some(expected) => Ok(elem.source_location().clone()),
@@ -82,14 +134,35 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
         placeholder: &'static str,
     ) -> Result<(Identifier, SourceLocation)> {
         match self.source.next()? {
-            Some(elem) if elem.identifier().is_some() => {
-                let loc = elem.source_location().clone();
-                Ok((Identifier::from(elem.into_identifier().unwrap()), loc))
+            Some(ref elem) if let Some(identifier) = as_identifier(self.strict, elem) => {
+                Ok((Identifier::from(identifier), elem.source_location().clone()))
             }
             elem => Err(Error::unexpected(Expected::Identifier(placeholder), elem)),
         }
     }
 
+    /// Expect an _IdentifierName_, which unlike an identifier, may also be a
+    /// reserved word, e.g. the property name in `obj.default`.
+    fn expect_identifier_name(
+        &mut self,
+        placeholder: &'static str,
+    ) -> Result<(Identifier, SourceLocation)> {
+        match self.source.next()? {
+            Some(ref elem) if let Some(identifier) = as_identifier_name(elem) => {
+                Ok((Identifier::from(identifier), elem.source_location().clone()))
+            }
+            elem => Err(Error::unexpected(Expected::Identifier(placeholder), elem)),
+        }
+    }
+
+    fn check_binding_identifier(
+        &self,
+        identifier: &Identifier,
+        loc: &SourceLocation,
+    ) -> Result<()> {
+        check_binding_identifier(self.strict, identifier, loc)
+    }
+
     fn expect_literal(&mut self) -> Result<(token::Literal, SourceLocation)> {
         match self.source.next()? {
             Some(elem) if elem.literal().is_some() => {
@@ -100,3 +173,36 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
         }
     }
 }
+
+/// Keywords which are only reserved in certain contexts can still be used as
+/// identifiers, e.g. `let of = 1;`.
+fn as_identifier(strict: bool, elem: &Element) -> Option<&str> {
+    match elem.keyword() {
+        Some(kw) if kw.is_contextual() => Some(kw.as_str()),
+        Some(kw) if kw.is_reserved_in_strict_mode_only() && !strict => Some(kw.as_str()),
+        Some(_) => None,
+        None => elem.identifier(),
+    }
+}
+
+fn as_identifier_name(elem: &Element) -> Option<&str> {
+    elem.identifier()
+        .or_else(|| elem.keyword().map(|kw| kw.as_str()))
+}
+
+/// `eval` and `arguments` can't be assigned to, or used as the name of a
+/// binding, in strict mode code.
+fn check_binding_identifier(
+    strict: bool,
+    identifier: &Identifier,
+    loc: &SourceLocation,
+) -> Result<()> {
+    if strict && matches!(identifier.as_str(), "eval" | "arguments") {
+        Err(Error::early(
+            EarlyErrorKind::StrictModeEvalOrArguments(identifier.clone()),
+            loc.clone(),
+        ))
+    } else {
+        Ok(())
+    }
+}
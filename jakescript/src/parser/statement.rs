@@ -1,4 +1,4 @@
-use super::error::{Error, Result};
+use super::error::{EarlyErrorKind, Error, Result};
 use super::Parser;
 use crate::ast::{self, *};
 use crate::iter::peek_fallible::PeekableNthFallibleIterator;
@@ -7,7 +7,7 @@ use crate::parser::Expected;
 use crate::token::Element;
 use crate::token::Keyword::{
     Break, Case, Catch, Const, Continue, Default, Do, Else, Finally, For, Function, If, Let,
-    Return, Switch, Throw, Try, Var, While,
+    Return, Switch, Throw, Try, Var, While, With,
 };
 use crate::token::Punctuator::{CloseBrace, CloseParen, Colon, OpenBrace, OpenParen, Semi};
 use fallible_iterator::FallibleIterator;
@@ -19,12 +19,12 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
                 OpenBrace => self.parse_block_statement().map(Statement::Block),
                 Semi => self.parse_empty_statement().map(Statement::Empty),
                 _ => self.parse_expression_statement().map(Statement::Expression),
-            }
+            },
 
             Some(elem) if let Some(kw) = elem.keyword() => match kw {
-                Const | Function | Let | Var => {
-                    self.parse_declaration_statement().map(Statement::Declaration)
-                }
+                Const | Function | Let | Var => self
+                    .parse_declaration_statement()
+                    .map(Statement::Declaration),
 
                 If => self.parse_if_statement().map(Statement::If),
                 Switch => self.parse_switch_statement().map(Statement::Switch),
@@ -34,13 +34,18 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
                 For => self.parse_for_statement().map(Statement::For),
                 While => self.parse_while_statement().map(Statement::While),
 
+                With if self.strict => Err(Error::early(
+                    EarlyErrorKind::StrictModeWith,
+                    elem.source_location().clone(),
+                )),
+
                 Break => self.parse_break_statement().map(Statement::Break),
                 Continue => self.parse_continue_statement().map(Statement::Continue),
                 Return => self.parse_return_statement().map(Statement::Return),
                 Throw => self.parse_throw_statement().map(Statement::Throw),
 
                 _ => self.parse_expression_statement().map(Statement::Expression),
-            }
+            },
 
             Some(_) => self.parse_expression_statement().map(Statement::Expression),
             None => Err(Error::unexpected_eoi(Expected::AnyStatement)),
@@ -54,8 +59,7 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
 
     fn parse_expression_statement(&mut self) -> Result<Expression> {
         let expression = self.parse_expression()?;
-        self.skip_non_tokens()?;
-        self.expect_punctuator(Semi)?;
+        self.expect_semicolon()?;
         Ok(expression)
     }
 
@@ -108,6 +112,21 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
         self.expect_punctuator(CloseParen)?;
         self.skip_non_tokens()?;
         self.expect_punctuator(OpenBrace)?;
+        let outer_context = self.context;
+        self.context.switch = true;
+        let cases = self.with_declaration_scope(|this| this.parse_switch_cases());
+        self.context = outer_context;
+        let (cases, default_case) = cases?;
+        self.expect_punctuator(CloseBrace)?;
+        Ok(SwitchStatement {
+            loc,
+            value,
+            cases,
+            default_case,
+        })
+    }
+
+    fn parse_switch_cases(&mut self) -> Result<(Vec<CaseStatement>, Option<DefaultCaseStatement>)> {
         let mut cases = Vec::new();
         let mut default_case = None;
         loop {
@@ -132,13 +151,7 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
                 }
             }
         }
-        self.expect_punctuator(CloseBrace)?;
-        Ok(SwitchStatement {
-            loc,
-            value,
-            cases,
-            default_case,
-        })
+        Ok((cases, default_case))
     }
 
     fn parse_case_statement(&mut self) -> Result<CaseStatement> {
@@ -218,7 +231,8 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
             .is_some()
         {
             self.skip_non_tokens()?;
-            let (exception_binding, _) = self.expect_identifier("exception_binding")?;
+            let (exception_binding, binding_loc) = self.expect_identifier("exception_binding")?;
+            self.check_binding_identifier(&exception_binding, &binding_loc)?;
             self.skip_non_tokens()?;
             self.expect_punctuator(CloseParen)?;
             Some(exception_binding)
@@ -226,7 +240,7 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
             None
         };
         self.skip_non_tokens()?;
-        let (_, body) = self.parse_block()?;
+        let body = self.parse_catch_body(exception_binding.as_ref())?;
         Ok(ast::CatchStatement {
             loc,
             exception_binding,
@@ -244,7 +258,7 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
     fn parse_do_statement(&mut self) -> Result<DoStatement> {
         let loc = self.expect_keyword(Do)?;
         self.skip_non_tokens()?;
-        let body = self.parse_statement()?;
+        let body = self.parse_iteration_body()?;
         self.skip_non_tokens()?;
         self.expect_keyword(While)?;
         self.skip_non_tokens()?;
@@ -253,8 +267,11 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
         let condition = self.parse_expression()?;
         self.skip_non_tokens()?;
         self.expect_punctuator(CloseParen)?;
+        // A semicolon is always inserted after a do-while statement if needed, even
+        // without a line terminator.
         self.skip_non_tokens()?;
-        self.expect_punctuator(Semi)?;
+        self.source
+            .next_if(|elem| elem.punctuator() == Some(Semi))?;
         Ok(DoStatement {
             loc,
             body: Box::new(body),
@@ -263,6 +280,11 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
     }
 
     fn parse_for_statement(&mut self) -> Result<ForStatement> {
+        // Lexical declarations in the initialiser are scoped to the loop.
+        self.with_declaration_scope(Self::parse_for_statement_impl)
+    }
+
+    fn parse_for_statement_impl(&mut self) -> Result<ForStatement> {
         let loc = self.expect_keyword(For)?;
         self.skip_non_tokens()?;
         self.expect_punctuator(OpenParen)?;
@@ -292,7 +314,7 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
         self.expect_punctuator(CloseParen)?;
         self.skip_non_tokens()?;
 
-        let body = self.parse_statement()?;
+        let body = self.parse_iteration_body()?;
         Ok(ForStatement {
             loc,
             initialiser,
@@ -331,7 +353,7 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
         self.skip_non_tokens()?;
         self.expect_punctuator(CloseParen)?;
         self.skip_non_tokens()?;
-        let body = self.parse_statement()?;
+        let body = self.parse_iteration_body()?;
         Ok(WhileStatement {
             loc,
             condition,
@@ -339,38 +361,80 @@ impl<I: FallibleIterator<Item = Element, Error = lexer::Error>> Parser<I> {
         })
     }
 
+    fn parse_iteration_body(&mut self) -> Result<Statement> {
+        let outer_context = self.context;
+        self.context.iteration = true;
+        let body = self.parse_statement();
+        self.context = outer_context;
+        body
+    }
+
     fn parse_break_statement(&mut self) -> Result<BreakStatement> {
         let loc = self.expect_keyword(Break)?;
-        self.skip_non_tokens()?;
-        self.expect_punctuator(Semi)?;
+        self.parse_jump_label()?;
+        if !self.context.iteration && !self.context.switch {
+            return Err(Error::early(EarlyErrorKind::BreakOutsideLoopOrSwitch, loc));
+        }
+        self.expect_semicolon()?;
         Ok(BreakStatement { loc })
     }
 
     fn parse_continue_statement(&mut self) -> Result<ContinueStatement> {
         let loc = self.expect_keyword(Continue)?;
-        self.skip_non_tokens()?;
-        self.expect_punctuator(Semi)?;
+        self.parse_jump_label()?;
+        if !self.context.iteration {
+            return Err(Error::early(EarlyErrorKind::ContinueOutsideLoop, loc));
+        }
+        self.expect_semicolon()?;
         Ok(ContinueStatement { loc })
     }
 
+    /// Labelled statements aren't supported, so any label given to a `break` or
+    /// `continue` statement can't possibly refer to an enclosing statement.
+    fn parse_jump_label(&mut self) -> Result<()> {
+        if self.line_terminator_before_next_token()? {
+            return Ok(());
+        }
+        match self.source.peek()? {
+            Some(elem) if elem.identifier().is_some() => {
+                let (label, loc) = self.expect_identifier("label")?;
+                Err(Error::early(EarlyErrorKind::UndefinedLabel(label), loc))
+            }
+            _ => Ok(()),
+        }
+    }
+
     fn parse_return_statement(&mut self) -> Result<ReturnStatement> {
         let loc = self.expect_keyword(Return)?;
-        self.skip_non_tokens()?;
-        let value = match self.source.peek()? {
-            Some(elem) if elem.punctuator() == Some(Semi) => None,
-            _ => Some(self.parse_expression()?),
+        if !self.context.function {
+            return Err(Error::early(EarlyErrorKind::ReturnOutsideFunction, loc));
+        }
+        // A line terminator straight after `return` ends the statement.
+        let value = if self.line_terminator_before_next_token()? {
+            None
+        } else {
+            match self.source.peek()? {
+                Some(elem) if matches!(elem.punctuator(), Some(Semi | CloseBrace)) => None,
+                None => None,
+                Some(_) => Some(self.parse_expression()?),
+            }
         };
-        self.skip_non_tokens()?;
-        self.expect_punctuator(Semi)?;
+        self.expect_semicolon()?;
         Ok(ReturnStatement { loc, value })
     }
 
     fn parse_throw_statement(&mut self) -> Result<ThrowStatement> {
         let loc = self.expect_keyword(Throw)?;
-        self.skip_non_tokens()?;
+        // Unlike `return`, a line terminator straight after `throw` is an error
+        // rather than the end of the statement.
+        if self.line_terminator_before_next_token()? {
+            return Err(Error::unexpected(
+                Expected::AnyExpressionOnSameLine,
+                self.source.peek()?.cloned(),
+            ));
+        }
         let exception = self.parse_expression()?;
-        self.skip_non_tokens()?;
-        self.expect_punctuator(Semi)?;
+        self.expect_semicolon()?;
         Ok(ThrowStatement { loc, exception })
     }
 }
@@ -1,7 +1,7 @@
 use super::error::{Actual, ErrorKind, Expected};
 use super::Parser;
 use crate::ast::{self, *};
-use crate::token::Keyword::{Function, Let, Return, While};
+use crate::token::Keyword::{Function, Let, Return, Throw, While};
 use crate::token::Punctuator::{
     CloseBrace, CloseParen, Eq, OpenBrace, OpenParen, Plus, Semi, StarStar,
 };
@@ -123,6 +123,7 @@ mod simple {
                             })),
                         })),
                     })),
+                    strict: false,
                 })],
                 vec![Statement::Expression(Expression::FunctionCall(
                     FunctionCallExpression {
@@ -140,6 +141,7 @@ mod simple {
                     },
                 ))],
             ),
+            false,
         )
     }
 }
@@ -235,3 +237,44 @@ fn parse_unfinished_binary_expression() {
         )
     );
 }
+
+#[test]
+fn parse_missing_semicolon_on_same_line() {
+    let loc = SourceLocation::at_start_of("test");
+    let source = vec![
+        identifier!("a", at![loc@0:0]),
+        whitespace!(" ", at![loc@0:1]),
+        identifier!("b", at![loc@0:2]),
+    ];
+
+    let parser = Parser::for_elements(source.into_iter());
+    assert_matches!(
+        parser.execute(),
+        Err(err) if matches!(
+            err.kind(),
+            ErrorKind::Parser(Expected::Punctuator(Semi), Actual::Element(actual))
+            if actual == &identifier!("b", at![loc@0:2])
+        )
+    );
+}
+
+#[test]
+fn parse_throw_followed_by_line_terminator() {
+    let loc = SourceLocation::at_start_of("test");
+    let source = vec![
+        Element::new_keyword(Throw, at![loc@0:0]),
+        line_terminator!(Lf, at![loc@0:5]),
+        identifier!("a", at![loc@1:0]),
+        Element::new_punctuator(Semi, at![loc@1:1]),
+    ];
+
+    let parser = Parser::for_elements(source.into_iter());
+    assert_matches!(
+        parser.execute(),
+        Err(err) if matches!(
+            err.kind(),
+            ErrorKind::Parser(Expected::AnyExpressionOnSameLine, Actual::Element(actual))
+            if actual == &identifier!("a", at![loc@1:0])
+        )
+    );
+}
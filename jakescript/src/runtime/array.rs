@@ -1,7 +1,8 @@
-use super::Builtin;
+use super::object::object_to_string;
+use super::{receiver_object, Builtin};
 use crate::interpreter::{
-    ErrorKind, Extensible, Heap, InitialisationError, Number, Object, ObjectData, Property,
-    PropertyKey, Reference, Value,
+    ErrorKind, Extensible, Heap, InitialisationError, Interpreter, JsString, Number, Object,
+    ObjectData, Property, PropertyKey, PropertyNotWritableError, Reference, Value,
 };
 use crate::{builtin_fn, prop_key};
 use common_macros::hash_map;
@@ -17,12 +18,14 @@ impl Builtin for ArrayProtoBuiltin {
         heap: &mut Heap,
         (obj_proto, fn_proto): Self::InitArgs,
     ) -> Result<Self, InitialisationError> {
-        let length = GetLengthBuiltin::init(heap, fn_proto)?;
+        let join = JoinBuiltin::init(heap, fn_proto)?;
         let push = PushBuiltin::init(heap, fn_proto)?;
+        let to_string = ToStringBuiltin::init(heap, fn_proto)?;
 
         let props = hash_map![
-            prop_key!("length") => Property::new_const_accessor(length.obj_ref()),
+            prop_key!("join") => Property::new_user(join.as_value()),
             prop_key!("push") => Property::new_user(push.as_value()),
+            prop_key!("toString") => Property::new_user(to_string.as_value()),
         ];
 
         let obj_ref = heap.allocate(Object::new(
@@ -46,36 +49,124 @@ builtin_fn!(pub ArrayCtorBuiltin, Extensible::Yes, (it, _receiver, args) => {
         .map_err(ErrorKind::from)
 });
 
-builtin_fn!(GetLengthBuiltin, Extensible::No, (it, receiver, _args) => {
-    let receiver = it.vm().heap().resolve(receiver);
-    let length = receiver.as_ref().own_property_keys().count();
-    let length = Number::try_from(length).unwrap_or_else(|_| {
-        // TODO
-        unreachable!()
-    });
-    Ok(Value::Number(length))
+builtin_fn!(JoinBuiltin, Extensible::Yes, (it, receiver, args) => {
+    let receiver = receiver_object(receiver)?;
+    let separator = match args.first().cloned().unwrap_or_default() {
+        Value::Undefined => ",".into(),
+        separator => it.to_string(separator)?,
+    };
+    // An array which contains itself is joined as if the inner reference were
+    // empty, as other engines do.
+    if !it.vm_mut().begin_join(receiver) {
+        return Ok(Value::String(JsString::default()));
+    }
+    let result = join(it, receiver, &separator);
+    it.vm_mut().end_join(receiver);
+    Ok(match result? {
+        Some(out) => Value::String(JsString::from(out)),
+        None => Value::Undefined,
+    })
 });
 
+/// Join the elements of an array-like object, or return `None` if the script is
+/// interrupted first.
+fn join(
+    it: &mut Interpreter,
+    receiver: Reference,
+    separator: &JsString,
+) -> Result<Option<Vec<u16>>, ErrorKind> {
+    let len = length_of_array_like(it, receiver)?;
+    let mut out = Vec::new();
+    for idx in 0..len {
+        if it.vm_mut().check_interrupt() {
+            return Ok(None);
+        }
+        if idx > 0 {
+            out.extend_from_slice(separator.as_utf16());
+        }
+        let elem = {
+            let array = it.vm().heap().resolve(receiver);
+            let elem = array.as_ref().get(it, &PropertyKey::from(idx), receiver)?;
+            elem
+        };
+        match elem.unwrap_or_default() {
+            Value::Null | Value::Undefined => {}
+            elem => out.extend_from_slice(it.to_string(elem)?.as_utf16()),
+        }
+        it.vm().check_string_length(out.len())?;
+    }
+    Ok(Some(out))
+}
+
 builtin_fn!(PushBuiltin, Extensible::Yes, (it, receiver, args) => {
-    let mut array = it.vm_mut().heap_mut().resolve_mut(receiver);
-    let start_len = array.as_ref().own_property_keys().count();
-    args.iter()
-        .copied()
-        .enumerate()
-        .map(|(idx, value)| {
-            (
-                PropertyKey::from(start_len + idx),
-                Property::new_enumerable(value),
-            )
-        })
-        .for_each(|(prop_key, prop_value)| {
-            let defined = array.as_ref_mut().define_own_property(prop_key, prop_value);
-            assert!(defined);
-        });
-    let finish_len = array.as_ref().own_property_keys().count();
-    let finish_len = Number::try_from(finish_len).unwrap_or_else(|_| {
-        // TODO
-        unreachable!()
-    });
-    Ok(Value::Number(finish_len))
+    let receiver = receiver_object(receiver)?;
+    let start_len = length_of_array_like(it, receiver)?;
+    for (idx, value) in args.iter().cloned().enumerate() {
+        set_or_throw(it, receiver, &PropertyKey::from(start_len + idx), value)?;
+    }
+    let finish_len = Value::Number(Number::try_from(start_len + args.len()).unwrap());
+    set_or_throw(it, receiver, &prop_key!("length"), finish_len.clone())?;
+    Ok(finish_len)
+});
+
+builtin_fn!(ToStringBuiltin, Extensible::Yes, (it, receiver, _args) => {
+    let receiver_ref = receiver_object(receiver.clone())?;
+    let join = {
+        let array = it.vm().heap().resolve(receiver_ref);
+        let join = array.as_ref().get(it, &prop_key!("join"), receiver_ref)?;
+        join
+    };
+    // Fall back to `Object.prototype.toString` if `join` has been replaced with
+    // something which can't be called.
+    if let Some(Value::Object(join_ref)) = join {
+        let join_obj = it.vm().heap().resolve(join_ref);
+        if join_obj.as_ref().call_data().is_some() {
+            return join_obj
+                .as_ref()
+                .call(it, join_ref, Value::Object(receiver_ref), &[]);
+        }
+    }
+    let s = object_to_string(it, receiver);
+    Ok(Value::String(JsString::from(s)))
 });
+
+/// [LengthOfArrayLike](https://tc39.es/ecma262/#sec-lengthofarraylike), so
+/// that the methods of `Array.prototype` work for any object with a `length`.
+pub(super) fn length_of_array_like(
+    it: &mut Interpreter,
+    obj_ref: Reference,
+) -> Result<usize, ErrorKind> {
+    let obj = it.vm().heap().resolve(obj_ref);
+    let length = obj
+        .as_ref()
+        .get(it, &prop_key!("length"), obj_ref)?
+        .unwrap_or_default();
+    drop(obj);
+    let n = it.to_number(length)?;
+    Ok(if n.is_nan() || n.is_negative() {
+        0
+    } else {
+        usize::try_from(n.as_i64()).unwrap_or(usize::MAX)
+    })
+}
+
+/// [Set](https://tc39.es/ecma262/#sec-set-o-p-v-throw) with `Throw` set to
+/// `true`, as failed assignments by built-in methods always throw.
+fn set_or_throw(
+    it: &mut Interpreter,
+    obj_ref: Reference,
+    key: &PropertyKey,
+    value: Value,
+) -> Result<(), ErrorKind> {
+    let set = it
+        .vm()
+        .heap()
+        .resolve(obj_ref)
+        .as_ref_mut()
+        .set(it, key, obj_ref, value)?;
+    if set {
+        Ok(())
+    } else {
+        Err(ErrorKind::from(PropertyNotWritableError::new(key.clone())))
+    }
+}
@@ -1,11 +1,10 @@
 use super::Builtin;
 use crate::interpreter::{
-    AssertionError, ErrorKind, Extensible, Heap, InitialisationError, Interpreter, Object,
-    ObjectData, Property, Reference, Value,
+    AssertionError, ErrorKind, Extensible, Heap, InitialisationError, Interpreter, JsString,
+    Object, ObjectData, Property, Reference, Value,
 };
 use crate::{builtin_fn, prop_key};
 use common_macros::hash_map;
-use std::borrow::Cow;
 
 pub struct ConsoleBuiltin {
     obj_ref: Reference,
@@ -46,51 +45,58 @@ impl Builtin for ConsoleBuiltin {
 
 builtin_fn!(AssertBuiltin, Extensible::Yes, (it, _receiver, args) => {
     let mut args = args.iter();
-    let assertion = args.next().copied().unwrap_or(Value::Undefined);
-    if it.is_truthy(assertion) {
+    let assertion = args.next().cloned().unwrap_or(Value::Undefined);
+    if it.is_truthy(&assertion) {
         Ok(Value::Undefined)
     } else {
-        let detail_msg = build_msg(it, args);
+        let detail_msg = build_msg(it, args)?;
         Err(ErrorKind::from(AssertionError::new(detail_msg)))
     }
 });
 
 builtin_fn!(AssertEqualBuiltin, Extensible::Yes, (it, _receiver, args) => {
-    fn is_nan(v: Value) -> bool {
+    fn is_nan(v: &Value) -> bool {
         matches!(v, Value::Number(n) if n.is_nan())
     }
 
     let mut args = args.iter();
-    let actual = args.next().copied().unwrap_or(Value::Undefined);
-    let expected = args.next().copied().unwrap_or(Value::Boolean(true));
-    if is_nan(expected) && is_nan(actual) || it.strictly_equal(actual, expected)
+    let actual = args.next().cloned().unwrap_or(Value::Undefined);
+    let expected = args.next().cloned().unwrap_or(Value::Boolean(true));
+    if is_nan(&expected) && is_nan(&actual) || it.is_strictly_equal(&actual, &expected)
     {
         Ok(Value::Undefined)
     } else {
         let detail_msg = format!(
             "expected '{}' but was '{}': {}",
-            it.coerce_to_string(expected),
-            it.coerce_to_string(actual),
-            build_msg(it, args)
+            it.to_string(expected)?,
+            it.to_string(actual)?,
+            build_msg(it, args)?
         );
         Err(ErrorKind::from(AssertionError::new(detail_msg)))
     }
 });
 
 builtin_fn!(AssertNotReachedBuiltin, Extensible::Yes, (it, _receiver, args) => {
-    let detail_msg = format!("entered unreachable code: {}", build_msg(it, args.iter()));
+    let detail_msg = format!("entered unreachable code: {}", build_msg(it, args.iter())?);
     Err(ErrorKind::from(AssertionError::new(detail_msg)))
 });
 
 builtin_fn!(LogBuiltin, Extensible::Yes, (it, _receiver, args) => {
-    let msg = build_msg(it, args.iter());
+    let msg = build_msg(it, args.iter())?;
     it.vm_mut().write_message(&msg);
     Ok(Value::Undefined)
 });
 
-fn build_msg<'a>(it: &Interpreter, values: impl Iterator<Item = &'a Value>) -> String {
-    values
-        .map(|&arg| it.coerce_to_string(arg))
-        .intersperse_with(|| Cow::Borrowed(" "))
-        .collect()
+fn build_msg<'a>(
+    it: &mut Interpreter,
+    values: impl Iterator<Item = &'a Value>,
+) -> Result<String, ErrorKind> {
+    let strs = values
+        .map(|arg| it.to_string(arg.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(strs
+        .iter()
+        .map(JsString::to_string_lossy)
+        .intersperse(" ".to_owned())
+        .collect())
 }
@@ -0,0 +1,237 @@
+use super::array::length_of_array_like;
+use super::{prototype_from_constructor, receiver_object, Builtin};
+use crate::interpreter::{
+    ErrorKind, Extensible, Heap, InitialisationError, Interpreter, JsString, NativeErrorKind,
+    NotIterableError, Object, ObjectData, Property, PropertyKey, Reference, Value,
+};
+use crate::{builtin_fn, prop_key};
+use common_macros::hash_map;
+
+pub struct ErrorProtoBuiltin {
+    obj_ref: Reference,
+}
+
+impl Builtin for ErrorProtoBuiltin {
+    type InitArgs = (Reference, Reference);
+
+    fn init(
+        heap: &mut Heap,
+        (obj_proto, fn_proto): Self::InitArgs,
+    ) -> Result<Self, InitialisationError> {
+        let to_string = ToStringBuiltin::init(heap, fn_proto)?;
+
+        let props = hash_map![
+            prop_key!("message") => Property::new_user(Value::String(JsString::default())),
+            prop_key!("name") => Property::new_user(Value::String(JsString::from("Error"))),
+            prop_key!("toString") => Property::new_user(to_string.as_value()),
+        ];
+
+        let obj_ref = heap.allocate(Object::new(
+            Some(obj_proto),
+            props,
+            ObjectData::None,
+            Extensible::Yes,
+        ))?;
+        Ok(Self { obj_ref })
+    }
+
+    fn obj_ref(&self) -> Reference {
+        self.obj_ref
+    }
+}
+
+/// The prototype of a subclass of `Error`, e.g. `TypeError.prototype`, which
+/// only overrides the `name` and `message`.
+pub struct ErrorSubclassProtoBuiltin {
+    obj_ref: Reference,
+}
+
+impl Builtin for ErrorSubclassProtoBuiltin {
+    type InitArgs = (Reference, &'static str);
+
+    fn init(
+        heap: &mut Heap,
+        (error_proto, name): Self::InitArgs,
+    ) -> Result<Self, InitialisationError> {
+        let props = hash_map![
+            prop_key!("message") => Property::new_user(Value::String(JsString::default())),
+            prop_key!("name") => Property::new_user(Value::String(JsString::from(name))),
+        ];
+
+        let obj_ref = heap.allocate(Object::new(
+            Some(error_proto),
+            props,
+            ObjectData::None,
+            Extensible::Yes,
+        ))?;
+        Ok(Self { obj_ref })
+    }
+
+    fn obj_ref(&self) -> Reference {
+        self.obj_ref
+    }
+}
+
+builtin_fn!(pub ErrorCtorBuiltin, Extensible::Yes, (it, _receiver, args) => {
+    let proto = it.vm().runtime().global_object().error_proto().obj_ref();
+    construct_error(it, proto, args)
+});
+
+builtin_fn!(pub EvalErrorCtorBuiltin, Extensible::Yes, (it, _receiver, args) => {
+    construct_native_error(it, NativeErrorKind::Eval, args)
+});
+
+builtin_fn!(pub RangeErrorCtorBuiltin, Extensible::Yes, (it, _receiver, args) => {
+    construct_native_error(it, NativeErrorKind::Range, args)
+});
+
+builtin_fn!(pub ReferenceErrorCtorBuiltin, Extensible::Yes, (it, _receiver, args) => {
+    construct_native_error(it, NativeErrorKind::Reference, args)
+});
+
+builtin_fn!(pub SyntaxErrorCtorBuiltin, Extensible::Yes, (it, _receiver, args) => {
+    construct_native_error(it, NativeErrorKind::Syntax, args)
+});
+
+builtin_fn!(pub TypeErrorCtorBuiltin, Extensible::Yes, (it, _receiver, args) => {
+    construct_native_error(it, NativeErrorKind::Type, args)
+});
+
+builtin_fn!(pub UriErrorCtorBuiltin, Extensible::Yes, (it, _receiver, args) => {
+    construct_native_error(it, NativeErrorKind::Uri, args)
+});
+
+builtin_fn!(pub AggregateErrorCtorBuiltin, Extensible::Yes, (it, _receiver, args) => {
+    let errors = args.first().cloned().unwrap_or_default();
+    let proto = it.vm().runtime().global_object().aggregate_error_proto().obj_ref();
+    let error = construct_error(it, proto, args.get(1..).unwrap_or_default())?;
+    // Getting the length of `errors` can call back into the interpreter.
+    it.vm_mut().stack_mut().push_temporary(&error);
+
+    // TODO: Accept any iterable, rather than only array-like objects.
+    let Value::Object(errors_ref) = errors else {
+        return Err(ErrorKind::from(NotIterableError::new()));
+    };
+    let len = length_of_array_like(it, errors_ref)?;
+    let errors_obj = it.vm().heap().resolve(errors_ref);
+    let mut elems = Vec::new();
+    for idx in 0..len {
+        if it.vm_mut().check_interrupt() {
+            return Ok(Value::Undefined);
+        }
+        let elem = errors_obj
+            .as_ref()
+            .get(it, &PropertyKey::from(idx), errors_ref)?
+            .unwrap_or_default();
+        elems.push(elem);
+    }
+    let errors = it.vm_mut().alloc_array(elems)?;
+
+    if let Value::Object(error_ref) = error {
+        let mut error_obj = it.vm_mut().heap_mut().resolve_mut(error_ref);
+        error_obj.define_own_property(prop_key!("errors"), Property::new_user(Value::Object(errors)));
+    }
+    Ok(error)
+});
+
+builtin_fn!(ToStringBuiltin, Extensible::Yes, (it, receiver, _args) => {
+    let receiver = receiver_object(receiver)?;
+    let name = get_string_property(it, receiver, &prop_key!("name"), "Error")?;
+    let message = get_string_property(it, receiver, &prop_key!("message"), "")?;
+    Ok(Value::String(JsString::from(describe(&name, &message))))
+});
+
+/// Create a new error object with the `message` and `options` given by
+/// `args`, inheriting from the prototype of `new.target`, or from
+/// `default_proto` (the prototype of the constructor being called) if there
+/// isn't one.
+fn construct_error(
+    it: &mut Interpreter,
+    default_proto: Reference,
+    args: &[Value],
+) -> Result<Value, ErrorKind> {
+    let proto = prototype_from_constructor(it, default_proto)?;
+    let mut args = args.iter().cloned();
+    let message = args.next().unwrap_or_default();
+    let options = args.next().unwrap_or_default();
+
+    let mut props = Vec::new();
+    if !matches!(message, Value::Undefined) {
+        let message = it.to_string(message)?;
+        props.push((
+            prop_key!("message"),
+            Property::new_user(Value::String(message)),
+        ));
+    }
+    if let Value::Object(options_ref) = options {
+        let options_obj = it.vm().heap().resolve(options_ref);
+        if options_obj.as_ref().has_property(it, &prop_key!("cause")) {
+            let cause = options_obj
+                .as_ref()
+                .get(it, &prop_key!("cause"), options_ref)?
+                .unwrap_or_default();
+            props.push((prop_key!("cause"), Property::new_user(cause)));
+        }
+    }
+
+    let obj_ref = it.vm_mut().heap_mut().allocate(Object::new(
+        Some(proto),
+        props,
+        ObjectData::None,
+        Extensible::Yes,
+    ))?;
+
+    // Capture the stack at construction time, like other engines, rather than
+    // when the error is thrown.
+    let name = get_string_property(it, obj_ref, &prop_key!("name"), "Error")?;
+    let message = get_string_property(it, obj_ref, &prop_key!("message"), "")?;
+    let stack = format!(
+        "{}{}",
+        describe(&name, &message),
+        it.vm().stack().stack_trace()
+    );
+    let stack = Value::String(JsString::from(stack));
+    let mut obj = it.vm_mut().heap_mut().resolve_mut(obj_ref);
+    obj.define_own_property(prop_key!("stack"), Property::new_user(stack));
+    Ok(Value::Object(obj_ref))
+}
+
+fn construct_native_error(
+    it: &mut Interpreter,
+    kind: NativeErrorKind,
+    args: &[Value],
+) -> Result<Value, ErrorKind> {
+    let proto = it
+        .vm()
+        .runtime()
+        .global_object()
+        .native_error_proto(kind)
+        .obj_ref();
+    construct_error(it, proto, args)
+}
+
+/// Combine the `name` and `message` of an error, as done by
+/// `Error.prototype.toString`.
+fn describe(name: &str, message: &str) -> String {
+    if name.is_empty() {
+        message.to_owned()
+    } else if message.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{name}: {message}")
+    }
+}
+
+fn get_string_property(
+    it: &mut Interpreter,
+    obj_ref: Reference,
+    key: &PropertyKey,
+    default: &str,
+) -> Result<String, ErrorKind> {
+    let obj = it.vm().heap().resolve(obj_ref);
+    let value = obj.as_ref().get(it, key, obj_ref)?.unwrap_or_default();
+    Ok(match value {
+        Value::Undefined => default.to_owned(),
+        value => it.to_string(value)?.to_string_lossy(),
+    })
+}
@@ -1,14 +1,21 @@
 use super::array::{ArrayCtorBuiltin, ArrayProtoBuiltin};
 use super::boolean::BooleanCtorBuiltin;
 use super::console::ConsoleBuiltin;
+use super::error::{
+    AggregateErrorCtorBuiltin, ErrorCtorBuiltin, ErrorProtoBuiltin, ErrorSubclassProtoBuiltin,
+    EvalErrorCtorBuiltin, RangeErrorCtorBuiltin, ReferenceErrorCtorBuiltin, SyntaxErrorCtorBuiltin,
+    TypeErrorCtorBuiltin, UriErrorCtorBuiltin,
+};
 use super::function::FunctionProtoBuiltin;
 use super::math::MathBuiltin;
-use super::number::NumberCtorBuiltin;
+use super::number::{NumberCtorBuiltin, NumberProtoBuiltin};
+use super::regexp::{RegExpCtorBuiltin, RegExpProtoBuiltin, RegExpStringIteratorProtoBuiltin};
 use super::string::{StringCtorBuiltin, StringProtoBuiltin};
+use super::symbol::SymbolBuiltin;
 use super::Builtin;
 use crate::interpreter::{
-    ExecutionState, Extensible, Heap, InitialisationError, Number, Object, ObjectData, Property,
-    Reference, Value,
+    ExecutionState, Extensible, Heap, InitialisationError, NativeErrorKind, Number, Object,
+    ObjectData, Property, Reference, Trace, Tracer, Value,
 };
 use crate::runtime::object::ObjectProtoBuiltin;
 use crate::{builtin_fn, prop_key};
@@ -22,6 +29,19 @@ pub struct GlobalObject {
     // TODO: Prototypes should probably be members of the `Runtime` rather than of the
     // `GlobalObject`.
     array_proto: ArrayProtoBuiltin,
+    error_proto: ErrorProtoBuiltin,
+    aggregate_error_proto: ErrorSubclassProtoBuiltin,
+    function_proto: FunctionProtoBuiltin,
+    eval_error_proto: ErrorSubclassProtoBuiltin,
+    range_error_proto: ErrorSubclassProtoBuiltin,
+    reference_error_proto: ErrorSubclassProtoBuiltin,
+    syntax_error_proto: ErrorSubclassProtoBuiltin,
+    type_error_proto: ErrorSubclassProtoBuiltin,
+    uri_error_proto: ErrorSubclassProtoBuiltin,
+    number_proto: NumberProtoBuiltin,
+    object_proto: ObjectProtoBuiltin,
+    regexp_proto: RegExpProtoBuiltin,
+    regexp_string_iterator_proto: RegExpStringIteratorProtoBuiltin,
     string_proto: StringProtoBuiltin,
     obj_ref: Reference,
 }
@@ -49,6 +69,45 @@ impl GlobalObject {
         &self.array_proto
     }
 
+    pub fn error_proto(&self) -> &ErrorProtoBuiltin {
+        &self.error_proto
+    }
+
+    pub fn aggregate_error_proto(&self) -> &ErrorSubclassProtoBuiltin {
+        &self.aggregate_error_proto
+    }
+
+    pub fn function_proto(&self) -> &FunctionProtoBuiltin {
+        &self.function_proto
+    }
+
+    pub fn native_error_proto(&self, kind: NativeErrorKind) -> &ErrorSubclassProtoBuiltin {
+        match kind {
+            NativeErrorKind::Eval => &self.eval_error_proto,
+            NativeErrorKind::Range => &self.range_error_proto,
+            NativeErrorKind::Reference => &self.reference_error_proto,
+            NativeErrorKind::Syntax => &self.syntax_error_proto,
+            NativeErrorKind::Type => &self.type_error_proto,
+            NativeErrorKind::Uri => &self.uri_error_proto,
+        }
+    }
+
+    pub fn number_proto(&self) -> &NumberProtoBuiltin {
+        &self.number_proto
+    }
+
+    pub fn object_proto(&self) -> &ObjectProtoBuiltin {
+        &self.object_proto
+    }
+
+    pub fn regexp_proto(&self) -> &RegExpProtoBuiltin {
+        &self.regexp_proto
+    }
+
+    pub fn regexp_string_iterator_proto(&self) -> &RegExpStringIteratorProtoBuiltin {
+        &self.regexp_string_iterator_proto
+    }
+
     pub fn string_proto(&self) -> &StringProtoBuiltin {
         &self.string_proto
     }
@@ -58,20 +117,68 @@ impl Builtin for GlobalObject {
     fn init(heap: &mut Heap, (): Self::InitArgs) -> Result<Self, InitialisationError> {
         let obj_proto = ObjectProtoBuiltin::init(heap, ())?;
         let fn_proto = FunctionProtoBuiltin::init(heap, obj_proto.obj_ref())?;
+        obj_proto.init_methods(heap, fn_proto.obj_ref())?;
         let global_obj_proto = GlobalObjectProto::init(heap, obj_proto.obj_ref())?;
 
         let array_proto = ArrayProtoBuiltin::init(heap, (obj_proto.obj_ref(), fn_proto.obj_ref()))?;
+        let number_proto =
+            NumberProtoBuiltin::init(heap, (obj_proto.obj_ref(), fn_proto.obj_ref()))?;
+        let regexp_proto =
+            RegExpProtoBuiltin::init(heap, (obj_proto.obj_ref(), fn_proto.obj_ref()))?;
+        let regexp_string_iterator_proto = RegExpStringIteratorProtoBuiltin::init(
+            heap,
+            (obj_proto.obj_ref(), fn_proto.obj_ref()),
+        )?;
         let string_proto =
             StringProtoBuiltin::init(heap, (obj_proto.obj_ref(), fn_proto.obj_ref()))?;
+        let error_proto = ErrorProtoBuiltin::init(heap, (obj_proto.obj_ref(), fn_proto.obj_ref()))?;
+        let error_subclass_proto = |heap: &mut Heap, name| {
+            ErrorSubclassProtoBuiltin::init(heap, (error_proto.obj_ref(), name))
+        };
+        let aggregate_error_proto = error_subclass_proto(heap, "AggregateError")?;
+        let eval_error_proto = error_subclass_proto(heap, NativeErrorKind::Eval.name())?;
+        let range_error_proto = error_subclass_proto(heap, NativeErrorKind::Range.name())?;
+        let reference_error_proto = error_subclass_proto(heap, NativeErrorKind::Reference.name())?;
+        let syntax_error_proto = error_subclass_proto(heap, NativeErrorKind::Syntax.name())?;
+        let type_error_proto = error_subclass_proto(heap, NativeErrorKind::Type.name())?;
+        let uri_error_proto = error_subclass_proto(heap, NativeErrorKind::Uri.name())?;
 
         let array = ArrayCtorBuiltin::init(heap, fn_proto.obj_ref())?;
         let boolean = BooleanCtorBuiltin::init(heap, fn_proto.obj_ref())?;
+        let error = ErrorCtorBuiltin::init(heap, fn_proto.obj_ref())?;
+        let aggregate_error = AggregateErrorCtorBuiltin::init(heap, fn_proto.obj_ref())?;
+        let eval_error = EvalErrorCtorBuiltin::init(heap, fn_proto.obj_ref())?;
+        let range_error = RangeErrorCtorBuiltin::init(heap, fn_proto.obj_ref())?;
+        let reference_error = ReferenceErrorCtorBuiltin::init(heap, fn_proto.obj_ref())?;
+        let syntax_error = SyntaxErrorCtorBuiltin::init(heap, fn_proto.obj_ref())?;
+        let type_error = TypeErrorCtorBuiltin::init(heap, fn_proto.obj_ref())?;
+        let uri_error = UriErrorCtorBuiltin::init(heap, fn_proto.obj_ref())?;
         let math = MathBuiltin::init(heap, (obj_proto.obj_ref(), fn_proto.obj_ref()))?;
         let number = NumberCtorBuiltin::init(heap, fn_proto.obj_ref())?;
+        let regexp = RegExpCtorBuiltin::init(heap, fn_proto.obj_ref())?;
         let string = StringCtorBuiltin::init(heap, fn_proto.obj_ref())?;
+        let symbol = SymbolBuiltin::init(heap, obj_proto.obj_ref())?;
+
+        for (ctor, proto) in [
+            (array.obj_ref(), array_proto.obj_ref()),
+            (error.obj_ref(), error_proto.obj_ref()),
+            (aggregate_error.obj_ref(), aggregate_error_proto.obj_ref()),
+            (eval_error.obj_ref(), eval_error_proto.obj_ref()),
+            (range_error.obj_ref(), range_error_proto.obj_ref()),
+            (reference_error.obj_ref(), reference_error_proto.obj_ref()),
+            (syntax_error.obj_ref(), syntax_error_proto.obj_ref()),
+            (type_error.obj_ref(), type_error_proto.obj_ref()),
+            (uri_error.obj_ref(), uri_error_proto.obj_ref()),
+            (number.obj_ref(), number_proto.obj_ref()),
+            (regexp.obj_ref(), regexp_proto.obj_ref()),
+            (string.obj_ref(), string_proto.obj_ref()),
+        ] {
+            link_constructor(heap, ctor, proto);
+        }
 
         let console = ConsoleBuiltin::init(heap, (obj_proto.obj_ref(), fn_proto.obj_ref()))?;
         let exit = ExitBuiltin::init(heap, fn_proto.obj_ref())?;
+        let gc = GcBuiltin::init(heap, fn_proto.obj_ref())?;
         let is_nan = IsNanBuiltin::init(heap, fn_proto.obj_ref())?;
 
         let props = hash_map![
@@ -79,14 +186,25 @@ impl Builtin for GlobalObject {
             prop_key!("NaN") => Property::new_const(Value::Number(Number::NAN)),
             prop_key!("undefined") => Property::new_const(Value::Undefined),
 
+            prop_key!("AggregateError") => Property::new_user(aggregate_error.as_value()),
             prop_key!("Array") => Property::new_user(array.as_value()),
             prop_key!("Boolean") => Property::new_user(boolean.as_value()),
+            prop_key!("Error") => Property::new_user(error.as_value()),
+            prop_key!("EvalError") => Property::new_user(eval_error.as_value()),
             prop_key!("Math") => Property::new_user(math.as_value()),
             prop_key!("Number") => Property::new_user(number.as_value()),
+            prop_key!("RangeError") => Property::new_user(range_error.as_value()),
+            prop_key!("ReferenceError") => Property::new_user(reference_error.as_value()),
+            prop_key!("RegExp") => Property::new_user(regexp.as_value()),
             prop_key!("String") => Property::new_user(string.as_value()),
+            prop_key!("Symbol") => Property::new_user(symbol.as_value()),
+            prop_key!("SyntaxError") => Property::new_user(syntax_error.as_value()),
+            prop_key!("TypeError") => Property::new_user(type_error.as_value()),
+            prop_key!("URIError") => Property::new_user(uri_error.as_value()),
 
             prop_key!("console") => Property::new_user(console.as_value()),
             prop_key!("exit") => Property::new_user(exit.as_value()),
+            prop_key!("gc") => Property::new_user(gc.as_value()),
             prop_key!("isNaN") => Property::new_user(is_nan.as_value()),
         ];
 
@@ -98,6 +216,19 @@ impl Builtin for GlobalObject {
         ))?;
         Ok(Self {
             array_proto,
+            error_proto,
+            aggregate_error_proto,
+            function_proto: fn_proto,
+            eval_error_proto,
+            range_error_proto,
+            reference_error_proto,
+            syntax_error_proto,
+            type_error_proto,
+            uri_error_proto,
+            number_proto,
+            object_proto: obj_proto,
+            regexp_proto,
+            regexp_string_iterator_proto,
             string_proto,
             obj_ref,
         })
@@ -108,15 +239,61 @@ impl Builtin for GlobalObject {
     }
 }
 
+/// Point the `prototype` property of a constructor at the prototype of the
+/// objects it creates, and the `constructor` property of that prototype back at
+/// the constructor.
+fn link_constructor(heap: &mut Heap, ctor: Reference, proto: Reference) {
+    heap.resolve_mut(ctor).define_own_property(
+        prop_key!("prototype"),
+        Property::new_const(Value::Object(proto)),
+    );
+    heap.resolve_mut(proto).define_own_property(
+        prop_key!("constructor"),
+        Property::new_user(Value::Object(ctor)),
+    );
+}
+
+/// The prototypes aren't necessarily reachable from the global object itself
+/// (e.g. `%RegExpStringIteratorPrototype%`), so they're roots as well.
+impl Trace for GlobalObject {
+    fn trace(&self, tracer: &mut Tracer) {
+        let protos = [
+            self.array_proto.obj_ref(),
+            self.error_proto.obj_ref(),
+            self.aggregate_error_proto.obj_ref(),
+            self.function_proto.obj_ref(),
+            self.eval_error_proto.obj_ref(),
+            self.range_error_proto.obj_ref(),
+            self.reference_error_proto.obj_ref(),
+            self.syntax_error_proto.obj_ref(),
+            self.type_error_proto.obj_ref(),
+            self.uri_error_proto.obj_ref(),
+            self.number_proto.obj_ref(),
+            self.object_proto.obj_ref(),
+            self.regexp_proto.obj_ref(),
+            self.regexp_string_iterator_proto.obj_ref(),
+            self.string_proto.obj_ref(),
+        ];
+        for proto in protos {
+            tracer.visit(proto);
+        }
+        tracer.visit(self.obj_ref);
+    }
+}
+
 builtin_fn!(ExitBuiltin, Extensible::Yes, (it, _receiver, _args) => {
     it.vm_mut().set_execution_state(ExecutionState::Exit);
     Ok(Value::Undefined)
 });
 
-builtin_fn!(IsNanBuiltin, Extensible::Yes, (_it, _receiver, args) => {
-    let arg = args.first().copied().unwrap_or(Value::Undefined);
-    Ok(Value::Boolean(match arg {
-        Value::Boolean(_) | Value::Object(_) | Value::Null | Value::Undefined => true,
-        Value::Number(arg) => arg.is_nan(),
-    }))
+// Request that garbage is collected as soon as it's safe to do so. This is
+// non-standard, and exists for testing.
+builtin_fn!(GcBuiltin, Extensible::Yes, (it, _receiver, _args) => {
+    it.vm_mut().request_garbage_collection();
+    Ok(Value::Undefined)
+});
+
+builtin_fn!(IsNanBuiltin, Extensible::Yes, (it, _receiver, args) => {
+    let arg = args.first().cloned().unwrap_or(Value::Undefined);
+    Ok(Value::Boolean(it.to_number(arg)?.is_nan()))
 });
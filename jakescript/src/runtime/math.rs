@@ -1,7 +1,7 @@
 use super::Builtin;
 use crate::interpreter::{
-    ErrorKind, Extensible, Heap, InitialisationError, Number, NumericOverflowError, Object,
-    ObjectData, Property, Reference, Value,
+    ErrorKind, Extensible, Heap, InitialisationError, Interpreter, Number, Object, ObjectData,
+    Property, Reference, Value,
 };
 use crate::{builtin_fn, prop_key};
 use common_macros::hash_map;
@@ -60,31 +60,25 @@ impl Builtin for MathBuiltin {
 }
 
 builtin_fn!(AbsBuiltin, Extensible::Yes, (it, _receiver, args) => {
-    let arg = args.first().copied().unwrap_or_default();
-    it.coerce_to_number(arg)
-        .checked_abs()
-        .map(Value::Number)
-        .ok_or(ErrorKind::NumericOverflow(NumericOverflowError::new()))
+    let arg = args.first().cloned().unwrap_or_default();
+    Ok(Value::Number(it.to_number(arg)?.abs()))
 });
 
 builtin_fn!(FloorBuiltin, Extensible::Yes, (it, _receiver, args) => {
-    let arg = args.first().copied().unwrap_or_default();
-    Ok(Value::Number(match it.coerce_to_number(arg) {
-        n if !n.is_finite() => n,
+    let arg = args.first().cloned().unwrap_or_default();
+    Ok(Value::Number(match it.to_number(arg)? {
         Number::Int(n) => Number::Int(n),
-        #[allow(clippy::cast_possible_truncation)]
-        Number::Float(n) => Number::Int(n.floor() as i64),
+        Number::Float(n) => Number::Float(n.floor()),
     }))
 });
 
 builtin_fn!(MaxBuiltin, Extensible::Yes, (it, _receiver, args) => {
     let mut acc = Number::NEG_INF;
-    for arg in args {
-        let n = it.coerce_to_number(*arg);
+    for n in to_numbers(it, args)? {
         if n.is_nan() {
             return Ok(Value::Number(Number::NAN));
         }
-        if n > acc {
+        if n > acc || (n.is_zero() && is_negative_zero(acc) && !is_negative_zero(n)) {
             acc = n;
         }
     }
@@ -93,29 +87,39 @@ builtin_fn!(MaxBuiltin, Extensible::Yes, (it, _receiver, args) => {
 
 builtin_fn!(MinBuiltin, Extensible::Yes, (it, _receiver, args) => {
     let mut acc = Number::POS_INF;
-    for arg in args {
-        let n = it.coerce_to_number(*arg);
+    for n in to_numbers(it, args)? {
         if n.is_nan() {
             return Ok(Value::Number(Number::NAN));
         }
-        if n < acc {
+        if n < acc || (acc.is_zero() && is_negative_zero(n) && !is_negative_zero(acc)) {
             acc = n;
         }
     }
     Ok(Value::Number(acc))
 });
 
+/// Convert every argument to a number up front, as `Math.max()` and
+/// `Math.min()` do, so that each conversion runs even if an earlier argument
+/// already decided the result (e.g. by being `NaN`).
+fn to_numbers(it: &mut Interpreter, args: &[Value]) -> Result<Vec<Number>, ErrorKind> {
+    args.iter().map(|arg| it.to_number(arg.clone())).collect()
+}
+
+/// `Math.max()` and `Math.min()` consider `-0` to be less than `+0`, unlike
+/// the `<` operator.
+fn is_negative_zero(n: Number) -> bool {
+    matches!(n, Number::Float(n) if n == 0.0 && n.is_sign_negative())
+}
+
 builtin_fn!(SqrtBuiltin, Extensible::Yes, (it, _receiver, args) => {
-    let arg = args.first().copied().unwrap_or_default();
-    Ok(Value::Number(it.coerce_to_number(arg).sqrt()))
+    let arg = args.first().cloned().unwrap_or_default();
+    Ok(Value::Number(it.to_number(arg)?.sqrt()))
 });
 
 builtin_fn!(TruncBuiltin, Extensible::Yes, (it, _receiver, args) => {
-    let arg = args.first().copied().unwrap_or_default();
-    let n = it.coerce_to_number(arg);
-    Ok(Value::Number(if n.is_finite() {
-        Number::Int(n.as_i64())
-    } else {
-        n
+    let arg = args.first().cloned().unwrap_or_default();
+    Ok(Value::Number(match it.to_number(arg)? {
+        Number::Int(n) => Number::Int(n),
+        Number::Float(n) => Number::Float(n.trunc()),
     }))
 });
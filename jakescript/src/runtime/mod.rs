@@ -1,16 +1,23 @@
-use crate::interpreter::{ErrorKind, Heap, InitialisationError, Interpreter, Reference, Value};
+use crate::interpreter::{
+    ErrorKind, Heap, IncompatibleReceiverError, InitialisationError, Interpreter, Reference, Value,
+};
+use crate::prop_key;
 pub use global::GlobalObject;
+pub use regexp::{RegExp, RegExpStringIterator};
 use std::fmt;
 
 mod array;
 mod boolean;
 mod console;
+mod error;
 mod function;
 mod global;
 mod math;
 mod number;
 mod object;
+mod regexp;
 mod string;
+mod symbol;
 
 #[macro_export]
 macro_rules! builtin_fn {
@@ -29,7 +36,7 @@ macro_rules! builtin_fn {
             #[allow(clippy::unnecessary_wraps)]
             fn call(
                 $it: &mut $crate::interpreter::Interpreter,
-                $receiver: $crate::interpreter::Reference,
+                $receiver: $crate::interpreter::Value,
                 $args: &[$crate::interpreter::Value],
             ) -> ::std::result::Result<$crate::interpreter::Value, $crate::interpreter::ErrorKind>
             {
@@ -44,10 +51,9 @@ macro_rules! builtin_fn {
                 heap: &mut $crate::interpreter::Heap,
                 fn_proto: Self::InitArgs,
             ) -> ::std::result::Result<Self, $crate::interpreter::InitialisationError> {
-                let props = ::std::collections::HashMap::default();
                 let obj_ref = heap.allocate($crate::interpreter::Object::new_native(
                     Some(fn_proto),
-                    props,
+                    [],
                     &Self::call,
                     $extensible,
                 ))?;
@@ -103,7 +109,42 @@ pub trait Builtin {
     }
 }
 
-type NativeFn<'a> = &'a dyn Fn(&mut Interpreter, Reference, &[Value]) -> Result<Value, ErrorKind>;
+/// Native functions are called with an `undefined` receiver, rather than the
+/// global object, when they're called as a plain function instead of as a
+/// method.
+fn receiver_object(receiver: Value) -> Result<Reference, ErrorKind> {
+    match receiver {
+        Value::Object(obj_ref) => Ok(obj_ref),
+        receiver => Err(ErrorKind::from(IncompatibleReceiverError::new(format!(
+            "expected an object receiver but was {receiver:?}"
+        )))),
+    }
+}
+
+/// [GetPrototypeFromConstructor ( constructor, intrinsicDefaultProto )](
+/// https://tc39.es/ecma262/#sec-getprototypefromconstructor), for native
+/// constructors, using the `prototype` of `new.target` if it's an object.
+///
+/// Native constructors called as plain functions act as if they were their own
+/// `new.target`, so `default_proto` should be their own prototype.
+fn prototype_from_constructor(
+    it: &mut Interpreter,
+    default_proto: Reference,
+) -> Result<Reference, ErrorKind> {
+    let Some(new_target) = it.vm().stack().new_target() else {
+        return Ok(default_proto);
+    };
+    let new_target_obj = it.vm().heap().resolve(new_target);
+    let proto = new_target_obj
+        .as_ref()
+        .get(it, &prop_key!("prototype"), new_target)?;
+    Ok(match proto {
+        Some(Value::Object(proto)) => proto,
+        _ => default_proto,
+    })
+}
+
+type NativeFn<'a> = &'a dyn Fn(&mut Interpreter, Value, &[Value]) -> Result<Value, ErrorKind>;
 
 #[derive(Clone)]
 pub struct NativeCall(NativeFn<'static>);
@@ -112,7 +153,7 @@ impl NativeCall {
     pub fn call(
         &self,
         it: &mut Interpreter,
-        receiver: Reference,
+        receiver: Value,
         args: &[Value],
     ) -> Result<Value, ErrorKind> {
         (self.0)(it, receiver, args)
@@ -121,7 +162,7 @@ impl NativeCall {
 
 impl<F> From<&'static F> for NativeCall
 where
-    F: Fn(&mut Interpreter, Reference, &[Value]) -> Result<Value, ErrorKind>,
+    F: Fn(&mut Interpreter, Value, &[Value]) -> Result<Value, ErrorKind>,
 {
     fn from(f: &'static F) -> Self {
         Self(f)
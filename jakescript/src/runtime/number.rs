@@ -1,10 +1,83 @@
-use crate::builtin_fn;
-use crate::interpreter::{Extensible, Number, Value};
+use super::Builtin;
+use crate::interpreter::{
+    ErrorKind, Extensible, Heap, IncompatibleReceiverError, InitialisationError, JsString, Number,
+    Object, ObjectData, OutOfRangeError, Property, Reference, Value,
+};
+use crate::{builtin_fn, prop_key};
+use common_macros::hash_map;
+
+pub struct NumberProtoBuiltin {
+    obj_ref: Reference,
+}
+
+impl Builtin for NumberProtoBuiltin {
+    type InitArgs = (Reference, Reference);
+
+    fn init(
+        heap: &mut Heap,
+        (obj_proto, fn_proto): Self::InitArgs,
+    ) -> Result<Self, InitialisationError> {
+        let to_string = ToStringBuiltin::init(heap, fn_proto)?;
+        let value_of = ValueOfBuiltin::init(heap, fn_proto)?;
+
+        let props = hash_map![
+            prop_key!("toString") => Property::new_user(to_string.as_value()),
+            prop_key!("valueOf") => Property::new_user(value_of.as_value()),
+        ];
+
+        let obj_ref = heap.allocate(Object::new(
+            Some(obj_proto),
+            props,
+            ObjectData::None,
+            Extensible::Yes,
+        ))?;
+        Ok(Self { obj_ref })
+    }
+
+    fn obj_ref(&self) -> Reference {
+        self.obj_ref
+    }
+}
 
 builtin_fn!(pub NumberCtorBuiltin, Extensible::Yes, (it, _receiver, args) => {
     let arg = args.first();
     Ok(Value::Number(match arg {
-        Some(arg) => it.coerce_to_number(*arg),
+        Some(arg) => it.to_number(arg.clone())?,
         None => Number::Int(0),
     }))
 });
+
+builtin_fn!(ToStringBuiltin, Extensible::Yes, (it, receiver, args) => {
+    let n = this_number_value(receiver)?;
+    let radix = match args.first().cloned().unwrap_or_default() {
+        Value::Undefined => 10,
+        radix => {
+            let radix = it.to_number(radix)?;
+            match u32::try_from(radix.as_i64()) {
+                Ok(radix) if (2..=36).contains(&radix) => radix,
+                _ => {
+                    return Err(ErrorKind::from(OutOfRangeError::new(format!(
+                        "radix must be between 2 and 36 but was {radix}"
+                    ))))
+                }
+            }
+        }
+    };
+    let s = n.to_string_radix(radix);
+    Ok(Value::String(JsString::from(s)))
+});
+
+builtin_fn!(ValueOfBuiltin, Extensible::Yes, (_it, receiver, _args) => {
+    this_number_value(receiver).map(Value::Number)
+});
+
+/// [ThisNumberValue](https://tc39.es/ecma262/#sec-thisnumbervalue)
+fn this_number_value(receiver: Value) -> Result<Number, ErrorKind> {
+    // TODO: Also accept `Number` wrapper objects once they exist.
+    match receiver {
+        Value::Number(n) => Ok(n),
+        receiver => Err(ErrorKind::from(IncompatibleReceiverError::new(format!(
+            "expected a number receiver but was {receiver:?}"
+        )))),
+    }
+}
@@ -1,5 +1,9 @@
 use super::Builtin;
-use crate::interpreter::{Extensible, Heap, InitialisationError, Object, ObjectData, Reference};
+use crate::interpreter::{
+    Extensible, Heap, InitialisationError, Interpreter, JsString, Object, ObjectData, Property,
+    Reference, Value,
+};
+use crate::{builtin_fn, prop_key};
 use common_macros::hash_map;
 
 pub struct ObjectProtoBuiltin {
@@ -21,3 +25,67 @@ impl Builtin for ObjectProtoBuiltin {
         self.obj_ref
     }
 }
+
+impl ObjectProtoBuiltin {
+    /// Define the methods of `Object.prototype`, which can't be created until
+    /// `Function.prototype` exists, which itself inherits from
+    /// `Object.prototype`.
+    pub fn init_methods(
+        &self,
+        heap: &mut Heap,
+        fn_proto: Reference,
+    ) -> Result<(), InitialisationError> {
+        let to_string = ToStringBuiltin::init(heap, fn_proto)?;
+        let value_of = ValueOfBuiltin::init(heap, fn_proto)?;
+
+        let mut obj = heap.resolve_mut(self.obj_ref);
+        for (key, value) in [
+            (prop_key!("toString"), to_string.as_value()),
+            (prop_key!("valueOf"), value_of.as_value()),
+        ] {
+            obj.define_own_property(key, Property::new_user(value));
+        }
+        Ok(())
+    }
+}
+
+builtin_fn!(ToStringBuiltin, Extensible::Yes, (it, receiver, _args) => {
+    let s = object_to_string(it, receiver);
+    Ok(Value::String(JsString::from(s)))
+});
+
+builtin_fn!(ValueOfBuiltin, Extensible::Yes, (_it, receiver, _args) => {
+    // TODO: Convert primitives to wrapper objects once `Boolean.prototype` and
+    //  `Number.prototype` exist.
+    Ok(receiver)
+});
+
+/// The result of `Object.prototype.toString`, e.g. `[object Array]`.
+pub(super) fn object_to_string(it: &Interpreter, receiver: Value) -> String {
+    let tag = match receiver {
+        Value::Boolean(_) => "Boolean",
+        Value::Number(_) => "Number",
+        Value::String(_) => "String",
+        Value::Object(obj_ref) => {
+            let array_proto = it.vm().runtime().global_object().array_proto().obj_ref();
+            let obj = it.vm().heap().resolve(obj_ref);
+            let obj = obj.as_ref();
+            if obj.string_data().is_some() {
+                "String"
+            } else if obj.symbol_data().is_some() {
+                "Symbol"
+            } else if obj.regexp_data().is_some() {
+                "RegExp"
+            } else if obj.call_data().is_some() {
+                "Function"
+            } else if obj.prototype() == Some(array_proto) {
+                "Array"
+            } else {
+                "Object"
+            }
+        }
+        Value::Null => "Null",
+        Value::Undefined => "Undefined",
+    };
+    format!("[object {tag}]")
+}
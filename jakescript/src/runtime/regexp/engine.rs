@@ -0,0 +1,794 @@
+use super::syntax::{
+    self, is_line_terminator, is_word_char, Assertion, ClassSet, Lookaround, Node, Pattern, Repeat,
+};
+use std::fmt;
+use std::ops::Range;
+use std::rc::Rc;
+
+/// A compiled regular expression.
+///
+/// Patterns are compiled into a [`Program`] for a backtracking virtual
+/// machine, which operates over UTF-16 code units (or code points in
+/// [Unicode mode](Flags::unicode)) as required by the spec. Backtracking state
+/// is kept on an explicit stack rather than the native stack, so long inputs
+/// can't cause a stack overflow.
+///
+/// Some patterns take exponential time to fail to match (e.g. `^(a|a)*$`), so
+/// the caller is polled periodically while backtracking and may stop the match
+/// early. See [`Self::match_at()`].
+#[derive(Clone, Debug)]
+pub struct RegExp {
+    source: Box<str>,
+    flags: Flags,
+    program: Rc<Program>,
+}
+
+impl RegExp {
+    pub fn new(source: &str, flags: &str) -> Result<Self, SyntaxError> {
+        let flags = Flags::parse(flags)?;
+        let pattern = syntax::parse(source, flags)?;
+        let program = Compiler::compile(pattern);
+        Ok(Self {
+            source: Box::from(source),
+            flags,
+            program: Rc::new(program),
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn flags(&self) -> Flags {
+        self.flags
+    }
+
+    /// Number of capturing groups, not including the implicit group for the
+    /// whole match.
+    pub fn capture_count(&self) -> usize {
+        self.program.capture_count
+    }
+
+    /// Names of capturing groups, in the order they appear in the pattern.
+    pub fn group_names(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.program
+            .group_names
+            .iter()
+            .map(|(name, idx)| (name.as_ref(), *idx))
+    }
+
+    /// Attempt to match the input starting at exactly `start`, without
+    /// searching forwards for a later match.
+    ///
+    /// `poll` is called every [`POLL_INTERVAL`] backtracking steps, and if it
+    /// returns `true` the match is abandoned with [`Interrupted`].
+    pub fn match_at(
+        &self,
+        input: &[u16],
+        start: usize,
+        poll: &mut dyn FnMut() -> bool,
+    ) -> Result<Option<Match>, Interrupted> {
+        if start > input.len() {
+            return Ok(None);
+        }
+        let slot_count = 2 * (self.program.capture_count + 1);
+        let mut vm = Matcher {
+            program: &self.program,
+            input,
+            flags: self.flags,
+            slots: vec![None; slot_count],
+            registers: vec![0; self.program.register_count],
+            poll,
+            backtracks: 0,
+        };
+        Ok(vm.run(0, start)?.then(|| Match {
+            slots: vm.slots.into_boxed_slice(),
+        }))
+    }
+
+    /// Index of the next position after `idx` at which a match could begin.
+    /// In Unicode mode, surrogate pairs are never split.
+    pub fn advance_index(&self, input: &[u16], idx: usize) -> usize {
+        if self.flags.unicode
+            && let Some(&lead) = input.get(idx)
+            && let Some(&trail) = input.get(idx + 1)
+            && is_lead_surrogate(lead)
+            && is_trail_surrogate(trail)
+        {
+            idx + 2
+        } else {
+            idx + 1
+        }
+    }
+}
+
+impl fmt::Display for RegExp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let source = if self.source.is_empty() {
+            "(?:)"
+        } else {
+            &self.source
+        };
+        write!(f, "/{source}/{}", self.flags)
+    }
+}
+
+/// The result of a successful match. Indices are in UTF-16 code units.
+#[derive(Clone, Debug)]
+pub struct Match {
+    slots: Box<[Option<usize>]>,
+}
+
+impl Match {
+    /// The range of the whole match.
+    pub fn range(&self) -> Range<usize> {
+        self.group(0).unwrap()
+    }
+
+    /// The range of the capturing group at `idx`, where `0` is the whole match,
+    /// or `None` if the group didn't participate in the match.
+    pub fn group(&self, idx: usize) -> Option<Range<usize>> {
+        match (self.slots[2 * idx], self.slots[2 * idx + 1]) {
+            (Some(start), Some(end)) => Some(start..end),
+            (_, _) => None,
+        }
+    }
+
+    /// Number of groups, including the implicit group for the whole match.
+    pub fn group_count(&self) -> usize {
+        self.slots.len() / 2
+    }
+}
+
+/// The number of backtracking steps between each call to the `poll` function
+/// passed to [`RegExp::match_at()`].
+pub const POLL_INTERVAL: u32 = 1024;
+
+/// A match which was abandoned because the `poll` function passed to
+/// [`RegExp::match_at()`] asked for it to stop.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Interrupted;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Flags {
+    /// `d`
+    pub has_indices: bool,
+    /// `g`
+    pub global: bool,
+    /// `i`
+    pub ignore_case: bool,
+    /// `m`
+    pub multiline: bool,
+    /// `s`
+    pub dot_all: bool,
+    /// `u`
+    pub unicode: bool,
+    /// `y`
+    pub sticky: bool,
+}
+
+impl Flags {
+    pub fn parse(s: &str) -> Result<Self, SyntaxError> {
+        let mut flags = Self::default();
+        for ch in s.chars() {
+            let flag = match ch {
+                'd' => &mut flags.has_indices,
+                'g' => &mut flags.global,
+                'i' => &mut flags.ignore_case,
+                'm' => &mut flags.multiline,
+                's' => &mut flags.dot_all,
+                'u' => &mut flags.unicode,
+                'y' => &mut flags.sticky,
+                ch => return Err(SyntaxError::new(format!("invalid flag `{ch}`"))),
+            };
+            if *flag {
+                return Err(SyntaxError::new(format!("duplicate flag `{ch}`")));
+            }
+            *flag = true;
+        }
+        Ok(flags)
+    }
+}
+
+impl fmt::Display for Flags {
+    /// Flags are always written in the canonical order.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (set, ch) in [
+            (self.has_indices, 'd'),
+            (self.global, 'g'),
+            (self.ignore_case, 'i'),
+            (self.multiline, 'm'),
+            (self.dot_all, 's'),
+            (self.unicode, 'u'),
+            (self.sticky, 'y'),
+        ] {
+            if set {
+                write!(f, "{ch}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SyntaxError {
+    detail_msg: String,
+}
+
+impl SyntaxError {
+    pub fn new(detail_msg: impl Into<String>) -> Self {
+        Self {
+            detail_msg: detail_msg.into(),
+        }
+    }
+
+    pub fn detail_msg(&self) -> &str {
+        &self.detail_msg
+    }
+}
+
+impl std::error::Error for SyntaxError {}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.detail_msg)
+    }
+}
+
+#[derive(Debug)]
+pub(super) struct Program {
+    insts: Vec<Inst>,
+    capture_count: usize,
+    group_names: Vec<(Box<str>, usize)>,
+    register_count: usize,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Direction {
+    Forward,
+    /// Used within lookbehinds, which are matched from right to left.
+    Backward,
+}
+
+#[derive(Debug)]
+enum Inst {
+    Char(u32, Direction),
+    Any(Direction),
+    Class(Box<ClassSet>, Direction),
+    Backreference(usize, Direction),
+    Assertion(Assertion),
+    /// Record the current position in a capture slot.
+    Save(usize),
+    /// Reset the slots of a range of capturing groups.
+    ClearCaptures(Range<usize>),
+    /// Continue at the first target, backtracking to the second on failure.
+    Split(usize, usize),
+    Jump(usize),
+    /// Run the body (which immediately follows, terminated by
+    /// [`Inst::Match`]) as an atomic sub-match, then continue at `next`.
+    Lookaround {
+        negated: bool,
+        next: usize,
+    },
+    /// Reset the iteration counter of a repetition.
+    RepeatInit {
+        counter: usize,
+    },
+    RepeatHead {
+        counter: usize,
+        min: u32,
+        max: Option<u32>,
+        greedy: bool,
+        exit: usize,
+    },
+    /// Record the position at the start of an iteration.
+    RepeatMark {
+        mark: usize,
+    },
+    RepeatTail {
+        counter: usize,
+        mark: usize,
+        min: u32,
+        head: usize,
+    },
+    Match,
+}
+
+struct Compiler {
+    insts: Vec<Inst>,
+    register_count: usize,
+}
+
+impl Compiler {
+    fn compile(pattern: Pattern) -> Program {
+        let mut compiler = Self {
+            insts: Vec::new(),
+            register_count: 0,
+        };
+        compiler.emit(Inst::Save(0));
+        compiler.compile_node(&pattern.node, Direction::Forward);
+        compiler.emit(Inst::Save(1));
+        compiler.emit(Inst::Match);
+        Program {
+            insts: compiler.insts,
+            capture_count: pattern.capture_count,
+            group_names: pattern.group_names,
+            register_count: compiler.register_count,
+        }
+    }
+
+    fn emit(&mut self, inst: Inst) -> usize {
+        self.insts.push(inst);
+        self.insts.len() - 1
+    }
+
+    fn next_pc(&self) -> usize {
+        self.insts.len()
+    }
+
+    fn alloc_register(&mut self) -> usize {
+        self.register_count += 1;
+        self.register_count - 1
+    }
+
+    fn compile_node(&mut self, node: &Node, dir: Direction) {
+        match node {
+            Node::Empty => {}
+            Node::Char(ch) => {
+                self.emit(Inst::Char(*ch, dir));
+            }
+            Node::Any => {
+                self.emit(Inst::Any(dir));
+            }
+            Node::Class(class) => {
+                self.emit(Inst::Class(Box::new(class.clone()), dir));
+            }
+            Node::Assertion(assertion) => {
+                self.emit(Inst::Assertion(*assertion));
+            }
+            Node::Backreference(idx) => {
+                self.emit(Inst::Backreference(*idx, dir));
+            }
+            Node::NamedBackreference(name) => unreachable!("unresolved named reference: {name}"),
+            Node::Sequence(nodes) => match dir {
+                Direction::Forward => nodes.iter().for_each(|node| self.compile_node(node, dir)),
+                Direction::Backward => nodes
+                    .iter()
+                    .rev()
+                    .for_each(|node| self.compile_node(node, dir)),
+            },
+            Node::Alternation(nodes) => self.compile_alternation(nodes, dir),
+            Node::Group(node, None) => self.compile_node(node, dir),
+            Node::Group(node, Some(idx)) => {
+                let (first, second) = match dir {
+                    Direction::Forward => (2 * idx, 2 * idx + 1),
+                    Direction::Backward => (2 * idx + 1, 2 * idx),
+                };
+                self.emit(Inst::Save(first));
+                self.compile_node(node, dir);
+                self.emit(Inst::Save(second));
+            }
+            Node::Lookaround(look) => self.compile_lookaround(look),
+            Node::Repeat(repeat) => self.compile_repeat(repeat, dir),
+        }
+    }
+
+    fn compile_alternation(&mut self, nodes: &[Node], dir: Direction) {
+        let mut jumps_to_end = Vec::with_capacity(nodes.len());
+        for (idx, node) in nodes.iter().enumerate() {
+            if idx == nodes.len() - 1 {
+                self.compile_node(node, dir);
+                break;
+            }
+            let split = self.emit(Inst::Split(0, 0));
+            self.compile_node(node, dir);
+            jumps_to_end.push(self.emit(Inst::Jump(0)));
+            self.insts[split] = Inst::Split(split + 1, self.next_pc());
+        }
+        let end = self.next_pc();
+        for jump in jumps_to_end {
+            self.insts[jump] = Inst::Jump(end);
+        }
+    }
+
+    fn compile_lookaround(&mut self, look: &Lookaround) {
+        let inst = self.emit(Inst::Match);
+        let dir = if look.behind {
+            Direction::Backward
+        } else {
+            Direction::Forward
+        };
+        self.compile_node(&look.node, dir);
+        self.emit(Inst::Match);
+        self.insts[inst] = Inst::Lookaround {
+            negated: look.negated,
+            next: self.next_pc(),
+        };
+    }
+
+    fn compile_repeat(&mut self, repeat: &Repeat, dir: Direction) {
+        let Repeat {
+            ref node,
+            min,
+            max,
+            greedy,
+            ref captures,
+        } = *repeat;
+        let clear_captures = || Inst::ClearCaptures(2 * captures.start..2 * captures.end);
+
+        if max == Some(0) {
+            return;
+        }
+        if min <= 1 && matches!(max, None | Some(1)) && !node.is_nullable() {
+            // Fast path for the common quantifiers (`*`, `+`, and `?`), which don't need an
+            // iteration counter or empty-match check when the node always consumes input.
+            if min == 1 {
+                self.emit(clear_captures());
+                self.compile_node(node, dir);
+                if max == Some(1) {
+                    return;
+                }
+            }
+            let head = self.emit(Inst::Split(0, 0));
+            self.emit(clear_captures());
+            self.compile_node(node, dir);
+            if max.is_none() {
+                self.emit(Inst::Jump(head));
+            }
+            let exit = self.next_pc();
+            self.insts[head] = if greedy {
+                Inst::Split(head + 1, exit)
+            } else {
+                Inst::Split(exit, head + 1)
+            };
+            return;
+        }
+
+        let counter = self.alloc_register();
+        let mark = self.alloc_register();
+        self.emit(Inst::RepeatInit { counter });
+        let head = self.emit(Inst::Match);
+        self.emit(Inst::RepeatMark { mark });
+        self.emit(clear_captures());
+        self.compile_node(node, dir);
+        self.emit(Inst::RepeatTail {
+            counter,
+            mark,
+            min,
+            head,
+        });
+        self.insts[head] = Inst::RepeatHead {
+            counter,
+            min,
+            max,
+            greedy,
+            exit: self.next_pc(),
+        };
+    }
+}
+
+enum Backtrack {
+    Branch { pc: usize, pos: usize },
+    RestoreSlot { slot: usize, value: Option<usize> },
+    RestoreRegister { register: usize, value: usize },
+    RestoreAll(Box<(Vec<Option<usize>>, Vec<usize>)>),
+}
+
+struct Matcher<'a> {
+    program: &'a Program,
+    input: &'a [u16],
+    flags: Flags,
+    slots: Vec<Option<usize>>,
+    registers: Vec<usize>,
+    poll: &'a mut dyn FnMut() -> bool,
+    /// Backtracking steps taken since `poll` was last called.
+    backtracks: u32,
+}
+
+impl Matcher<'_> {
+    /// Run the program from `pc` until it reaches [`Inst::Match`], or until
+    /// every alternative has been exhausted.
+    fn run(&mut self, mut pc: usize, mut pos: usize) -> Result<bool, Interrupted> {
+        let mut stack = Vec::new();
+        loop {
+            let ok = match self.program.insts[pc] {
+                Inst::Char(expected, dir) => match self.read_char(pos, dir) {
+                    Some((ch, next)) if self.chars_equal(ch, expected) => {
+                        pos = next;
+                        true
+                    }
+                    Some(_) | None => false,
+                },
+                Inst::Any(dir) => match self.read_char(pos, dir) {
+                    Some((ch, next)) if self.flags.dot_all || !is_line_terminator(ch) => {
+                        pos = next;
+                        true
+                    }
+                    Some(_) | None => false,
+                },
+                Inst::Class(ref class, dir) => match self.read_char(pos, dir) {
+                    Some((ch, next)) if self.class_contains(class, ch) => {
+                        pos = next;
+                        true
+                    }
+                    Some(_) | None => false,
+                },
+                Inst::Backreference(idx, dir) => match self.match_backreference(idx, pos, dir) {
+                    Some(next) => {
+                        pos = next;
+                        true
+                    }
+                    None => false,
+                },
+                Inst::Assertion(assertion) => self.check_assertion(assertion, pos),
+                Inst::Save(slot) => {
+                    stack.push(Backtrack::RestoreSlot {
+                        slot,
+                        value: self.slots[slot],
+                    });
+                    self.slots[slot] = Some(pos);
+                    true
+                }
+                Inst::ClearCaptures(ref slots) => {
+                    for slot in slots.clone() {
+                        if self.slots[slot].is_some() {
+                            stack.push(Backtrack::RestoreSlot {
+                                slot,
+                                value: self.slots[slot].take(),
+                            });
+                        }
+                    }
+                    true
+                }
+                Inst::Split(first, second) => {
+                    stack.push(Backtrack::Branch { pc: second, pos });
+                    pc = first;
+                    continue;
+                }
+                Inst::Jump(target) => {
+                    pc = target;
+                    continue;
+                }
+                Inst::Lookaround { negated, next } => {
+                    let saved = (self.slots.clone(), self.registers.clone());
+                    let matched = self.run(pc + 1, pos)?;
+                    if matched && !negated {
+                        stack.push(Backtrack::RestoreAll(Box::new(saved)));
+                        pc = next;
+                        continue;
+                    }
+                    (self.slots, self.registers) = saved;
+                    if matched == negated {
+                        false
+                    } else {
+                        pc = next;
+                        continue;
+                    }
+                }
+                Inst::RepeatInit { counter } => {
+                    self.set_register(&mut stack, counter, 0);
+                    true
+                }
+                Inst::RepeatHead {
+                    counter,
+                    min,
+                    max,
+                    greedy,
+                    exit,
+                } => {
+                    let count = self.registers[counter];
+                    if count < min as usize {
+                        true
+                    } else if max.is_some_and(|max| count >= max as usize) {
+                        pc = exit;
+                        continue;
+                    } else if greedy {
+                        stack.push(Backtrack::Branch { pc: exit, pos });
+                        true
+                    } else {
+                        stack.push(Backtrack::Branch { pc: pc + 1, pos });
+                        pc = exit;
+                        continue;
+                    }
+                }
+                Inst::RepeatMark { mark } => {
+                    self.set_register(&mut stack, mark, pos);
+                    true
+                }
+                Inst::RepeatTail {
+                    counter,
+                    mark,
+                    min,
+                    head,
+                } => {
+                    let count = self.registers[counter];
+                    // Once the minimum number of iterations have been matched, an iteration
+                    // which matches the empty string is rejected to avoid looping forever.
+                    if count >= min as usize && self.registers[mark] == pos {
+                        false
+                    } else {
+                        self.set_register(&mut stack, counter, count + 1);
+                        pc = head;
+                        continue;
+                    }
+                }
+                Inst::Match => return Ok(true),
+            };
+            if ok {
+                pc += 1;
+                continue;
+            }
+            self.backtracks += 1;
+            if self.backtracks == POLL_INTERVAL {
+                self.backtracks = 0;
+                if (self.poll)() {
+                    return Err(Interrupted);
+                }
+            }
+            loop {
+                match stack.pop() {
+                    Some(Backtrack::Branch {
+                        pc: next_pc,
+                        pos: next_pos,
+                    }) => {
+                        pc = next_pc;
+                        pos = next_pos;
+                        break;
+                    }
+                    Some(Backtrack::RestoreSlot { slot, value }) => self.slots[slot] = value,
+                    Some(Backtrack::RestoreRegister { register, value }) => {
+                        self.registers[register] = value;
+                    }
+                    Some(Backtrack::RestoreAll(box (slots, registers))) => {
+                        self.slots = slots;
+                        self.registers = registers;
+                    }
+                    None => return Ok(false),
+                }
+            }
+        }
+    }
+
+    fn set_register(&mut self, stack: &mut Vec<Backtrack>, register: usize, value: usize) {
+        stack.push(Backtrack::RestoreRegister {
+            register,
+            value: self.registers[register],
+        });
+        self.registers[register] = value;
+    }
+
+    /// Read the character before or after `pos`, depending on the direction,
+    /// and return it along with the position on the other side of it.
+    fn read_char(&self, pos: usize, dir: Direction) -> Option<(u32, usize)> {
+        match dir {
+            Direction::Forward => {
+                let cu = *self.input.get(pos)?;
+                if self.flags.unicode
+                    && is_lead_surrogate(cu)
+                    && let Some(&trail) = self.input.get(pos + 1)
+                    && is_trail_surrogate(trail)
+                {
+                    Some((combine_surrogates(cu, trail), pos + 2))
+                } else {
+                    Some((u32::from(cu), pos + 1))
+                }
+            }
+            Direction::Backward => {
+                let cu = *self.input.get(pos.checked_sub(1)?)?;
+                if self.flags.unicode
+                    && is_trail_surrogate(cu)
+                    && pos >= 2
+                    && is_lead_surrogate(self.input[pos - 2])
+                {
+                    Some((combine_surrogates(self.input[pos - 2], cu), pos - 2))
+                } else {
+                    Some((u32::from(cu), pos - 1))
+                }
+            }
+        }
+    }
+
+    fn match_backreference(&self, idx: usize, pos: usize, dir: Direction) -> Option<usize> {
+        let (Some(start), Some(end)) = (self.slots[2 * idx], self.slots[2 * idx + 1]) else {
+            // A backreference to a group which didn't participate always succeeds.
+            return Some(pos);
+        };
+        let len = end - start;
+        let range = match dir {
+            Direction::Forward => pos..pos.checked_add(len)?,
+            Direction::Backward => pos.checked_sub(len)?..pos,
+        };
+        let actual = self.input.get(range)?;
+        let expected = &self.input[start..end];
+        let equal = actual
+            .iter()
+            .zip(expected)
+            .all(|(&lhs, &rhs)| self.chars_equal(u32::from(lhs), u32::from(rhs)));
+        equal.then_some(match dir {
+            Direction::Forward => pos + len,
+            Direction::Backward => pos - len,
+        })
+    }
+
+    fn check_assertion(&self, assertion: Assertion, pos: usize) -> bool {
+        match assertion {
+            Assertion::Start => {
+                pos == 0
+                    || (self.flags.multiline && is_line_terminator(u32::from(self.input[pos - 1])))
+            }
+            Assertion::End => {
+                pos == self.input.len()
+                    || (self.flags.multiline && is_line_terminator(u32::from(self.input[pos])))
+            }
+            Assertion::WordBoundary => self.is_word_boundary(pos),
+            Assertion::NotWordBoundary => !self.is_word_boundary(pos),
+        }
+    }
+
+    fn is_word_boundary(&self, pos: usize) -> bool {
+        let before = pos > 0 && is_word_char(u32::from(self.input[pos - 1]));
+        let after = pos < self.input.len() && is_word_char(u32::from(self.input[pos]));
+        before != after
+    }
+
+    fn chars_equal(&self, lhs: u32, rhs: u32) -> bool {
+        lhs == rhs || (self.flags.ignore_case && self.canonicalize(lhs) == self.canonicalize(rhs))
+    }
+
+    fn class_contains(&self, class: &ClassSet, ch: u32) -> bool {
+        let contains = class.contains(ch)
+            || (self.flags.ignore_case && {
+                let canonical = self.canonicalize(ch);
+                case_variants(ch)
+                    .into_iter()
+                    .flatten()
+                    .any(|other| self.canonicalize(other) == canonical && class.contains(other))
+            });
+        contains != class.negated
+    }
+
+    /// [Canonicalize ( rer, ch )](https://tc39.es/ecma262/#sec-runtime-semantics-canonicalize-ch)
+    fn canonicalize(&self, ch: u32) -> u32 {
+        let Some(c) = char::from_u32(ch) else {
+            return ch;
+        };
+        if self.flags.unicode {
+            // Approximates simple case folding.
+            single_char(c.to_lowercase()).map_or(ch, u32::from)
+        } else {
+            match single_char(c.to_uppercase()) {
+                Some(upper) if u32::from(upper) > 0xFFFF => ch,
+                Some(upper) if ch >= 128 && u32::from(upper) < 128 => ch,
+                Some(upper) => u32::from(upper),
+                None => ch,
+            }
+        }
+    }
+}
+
+fn single_char(mut chars: impl Iterator<Item = char>) -> Option<char> {
+    let ch = chars.next()?;
+    chars.next().is_none().then_some(ch)
+}
+
+fn case_variants(ch: u32) -> [Option<u32>; 2] {
+    let Some(c) = char::from_u32(ch) else {
+        return [None, None];
+    };
+    [
+        single_char(c.to_lowercase()).map(u32::from),
+        single_char(c.to_uppercase()).map(u32::from),
+    ]
+}
+
+fn is_lead_surrogate(cu: u16) -> bool {
+    (0xD800..=0xDBFF).contains(&cu)
+}
+
+fn is_trail_surrogate(cu: u16) -> bool {
+    (0xDC00..=0xDFFF).contains(&cu)
+}
+
+fn combine_surrogates(lead: u16, trail: u16) -> u32 {
+    0x10000 + ((u32::from(lead) - 0xD800) << 10) + (u32::from(trail) - 0xDC00)
+}
@@ -0,0 +1,960 @@
+use super::{receiver_object, Builtin};
+use crate::interpreter::{
+    ErrorKind, Extensible, Heap, IncompatibleRegExpError, InitialisationError, Interpreter,
+    InvalidRegExpError, JsString, Number, Object, ObjectData, Property, PropertyKey, Reference,
+    Trace, Tracer, Value,
+};
+use crate::{builtin_fn, prop_key};
+use common_macros::hash_map;
+pub use engine::RegExp;
+use engine::{Flags, Interrupted, Match};
+
+mod engine;
+mod syntax;
+#[cfg(test)]
+mod test;
+
+pub struct RegExpProtoBuiltin {
+    obj_ref: Reference,
+}
+
+impl Builtin for RegExpProtoBuiltin {
+    type InitArgs = (Reference, Reference);
+
+    fn init(
+        heap: &mut Heap,
+        (obj_proto, fn_proto): Self::InitArgs,
+    ) -> Result<Self, InitialisationError> {
+        let exec = ExecBuiltin::init(heap, fn_proto)?;
+        let test = TestBuiltin::init(heap, fn_proto)?;
+        let to_string = ToStringBuiltin::init(heap, fn_proto)?;
+        let dot_all = GetDotAllBuiltin::init(heap, fn_proto)?;
+        let flags = GetFlagsBuiltin::init(heap, fn_proto)?;
+        let global = GetGlobalBuiltin::init(heap, fn_proto)?;
+        let has_indices = GetHasIndicesBuiltin::init(heap, fn_proto)?;
+        let ignore_case = GetIgnoreCaseBuiltin::init(heap, fn_proto)?;
+        let multiline = GetMultilineBuiltin::init(heap, fn_proto)?;
+        let source = GetSourceBuiltin::init(heap, fn_proto)?;
+        let sticky = GetStickyBuiltin::init(heap, fn_proto)?;
+        let unicode = GetUnicodeBuiltin::init(heap, fn_proto)?;
+
+        let props = hash_map![
+            prop_key!("exec") => Property::new_user(exec.as_value()),
+            prop_key!("test") => Property::new_user(test.as_value()),
+            prop_key!("toString") => Property::new_user(to_string.as_value()),
+            prop_key!("dotAll") => Property::new_const_accessor(dot_all.obj_ref()),
+            prop_key!("flags") => Property::new_const_accessor(flags.obj_ref()),
+            prop_key!("global") => Property::new_const_accessor(global.obj_ref()),
+            prop_key!("hasIndices") => Property::new_const_accessor(has_indices.obj_ref()),
+            prop_key!("ignoreCase") => Property::new_const_accessor(ignore_case.obj_ref()),
+            prop_key!("multiline") => Property::new_const_accessor(multiline.obj_ref()),
+            prop_key!("source") => Property::new_const_accessor(source.obj_ref()),
+            prop_key!("sticky") => Property::new_const_accessor(sticky.obj_ref()),
+            prop_key!("unicode") => Property::new_const_accessor(unicode.obj_ref()),
+        ];
+
+        let obj_ref = heap.allocate(Object::new(
+            Some(obj_proto),
+            props,
+            ObjectData::None,
+            Extensible::Yes,
+        ))?;
+        Ok(Self { obj_ref })
+    }
+
+    fn obj_ref(&self) -> Reference {
+        self.obj_ref
+    }
+}
+
+pub struct RegExpStringIteratorProtoBuiltin {
+    obj_ref: Reference,
+}
+
+impl Builtin for RegExpStringIteratorProtoBuiltin {
+    type InitArgs = (Reference, Reference);
+
+    fn init(
+        heap: &mut Heap,
+        (obj_proto, fn_proto): Self::InitArgs,
+    ) -> Result<Self, InitialisationError> {
+        let next = IteratorNextBuiltin::init(heap, fn_proto)?;
+
+        let props = hash_map![
+            prop_key!("next") => Property::new_user(next.as_value()),
+        ];
+
+        let obj_ref = heap.allocate(Object::new(
+            Some(obj_proto),
+            props,
+            ObjectData::None,
+            Extensible::Yes,
+        ))?;
+        Ok(Self { obj_ref })
+    }
+
+    fn obj_ref(&self) -> Reference {
+        self.obj_ref
+    }
+}
+
+/// State of the iterator returned by `String.prototype.matchAll()`.
+#[derive(Clone, Debug)]
+pub struct RegExpStringIterator {
+    regexp: Reference,
+    input: JsString,
+    done: bool,
+}
+
+impl Trace for RegExpStringIterator {
+    fn trace(&self, tracer: &mut Tracer) {
+        tracer.visit(self.regexp);
+    }
+}
+
+builtin_fn!(pub RegExpCtorBuiltin, Extensible::Yes, (it, _receiver, args) => {
+    let mut args = args.iter().cloned();
+    let pattern = args.next().unwrap_or_default();
+    let flags = args.next().unwrap_or_default();
+
+    let pattern_regexp = match pattern {
+        Value::Object(obj_ref) => regexp_data(it, obj_ref),
+        _ => None,
+    };
+    let (source, default_flags) = match (pattern, pattern_regexp) {
+        (_, Some(regexp)) => (JsString::from(regexp.source()), regexp.flags().to_string()),
+        (Value::Undefined, None) => (JsString::default(), String::new()),
+        (pattern, None) => (it.to_string(pattern)?, String::new()),
+    };
+    let flags = match flags {
+        Value::Undefined => default_flags,
+        flags => it.to_string(flags)?.to_string_lossy(),
+    };
+    alloc_regexp(it, &source.to_string_lossy(), &flags)
+});
+
+builtin_fn!(ExecBuiltin, Extensible::Yes, (it, receiver, args) => {
+    let (receiver, regexp) = this_regexp(it, receiver)?;
+    let input = it.to_string(args.first().cloned().unwrap_or_default())?;
+    let input_utf16 = input.as_utf16();
+    match builtin_exec(it, receiver, &regexp, input_utf16)? {
+        Some(m) => alloc_match_result(it, &regexp, &input, input_utf16, &m),
+        None => Ok(Value::Null),
+    }
+});
+
+builtin_fn!(TestBuiltin, Extensible::Yes, (it, receiver, args) => {
+    let (receiver, regexp) = this_regexp(it, receiver)?;
+    let input = it.to_string(args.first().cloned().unwrap_or_default())?;
+    let input_utf16 = input.as_utf16();
+    let m = builtin_exec(it, receiver, &regexp, input_utf16)?;
+    Ok(Value::Boolean(m.is_some()))
+});
+
+builtin_fn!(ToStringBuiltin, Extensible::Yes, (it, receiver, _args) => {
+    let (_, regexp) = this_regexp(it, receiver)?;
+    Ok(Value::String(JsString::from(regexp.to_string())))
+});
+
+builtin_fn!(GetSourceBuiltin, Extensible::No, (it, receiver, _args) => {
+    let source = match this_regexp(it, receiver) {
+        Ok((_, regexp)) if regexp.source().is_empty() => Box::from("(?:)"),
+        Ok((_, regexp)) => Box::from(regexp.source()),
+        // `RegExp.prototype.source` is special-cased by the spec.
+        Err(_) => Box::from("(?:)"),
+    };
+    Ok(Value::String(JsString::from(source)))
+});
+
+builtin_fn!(GetFlagsBuiltin, Extensible::No, (it, receiver, _args) => {
+    let flags = this_regexp(it, receiver)
+        .ok()
+        .map(|(_, regexp)| regexp.flags().to_string())
+        .unwrap_or_default();
+    Ok(Value::String(JsString::from(flags)))
+});
+
+builtin_fn!(GetDotAllBuiltin, Extensible::No, (it, receiver, _args) => {
+    Ok(flag_value(it, receiver, |flags| flags.dot_all))
+});
+
+builtin_fn!(GetGlobalBuiltin, Extensible::No, (it, receiver, _args) => {
+    Ok(flag_value(it, receiver, |flags| flags.global))
+});
+
+builtin_fn!(GetHasIndicesBuiltin, Extensible::No, (it, receiver, _args) => {
+    Ok(flag_value(it, receiver, |flags| flags.has_indices))
+});
+
+builtin_fn!(GetIgnoreCaseBuiltin, Extensible::No, (it, receiver, _args) => {
+    Ok(flag_value(it, receiver, |flags| flags.ignore_case))
+});
+
+builtin_fn!(GetMultilineBuiltin, Extensible::No, (it, receiver, _args) => {
+    Ok(flag_value(it, receiver, |flags| flags.multiline))
+});
+
+builtin_fn!(GetStickyBuiltin, Extensible::No, (it, receiver, _args) => {
+    Ok(flag_value(it, receiver, |flags| flags.sticky))
+});
+
+builtin_fn!(GetUnicodeBuiltin, Extensible::No, (it, receiver, _args) => {
+    Ok(flag_value(it, receiver, |flags| flags.unicode))
+});
+
+builtin_fn!(IteratorNextBuiltin, Extensible::Yes, (it, receiver, _args) => {
+    let receiver = receiver_object(receiver)?;
+    let state = {
+        let obj = it.vm().heap().resolve(receiver);
+        let state = obj.as_ref().regexp_string_iterator_data().cloned();
+        state
+    };
+    let Some(state) = state else {
+        return Err(ErrorKind::from(IncompatibleRegExpError::new(
+            "receiver is not a RegExp string iterator".to_owned(),
+        )));
+    };
+    if state.done {
+        return alloc_iterator_result(it, Value::Undefined, true);
+    }
+    let (_, regexp) = this_regexp(it, Value::Object(state.regexp))?;
+    let input_utf16 = state.input.as_utf16();
+    let Some(m) = builtin_exec(it, state.regexp, &regexp, input_utf16)? else {
+        set_iterator_done(it, receiver);
+        return alloc_iterator_result(it, Value::Undefined, true);
+    };
+    if regexp.flags().global {
+        if m.range().is_empty() {
+            let last_index = get_last_index(it, state.regexp)?;
+            let last_index = regexp.advance_index(input_utf16, last_index);
+            set_last_index(it, state.regexp, last_index)?;
+        }
+    } else {
+        set_iterator_done(it, receiver);
+    }
+    let value = alloc_match_result(it, &regexp, &state.input, input_utf16, &m)?;
+    alloc_iterator_result(it, value, false)
+});
+
+/// If the value is a `RegExp` object, return it. Otherwise, create a new
+/// `RegExp` object using the value as the pattern.
+pub(super) fn coerce_to_regexp(
+    it: &mut Interpreter,
+    value: Value,
+    flags: &str,
+) -> Result<(Reference, RegExp), ErrorKind> {
+    if let Value::Object(obj_ref) = value
+        && let Some(regexp) = regexp_data(it, obj_ref)
+    {
+        return Ok((obj_ref, regexp));
+    }
+    let source = match value {
+        Value::Undefined => Box::default(),
+        value => Box::from(it.to_string(value)?.to_string_lossy()),
+    };
+    let Value::Object(obj_ref) = alloc_regexp(it, &source, flags)? else {
+        unreachable!()
+    };
+    let regexp = regexp_data(it, obj_ref).unwrap();
+    Ok((obj_ref, regexp))
+}
+
+pub(super) fn regexp_data(it: &Interpreter, obj_ref: Reference) -> Option<RegExp> {
+    let obj = it.vm().heap().resolve(obj_ref);
+    let regexp = obj.as_ref().regexp_data().cloned();
+    regexp
+}
+
+/// Implements `RegExp.prototype[@@match]`.
+pub(super) fn regexp_match(
+    it: &mut Interpreter,
+    regexp_ref: Reference,
+    regexp: &RegExp,
+    input: &JsString,
+) -> Result<Value, ErrorKind> {
+    let input_utf16 = input.as_utf16();
+    if !regexp.flags().global {
+        return match builtin_exec(it, regexp_ref, regexp, input_utf16)? {
+            Some(m) => alloc_match_result(it, regexp, input, input_utf16, &m),
+            None => Ok(Value::Null),
+        };
+    }
+    let matches = exec_all(it, regexp_ref, regexp, input_utf16)?;
+    if matches.is_empty() {
+        return Ok(Value::Null);
+    }
+    let mut elems = Vec::with_capacity(matches.len());
+    for m in matches {
+        elems.push(utf16_string(&input_utf16[m.range()]));
+    }
+    it.vm_mut()
+        .alloc_array(elems)
+        .map(Value::Object)
+        .map_err(ErrorKind::from)
+}
+
+/// Implements `RegExp.prototype[@@matchAll]`.
+pub(super) fn regexp_match_all(
+    it: &mut Interpreter,
+    regexp_ref: Reference,
+    regexp: &RegExp,
+    input: &JsString,
+) -> Result<Value, ErrorKind> {
+    // The iterator operates on its own copy of the regex so that it doesn't
+    // interfere with the `lastIndex` of the original.
+    let last_index = get_last_index(it, regexp_ref)?;
+    let matcher = it.vm_mut().alloc_regexp(regexp.clone())?;
+    set_last_index(it, matcher, last_index)?;
+    let proto = it
+        .vm()
+        .runtime()
+        .global_object()
+        .regexp_string_iterator_proto()
+        .obj_ref();
+    let state = RegExpStringIterator {
+        regexp: matcher,
+        input: input.clone(),
+        done: false,
+    };
+    it.vm_mut()
+        .heap_mut()
+        .allocate(Object::new(
+            Some(proto),
+            hash_map![],
+            ObjectData::RegExpStringIterator(state),
+            Extensible::Yes,
+        ))
+        .map(Value::Object)
+        .map_err(ErrorKind::from)
+}
+
+/// Implements `RegExp.prototype[@@replace]`.
+pub(super) fn regexp_replace(
+    it: &mut Interpreter,
+    regexp_ref: Reference,
+    regexp: &RegExp,
+    input: &JsString,
+    replacement: Value,
+) -> Result<Value, ErrorKind> {
+    let input_utf16 = input.as_utf16();
+    let global = regexp.flags().global;
+    // The matches of a global regex are found as they're replaced, rather than
+    // all being collected first, so that the memory needed doesn't grow with
+    // the number of matches. The script can't tell the difference, as
+    // `lastIndex` is reset up front, like it would be after the last match,
+    // and isn't used to find the rest. If the script is interrupted, there are
+    // no more matches, and the partial result is discarded as the script stops.
+    let mut next = if global {
+        set_last_index(it, regexp_ref, 0)?;
+        next_match(it, regexp, input_utf16, 0).unwrap_or(None)
+    } else {
+        builtin_exec(it, regexp_ref, regexp, input_utf16)?
+    };
+
+    let mut result = Vec::with_capacity(input_utf16.len());
+    let mut next_source_position = 0;
+    while let Some(m) = next.take() {
+        let range = m.range();
+        if global {
+            let next_index = if range.is_empty() {
+                regexp.advance_index(input_utf16, range.end)
+            } else {
+                range.end
+            };
+            next = next_match(it, regexp, input_utf16, next_index).unwrap_or(None);
+        }
+        let captures = (1..m.group_count())
+            .map(|idx| m.group(idx))
+            .collect::<Vec<_>>();
+        let named_captures = regexp
+            .group_names()
+            .map(|(name, idx)| (name, m.group(idx)))
+            .collect::<Vec<_>>();
+        let replacement = match replacement_fn(it, &replacement) {
+            Some(fn_ref) => {
+                let mut args = Vec::with_capacity(captures.len() + 4);
+                args.push(utf16_string(&input_utf16[range.clone()]));
+                for capture in &captures {
+                    args.push(match capture {
+                        Some(capture) => utf16_string(&input_utf16[capture.clone()]),
+                        None => Value::Undefined,
+                    });
+                }
+                args.push(Value::Number(usize_to_number(range.start)));
+                args.push(Value::String(input.clone()));
+                if !named_captures.is_empty() {
+                    args.push(alloc_groups_object(it, input_utf16, &named_captures)?);
+                }
+                let fn_obj = it.vm().heap().resolve(fn_ref);
+                let value = fn_obj.as_ref().call(it, fn_ref, Value::Undefined, &args)?;
+                it.to_string(value)?.as_utf16().to_vec()
+            }
+            None => {
+                let template = it.to_string(replacement.clone())?;
+                get_substitution(
+                    input_utf16,
+                    range.clone(),
+                    &captures,
+                    &named_captures,
+                    template.as_utf16(),
+                )
+            }
+        };
+        if range.start >= next_source_position {
+            result.extend_from_slice(&input_utf16[next_source_position..range.start]);
+            result.extend(replacement);
+            next_source_position = range.end;
+            it.vm().check_string_length(result.len())?;
+        }
+    }
+    if next_source_position < input_utf16.len() {
+        result.extend_from_slice(&input_utf16[next_source_position..]);
+    }
+    Ok(utf16_string(&result))
+}
+
+/// Replace occurrences of a plain string pattern, as done by
+/// `String.prototype.replace()` and `String.prototype.replaceAll()` when the
+/// pattern isn't a `RegExp`.
+pub(super) fn string_replace(
+    it: &mut Interpreter,
+    input: &JsString,
+    pattern: &JsString,
+    replacement: Value,
+    all: bool,
+) -> Result<Value, ErrorKind> {
+    let input_utf16 = input.as_utf16();
+    let pattern_utf16 = pattern.as_utf16();
+    let mut result = Vec::with_capacity(input_utf16.len());
+    let mut end_of_last_match = 0;
+    // Nothing the script does while replacing can change where the pattern
+    // occurs, so each occurrence is found after the previous one has been
+    // replaced rather than collecting all of them first.
+    let mut next = find_utf16(input_utf16, pattern_utf16, 0);
+    while let Some(position) = next.take() {
+        if all {
+            let next_position = position + pattern_utf16.len().max(1);
+            next = find_utf16(input_utf16, pattern_utf16, next_position);
+        }
+        let range = position..(position + pattern_utf16.len());
+        let replacement = match replacement_fn(it, &replacement) {
+            Some(fn_ref) => {
+                let args = [
+                    utf16_string(pattern_utf16),
+                    Value::Number(usize_to_number(position)),
+                    Value::String(input.clone()),
+                ];
+                let fn_obj = it.vm().heap().resolve(fn_ref);
+                let value = fn_obj.as_ref().call(it, fn_ref, Value::Undefined, &args)?;
+                it.to_string(value)?.as_utf16().to_vec()
+            }
+            None => {
+                let template = it.to_string(replacement.clone())?;
+                get_substitution(input_utf16, range.clone(), &[], &[], template.as_utf16())
+            }
+        };
+        result.extend_from_slice(&input_utf16[end_of_last_match..range.start]);
+        result.extend(replacement);
+        end_of_last_match = range.end;
+        it.vm().check_string_length(result.len())?;
+    }
+    result.extend_from_slice(&input_utf16[end_of_last_match..]);
+    Ok(utf16_string(&result))
+}
+
+/// Implements `RegExp.prototype[@@search]`.
+pub(super) fn regexp_search(
+    it: &mut Interpreter,
+    regexp_ref: Reference,
+    regexp: &RegExp,
+    input: &JsString,
+) -> Result<Value, ErrorKind> {
+    let input_utf16 = input.as_utf16();
+    let previous_last_index = get_last_index(it, regexp_ref)?;
+    set_last_index(it, regexp_ref, 0)?;
+    let m = builtin_exec(it, regexp_ref, regexp, input_utf16)?;
+    set_last_index(it, regexp_ref, previous_last_index)?;
+    Ok(match m {
+        Some(m) => Value::Number(usize_to_number(m.range().start)),
+        None => Value::Number(Number::from(-1)),
+    })
+}
+
+/// Implements `RegExp.prototype[@@split]`.
+pub(super) fn regexp_split(
+    it: &mut Interpreter,
+    regexp: &RegExp,
+    input: &JsString,
+    limit: usize,
+) -> Result<Value, ErrorKind> {
+    let input_utf16 = input.as_utf16();
+    let mut parts = Vec::new();
+    if limit == 0 {
+        return it
+            .vm_mut()
+            .alloc_array(parts)
+            .map(Value::Object)
+            .map_err(ErrorKind::from);
+    }
+    if input_utf16.is_empty() {
+        let Ok(m) = match_at(it, regexp, input_utf16, 0) else {
+            return Ok(Value::Undefined);
+        };
+        if m.is_none() {
+            parts.push(utf16_string(input_utf16));
+        }
+        return it
+            .vm_mut()
+            .alloc_array(parts)
+            .map(Value::Object)
+            .map_err(ErrorKind::from);
+    }
+
+    let mut p = 0;
+    let mut q = p;
+    while q < input_utf16.len() {
+        // The spec uses a sticky copy of the regex, which is equivalent to only
+        // attempting to match at exactly `q`.
+        let m = match match_at(it, regexp, input_utf16, q) {
+            Ok(Some(m)) => m,
+            Ok(None) => {
+                q = regexp.advance_index(input_utf16, q);
+                continue;
+            }
+            Err(Interrupted) => return Ok(Value::Undefined),
+        };
+        let e = m.range().end.min(input_utf16.len());
+        if e == p {
+            q = regexp.advance_index(input_utf16, q);
+            continue;
+        }
+        parts.push(utf16_string(&input_utf16[p..q]));
+        if parts.len() == limit {
+            return it
+                .vm_mut()
+                .alloc_array(parts)
+                .map(Value::Object)
+                .map_err(ErrorKind::from);
+        }
+        p = e;
+        for idx in 1..m.group_count() {
+            parts.push(match m.group(idx) {
+                Some(capture) => utf16_string(&input_utf16[capture]),
+                None => Value::Undefined,
+            });
+            if parts.len() == limit {
+                return it
+                    .vm_mut()
+                    .alloc_array(parts)
+                    .map(Value::Object)
+                    .map_err(ErrorKind::from);
+            }
+        }
+        q = p;
+    }
+    parts.push(utf16_string(&input_utf16[p..]));
+    it.vm_mut()
+        .alloc_array(parts)
+        .map(Value::Object)
+        .map_err(ErrorKind::from)
+}
+
+/// [RegExpBuiltinExec ( R, S )](https://tc39.es/ecma262/#sec-regexpbuiltinexec)
+///
+/// If the script is interrupted while matching, there's no match and
+/// `lastIndex` is left alone. The script stops once the native function
+/// returns, so nothing else needs to tell the difference.
+fn builtin_exec(
+    it: &mut Interpreter,
+    regexp_ref: Reference,
+    regexp: &RegExp,
+    input: &[u16],
+) -> Result<Option<Match>, ErrorKind> {
+    let flags = regexp.flags();
+    let global_or_sticky = flags.global || flags.sticky;
+    let last_index = if global_or_sticky {
+        get_last_index(it, regexp_ref)?
+    } else {
+        0
+    };
+    let Ok(m) = next_match(it, regexp, input, last_index) else {
+        return Ok(None);
+    };
+    if global_or_sticky {
+        let last_index = m.as_ref().map_or(0, |m| m.range().end);
+        set_last_index(it, regexp_ref, last_index)?;
+    }
+    Ok(m)
+}
+
+/// Find the first match starting at or after `last_index`, or only at it if
+/// the regex is sticky, without reading or updating the `lastIndex` property.
+fn next_match(
+    it: &mut Interpreter,
+    regexp: &RegExp,
+    input: &[u16],
+    mut last_index: usize,
+) -> Result<Option<Match>, Interrupted> {
+    loop {
+        if last_index > input.len() {
+            return Ok(None);
+        }
+        if let Some(m) = match_at(it, regexp, input, last_index)? {
+            return Ok(Some(m));
+        }
+        if regexp.flags().sticky {
+            return Ok(None);
+        }
+        last_index = regexp.advance_index(input, last_index);
+    }
+}
+
+/// Attempt to match at exactly `start`, checking for an interrupt (and
+/// consuming [fuel][crate::interpreter::Vm::set_fuel()]) while backtracking so
+/// that a pattern which takes exponential time can be stopped like a loop.
+fn match_at(
+    it: &mut Interpreter,
+    regexp: &RegExp,
+    input: &[u16],
+    start: usize,
+) -> Result<Option<Match>, Interrupted> {
+    regexp.match_at(input, start, &mut || it.vm_mut().check_interrupt())
+}
+
+/// Find every match of a global regex, starting from the beginning of the
+/// input.
+fn exec_all(
+    it: &mut Interpreter,
+    regexp_ref: Reference,
+    regexp: &RegExp,
+    input: &[u16],
+) -> Result<Vec<Match>, ErrorKind> {
+    debug_assert!(regexp.flags().global);
+    set_last_index(it, regexp_ref, 0)?;
+    let mut matches = Vec::new();
+    while let Some(m) = builtin_exec(it, regexp_ref, regexp, input)? {
+        if m.range().is_empty() {
+            let last_index = get_last_index(it, regexp_ref)?;
+            set_last_index(it, regexp_ref, regexp.advance_index(input, last_index))?;
+        }
+        matches.push(m);
+    }
+    Ok(matches)
+}
+
+/// [GetSubstitution ( matched, str, position, captures, namedCaptures,
+/// replacementTemplate )]( https://tc39.es/ecma262/#sec-getsubstitution)
+fn get_substitution(
+    input: &[u16],
+    range: std::ops::Range<usize>,
+    captures: &[Option<std::ops::Range<usize>>],
+    named_captures: &[(&str, Option<std::ops::Range<usize>>)],
+    template: &[u16],
+) -> Vec<u16> {
+    const DOLLAR: u16 = b'$' as u16;
+
+    let digit = |idx: usize| {
+        template
+            .get(idx)
+            .and_then(|&cu| char::from_u32(u32::from(cu)))
+            .and_then(|ch| ch.to_digit(10))
+            .map(|digit| digit as usize)
+    };
+
+    let mut result = Vec::with_capacity(template.len());
+    let mut idx = 0;
+    while idx < template.len() {
+        let cu = template[idx];
+        if cu != DOLLAR || idx + 1 == template.len() {
+            result.push(cu);
+            idx += 1;
+            continue;
+        }
+        let next = template[idx + 1];
+        match char::from_u32(u32::from(next)) {
+            Some('$') => {
+                result.push(DOLLAR);
+                idx += 2;
+            }
+            Some('&') => {
+                result.extend_from_slice(&input[range.clone()]);
+                idx += 2;
+            }
+            Some('`') => {
+                result.extend_from_slice(&input[..range.start]);
+                idx += 2;
+            }
+            Some('\'') => {
+                result.extend_from_slice(&input[range.end.min(input.len())..]);
+                idx += 2;
+            }
+            Some('0'..='9') => {
+                let d0 = digit(idx + 1).unwrap();
+                // Prefer a two-digit group reference if it refers to a group which exists.
+                let (group, len) = match digit(idx + 2) {
+                    Some(d1) if (1..=captures.len()).contains(&(d0 * 10 + d1)) => (d0 * 10 + d1, 3),
+                    Some(_) | None => (d0, 2),
+                };
+                if (1..=captures.len()).contains(&group) {
+                    if let Some(ref capture) = captures[group - 1] {
+                        result.extend_from_slice(&input[capture.clone()]);
+                    }
+                    idx += len;
+                } else {
+                    result.push(DOLLAR);
+                    idx += 1;
+                }
+            }
+            Some('<') if !named_captures.is_empty() => {
+                let name_start = idx + 2;
+                let Some(name_len) = template[name_start..]
+                    .iter()
+                    .position(|&cu| cu == u16::from(b'>'))
+                else {
+                    result.push(DOLLAR);
+                    idx += 1;
+                    continue;
+                };
+                let name = String::from_utf16_lossy(&template[name_start..(name_start + name_len)]);
+                let capture = named_captures
+                    .iter()
+                    .find(|(it, _)| *it == name)
+                    .and_then(|(_, capture)| capture.clone());
+                if let Some(capture) = capture {
+                    result.extend_from_slice(&input[capture]);
+                }
+                idx = name_start + name_len + 1;
+            }
+            Some(_) | None => {
+                result.push(DOLLAR);
+                idx += 1;
+            }
+        }
+    }
+    result
+}
+
+fn alloc_regexp(it: &mut Interpreter, source: &str, flags: &str) -> Result<Value, ErrorKind> {
+    let regexp = RegExp::new(source, flags)
+        .map_err(|err| InvalidRegExpError::new(format!("/{source}/{flags}: {err}")))?;
+    it.vm_mut()
+        .alloc_regexp(regexp)
+        .map(Value::Object)
+        .map_err(ErrorKind::from)
+}
+
+/// Create the array returned by `RegExp.prototype.exec()`.
+fn alloc_match_result(
+    it: &mut Interpreter,
+    regexp: &RegExp,
+    input: &JsString,
+    input_utf16: &[u16],
+    m: &Match,
+) -> Result<Value, ErrorKind> {
+    let mut elems = Vec::with_capacity(m.group_count());
+    for idx in 0..m.group_count() {
+        elems.push(match m.group(idx) {
+            Some(range) => utf16_string(&input_utf16[range]),
+            None => Value::Undefined,
+        });
+    }
+    let named_captures = regexp
+        .group_names()
+        .map(|(name, idx)| (name, m.group(idx)))
+        .collect::<Vec<_>>();
+    let groups = if named_captures.is_empty() {
+        Value::Undefined
+    } else {
+        alloc_groups_object(it, input_utf16, &named_captures)?
+    };
+    let indices = if regexp.flags().has_indices {
+        let mut ranges = Vec::with_capacity(m.group_count());
+        for idx in 0..m.group_count() {
+            ranges.push(match m.group(idx) {
+                Some(range) => alloc_range_array(it, range)?,
+                None => Value::Undefined,
+            });
+        }
+        let indices_ref = it.vm_mut().alloc_array(ranges)?;
+        let index_groups = if named_captures.is_empty() {
+            Value::Undefined
+        } else {
+            let mut props = Vec::with_capacity(named_captures.len());
+            for (name, capture) in &named_captures {
+                let value = match capture {
+                    Some(range) => alloc_range_array(it, range.clone())?,
+                    None => Value::Undefined,
+                };
+                props.push((PropertyKey::from(*name), value));
+            }
+            Value::Object(alloc_null_proto_object(it, props)?)
+        };
+        define_enumerable(it, indices_ref, prop_key!("groups"), index_groups);
+        Some(Value::Object(indices_ref))
+    } else {
+        None
+    };
+    let input = Value::String(input.clone());
+
+    let result_ref = it.vm_mut().alloc_array(elems)?;
+    let index = Value::Number(usize_to_number(m.range().start));
+    define_enumerable(it, result_ref, prop_key!("index"), index);
+    define_enumerable(it, result_ref, prop_key!("input"), input);
+    define_enumerable(it, result_ref, prop_key!("groups"), groups);
+    if let Some(indices) = indices {
+        define_enumerable(it, result_ref, prop_key!("indices"), indices);
+    }
+    Ok(Value::Object(result_ref))
+}
+
+fn alloc_groups_object(
+    it: &mut Interpreter,
+    input_utf16: &[u16],
+    named_captures: &[(&str, Option<std::ops::Range<usize>>)],
+) -> Result<Value, ErrorKind> {
+    let mut props = Vec::with_capacity(named_captures.len());
+    for (name, capture) in named_captures {
+        let value = match capture {
+            Some(range) => utf16_string(&input_utf16[range.clone()]),
+            None => Value::Undefined,
+        };
+        props.push((PropertyKey::from(*name), value));
+    }
+    alloc_null_proto_object(it, props).map(Value::Object)
+}
+
+/// Groups objects don't inherit from `Object.prototype`, so that a group name
+/// can't clash with an inherited property such as `toString`.
+fn alloc_null_proto_object(
+    it: &mut Interpreter,
+    props: Vec<(PropertyKey, Value)>,
+) -> Result<Reference, ErrorKind> {
+    it.vm_mut()
+        .heap_mut()
+        .allocate(Object::new_object(None, props, Extensible::Yes))
+        .map_err(ErrorKind::from)
+}
+
+fn alloc_range_array(
+    it: &mut Interpreter,
+    range: std::ops::Range<usize>,
+) -> Result<Value, ErrorKind> {
+    let elems = vec![
+        Value::Number(usize_to_number(range.start)),
+        Value::Number(usize_to_number(range.end)),
+    ];
+    it.vm_mut()
+        .alloc_array(elems)
+        .map(Value::Object)
+        .map_err(ErrorKind::from)
+}
+
+fn alloc_iterator_result(
+    it: &mut Interpreter,
+    value: Value,
+    done: bool,
+) -> Result<Value, ErrorKind> {
+    let props = [
+        (prop_key!("value"), value),
+        (prop_key!("done"), Value::Boolean(done)),
+    ];
+    it.vm_mut()
+        .alloc_object(props)
+        .map(Value::Object)
+        .map_err(ErrorKind::from)
+}
+
+fn utf16_string(s: &[u16]) -> Value {
+    Value::String(JsString::from(s))
+}
+
+fn define_enumerable(it: &mut Interpreter, obj_ref: Reference, key: PropertyKey, value: Value) {
+    let mut obj = it.vm_mut().heap_mut().resolve_mut(obj_ref);
+    let defined = obj.define_own_property(key, Property::new_enumerable(value));
+    assert!(defined);
+}
+
+fn this_regexp(it: &Interpreter, receiver: Value) -> Result<(Reference, RegExp), ErrorKind> {
+    match receiver {
+        Value::Object(obj_ref) if let Some(regexp) = regexp_data(it, obj_ref) => {
+            Ok((obj_ref, regexp))
+        }
+        _ => Err(ErrorKind::from(IncompatibleRegExpError::new(
+            "receiver is not a RegExp".to_owned(),
+        ))),
+    }
+}
+
+fn flag_value(it: &Interpreter, receiver: Value, f: impl FnOnce(Flags) -> bool) -> Value {
+    // `RegExp.prototype` itself has no flags, but isn't an error according to the
+    // spec.
+    this_regexp(it, receiver).map_or(Value::Undefined, |(_, regexp)| {
+        Value::Boolean(f(regexp.flags()))
+    })
+}
+
+fn replacement_fn(it: &Interpreter, replacement: &Value) -> Option<Reference> {
+    match *replacement {
+        Value::Object(obj_ref) => {
+            let obj = it.vm().heap().resolve(obj_ref);
+            let callable = obj.as_ref().call_data().is_some();
+            callable.then_some(obj_ref)
+        }
+        _ => None,
+    }
+}
+
+fn get_last_index(it: &mut Interpreter, regexp_ref: Reference) -> Result<usize, ErrorKind> {
+    let obj = it.vm().heap().resolve(regexp_ref);
+    let value = obj
+        .as_ref()
+        .get(it, &prop_key!("lastIndex"), regexp_ref)?
+        .unwrap_or_default();
+    let n = it.to_number(value)?;
+    Ok(if n.is_nan() || n.is_negative() {
+        0
+    } else if n.is_infinite() {
+        usize::MAX
+    } else {
+        usize::try_from(n.as_i64()).unwrap_or(usize::MAX)
+    })
+}
+
+fn set_last_index(
+    it: &mut Interpreter,
+    regexp_ref: Reference,
+    last_index: usize,
+) -> Result<(), ErrorKind> {
+    let value = Value::Number(usize_to_number(last_index));
+    let mut obj = it.vm().heap().resolve(regexp_ref);
+    obj.as_ref_mut()
+        .set(it, &prop_key!("lastIndex"), regexp_ref, value)?;
+    Ok(())
+}
+
+fn set_iterator_done(it: &mut Interpreter, iter_ref: Reference) {
+    let mut obj = it.vm_mut().heap_mut().resolve_mut(iter_ref);
+    if let Some(state) = obj.regexp_string_iterator_data_mut() {
+        state.done = true;
+    };
+}
+
+pub(super) fn find_utf16(haystack: &[u16], needle: &[u16], from: usize) -> Option<usize> {
+    if from > haystack.len() {
+        return None;
+    }
+    if needle.is_empty() {
+        return Some(from);
+    }
+    haystack[from..]
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|idx| from + idx)
+}
+
+fn usize_to_number(n: usize) -> Number {
+    Number::try_from(n).unwrap_or_else(|_| {
+        // TODO
+        unreachable!()
+    })
+}
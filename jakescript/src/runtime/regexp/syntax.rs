@@ -0,0 +1,910 @@
+use super::engine::{Flags, SyntaxError};
+use std::ops::Range;
+
+type Result<T> = std::result::Result<T, SyntaxError>;
+
+/// A parsed regular expression pattern, prior to being compiled into a
+/// [`Program`](super::engine::Program).
+#[derive(Debug)]
+pub(super) struct Pattern {
+    pub(super) node: Node,
+    /// Number of capturing groups, not including the implicit group for the
+    /// whole match.
+    pub(super) capture_count: usize,
+    pub(super) group_names: Vec<(Box<str>, usize)>,
+}
+
+#[derive(Clone, Debug)]
+pub(super) enum Node {
+    Empty,
+    /// A code unit, or a code point in Unicode mode.
+    Char(u32),
+    /// `.`
+    Any,
+    Class(ClassSet),
+    Assertion(Assertion),
+    Backreference(usize),
+    /// A backreference to a named group, which may appear later in the
+    /// pattern. Replaced by [`Node::Backreference`] once parsing is complete.
+    NamedBackreference(Box<str>),
+    Sequence(Vec<Node>),
+    Alternation(Vec<Node>),
+    Group(Box<Node>, Option<usize>),
+    Lookaround(Box<Lookaround>),
+    Repeat(Box<Repeat>),
+}
+
+impl Node {
+    /// Whether the node could possibly match without consuming any input.
+    pub(super) fn is_nullable(&self) -> bool {
+        match self {
+            Self::Char(_) | Self::Any | Self::Class(_) => false,
+            Self::Empty
+            | Self::Assertion(_)
+            | Self::Backreference(_)
+            | Self::NamedBackreference(_)
+            | Self::Lookaround(_) => true,
+            Self::Sequence(nodes) => nodes.iter().all(Self::is_nullable),
+            Self::Alternation(nodes) => nodes.iter().any(Self::is_nullable),
+            Self::Group(node, _) => node.is_nullable(),
+            Self::Repeat(repeat) => repeat.min == 0 || repeat.node.is_nullable(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum Assertion {
+    /// `^`
+    Start,
+    /// `$`
+    End,
+    /// `\b`
+    WordBoundary,
+    /// `\B`
+    NotWordBoundary,
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct Lookaround {
+    pub(super) node: Node,
+    pub(super) behind: bool,
+    pub(super) negated: bool,
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct Repeat {
+    pub(super) node: Node,
+    pub(super) min: u32,
+    pub(super) max: Option<u32>,
+    pub(super) greedy: bool,
+    /// Capturing groups contained within the repeated node, which must be
+    /// reset at the start of each iteration.
+    pub(super) captures: Range<usize>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(super) struct ClassSet {
+    pub(super) negated: bool,
+    pub(super) items: Vec<ClassItem>,
+}
+
+impl ClassSet {
+    fn of(item: ClassItem) -> Self {
+        Self {
+            negated: false,
+            items: vec![item],
+        }
+    }
+
+    pub(super) fn contains(&self, ch: u32) -> bool {
+        self.items.iter().any(|item| item.contains(ch))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(super) enum ClassItem {
+    Range(u32, u32),
+    /// `\d`
+    Digit(bool),
+    /// `\s`
+    Space(bool),
+    /// `\w`
+    Word(bool),
+    /// `\p{...}` and `\P{...}`
+    Property(UnicodeProperty, bool),
+}
+
+impl ClassItem {
+    fn contains(self, ch: u32) -> bool {
+        match self {
+            Self::Range(lo, hi) => (lo..=hi).contains(&ch),
+            Self::Digit(negated) => is_digit(ch) != negated,
+            Self::Space(negated) => is_space(ch) != negated,
+            Self::Word(negated) => is_word_char(ch) != negated,
+            Self::Property(prop, negated) => prop.contains(ch) != negated,
+        }
+    }
+}
+
+/// The subset of Unicode properties which can be approximated using the
+/// standard library.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(super) enum UnicodeProperty {
+    Alphabetic,
+    Any,
+    Ascii,
+    AsciiHexDigit,
+    Lowercase,
+    Numeric,
+    Uppercase,
+    WhiteSpace,
+}
+
+impl UnicodeProperty {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "Alphabetic" | "Alpha" | "L" | "Letter" => Self::Alphabetic,
+            "Any" => Self::Any,
+            "ASCII" => Self::Ascii,
+            "ASCII_Hex_Digit" | "AHex" => Self::AsciiHexDigit,
+            "Lowercase" | "Lower" | "Ll" | "Lowercase_Letter" => Self::Lowercase,
+            "N" | "Number" => Self::Numeric,
+            "Uppercase" | "Upper" | "Lu" | "Uppercase_Letter" => Self::Uppercase,
+            "White_Space" | "space" => Self::WhiteSpace,
+            _ => return None,
+        })
+    }
+
+    fn contains(self, ch: u32) -> bool {
+        let Some(ch) = char::from_u32(ch) else {
+            return matches!(self, Self::Any);
+        };
+        match self {
+            Self::Alphabetic => ch.is_alphabetic(),
+            Self::Any => true,
+            Self::Ascii => ch.is_ascii(),
+            Self::AsciiHexDigit => ch.is_ascii_hexdigit(),
+            Self::Lowercase => ch.is_lowercase(),
+            Self::Numeric => ch.is_numeric(),
+            Self::Uppercase => ch.is_uppercase(),
+            Self::WhiteSpace => ch.is_whitespace(),
+        }
+    }
+}
+
+pub(super) fn is_digit(ch: u32) -> bool {
+    (u32::from('0')..=u32::from('9')).contains(&ch)
+}
+
+pub(super) fn is_word_char(ch: u32) -> bool {
+    char::from_u32(ch).is_some_and(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+}
+
+pub(super) fn is_space(ch: u32) -> bool {
+    matches!(
+        ch,
+        0x09..=0x0D
+            | 0x20
+            | 0xA0
+            | 0x1680
+            | 0x2000..=0x200A
+            | 0x2028
+            | 0x2029
+            | 0x202F
+            | 0x205F
+            | 0x3000
+            | 0xFEFF
+    )
+}
+
+pub(super) fn is_line_terminator(ch: u32) -> bool {
+    matches!(ch, 0x0A | 0x0D | 0x2028 | 0x2029)
+}
+
+/// ```plain
+/// Pattern[UnicodeMode, NamedCaptureGroups]::
+///     Disjunction[?UnicodeMode, ?NamedCaptureGroups]
+/// ```
+///
+/// Includes the syntax extensions in [Annex B](
+/// https://tc39.es/ecma262/#sec-regular-expressions-patterns) when not in
+/// Unicode mode.
+pub(super) fn parse(source: &str, flags: Flags) -> Result<Pattern> {
+    let mut parser = Parser {
+        source: source.chars().collect(),
+        pos: 0,
+        unicode: flags.unicode,
+        named_groups: false,
+        total_capture_count: 0,
+        capture_count: 0,
+        group_names: Vec::new(),
+    };
+    let (total_capture_count, named_groups) = parser.count_capturing_groups();
+    parser.total_capture_count = total_capture_count;
+    parser.named_groups = named_groups || parser.unicode;
+
+    let node = parser.parse_disjunction()?;
+    match parser.peek() {
+        None => {}
+        Some(')') => return Err(SyntaxError::new("unmatched `)`")),
+        Some(ch) => unreachable!("{ch}"),
+    }
+    Ok(Pattern {
+        node: resolve_named_backreferences(node, &parser.group_names)?,
+        capture_count: parser.capture_count,
+        group_names: parser.group_names,
+    })
+}
+
+/// Named backreferences may refer to groups which appear later in the pattern,
+/// so can only be resolved once all groups are known.
+fn resolve_named_backreferences(node: Node, group_names: &[(Box<str>, usize)]) -> Result<Node> {
+    let resolve = |node| resolve_named_backreferences(node, group_names);
+    Ok(match node {
+        Node::Sequence(nodes) => {
+            Node::Sequence(nodes.into_iter().map(resolve).collect::<Result<_>>()?)
+        }
+        Node::Alternation(nodes) => {
+            Node::Alternation(nodes.into_iter().map(resolve).collect::<Result<_>>()?)
+        }
+        Node::Group(node, capture) => Node::Group(Box::new(resolve(*node)?), capture),
+        Node::Lookaround(box look) => Node::Lookaround(Box::new(Lookaround {
+            node: resolve(look.node)?,
+            ..look
+        })),
+        Node::Repeat(box repeat) => Node::Repeat(Box::new(Repeat {
+            node: resolve(repeat.node)?,
+            ..repeat
+        })),
+        Node::NamedBackreference(name) => match group_names.iter().find(|(it, _)| *it == name) {
+            Some(&(_, idx)) => Node::Backreference(idx),
+            None => {
+                return Err(SyntaxError::new(format!(
+                    "invalid named reference `{name}`"
+                )))
+            }
+        },
+        node => node,
+    })
+}
+
+struct Parser {
+    source: Vec<char>,
+    pos: usize,
+    unicode: bool,
+    named_groups: bool,
+    total_capture_count: usize,
+    capture_count: usize,
+    group_names: Vec<(Box<str>, usize)>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.source.get(self.pos).copied()
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<char> {
+        self.source.get(self.pos + n).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.pos += 1;
+        Some(ch)
+    }
+
+    fn next_if_eq(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn next_if_str(&mut self, expected: &str) -> bool {
+        let matched = expected
+            .chars()
+            .enumerate()
+            .all(|(offset, ch)| self.peek_nth(offset) == Some(ch));
+        if matched {
+            self.pos += expected.chars().count();
+        }
+        matched
+    }
+
+    /// Scan ahead to count the capturing groups in the pattern, which is
+    /// needed to disambiguate backreferences from legacy octal escapes, and
+    /// to determine whether `\k` is a named backreference.
+    fn count_capturing_groups(&self) -> (usize, bool) {
+        let mut count = 0;
+        let mut named = false;
+        let mut in_class = false;
+        let mut chars = self.source.iter().copied().peekable();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '\\' => {
+                    chars.next();
+                }
+                '[' => in_class = true,
+                ']' => in_class = false,
+                '(' if !in_class => {
+                    if chars.peek() != Some(&'?') {
+                        count += 1;
+                        continue;
+                    }
+                    chars.next();
+                    if chars.next_if_eq(&'<').is_some() && !matches!(chars.peek(), Some('=' | '!'))
+                    {
+                        count += 1;
+                        named = true;
+                    }
+                }
+                _ => {}
+            }
+        }
+        (count, named)
+    }
+
+    /// ```plain
+    /// Disjunction::
+    ///     Alternative
+    ///     Alternative | Disjunction
+    /// ```
+    fn parse_disjunction(&mut self) -> Result<Node> {
+        let mut alternatives = vec![self.parse_alternative()?];
+        while self.next_if_eq('|') {
+            alternatives.push(self.parse_alternative()?);
+        }
+        Ok(if alternatives.len() == 1 {
+            alternatives.pop().unwrap()
+        } else {
+            Node::Alternation(alternatives)
+        })
+    }
+
+    /// ```plain
+    /// Alternative::
+    ///     [empty]
+    ///     Alternative Term
+    /// ```
+    fn parse_alternative(&mut self) -> Result<Node> {
+        let mut terms = Vec::new();
+        while !matches!(self.peek(), None | Some('|' | ')')) {
+            self.parse_term(&mut terms)?;
+        }
+        Ok(match terms.len() {
+            0 => Node::Empty,
+            1 => terms.pop().unwrap(),
+            _ => Node::Sequence(terms),
+        })
+    }
+
+    /// ```plain
+    /// Term::
+    ///     Assertion
+    ///     Atom
+    ///     Atom Quantifier
+    /// ```
+    fn parse_term(&mut self, terms: &mut Vec<Node>) -> Result<()> {
+        let captures_before = self.capture_count;
+        let (atom, quantifiable) = self.parse_atom()?;
+        let captures = (captures_before + 1)..(self.capture_count + 1);
+        let Some((min, max)) = self.parse_quantifier_prefix()? else {
+            // Code points outside the BMP are matched as a pair of surrogates when not in
+            // Unicode mode.
+            match atom {
+                Node::Char(ch) if !self.unicode && ch > 0xFFFF => {
+                    let mut buf = [0; 2];
+                    let ch = char::from_u32(ch).unwrap();
+                    terms.extend(
+                        ch.encode_utf16(&mut buf)
+                            .iter()
+                            .map(|&cu| Node::Char(u32::from(cu))),
+                    );
+                }
+                atom => terms.push(atom),
+            }
+            return Ok(());
+        };
+        if !quantifiable {
+            return Err(SyntaxError::new("nothing to repeat"));
+        }
+        let greedy = !self.next_if_eq('?');
+        terms.push(Node::Repeat(Box::new(Repeat {
+            node: atom,
+            min,
+            max,
+            greedy,
+            captures,
+        })));
+        Ok(())
+    }
+
+    /// ```plain
+    /// QuantifierPrefix::
+    ///     *
+    ///     +
+    ///     ?
+    ///     { DecimalDigits }
+    ///     { DecimalDigits , }
+    ///     { DecimalDigits , DecimalDigits }
+    /// ```
+    fn parse_quantifier_prefix(&mut self) -> Result<Option<(u32, Option<u32>)>> {
+        Ok(match self.peek() {
+            Some('*') => {
+                self.pos += 1;
+                Some((0, None))
+            }
+            Some('+') => {
+                self.pos += 1;
+                Some((1, None))
+            }
+            Some('?') => {
+                self.pos += 1;
+                Some((0, Some(1)))
+            }
+            Some('{') => {
+                let Some((min, max)) = self.try_parse_braced_quantifier() else {
+                    if self.unicode {
+                        return Err(SyntaxError::new("incomplete quantifier"));
+                    }
+                    return Ok(None);
+                };
+                if max.is_some_and(|max| max < min) {
+                    return Err(SyntaxError::new("numbers out of order in `{}` quantifier"));
+                }
+                Some((min, max))
+            }
+            _ => None,
+        })
+    }
+
+    fn try_parse_braced_quantifier(&mut self) -> Option<(u32, Option<u32>)> {
+        let start = self.pos;
+        let result = (|| {
+            if !self.next_if_eq('{') {
+                return None;
+            }
+            let min = self.parse_decimal_digits()?;
+            let max = if self.next_if_eq(',') {
+                self.parse_decimal_digits()
+            } else {
+                Some(min)
+            };
+            self.next_if_eq('}').then_some((min, max))
+        })();
+        if result.is_none() {
+            self.pos = start;
+        }
+        result
+    }
+
+    fn parse_decimal_digits(&mut self) -> Option<u32> {
+        let mut value: Option<u32> = None;
+        while let Some(digit) = self.peek().and_then(|ch| ch.to_digit(10)) {
+            self.pos += 1;
+            value = Some(value.unwrap_or(0).saturating_mul(10).saturating_add(digit));
+        }
+        value
+    }
+
+    /// Returns the atom (or assertion), and whether it may be followed by a
+    /// quantifier.
+    ///
+    /// ```plain
+    /// Atom::
+    ///     PatternCharacter
+    ///     .
+    ///     \ AtomEscape
+    ///     CharacterClass
+    ///     ( GroupSpecifier(opt) Disjunction )
+    ///     (?: Disjunction )
+    /// ```
+    fn parse_atom(&mut self) -> Result<(Node, bool)> {
+        let ch = self.next().unwrap();
+        Ok(match ch {
+            '^' => (Node::Assertion(Assertion::Start), false),
+            '$' => (Node::Assertion(Assertion::End), false),
+            '.' => (Node::Any, true),
+            '\\' => self.parse_atom_escape()?,
+            '[' => (Node::Class(self.parse_class()?), true),
+            '(' => self.parse_group()?,
+            '*' | '+' | '?' => return Err(SyntaxError::new("nothing to repeat")),
+            '{' => {
+                self.pos -= 1;
+                if self.unicode || self.try_parse_braced_quantifier().is_some() {
+                    return Err(SyntaxError::new("nothing to repeat"));
+                }
+                self.pos += 1;
+                (Node::Char(u32::from('{')), true)
+            }
+            '}' | ']' if self.unicode => return Err(SyntaxError::new(format!("lone `{ch}`"))),
+            ch => (Node::Char(u32::from(ch)), true),
+        })
+    }
+
+    fn parse_group(&mut self) -> Result<(Node, bool)> {
+        let (node, quantifiable) = if self.next_if_str("?:") {
+            (Node::Group(Box::new(self.parse_disjunction()?), None), true)
+        } else if self.next_if_str("?=") || self.next_if_str("?!") {
+            let negated = self.source[self.pos - 1] == '!';
+            let node = self.parse_disjunction()?;
+            let look = Lookaround {
+                node,
+                behind: false,
+                negated,
+            };
+            // Annex B allows lookaheads to be quantified.
+            (Node::Lookaround(Box::new(look)), !self.unicode)
+        } else if self.next_if_str("?<=") || self.next_if_str("?<!") {
+            let negated = self.source[self.pos - 1] == '!';
+            let node = self.parse_disjunction()?;
+            let look = Lookaround {
+                node,
+                behind: true,
+                negated,
+            };
+            (Node::Lookaround(Box::new(look)), false)
+        } else if self.next_if_str("?<") {
+            let name = self.parse_group_name()?;
+            if self.group_names.iter().any(|(it, _)| *it == name) {
+                return Err(SyntaxError::new(format!(
+                    "duplicate capture group name `{name}`"
+                )));
+            }
+            self.capture_count += 1;
+            let idx = self.capture_count;
+            self.group_names.push((name, idx));
+            (
+                Node::Group(Box::new(self.parse_disjunction()?), Some(idx)),
+                true,
+            )
+        } else if self.peek() == Some('?') {
+            return Err(SyntaxError::new("invalid group"));
+        } else {
+            self.capture_count += 1;
+            let idx = self.capture_count;
+            (
+                Node::Group(Box::new(self.parse_disjunction()?), Some(idx)),
+                true,
+            )
+        };
+        if !self.next_if_eq(')') {
+            return Err(SyntaxError::new("unterminated group"));
+        }
+        Ok((node, quantifiable))
+    }
+
+    /// Parses the name of a group, including the closing `>`. The opening `<`
+    /// must already have been consumed.
+    fn parse_group_name(&mut self) -> Result<Box<str>> {
+        let mut name = String::new();
+        loop {
+            match self.next() {
+                Some('>') if !name.is_empty() => break,
+                Some(ch @ ('$' | '_')) => name.push(ch),
+                Some(ch) if ch.is_alphabetic() => name.push(ch),
+                Some(ch @ ('\u{200C}' | '\u{200D}')) if !name.is_empty() => name.push(ch),
+                Some(ch) if !name.is_empty() && ch.is_alphanumeric() => name.push(ch),
+                Some(_) | None => return Err(SyntaxError::new("invalid capture group name")),
+            }
+        }
+        Ok(name.into_boxed_str())
+    }
+
+    /// ```plain
+    /// AtomEscape::
+    ///     DecimalEscape
+    ///     CharacterClassEscape
+    ///     CharacterEscape
+    ///     k GroupName
+    /// ```
+    fn parse_atom_escape(&mut self) -> Result<(Node, bool)> {
+        let Some(ch) = self.peek() else {
+            return Err(SyntaxError::new("`\\` at end of pattern"));
+        };
+        Ok(match ch {
+            'b' => {
+                self.pos += 1;
+                (Node::Assertion(Assertion::WordBoundary), false)
+            }
+            'B' => {
+                self.pos += 1;
+                (Node::Assertion(Assertion::NotWordBoundary), false)
+            }
+            'k' if self.named_groups => {
+                self.pos += 1;
+                if !self.next_if_eq('<') {
+                    return Err(SyntaxError::new("invalid named reference"));
+                }
+                (Node::NamedBackreference(self.parse_group_name()?), true)
+            }
+            '1'..='9' => {
+                let start = self.pos;
+                let idx = self.parse_decimal_digits().unwrap() as usize;
+                if idx <= self.total_capture_count {
+                    (Node::Backreference(idx), true)
+                } else if self.unicode {
+                    return Err(SyntaxError::new("invalid escape"));
+                } else {
+                    self.pos = start;
+                    (Node::Char(self.parse_legacy_octal_escape()), true)
+                }
+            }
+            _ => match self.parse_class_escape(false)? {
+                ClassAtom::Char(ch) => (Node::Char(ch), true),
+                ClassAtom::Class(item) => (Node::Class(ClassSet::of(item)), true),
+            },
+        })
+    }
+
+    /// Annex B `LegacyOctalEscapeSequence`, or an identity escape for `\8` and
+    /// `\9`.
+    fn parse_legacy_octal_escape(&mut self) -> u32 {
+        let ch0 = self.next().unwrap();
+        let Some(d0) = ch0.to_digit(8) else {
+            return u32::from(ch0);
+        };
+        let mut value = d0;
+        let max_len = if d0 <= 3 { 3 } else { 2 };
+        for _ in 1..max_len {
+            match self.peek().and_then(|ch| ch.to_digit(8)) {
+                Some(digit) => {
+                    self.pos += 1;
+                    value = value * 8 + digit;
+                }
+                None => break,
+            }
+        }
+        value
+    }
+
+    /// ```plain
+    /// CharacterClass::
+    ///     [ [lookahead ≠ ^] ClassContents ]
+    ///     [^ ClassContents ]
+    /// ```
+    ///
+    /// The opening `[` must already have been consumed.
+    fn parse_class(&mut self) -> Result<ClassSet> {
+        let negated = self.next_if_eq('^');
+        let mut items = Vec::new();
+        loop {
+            let lo = match self.peek() {
+                Some(']') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(_) => self.parse_class_atom()?,
+                None => return Err(SyntaxError::new("unterminated character class")),
+            };
+            if self.peek() == Some('-') && !matches!(self.peek_nth(1), Some(']') | None) {
+                self.pos += 1;
+                let hi = self.parse_class_atom()?;
+                match (lo, hi) {
+                    (ClassAtom::Char(lo), ClassAtom::Char(hi)) => {
+                        if lo > hi {
+                            return Err(SyntaxError::new("range out of order in character class"));
+                        }
+                        items.push(ClassItem::Range(lo, hi));
+                    }
+                    (_, _) if self.unicode => {
+                        return Err(SyntaxError::new("invalid character class range"));
+                    }
+                    (lo, hi) => {
+                        // Annex B: A range involving a class escape is treated as a union
+                        // including a literal `-`.
+                        items.push(lo.into_item());
+                        items.push(ClassItem::Range(u32::from('-'), u32::from('-')));
+                        items.push(hi.into_item());
+                    }
+                }
+            } else {
+                items.push(lo.into_item());
+            }
+        }
+        Ok(ClassSet { negated, items })
+    }
+
+    fn parse_class_atom(&mut self) -> Result<ClassAtom> {
+        let ch = self.next().unwrap();
+        if ch != '\\' {
+            return Ok(ClassAtom::Char(u32::from(ch)));
+        }
+        match self.peek() {
+            Some('b') => {
+                self.pos += 1;
+                Ok(ClassAtom::Char(0x08))
+            }
+            Some('-') if self.unicode => {
+                self.pos += 1;
+                Ok(ClassAtom::Char(u32::from('-')))
+            }
+            Some('c')
+                if !self.unicode
+                    && matches!(self.peek_nth(1), Some(ch) if ch.is_ascii_digit() || ch == '_') =>
+            {
+                // Annex B `ClassControlLetter`.
+                self.pos += 1;
+                let ch = self.next().unwrap();
+                Ok(ClassAtom::Char(u32::from(ch) % 32))
+            }
+            Some('1'..='9') if !self.unicode => {
+                Ok(ClassAtom::Char(self.parse_legacy_octal_escape()))
+            }
+            _ => self.parse_class_escape(true),
+        }
+    }
+
+    /// ```plain
+    /// CharacterClassEscape::
+    ///     d D s S w W
+    ///     p{ UnicodePropertyValueExpression }
+    ///     P{ UnicodePropertyValueExpression }
+    /// CharacterEscape::
+    ///     ControlEscape
+    ///     c AsciiLetter
+    ///     0 [lookahead ∉ DecimalDigit]
+    ///     HexEscapeSequence
+    ///     RegExpUnicodeEscapeSequence
+    ///     IdentityEscape
+    /// ```
+    ///
+    /// The `\` must already have been consumed.
+    fn parse_class_escape(&mut self, in_class: bool) -> Result<ClassAtom> {
+        let Some(ch) = self.next() else {
+            return Err(SyntaxError::new("`\\` at end of pattern"));
+        };
+        Ok(match ch {
+            'd' => ClassAtom::Class(ClassItem::Digit(false)),
+            'D' => ClassAtom::Class(ClassItem::Digit(true)),
+            's' => ClassAtom::Class(ClassItem::Space(false)),
+            'S' => ClassAtom::Class(ClassItem::Space(true)),
+            'w' => ClassAtom::Class(ClassItem::Word(false)),
+            'W' => ClassAtom::Class(ClassItem::Word(true)),
+            'p' | 'P' if self.unicode => {
+                let prop = self.parse_unicode_property()?;
+                ClassAtom::Class(ClassItem::Property(prop, ch == 'P'))
+            }
+            'f' => ClassAtom::Char(0x0C),
+            'n' => ClassAtom::Char(0x0A),
+            'r' => ClassAtom::Char(0x0D),
+            't' => ClassAtom::Char(0x09),
+            'v' => ClassAtom::Char(0x0B),
+            'c' => match self.peek() {
+                Some(letter) if letter.is_ascii_alphabetic() => {
+                    self.pos += 1;
+                    ClassAtom::Char(u32::from(letter) % 32)
+                }
+                _ if self.unicode => return Err(SyntaxError::new("invalid unicode escape")),
+                _ => {
+                    // Annex B: `\c` not followed by a letter is a literal backslash
+                    // followed by `c`.
+                    self.pos -= 1;
+                    ClassAtom::Char(u32::from('\\'))
+                }
+            },
+            '0' if !self.peek().is_some_and(|ch| ch.is_ascii_digit()) => ClassAtom::Char(0),
+            '0' if self.unicode => return Err(SyntaxError::new("invalid decimal escape")),
+            '0' => {
+                self.pos -= 1;
+                ClassAtom::Char(self.parse_legacy_octal_escape())
+            }
+            'x' => match self.parse_hex_digits(2) {
+                Some(value) => ClassAtom::Char(value),
+                None if self.unicode => return Err(SyntaxError::new("invalid escape")),
+                None => ClassAtom::Char(u32::from('x')),
+            },
+            'u' => match self.parse_unicode_escape()? {
+                Some(value) => ClassAtom::Char(value),
+                None if self.unicode => return Err(SyntaxError::new("invalid unicode escape")),
+                None => ClassAtom::Char(u32::from('u')),
+            },
+            ch if self.unicode => {
+                if !is_syntax_char(ch) && ch != '/' {
+                    return Err(SyntaxError::new("invalid escape"));
+                }
+                ClassAtom::Char(u32::from(ch))
+            }
+            'k' if self.named_groups && !in_class => {
+                return Err(SyntaxError::new("invalid named reference"));
+            }
+            ch => ClassAtom::Char(u32::from(ch)),
+        })
+    }
+
+    fn parse_unicode_property(&mut self) -> Result<UnicodeProperty> {
+        if !self.next_if_eq('{') {
+            return Err(SyntaxError::new("invalid property name"));
+        }
+        let mut name = String::new();
+        loop {
+            match self.next() {
+                Some('}') => break,
+                Some(ch) if ch.is_ascii_alphanumeric() || matches!(ch, '_' | '=') => name.push(ch),
+                Some(_) | None => return Err(SyntaxError::new("invalid property name")),
+            }
+        }
+        let name = name
+            .strip_prefix("General_Category=")
+            .or_else(|| name.strip_prefix("gc="))
+            .unwrap_or(&name);
+        UnicodeProperty::from_name(name)
+            .ok_or_else(|| SyntaxError::new(format!("unsupported property name `{name}`")))
+    }
+
+    /// ```plain
+    /// RegExpUnicodeEscapeSequence::
+    ///     u HexLeadSurrogate \u HexTrailSurrogate
+    ///     u HexLeadSurrogate
+    ///     u HexTrailSurrogate
+    ///     u HexNonSurrogate
+    ///     u{ CodePoint }
+    /// ```
+    ///
+    /// The `u` must already have been consumed. Returns `None` without
+    /// consuming anything more if the escape is invalid.
+    fn parse_unicode_escape(&mut self) -> Result<Option<u32>> {
+        if self.unicode && self.next_if_eq('{') {
+            let start = self.pos;
+            let mut value: u32 = 0;
+            while let Some(digit) = self.peek().and_then(|ch| ch.to_digit(16)) {
+                self.pos += 1;
+                value = value.saturating_mul(16).saturating_add(digit);
+            }
+            if self.pos == start || value > 0x10_FFFF || !self.next_if_eq('}') {
+                return Err(SyntaxError::new("invalid unicode escape"));
+            }
+            return Ok(Some(value));
+        }
+        let Some(lead) = self.parse_hex_digits(4) else {
+            return Ok(None);
+        };
+        if self.unicode
+            && (0xD800..=0xDBFF).contains(&lead)
+            && self.peek() == Some('\\')
+            && self.peek_nth(1) == Some('u')
+        {
+            let start = self.pos;
+            self.pos += 2;
+            match self.parse_hex_digits(4) {
+                Some(trail @ 0xDC00..=0xDFFF) => {
+                    return Ok(Some(0x10000 + ((lead - 0xD800) << 10) + (trail - 0xDC00)));
+                }
+                Some(_) | None => self.pos = start,
+            }
+        }
+        Ok(Some(lead))
+    }
+
+    fn parse_hex_digits(&mut self, len: usize) -> Option<u32> {
+        let mut value = 0;
+        for offset in 0..len {
+            value = value * 16 + self.peek_nth(offset)?.to_digit(16)?;
+        }
+        self.pos += len;
+        Some(value)
+    }
+}
+
+enum ClassAtom {
+    Char(u32),
+    Class(ClassItem),
+}
+
+impl ClassAtom {
+    fn into_item(self) -> ClassItem {
+        match self {
+            Self::Char(ch) => ClassItem::Range(ch, ch),
+            Self::Class(item) => item,
+        }
+    }
+}
+
+fn is_syntax_char(ch: char) -> bool {
+    matches!(
+        ch,
+        '^' | '$' | '\\' | '.' | '*' | '+' | '?' | '(' | ')' | '[' | ']' | '{' | '}' | '|'
+    )
+}
@@ -0,0 +1,191 @@
+use super::engine::Interrupted;
+use super::RegExp;
+
+fn utf16(s: &str) -> Vec<u16> {
+    s.encode_utf16().collect()
+}
+
+/// Find the first match, searching forwards from the start of the input, and
+/// return the text of each group.
+fn exec(pattern: &str, flags: &str, input: &str) -> Option<Vec<Option<String>>> {
+    let regexp = RegExp::new(pattern, flags).unwrap();
+    let input = utf16(input);
+    let mut idx = 0;
+    let m = loop {
+        if let Some(m) = regexp.match_at(&input, idx, &mut || false).unwrap() {
+            break m;
+        }
+        if idx >= input.len() {
+            return None;
+        }
+        idx = regexp.advance_index(&input, idx);
+    };
+    Some(
+        (0..m.group_count())
+            .map(|idx| {
+                m.group(idx)
+                    .map(|range| String::from_utf16_lossy(&input[range]))
+            })
+            .collect(),
+    )
+}
+
+fn groups(expected: &[Option<&str>]) -> Option<Vec<Option<String>>> {
+    Some(expected.iter().map(|it| it.map(str::to_owned)).collect())
+}
+
+#[test]
+fn match_literals_and_classes() {
+    assert_eq!(exec("ab+c", "", "xxabbbcxx"), groups(&[Some("abbbc")]));
+    assert_eq!(exec("a.c", "", "a\nc abc"), groups(&[Some("abc")]));
+    assert_eq!(exec("a.c", "s", "a\nc"), groups(&[Some("a\nc")]));
+    assert_eq!(exec(r"\d+", "", "abc 123 def"), groups(&[Some("123")]));
+    assert_eq!(exec(r"[^a-c\s]+", "", "abc xyz"), groups(&[Some("xyz")]));
+    assert_eq!(exec(r"\bfoo\b", "", "foobar foo"), groups(&[Some("foo")]));
+    assert_eq!(exec("^b", "", "a\nb"), None);
+    assert_eq!(exec("^b", "m", "a\nb"), groups(&[Some("b")]));
+    assert_eq!(exec("ABC", "i", "xabcx"), groups(&[Some("abc")]));
+    assert_eq!(exec("[a-z]+", "i", "123XyZ"), groups(&[Some("XyZ")]));
+}
+
+#[test]
+fn match_quantifiers() {
+    assert_eq!(exec("a{2,3}", "", "aaaa"), groups(&[Some("aaa")]));
+    assert_eq!(exec("a{2,3}?", "", "aaaa"), groups(&[Some("aa")]));
+    assert_eq!(exec("a{2,}", "", "a aaaaa"), groups(&[Some("aaaaa")]));
+    assert_eq!(exec("<.*?>", "", "<a><b>"), groups(&[Some("<a>")]));
+    assert_eq!(exec("x{1}{", "", "x{"), groups(&[Some("x{")]));
+    // Empty iterations terminate the loop rather than repeating forever.
+    assert_eq!(
+        exec("(a*)*b", "", "aab"),
+        groups(&[Some("aab"), Some("aa")])
+    );
+    assert_eq!(exec("(?:a|)*", "", "aa"), groups(&[Some("aa")]));
+    // Captures are reset at the start of each iteration.
+    assert_eq!(
+        exec("(z)((a+)?(b+)?(c))*", "", "zaacbbbcac"),
+        groups(&[
+            Some("zaacbbbcac"),
+            Some("z"),
+            Some("ac"),
+            Some("a"),
+            None,
+            Some("c"),
+        ])
+    );
+}
+
+#[test]
+fn match_groups_and_backreferences() {
+    assert_eq!(
+        exec("(a|ab)(c|bcd)(d*)", "", "abcd"),
+        groups(&[Some("abcd"), Some("a"), Some("bcd"), Some("")])
+    );
+    assert_eq!(
+        exec(r"(\w)\1", "", "abccd"),
+        groups(&[Some("cc"), Some("c")])
+    );
+    assert_eq!(
+        exec(r"(?<q>['\x22]).*?\k<q>", "", r#"say "hi" 'there'"#),
+        groups(&[Some(r#""hi""#), Some("\"")])
+    );
+    // Forward references match the empty string.
+    assert_eq!(
+        exec(r"\k<a>(?<a>x)", "", "x"),
+        groups(&[Some("x"), Some("x")])
+    );
+    // Legacy octal escape, as there's no group 2.
+    assert_eq!(
+        exec(r"(a)\2", "", "a\u{2}"),
+        groups(&[Some("a\u{2}"), Some("a")])
+    );
+}
+
+#[test]
+fn match_lookarounds() {
+    assert_eq!(exec(r"\d+(?=%)", "", "10 20%"), groups(&[Some("20")]));
+    assert_eq!(exec(r"\d+(?!%|\d)", "", "10% 20"), groups(&[Some("20")]));
+    assert_eq!(exec(r"(?<=\$)\d+", "", "10 $20"), groups(&[Some("20")]));
+    assert_eq!(exec(r"(?<!\$)\b\d+", "", "$10 20"), groups(&[Some("20")]));
+    assert_eq!(
+        exec(r"(?<=(\d+)(\d+))$", "", "1053"),
+        groups(&[Some(""), Some("1"), Some("053")])
+    );
+    assert_eq!(
+        exec(r"(?<=\1(a))b", "", "aab"),
+        groups(&[Some("b"), Some("a")])
+    );
+}
+
+#[test]
+fn match_unicode() {
+    assert_eq!(exec("^.$", "", "😀"), None);
+    assert_eq!(exec("^.$", "u", "😀"), groups(&[Some("😀")]));
+    assert_eq!(exec(r"\u{1F600}", "u", "x😀"), groups(&[Some("😀")]));
+    assert_eq!(exec(r"😀", "u", "x😀"), groups(&[Some("😀")]));
+    assert_eq!(exec(r"\p{Lu}+", "u", "abcDEF"), groups(&[Some("DEF")]));
+}
+
+#[test]
+fn match_sticky() {
+    let regexp = RegExp::new("b", "y").unwrap();
+    let input = utf16("abc");
+    assert!(regexp.match_at(&input, 0, &mut || false).unwrap().is_none());
+    assert_eq!(
+        regexp
+            .match_at(&input, 1, &mut || false)
+            .unwrap()
+            .map(|m| m.range()),
+        Some(1..2)
+    );
+}
+
+#[test]
+fn match_long_input() {
+    let input = "ab".repeat(100_000);
+    assert_eq!(
+        exec("(?:ab)*$", "", &input).map(|m| m[0].as_ref().unwrap().len()),
+        Some(200_000)
+    );
+    assert_eq!(exec("^(a|b)*?$", "", &input).map(|m| m.len()), Some(2));
+}
+
+#[test]
+fn match_can_be_interrupted_while_backtracking() {
+    let regexp = RegExp::new("^(a|a)*$", "").unwrap();
+    let input = utf16(&format!("{}!", "a".repeat(30)));
+    let mut polls = 0;
+    let result = regexp.match_at(&input, 0, &mut || {
+        polls += 1;
+        polls == 3
+    });
+    assert_eq!(result.map(|m| m.is_some()), Err(Interrupted));
+    assert_eq!(polls, 3);
+}
+
+#[test]
+fn reject_invalid_patterns() {
+    for (pattern, flags) in [
+        ("(", ""),
+        (")", ""),
+        ("a**", ""),
+        ("?", ""),
+        ("[b-a]", ""),
+        ("a{2,1}", ""),
+        (r"\", ""),
+        ("(?<n>a)(?<n>b)", ""),
+        (r"\k<missing>(?<n>a)", ""),
+        ("(?<=a)+", ""),
+        ("{", "u"),
+        (r"\-", "u"),
+        (r"\1", "u"),
+        (r"\p{Unknown}", "u"),
+        ("a", "gg"),
+        ("a", "x"),
+    ] {
+        assert!(
+            RegExp::new(pattern, flags).is_err(),
+            "expected /{pattern}/{flags} to be rejected"
+        );
+    }
+}
@@ -1,11 +1,15 @@
+use super::regexp::{
+    coerce_to_regexp, find_utf16, regexp_data, regexp_match, regexp_match_all, regexp_replace,
+    regexp_search, regexp_split, string_replace,
+};
 use super::Builtin;
 use crate::interpreter::{
-    ErrorKind, Extensible, Heap, InitialisationError, Number, Object, ObjectData, Property,
-    Reference, Value,
+    ErrorKind, Extensible, Heap, IncompatibleReceiverError, IncompatibleRegExpError,
+    InitialisationError, Interpreter, JsString, Number, Object, ObjectData, OutOfRangeError,
+    Property, Reference, Value,
 };
 use crate::{builtin_fn, prop_key};
 use common_macros::hash_map;
-use std::borrow::Cow;
 use std::mem;
 
 pub struct StringProtoBuiltin {
@@ -19,16 +23,32 @@ impl Builtin for StringProtoBuiltin {
         heap: &mut Heap,
         (obj_proto, fn_proto): Self::InitArgs,
     ) -> Result<Self, InitialisationError> {
-        let length = GetLengthBuiltin::init(heap, fn_proto)?;
         let char_at = CharAtBuiltin::init(heap, fn_proto)?;
+        let char_code_at = CharCodeAtBuiltin::init(heap, fn_proto)?;
+        let code_point_at = CodePointAtBuiltin::init(heap, fn_proto)?;
+        let r#match = MatchBuiltin::init(heap, fn_proto)?;
+        let match_all = MatchAllBuiltin::init(heap, fn_proto)?;
+        let replace = ReplaceBuiltin::init(heap, fn_proto)?;
+        let replace_all = ReplaceAllBuiltin::init(heap, fn_proto)?;
+        let search = SearchBuiltin::init(heap, fn_proto)?;
         let split = SplitBuiltin::init(heap, fn_proto)?;
         let substring = SubstringBuiltin::init(heap, fn_proto)?;
+        let to_string = ToStringBuiltin::init(heap, fn_proto)?;
+        let value_of = ValueOfBuiltin::init(heap, fn_proto)?;
 
         let props = hash_map![
-            prop_key!("length") => Property::new_const_accessor(length.obj_ref()),
             prop_key!("charAt") => Property::new_user(char_at.as_value()),
+            prop_key!("charCodeAt") => Property::new_user(char_code_at.as_value()),
+            prop_key!("codePointAt") => Property::new_user(code_point_at.as_value()),
+            prop_key!("match") => Property::new_user(r#match.as_value()),
+            prop_key!("matchAll") => Property::new_user(match_all.as_value()),
+            prop_key!("replace") => Property::new_user(replace.as_value()),
+            prop_key!("replaceAll") => Property::new_user(replace_all.as_value()),
+            prop_key!("search") => Property::new_user(search.as_value()),
             prop_key!("split") => Property::new_user(split.as_value()),
             prop_key!("substring") => Property::new_user(substring.as_value()),
+            prop_key!("toString") => Property::new_user(to_string.as_value()),
+            prop_key!("valueOf") => Property::new_user(value_of.as_value()),
         ];
 
         let obj_ref = heap.allocate(Object::new(
@@ -45,61 +65,178 @@ impl Builtin for StringProtoBuiltin {
     }
 }
 
-builtin_fn!(pub StringCtorBuiltin, Extensible::Yes, (it, _receiver, args) => {
-    let arg = args.first();
-    let str = arg.map(|arg| it.coerce_to_string(*arg)).unwrap_or_default();
-    it.vm_mut()
-        .alloc_string(Box::from(str))
-        .map(Value::Object)
-        .map_err(ErrorKind::from)
+/// The `String` constructor, which also has static methods of its own.
+pub struct StringCtorBuiltin {
+    obj_ref: Reference,
+}
+
+impl StringCtorBuiltin {
+    #[allow(clippy::unnecessary_wraps)]
+    fn call(it: &mut Interpreter, _receiver: Value, args: &[Value]) -> Result<Value, ErrorKind> {
+        let str = match args.first() {
+            Some(arg) => it.to_string(arg.clone())?,
+            None => JsString::default(),
+        };
+        Ok(Value::String(str))
+    }
+}
+
+impl Builtin for StringCtorBuiltin {
+    type InitArgs = Reference;
+
+    fn init(heap: &mut Heap, fn_proto: Self::InitArgs) -> Result<Self, InitialisationError> {
+        let from_char_code = FromCharCodeBuiltin::init(heap, fn_proto)?;
+        let from_code_point = FromCodePointBuiltin::init(heap, fn_proto)?;
+
+        let props = hash_map![
+            prop_key!("fromCharCode") => Property::new_user(from_char_code.as_value()),
+            prop_key!("fromCodePoint") => Property::new_user(from_code_point.as_value()),
+        ];
+
+        let obj_ref = heap.allocate(Object::new_native(
+            Some(fn_proto),
+            props,
+            &Self::call,
+            Extensible::Yes,
+        ))?;
+        Ok(Self { obj_ref })
+    }
+
+    fn obj_ref(&self) -> Reference {
+        self.obj_ref
+    }
+}
+
+builtin_fn!(FromCharCodeBuiltin, Extensible::Yes, (it, _receiver, args) => {
+    let mut code_units = Vec::with_capacity(args.len());
+    for arg in args {
+        let n = it.to_number(arg.clone())?;
+        // ToUint16, i.e. the code unit is the number modulo 2^16.
+        #[allow(clippy::cast_possible_truncation)]
+        code_units.push(n.to_uint32() as u16);
+    }
+    Ok(Value::String(JsString::from(code_units)))
 });
 
-builtin_fn!(GetLengthBuiltin, Extensible::No, (it, receiver, _args) => {
-    let receiver = it.vm().heap().resolve(receiver);
-    let length = receiver.as_ref().string_data().unwrap().len();
-    let length = Number::try_from(length).unwrap_or_else(|_| {
-        // TODO
-        unreachable!()
-    });
-    Ok(Value::Number(length))
+builtin_fn!(FromCodePointBuiltin, Extensible::Yes, (it, _receiver, args) => {
+    let mut code_units = Vec::with_capacity(args.len());
+    for arg in args {
+        let n = it.to_number(arg.clone())?;
+        let code_point = n.as_f64();
+        if code_point.fract() != 0.0 || !(0.0..=f64::from(0x10FFFF)).contains(&code_point) {
+            return Err(ErrorKind::from(OutOfRangeError::new(format!(
+                "invalid code point: {n}"
+            ))));
+        }
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let code_point = JsString::from_code_point(code_point as u32);
+        code_units.extend_from_slice(code_point.as_utf16());
+    }
+    Ok(Value::String(JsString::from(code_units)))
 });
 
 builtin_fn!(CharAtBuiltin, Extensible::Yes, (it, receiver, args) => {
-    let arg = args.first().copied().unwrap_or_default();
-    let idx = {
-        let n = it.coerce_to_number(arg);
-        if !n.is_nan() {
-            n
-        } else {
-            Number::Int(0)
+    let receiver = it.to_string(receiver)?;
+    let idx = code_unit_index(it, &receiver, args.first().cloned().unwrap_or_default())?;
+    Ok(Value::String(match idx {
+        Some(idx) => receiver.substring(idx, idx + 1),
+        None => JsString::default(),
+    }))
+});
+
+builtin_fn!(CharCodeAtBuiltin, Extensible::Yes, (it, receiver, args) => {
+    let receiver = it.to_string(receiver)?;
+    let idx = code_unit_index(it, &receiver, args.first().cloned().unwrap_or_default())?;
+    Ok(Value::Number(match idx.and_then(|idx| receiver.code_unit_at(idx)) {
+        Some(code_unit) => Number::from(i64::from(code_unit)),
+        None => Number::NAN,
+    }))
+});
+
+builtin_fn!(CodePointAtBuiltin, Extensible::Yes, (it, receiver, args) => {
+    let receiver = it.to_string(receiver)?;
+    let idx = code_unit_index(it, &receiver, args.first().cloned().unwrap_or_default())?;
+    Ok(match idx.and_then(|idx| receiver.code_point_at(idx)) {
+        Some(code_point) => Value::Number(Number::from(i64::from(code_point))),
+        None => Value::Undefined,
+    })
+});
+
+builtin_fn!(MatchBuiltin, Extensible::Yes, (it, receiver, args) => {
+    let receiver = it.to_string(receiver)?;
+    let regexp = args.first().cloned().unwrap_or_default();
+    let (regexp_ref, regexp) = coerce_to_regexp(it, regexp, "")?;
+    regexp_match(it, regexp_ref, &regexp, &receiver)
+});
+
+builtin_fn!(MatchAllBuiltin, Extensible::Yes, (it, receiver, args) => {
+    let receiver = it.to_string(receiver)?;
+    let regexp = args.first().cloned().unwrap_or_default();
+    let (regexp_ref, regexp) = coerce_to_regexp(it, regexp, "g")?;
+    if !regexp.flags().global {
+        return Err(ErrorKind::from(IncompatibleRegExpError::new(
+            "matchAll() requires a global RegExp".to_owned(),
+        )));
+    }
+    regexp_match_all(it, regexp_ref, &regexp, &receiver)
+});
+
+builtin_fn!(ReplaceBuiltin, Extensible::Yes, (it, receiver, args) => {
+    let receiver = it.to_string(receiver)?;
+    let mut args = args.iter().cloned();
+    let pattern = args.next().unwrap_or_default();
+    let replacement = args.next().unwrap_or_default();
+    if let Value::Object(pattern_ref) = pattern
+        && let Some(regexp) = regexp_data(it, pattern_ref)
+    {
+        regexp_replace(it, pattern_ref, &regexp, &receiver, replacement)
+    } else {
+        let pattern = it.to_string(pattern)?;
+        string_replace(it, &receiver, &pattern, replacement, false)
+    }
+});
+
+builtin_fn!(ReplaceAllBuiltin, Extensible::Yes, (it, receiver, args) => {
+    let receiver = it.to_string(receiver)?;
+    let mut args = args.iter().cloned();
+    let pattern = args.next().unwrap_or_default();
+    let replacement = args.next().unwrap_or_default();
+    if let Value::Object(pattern_ref) = pattern
+        && let Some(regexp) = regexp_data(it, pattern_ref)
+    {
+        if !regexp.flags().global {
+            return Err(ErrorKind::from(IncompatibleRegExpError::new(
+                "replaceAll() requires a global RegExp".to_owned(),
+            )));
         }
-    };
-    let char_str = if idx >= Number::Int(0) {
-        let idx = usize::try_from(idx.as_i64()).unwrap();
-        it.coerce_to_string(Value::Object(receiver))
-            .chars()
-            .nth(idx)
-            .map(|ch| ch.to_string().into_boxed_str())
-            .unwrap_or_default()
+        regexp_replace(it, pattern_ref, &regexp, &receiver, replacement)
     } else {
-        Box::default()
-    };
-    it.vm_mut()
-        .alloc_string(char_str)
-        .map(Value::Object)
-        .map_err(ErrorKind::from)
+        let pattern = it.to_string(pattern)?;
+        string_replace(it, &receiver, &pattern, replacement, true)
+    }
+});
+
+builtin_fn!(SearchBuiltin, Extensible::Yes, (it, receiver, args) => {
+    let receiver = it.to_string(receiver)?;
+    let regexp = args.first().cloned().unwrap_or_default();
+    let (regexp_ref, regexp) = coerce_to_regexp(it, regexp, "")?;
+    regexp_search(it, regexp_ref, &regexp, &receiver)
 });
 
 builtin_fn!(SplitBuiltin, Extensible::Yes, (it, receiver, args) => {
-    let receiver = it.coerce_to_string(Value::Object(receiver));
+    let receiver = it.to_string(receiver)?;
     let mut args = args.iter();
-    let separator = if let Some(&arg) = args.next() {
-        it.coerce_to_string(arg)
+    let separator_regexp = match args.clone().next() {
+        Some(&Value::Object(separator_ref)) => regexp_data(it, separator_ref),
+        _ => None,
+    };
+    let separator = if let Some(arg) = args.next() {
+        it.to_string(arg.clone())?
     } else {
-        Cow::Borrowed(",")
+        JsString::from(",")
     };
-    let limit = if let Some(&arg) = args.next() {
-        it.coerce_to_number(arg)
+    let limit = if let Some(arg) = args.next() {
+        it.to_number(arg.clone())?
     } else {
         Number::from(-1)
     };
@@ -109,43 +246,89 @@ builtin_fn!(SplitBuiltin, Extensible::Yes, (it, receiver, args) => {
         usize::MAX
     };
 
+    if let Some(separator_regexp) = separator_regexp {
+        return regexp_split(it, &separator_regexp, &receiver, limit);
+    }
     let mut parts = Vec::new();
-    for part in receiver.split(separator.as_ref()).take(limit) {
-        let part = it.vm_mut().alloc_string(Box::from(part))?;
-        parts.push(Value::Object(part));
+    if separator.is_empty() {
+        // Split into individual code units, even if that splits a surrogate pair.
+        for idx in 0..receiver.len().min(limit) {
+            parts.push(Value::String(receiver.substring(idx, idx + 1)));
+        }
+    } else {
+        let mut start = 0;
+        while parts.len() < limit {
+            match find_utf16(receiver.as_utf16(), separator.as_utf16(), start) {
+                Some(end) => {
+                    parts.push(Value::String(receiver.substring(start, end)));
+                    start = end + separator.len();
+                }
+                None => {
+                    parts.push(Value::String(receiver.substring(start, receiver.len())));
+                    break;
+                }
+            }
+        }
     }
     it.vm_mut().alloc_array(parts).map(Value::Object).map_err(ErrorKind::from)
 });
 
 builtin_fn!(SubstringBuiltin, Extensible::Yes, (it, receiver, args) => {
     let mut args = args.iter();
-    let start_idx = args.next().copied().unwrap_or_default();
-    let end_idx = args.next().copied().unwrap_or_default();
-
-    let str = it.coerce_to_string(Value::Object(receiver));
-    let mut start_idx = match it.coerce_to_number(start_idx) {
-        n if n.is_nan() => 0,
-        n if n < Number::Int(0) => 0,
-        n if n > Number::Int(str.len() as i64) => str.len(),
-        n => usize::try_from(n.as_i64()).unwrap_or_else(|_| unreachable!()),
-    };
-    let mut end_idx = match it.coerce_to_number(end_idx) {
-        n if n.is_nan() => str.len(),
-        n if n < Number::Int(0) => 0,
-        n if n > Number::Int(str.len() as i64) => str.len(),
-        n => usize::try_from(n.as_i64()).unwrap_or_else(|_| unreachable!()),
+    let start_idx = args.next().cloned().unwrap_or_default();
+    let end_idx = args.next().cloned().unwrap_or_default();
+
+    let str = it.to_string(receiver)?;
+    let len = str.len();
+    let mut start_idx = clamp_index(it.to_number(start_idx)?, len);
+    let mut end_idx = match end_idx {
+        Value::Undefined => len,
+        end_idx => clamp_index(it.to_number(end_idx)?, len),
     };
     if start_idx > end_idx {
         mem::swap(&mut start_idx, &mut end_idx);
     }
-    let substr = str
-        .chars()
-        .skip(start_idx)
-        .take(end_idx - start_idx)
-        .collect::<String>()
-        .into_boxed_str();
-    it.vm_mut()
-        .alloc_string(substr)
-        .map(Value::Object)
-        .map_err(ErrorKind::from)
+    Ok(Value::String(str.substring(start_idx, end_idx)))
+});
+
+builtin_fn!(ToStringBuiltin, Extensible::Yes, (it, receiver, _args) => {
+    this_string_value(it, receiver).map(Value::String)
+});
+
+builtin_fn!(ValueOfBuiltin, Extensible::Yes, (it, receiver, _args) => {
+    this_string_value(it, receiver).map(Value::String)
 });
+
+/// Get the primitive value of either a string or a `String` wrapper object.
+fn this_string_value(it: &Interpreter, receiver: Value) -> Result<JsString, ErrorKind> {
+    match receiver {
+        Value::String(s) => Ok(s),
+        Value::Object(obj_ref)
+            if let Some(s) = it.vm().heap().resolve(obj_ref).as_ref().string_data() =>
+        {
+            Ok(s.clone())
+        }
+        receiver => Err(ErrorKind::from(IncompatibleReceiverError::new(format!(
+            "expected a string receiver but was {receiver:?}"
+        )))),
+    }
+}
+
+/// The code unit index given by `arg`, as done by e.g. `charAt()`, or `None`
+/// if it's out of bounds.
+fn code_unit_index(
+    it: &mut Interpreter,
+    s: &JsString,
+    arg: Value,
+) -> Result<Option<usize>, ErrorKind> {
+    // ToIntegerOrInfinity, where NaN becomes zero.
+    let idx = it.to_number(arg)?.as_i64();
+    Ok(usize::try_from(idx).ok().filter(|&idx| idx < s.len()))
+}
+
+/// Clamp an index to within the bounds of a string of length `len`, as done
+/// by e.g. `substring()`.
+fn clamp_index(n: Number, len: usize) -> usize {
+    // ToIntegerOrInfinity, where NaN becomes zero.
+    usize::try_from(n.as_i64()).map_or(0, |idx| idx.min(len))
+}
@@ -0,0 +1,41 @@
+use super::Builtin;
+use crate::ast::WellKnownSymbol;
+use crate::interpreter::{
+    Extensible, Heap, InitialisationError, Object, ObjectData, Property, PropertyKey, Reference,
+    Value,
+};
+use std::collections::HashMap;
+
+/// Only the well-known symbols are supported for now, so `Symbol` holds them
+/// but can't be called to create new symbols.
+pub struct SymbolBuiltin {
+    obj_ref: Reference,
+}
+
+impl Builtin for SymbolBuiltin {
+    type InitArgs = Reference;
+
+    fn init(heap: &mut Heap, obj_proto: Self::InitArgs) -> Result<Self, InitialisationError> {
+        let mut props = HashMap::with_capacity(WellKnownSymbol::all().len());
+        for &symbol in WellKnownSymbol::all() {
+            let symbol_ref = heap.allocate(Object::new_symbol(symbol))?;
+            let name = symbol.as_str().trim_start_matches("Symbol.");
+            props.insert(
+                PropertyKey::from(name),
+                Property::new_const(Value::Object(symbol_ref)),
+            );
+        }
+
+        let obj_ref = heap.allocate(Object::new(
+            Some(obj_proto),
+            props,
+            ObjectData::None,
+            Extensible::Yes,
+        ))?;
+        Ok(Self { obj_ref })
+    }
+
+    fn obj_ref(&self) -> Reference {
+        self.obj_ref
+    }
+}
@@ -1,8 +1,8 @@
 use crate::simple_enumeration;
 
-// TODO: Some variants should only be _contextually_ disallowed as identifiers,
-// i.e. in certain circumstances they should be allowed as identifiers.
-// Unfortunately, this may need to be handled by the parser.
+// Some variants are only _contextually_ disallowed as identifiers, which is
+// handled by the parser. See `Keyword::is_contextual()` and
+// `Keyword::is_reserved_in_strict_mode_only()`.
 simple_enumeration!(pub Keyword {
     As => "as",
     Async => "async",
@@ -55,3 +55,30 @@ simple_enumeration!(pub Keyword {
     With => "with",
     Yield => "yield",
 });
+
+impl Keyword {
+    /// Keywords which have special meaning in certain syntactic contexts, but
+    /// are otherwise allowed as identifiers, e.g. `let of = 1;`.
+    pub fn is_contextual(self) -> bool {
+        matches!(
+            self,
+            Self::As | Self::Async | Self::From | Self::Get | Self::Of | Self::Set | Self::Target
+        )
+    }
+
+    /// Keywords which are reserved in strict mode code, but are allowed as
+    /// identifiers in non-strict code.
+    pub fn is_reserved_in_strict_mode_only(self) -> bool {
+        matches!(
+            self,
+            Self::Implements
+                | Self::Interface
+                | Self::Package
+                | Self::Private
+                | Self::Protected
+                | Self::Public
+                | Self::Static
+                | Self::Yield
+        )
+    }
+}
@@ -1,6 +1,5 @@
 use std::fmt;
 
-// TODO: Support RegEx literals.
 #[derive(Clone, Debug, PartialEq)]
 pub enum Literal {
     Boolean(bool),
@@ -28,6 +27,13 @@ impl fmt::Display for Literal {
 pub enum NumericLiteral {
     BinInt(u64),
     OctInt(u64),
+    /// An octal integer without the `0o` prefix, e.g. `017`. Not allowed in
+    /// strict mode code.
+    LegacyOctInt(u64),
+    /// A decimal integer with a leading zero, which can't be read as a legacy
+    /// octal integer because it contains an 8 or 9, e.g. `019`. Not allowed in
+    /// strict mode code.
+    NonOctalDecInt(u64),
     DecInt(u64),
     HexInt(u64),
     Decimal(f64),
@@ -38,6 +44,8 @@ impl fmt::Display for NumericLiteral {
         match self {
             Self::BinInt(value) => write!(f, "{value:#b}"),
             Self::OctInt(value) => write!(f, "{value:#o}"),
+            Self::LegacyOctInt(value) => write!(f, "0{value:o}"),
+            Self::NonOctalDecInt(value) => write!(f, "0{value}"),
             Self::DecInt(value) => write!(f, "{value}"),
             Self::HexInt(value) => write!(f, "{value:#x}"),
             Self::Decimal(value) => write!(f, "{value}"),
@@ -25,7 +25,13 @@ console.assertEqual(numbers[500 + 1], undefined);
 let foo = ["foo", "bar", "baz"];
 console.assertEqual(foo.length, 3);
 foo.length = 123;
-console.assertEqual(foo.length, 3);
+console.assertEqual(foo.length, 123);
+console.assertEqual(foo[2], "baz");
+console.assertEqual(foo[3], undefined);
+foo.length = 1;
+console.assertEqual(foo.length, 1);
+console.assertEqual(foo[0], "foo");
+console.assertEqual(foo[1], undefined);
 
 let updated = [1, 2, 3];
 updated[0] = "foo";
@@ -39,3 +45,78 @@ console.assertEqual(updated[0], "foo");
 console.assertEqual(updated[1], 2);
 console.assertEqual(updated[2], "baz");
 console.assertEqual(updated.length, 3);
+
+let grown = [];
+for (let i = 0; i < 100; i += 1) {
+    grown[i] = i * 2;
+}
+console.assertEqual(grown.length, 100);
+console.assertEqual(grown[0], 0);
+console.assertEqual(grown[99], 198);
+console.assertEqual(grown["99"], 198);
+console.assertEqual(grown["099"], undefined);
+
+let holey = [1, 2];
+holey[5] = 6;
+console.assertEqual(holey.length, 6);
+console.assertEqual(holey[1], 2);
+console.assertEqual(holey[2], undefined);
+console.assertEqual(holey[5], 6);
+console.assertEqual(holey.join(), "1,2,,,,6");
+holey.length = 2;
+console.assertEqual(holey.length, 2);
+console.assertEqual(holey[5], undefined);
+holey[3] = 4;
+console.assertEqual(holey.length, 4);
+console.assertEqual(holey.join("-"), "1-2--4");
+
+let truncated = [1, 2, 3, 4];
+truncated.length = 2;
+truncated.push(5);
+console.assertEqual(truncated.length, 3);
+console.assertEqual(truncated.join(), "1,2,5");
+
+let named = [1];
+named.name = "named";
+named[1] = 2;
+console.assertEqual(named.length, 2);
+console.assertEqual(named.name, "named");
+
+let invalidLength;
+try {
+    named.length = -1;
+} catch (e) {
+    invalidLength = e.name;
+}
+console.assertEqual(invalidLength, "RangeError");
+console.assertEqual(named.length, 2);
+
+// The longest an array can be is 2^32 - 1, which leaves no room to push onto it.
+let longest = [];
+longest.length = 4294967295;
+console.assertEqual(longest.length, 4294967295);
+let tooLong;
+try {
+    longest.push("overflow");
+} catch (e) {
+    tooLong = e.name;
+}
+console.assertEqual(tooLong, "RangeError");
+console.assertEqual(longest.length, 4294967295);
+console.assertEqual(longest[4294967295], "overflow");
+longest.length = 0;
+console.assertEqual(longest.length, 0);
+
+let arrayLike = { length: 1 };
+arrayLike[0] = "a";
+arrayLike.push = [].push;
+arrayLike.join = [].join;
+console.assertEqual(arrayLike.push("b"), 2);
+console.assertEqual(arrayLike.length, 2);
+console.assertEqual(arrayLike.join(), "a,b");
+
+let cyclic = [1];
+cyclic.push(cyclic, [2, cyclic]);
+console.assertEqual(cyclic.join(), "1,,2,");
+console.assertEqual(String(cyclic), "1,,2,");
+console.assertEqual(cyclic.join(), "1,,2,");
@@ -0,0 +1,61 @@
+// A semicolon is inserted before a line terminator, a `}`, or the end of input.
+let a = 1
+let b = 2, c = 3
+console.assertEqual(a + b + c, 6)
+function sum(x, y) { return x + y }
+console.assertEqual(sum(a, b), 3)
+let obj = { value: 4 }
+console.assertEqual(obj.value, 4)
+
+// A semicolon isn't inserted if the next line can continue the statement.
+let d = a
+    + b
+console.assertEqual(d, 3)
+let e = sum
+(1, 2)
+console.assertEqual(e, 3)
+
+// A line terminator straight after `return` ends the statement.
+function returnsUndefined() {
+    return
+    42;
+}
+console.assertEqual(returnsUndefined(), undefined);
+
+// A postfix `++` or `--` must be on the same line as its operand.
+let f = 1;
+let g = 1;
+f
+++g
+console.assertEqual(f, 1);
+console.assertEqual(g, 2);
+f
+--g
+console.assertEqual(f, 1);
+console.assertEqual(g, 1);
+
+// Line terminators within multi-line comments count too.
+let h = 1 /*
+*/ let i = 2
+console.assertEqual(h + i, 3);
+
+// `break` and `continue` end at the line terminator.
+let count = 0;
+while (true) {
+    count++
+    if (count < 3) continue
+    break
+}
+console.assertEqual(count, 3)
+
+// The semicolon after a do-while statement is always optional.
+let j = 0;
+do j++; while (j < 5) console.assertEqual(j, 5)
+
+// Throw statements are terminated the same way.
+try {
+    throw "thrown"
+} catch (err) {
+    console.assertEqual(err, "thrown")
+}
+console.assert(true)
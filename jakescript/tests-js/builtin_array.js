@@ -31,7 +31,13 @@ console.assertEqual(Array(undefined).length, 1);
 let foo = Array("foo", "bar", "baz");
 console.assertEqual(foo.length, 3);
 foo.length = 123;
-console.assertEqual(foo.length, 3);
+console.assertEqual(foo.length, 123);
+console.assertEqual(foo[2], "baz");
+console.assertEqual(foo[3], undefined);
+foo.length = 1;
+console.assertEqual(foo.length, 1);
+console.assertEqual(foo[0], "foo");
+console.assertEqual(foo[1], undefined);
 
 let shove = [1, 2];
 console.assertEqual(shove.push(3), 3);
@@ -0,0 +1,80 @@
+console.assert(Error);
+let error = new Error("something went wrong");
+console.assertEqual(error.name, "Error");
+console.assertEqual(error.message, "something went wrong");
+console.assertEqual(error.cause, undefined);
+console.assertEqual(error.toString(), "Error: something went wrong");
+
+// Error constructors can also be called as plain functions.
+console.assertEqual(Error("no new").message, "no new");
+
+// Without a message, it's inherited from the prototype.
+let empty = new Error();
+console.assertEqual(empty.message, "");
+console.assertEqual(empty.toString(), "Error");
+
+let caused = new Error("outer", { cause: error });
+console.assertEqual(caused.cause, error);
+let causedByUndefined = new Error("outer", { cause: undefined });
+console.assertEqual(causedByUndefined.cause, undefined);
+
+let typeError = new TypeError("bad type");
+console.assertEqual(typeError.name, "TypeError");
+console.assertEqual(typeError.toString(), "TypeError: bad type");
+console.assertEqual(new RangeError("x").name, "RangeError");
+console.assertEqual(new ReferenceError("x").name, "ReferenceError");
+console.assertEqual(new SyntaxError("x").name, "SyntaxError");
+console.assertEqual(new EvalError("x").name, "EvalError");
+console.assertEqual(new URIError("x").name, "URIError");
+
+// Subclasses inherit `toString` from `Error.prototype`.
+console.assertEqual(new RangeError().toString(), "RangeError");
+
+let aggregate = new AggregateError([error, typeError], "several things went wrong");
+console.assertEqual(aggregate.name, "AggregateError");
+console.assertEqual(aggregate.message, "several things went wrong");
+console.assertEqual(aggregate.errors.length, 2);
+console.assertEqual(aggregate.errors[1], typeError);
+
+// `toString` uses whatever `name` and `message` the receiver has.
+let custom = { name: "CustomError", message: "custom message", toString: error.toString };
+console.assertEqual(custom.toString(), "CustomError: custom message");
+let unnamed = { name: "", message: "only a message", toString: error.toString };
+console.assertEqual(unnamed.toString(), "only a message");
+
+let caught;
+try {
+    throw new TypeError("thrown");
+} catch (e) {
+    caught = e;
+}
+console.assertEqual(caught.name, "TypeError");
+console.assertEqual(caught.message, "thrown");
+
+// Errors thrown by the interpreter itself use the same constructors.
+try {
+    undefinedFunction();
+} catch (e) {
+    console.assertEqual(e.toString(), "ReferenceError: `undefinedFunction` is not visible from the current scope");
+}
+
+// Constructors and their prototypes refer to each other.
+console.assertEqual(typeof Error.prototype, "object");
+console.assertEqual(Error.prototype.constructor, Error);
+console.assertEqual(TypeError.prototype.constructor, TypeError);
+console.assertEqual(AggregateError.prototype.constructor, AggregateError);
+console.assertEqual(caught.constructor, TypeError);
+console.assertEqual(caught.toString(), "TypeError: thrown");
+Error.prototype = {};
+console.assertEqual(Error.prototype.constructor, Error);
+console.assertEqual([].constructor, Array);
+console.assertEqual("".constructor, String);
+console.assertEqual((1).constructor, Number);
+console.assertEqual(/a/.constructor, RegExp);
+
+// The prototype is looked up when an error is constructed, whether or not
+// `new` is used.
+RangeError.prototype.name = "RenamedRangeError";
+console.assertEqual(new RangeError("a").toString(), "RenamedRangeError: a");
+console.assertEqual(RangeError("b").toString(), "RenamedRangeError: b");
+RangeError.prototype.name = "RangeError";
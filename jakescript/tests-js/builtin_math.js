@@ -81,6 +81,26 @@ console.assertEqual(Math.min(48, 93, "fish", 68, 33), NaN);
 console.assertEqual(Math.min(48, 93, Infinity, 68, 33), 33);
 console.assertEqual(Math.min(48, 93, -Infinity, 68, 33), -Infinity);
 
+// Every argument is converted, even after a NaN.
+let conversions = 0;
+let converted = {
+    valueOf: function () {
+        conversions++;
+        return 1;
+    },
+};
+console.assertEqual(Math.max(NaN, converted), NaN);
+console.assertEqual(Math.min(NaN, converted), NaN);
+console.assertEqual(conversions, 2);
+
+// -0 is less than +0.
+console.assertEqual(1 / Math.max(-0, 0), Infinity);
+console.assertEqual(1 / Math.max(0, -0), Infinity);
+console.assertEqual(1 / Math.max(-0, -0), -Infinity);
+console.assertEqual(1 / Math.min(-0, 0), -Infinity);
+console.assertEqual(1 / Math.min(0, -0), -Infinity);
+console.assertEqual(1 / Math.min(0, 0), Infinity);
+
 console.assert(Math.sqrt);
 console.assertEqual(Math.sqrt(1), 1);
 console.assertEqual(Math.sqrt(4), 2);
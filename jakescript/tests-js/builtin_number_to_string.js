@@ -0,0 +1,80 @@
+// Numbers are displayed with the fewest digits needed to round-trip them, and
+// switch to exponential notation outside of a fixed range.
+console.assertEqual(String(1 / 3), "0.3333333333333333");
+console.assertEqual(String(2 / 3), "0.6666666666666666");
+console.assertEqual(String(1 / 10 + 2 / 10), "0.30000000000000004");
+console.assertEqual(String(10 ** 20), "100000000000000000000");
+console.assertEqual(String(10 ** 21), "1e+21");
+console.assertEqual(String(123 * 10 ** 20), "1.23e+22");
+console.assertEqual(String(1 / 10 ** 6), "0.000001");
+console.assertEqual(String(1 / 10 ** 7), "1e-7");
+console.assertEqual(String(15 / 10 ** 8), "1.5e-7");
+console.assertEqual(String(-(1 / 4)), "-0.25");
+console.assertEqual(String(-0), "0");
+console.assertEqual(String(2 ** 53 * 4), "36028797018963970");
+console.assertEqual(String(-(2 ** 70)), "-1.1805916207174113e+21");
+console.assertEqual(String(2 ** -1074), "5e-324");
+console.assertEqual(String(NaN), "NaN");
+console.assertEqual(String(-Infinity), "-Infinity");
+console.assertEqual("" + 10 / 4, "2.5");
+
+// `toString()` accepts a radix.
+console.assertEqual((255).toString(), "255");
+console.assertEqual((255).toString(16), "ff");
+console.assertEqual((255).toString(2), "11111111");
+console.assertEqual((-255).toString(36), "-73");
+console.assertEqual((1 / 2).toString(2), "0.1");
+console.assertEqual((-5 / 4).toString(2), "-1.01");
+console.assertEqual((1 / 3).toString(3), "0.1");
+console.assertEqual((2 ** 60).toString(16), "1000000000000000");
+console.assertEqual((2 ** 80).toString(2).length, 81);
+console.assertEqual((1 / 10).toString(2), "0.0001100110011001100110011001100110011001100110011001101");
+console.assertEqual(NaN.toString(16), "NaN");
+console.assertEqual((-Infinity).toString(2), "-Infinity");
+console.assertEqual((8).toString(8), "10");
+console.assertEqual((255).valueOf(), 255);
+
+let caught;
+try {
+    (10).toString(37);
+} catch (e) {
+    caught = e;
+}
+console.assertEqual(caught.name, "RangeError");
+caught = undefined;
+try {
+    (10).toString(1);
+} catch (e) {
+    caught = e;
+}
+console.assertEqual(caught.name, "RangeError");
+
+// Strings are parsed as a StringNumericLiteral, which may be surrounded by
+// whitespace and line terminators.
+console.assertEqual(Number(""), 0);
+console.assertEqual(Number("  \n\t "), 0);
+console.assertEqual(Number(" 42\n"), 42);
+console.assertEqual(Number("-17"), -17);
+console.assertEqual(Number("+17"), 17);
+console.assertEqual(Number("1e3"), 1000);
+console.assertEqual(Number("1E-3"), 1 / 1000);
+console.assertEqual(Number("0.5"), 1 / 2);
+console.assertEqual(Number("5."), 5);
+console.assertEqual(Number(".5e1"), 5);
+console.assertEqual(Number("0x10"), 16);
+console.assertEqual(Number("0XfF"), 255);
+console.assertEqual(Number("0o17"), 15);
+console.assertEqual(Number("0b101"), 5);
+console.assertEqual(Number("Infinity"), Infinity);
+console.assertEqual(Number("-Infinity"), -Infinity);
+console.assertEqual(1 / Number("-0"), -Infinity);
+console.assertEqual(Number("9007199254740993"), 9007199254740992);
+console.assertEqual(Number("0x1fffffffffffff1"), 2 ** 57 - 16);
+console.assertEqual(Number("infinity"), NaN);
+console.assertEqual(Number("NaN"), NaN);
+console.assertEqual(Number("1_000"), NaN);
+console.assertEqual(Number("0x"), NaN);
+console.assertEqual(Number("-0x10"), NaN);
+console.assertEqual(Number("1e"), NaN);
+console.assertEqual(Number("."), NaN);
+console.assertEqual(Number("12 34"), NaN);
@@ -0,0 +1,142 @@
+console.assert(RegExp);
+console.assertEqual(String(/ab+c/gi), "/ab+c/gi");
+console.assertEqual(String(RegExp("ab+c", "ig")), "/ab+c/gi");
+console.assertEqual(String(RegExp(/xyz/m)), "/xyz/m");
+console.assertEqual(String(RegExp(/xyz/m, "y")), "/xyz/y");
+console.assertEqual(String(RegExp()), "/(?:)/");
+console.assertEqual(/a\/b/.source, "a\\/b");
+console.assertEqual(/a/dgimsuy.flags, "dgimsuy");
+console.assertEqual(/a/g.global, true);
+console.assertEqual(/a/.global, false);
+console.assertEqual(/a/i.ignoreCase, true);
+console.assertEqual(/a/m.multiline, true);
+console.assertEqual(/a/s.dotAll, true);
+console.assertEqual(/a/u.unicode, true);
+console.assertEqual(/a/y.sticky, true);
+console.assertEqual(/a/d.hasIndices, true);
+
+// A `/` is only the start of a regex where an expression is expected.
+let six = 6;
+console.assertEqual(six / 2 / 3, 1);
+console.assertEqual((six + 6) / 4, 3);
+console.assertEqual([six][0] / 2, 3);
+
+console.assertEqual(/\d+/.test("abc"), false);
+console.assertEqual(/\d+/.test("abc123"), true);
+
+let m = /(\d{4})-(\d{2})-(\d{2})/.exec("Released on 2012-04-23.");
+console.assertEqual(m.length, 4);
+console.assertEqual(m[0], "2012-04-23");
+console.assertEqual(m[1], "2012");
+console.assertEqual(m[3], "23");
+console.assertEqual(m.index, 12);
+console.assertEqual(m.input, "Released on 2012-04-23.");
+console.assertEqual(m.groups, undefined);
+console.assertEqual(/x/.exec("abc"), null);
+
+let named = /(?<year>\d{4})-(?<month>\d{2})/.exec("2012-04");
+console.assertEqual(named.groups.year, "2012");
+console.assertEqual(named.groups.month, "04");
+
+let optional = /a(b)?c/.exec("ac");
+console.assertEqual(optional[1], undefined);
+
+let indices = /b(c)/d.exec("abc").indices;
+console.assertEqual(indices[0][0], 1);
+console.assertEqual(indices[0][1], 3);
+console.assertEqual(indices[1][0], 2);
+
+// Global and sticky regexes track their position with `lastIndex`.
+let global = /o/g;
+console.assertEqual(global.lastIndex, 0);
+console.assertEqual(global.exec("foo").index, 1);
+console.assertEqual(global.lastIndex, 2);
+console.assertEqual(global.exec("foo").index, 2);
+console.assertEqual(global.lastIndex, 3);
+console.assertEqual(global.exec("foo"), null);
+console.assertEqual(global.lastIndex, 0);
+
+let sticky = /o/y;
+console.assertEqual(sticky.test("foo"), false);
+sticky.lastIndex = 1;
+console.assertEqual(sticky.test("foo"), true);
+console.assertEqual(sticky.lastIndex, 2);
+
+console.assertEqual(/(?<=\$)\d+/.exec("cost: $42")[0], "42");
+console.assertEqual(/(?<!\$)\b\d+/.exec("$4 2")[0], "2");
+console.assertEqual(/(\w)\1/.exec("hello")[0], "ll");
+console.assertEqual(/(?<c>\w)\k<c>/.exec("hello")[0], "ll");
+console.assertEqual(/^.$/.test("😀"), false);
+console.assertEqual(/^.$/u.test("😀"), true);
+console.assertEqual(/a.c/.test("a\nc"), false);
+console.assertEqual(/a.c/s.test("a\nc"), true);
+console.assertEqual(/^b/m.test("a\nb"), true);
+
+console.assertEqual("a1b22c333".match(/\d+/)[0], "1");
+console.assertEqual("a1b22c333".match(/\d+/).index, 1);
+let all = "a1b22c333".match(/\d+/g);
+console.assertEqual(all.length, 3);
+console.assertEqual(all[2], "333");
+console.assertEqual("abc".match(/\d/g), null);
+console.assertEqual("a.b".match(".")[0], "a");
+
+let iter = "t1e2s3t".matchAll(/[a-z](\d)/g);
+let first = iter.next();
+console.assertEqual(first.done, false);
+console.assertEqual(first.value[0], "t1");
+console.assertEqual(first.value[1], "1");
+console.assertEqual(first.value.index, 0);
+console.assertEqual(iter.next().value[0], "e2");
+console.assertEqual(iter.next().value.index, 4);
+console.assertEqual(iter.next().done, true);
+
+console.assertEqual("aaa".replace(/a/, "b"), "baa");
+console.assertEqual("aaa".replace(/a/g, "b"), "bbb");
+console.assertEqual("aaa".replace("a", "b"), "baa");
+console.assertEqual("John Smith".replace(/(\w+)\s(\w+)/, "$2, $1"), "Smith, John");
+console.assertEqual("2012-04".replace(/(?<y>\d+)-(?<m>\d+)/, "$<m>/$<y>"), "04/2012");
+console.assertEqual("abc".replace(/b/, "[$`|$&|$'|$$]"), "a[a|b|c|$]c");
+console.assertEqual("abc".replace(/b/, "$3"), "a$3c");
+console.assertEqual("x".replace(/(?:)/g, "-"), "-x-");
+console.assertEqual("a-b-c".replace(/-/g, function (match, offset) {
+  return "(" + offset + ")";
+}), "a(1)b(3)c");
+console.assertEqual("border-top".replace(/-(\w)/, function (match, letter) {
+  return letter + letter;
+}), "borderttop");
+// Every match is found before the replacement function is called, so it sees
+// `lastIndex` reset, and changing it doesn't affect the remaining matches.
+let replacer = /a/g;
+replacer.lastIndex = 2;
+let seenLastIndex = [];
+console.assertEqual("aaa".replace(replacer, function (match) {
+  seenLastIndex.push(replacer.lastIndex);
+  replacer.lastIndex = 5;
+  return "b";
+}), "bbb");
+console.assertEqual(seenLastIndex.join(), "0,5,5");
+console.assertEqual(replacer.lastIndex, 5);
+console.assertEqual("aaba".replace(/a/gy, "c"), "ccba");
+
+console.assertEqual("aaa".replaceAll("a", "b"), "bbb");
+console.assertEqual("aaa".replaceAll(/a/g, "b"), "bbb");
+console.assertEqual("abc".replaceAll("", "_"), "_a_b_c_");
+
+console.assertEqual("Hello world".search(/o/), 4);
+console.assertEqual("Hello world".search(/z/), -1);
+console.assertEqual("Hello world".search("w"), 6);
+
+let parts = "a1b22c".split(/\d+/);
+console.assertEqual(parts.length, 3);
+console.assertEqual(parts[1], "b");
+console.assertEqual(parts[2], "c");
+let withCaptures = "a1b2c".split(/(\d)/);
+console.assertEqual(withCaptures.length, 5);
+console.assertEqual(withCaptures[1], "1");
+console.assertEqual(withCaptures[4], "c");
+let chars = "abc".split(/(?:)/);
+console.assertEqual(chars.length, 3);
+console.assertEqual(chars[2], "c");
+console.assertEqual("a, b,c".split(/\s*,\s*/, 2).length, 2);
+console.assertEqual("".split(/x/).length, 1);
+console.assertEqual("".split(/(?:)/).length, 0);
@@ -0,0 +1,43 @@
+// Contextual keywords can be used as identifiers.
+let of = 1;
+let as = 2;
+let async = 3;
+let from = 4;
+let get = 5;
+let set = 6;
+let target = 7;
+console.assertEqual(of + as + async + from + get + set + target, 28);
+console.assertEqual(target / 7, 1);
+
+function subtract(of, target) {
+    return of - target;
+}
+console.assertEqual(subtract(3, 1), 2);
+
+let fn = function get(set) {
+    return set * 2;
+};
+console.assertEqual(fn(4), 8);
+
+// As can keywords which are only reserved in strict mode code.
+let static = "static";
+var yield = 10;
+console.assertEqual(static, "static");
+console.assertEqual(yield, 10);
+
+// Any reserved word can be used as a property name.
+let obj = {
+    get: 1,
+    set: 2,
+    default: 3,
+    if: 4,
+    new: 5,
+};
+console.assertEqual(obj.get, 1);
+console.assertEqual(obj.set, 2);
+console.assertEqual(obj.default, 3);
+console.assertEqual(obj.if, 4);
+console.assertEqual(obj.new, 5);
+obj.return = 6;
+console.assertEqual(obj.return, 6);
+console.assertEqual(obj.default + obj.if, 7);
@@ -0,0 +1,34 @@
+// Errors capture the stack at the point they're constructed.
+function inner() {
+    return new Error("from inner");
+}
+function outer() {
+    return inner();
+}
+let error = outer();
+let lines = error.stack.split("\n");
+console.assertEqual(lines[0], "Error: from inner");
+console.assert(/^    at inner \(/.test(lines[1]));
+console.assert(/^    at outer \(/.test(lines[2]));
+console.assert(/^    at /.test(lines[3]));
+console.assertEqual(lines.length, 4);
+
+// Errors thrown by the interpreter itself record where they were raised.
+function fails() {
+    undefinedFunction();
+}
+try {
+    fails();
+    console.assertNotReached();
+} catch (e) {
+    let lines = e.stack.split("\n");
+    console.assertEqual(lines[0], "ReferenceError: " + e.message);
+    console.assert(/^    at fails \(/.test(lines[1]));
+    console.assertEqual(lines.length, 3);
+}
+
+// Anonymous functions are named as such.
+let anonymous = function () {
+    return new TypeError("anonymous");
+};
+console.assert(/^    at <anonymous> \(/.test(anonymous().stack.split("\n")[1]));
@@ -0,0 +1,62 @@
+// Anything which is still reachable survives a garbage collection.
+let obj = { inner: { value: 42 } };
+let arr = [{ value: 1 }, { value: 2 }];
+function makeCounter() {
+    let counter = { count: 0 };
+    return function () {
+        counter.count += 1;
+        return counter.count;
+    };
+}
+let next = makeCounter();
+next();
+let iter = "a1b2".matchAll(/[a-z](\d)/g);
+let error;
+try {
+    null.foo;
+} catch (e) {
+    error = e;
+}
+
+// Make plenty of garbage.
+let garbage;
+for (let i = 0; i < 5000; i += 1) {
+    garbage = { index: i, nested: [i] };
+}
+gc();
+
+console.assertEqual(obj.inner.value, 42);
+console.assertEqual(arr[1].value, 2);
+console.assertEqual(next(), 2);
+console.assertEqual(garbage.nested[0], 4999);
+console.assertEqual(iter.next().value[1], "1");
+console.assertEqual(iter.next().value[1], "2");
+console.assertEqual(iter.next().done, true);
+console.assertEqual(error.name, "TypeError");
+
+// Built-in objects are roots, even those which aren't reachable from the global object.
+console.assertEqual("abc".charAt(1), "b");
+console.assertEqual([1, 2].join("-"), "1-2");
+console.assertEqual(/b/.test("abc"), true);
+console.assertEqual(new RangeError("oops").name, "RangeError");
+
+// Objects allocated after a collection may reuse the slots of freed objects.
+let fresh = [];
+for (let i = 0; i < 100; i += 1) {
+    fresh[i] = { index: i };
+}
+gc();
+console.assertEqual(fresh[99].index, 99);
+console.assertEqual(obj.inner.value, 42);
+
+// Scopes captured by closures survive, even after the function which created them returned.
+let getters = [];
+for (let i = 0; i < 3; i += 1) {
+    let captured = { value: i * 10 };
+    getters[i] = function () {
+        return captured.value;
+    };
+}
+gc();
+console.assertEqual(getters[0](), 0);
+console.assertEqual(getters[2](), 20);
@@ -0,0 +1,88 @@
+// The base of a member expression is only evaluated once, however the member is used.
+let calls = 0;
+let obj = {
+    count: 0,
+    items: [10, 20],
+    method: function () {
+        return this.count;
+    },
+};
+function getObj() {
+    calls++;
+    return obj;
+}
+
+console.assertEqual(getObj().method(), 0);
+console.assertEqual(calls, 1);
+
+calls = 0;
+getObj().count = 5;
+console.assertEqual(calls, 1);
+console.assertEqual(obj.count, 5);
+
+calls = 0;
+getObj().count += 2;
+console.assertEqual(calls, 1);
+console.assertEqual(obj.count, 7);
+
+calls = 0;
+console.assertEqual(getObj().count++, 7);
+console.assertEqual(calls, 1);
+console.assertEqual(obj.count, 8);
+
+calls = 0;
+console.assertEqual(--getObj().count, 7);
+console.assertEqual(calls, 1);
+
+calls = 0;
+getObj().items[1]++;
+console.assertEqual(calls, 1);
+console.assertEqual(obj.items[1], 21);
+
+// The computed key is also only evaluated once.
+let keyCalls = 0;
+function key() {
+    keyCalls++;
+    return "count";
+}
+obj[key()] *= 2;
+console.assertEqual(keyCalls, 1);
+console.assertEqual(obj.count, 14);
+console.assertEqual(obj["count"], 14);
+
+// Parentheses don't lose the receiver.
+console.assertEqual((obj.method)(), 14);
+
+// Calling the result of a call passes no receiver.
+function makeGetThis() {
+    return function () {
+        "use strict";
+        return this;
+    };
+}
+console.assertEqual(makeGetThis()(), undefined);
+
+// Accessing a property of null or undefined throws a TypeError.
+try {
+    null.x;
+    console.assertNotReached();
+} catch (e) {
+    console.assertEqual(e.name, "TypeError");
+}
+try {
+    let u;
+    u.x = 1;
+    console.assertNotReached();
+} catch (e) {
+    console.assertEqual(e.name, "TypeError");
+}
+
+// Compound assignment to an undeclared variable fails before evaluating the right-hand side.
+let rhsEvaluated = false;
+try {
+    undeclared += (rhsEvaluated = true);
+    console.assertNotReached();
+} catch (e) {
+    console.assertEqual(e.name, "ReferenceError");
+}
+console.assert(!rhsEvaluated);
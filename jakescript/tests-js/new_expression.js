@@ -0,0 +1,36 @@
+function Point(x, y) {
+    this.x = x;
+    this.y = y;
+}
+let p = new Point(1, 2);
+console.assertEqual(p.x, 1);
+console.assertEqual(p.y, 2);
+
+// The argument list is optional.
+function Empty() {}
+console.assertEqual(typeof new Empty, "object");
+
+// Instances inherit from the constructor's `prototype`.
+function Named(name) {
+    this.name = name;
+}
+Named.prototype = {
+    greet: function () {
+        return "hello " + this.name;
+    },
+};
+console.assertEqual(new Named("world").greet(), "hello world");
+
+// Returning an object from a constructor replaces the new instance.
+function Replaced() {
+    this.a = 1;
+    return { b: 2 };
+}
+let replaced = new Replaced();
+console.assertEqual(replaced.a, undefined);
+console.assertEqual(replaced.b, 2);
+
+// The constructor may be a member access, but the first argument list belongs to `new`.
+let ns = { Point: Point };
+console.assertEqual(new ns.Point(3, 4).x, 3);
+console.assertEqual(new ns["Point"](5, 6).y, 6);
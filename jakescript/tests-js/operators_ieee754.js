@@ -0,0 +1,107 @@
+// Integer arithmetic overflows into floating point rather than failing.
+const maxSafe = 9007199254740991;
+console.assertEqual(maxSafe + 2, 9007199254740992);
+console.assertEqual(maxSafe * maxSafe > maxSafe, true);
+console.assertEqual(9223372036854775807 + 1, 9223372036854775808);
+console.assertEqual(-(-9223372036854775807 - 1) > 0, true);
+console.assertEqual(2 ** 53 + 1, 2 ** 53);
+console.assertEqual(2 ** 1024, Infinity);
+console.assertEqual(-(2 ** 1024), -Infinity);
+
+// Division isn't truncated.
+const half = 1 / 2;
+console.assertEqual(7 / 2, 3 + half);
+console.assertEqual(-7 / 2, -3 - half);
+console.assertEqual(1 / 3 * 3, 1);
+
+// Negative zero is preserved, and is only observable by dividing by it.
+console.assertEqual(1 / -0, -Infinity);
+console.assertEqual(1 / (0 * -1), -Infinity);
+console.assertEqual(1 / (-1 * 0), -Infinity);
+console.assertEqual(1 / (0 / -5), -Infinity);
+console.assertEqual(1 / (-5 % 5), -Infinity);
+console.assertEqual(1 / (-0 + 0), Infinity);
+console.assertEqual(1 / (-0 - 0), -Infinity);
+console.assertEqual(1 / Math.trunc(-half), -Infinity);
+console.assertEqual(-0, 0);
+console.assertEqual(String(-0), "0");
+let zero = 0;
+console.assertEqual(1 / -zero, -Infinity);
+
+// Remainders take the sign of the dividend.
+console.assertEqual((11 / 2) % 2, 3 / 2);
+console.assertEqual(-(11 / 2) % 2, -3 / 2);
+console.assertEqual((11 / 2) % -2, 3 / 2);
+console.assertEqual(5 % (5 / 2), 0);
+console.assertEqual(1 / (-5 % (5 / 2)), -Infinity);
+console.assertEqual((5 / 2) % Infinity, 5 / 2);
+
+// Exponentiation edge cases differ from `powf()`.
+console.assertEqual(NaN ** 0, 1);
+console.assertEqual(NaN ** -0, 1);
+console.assertEqual(1 ** NaN, NaN);
+console.assertEqual(1 ** Infinity, NaN);
+console.assertEqual((-1) ** -Infinity, NaN);
+console.assertEqual(half ** Infinity, 0);
+console.assertEqual(2 ** -Infinity, 0);
+console.assertEqual(2 ** -1, half);
+console.assertEqual((-8) ** (1 / 3), NaN);
+console.assertEqual((-2) ** 3, -8);
+console.assertEqual(1 / ((-0) ** 3), -Infinity);
+console.assertEqual(3 ** 40, 12157665459056929000);
+
+// Bitwise operators convert their operands with ToInt32 or ToUint32.
+console.assertEqual(NaN | 0, 0);
+console.assertEqual(Infinity | 0, 0);
+console.assertEqual((37 / 10) | 0, 3);
+console.assertEqual(-(37 / 10) | 0, -3);
+console.assertEqual(2 ** 32 | 0, 0);
+console.assertEqual(2 ** 31 | 0, -2147483648);
+console.assertEqual((2 ** 32 + 5) & 7, 5);
+console.assertEqual(6 & 3, 2);
+console.assertEqual(6 ^ 3, 5);
+console.assertEqual(~5, -6);
+console.assertEqual(~2147483648, 2147483647);
+console.assertEqual(1 << 31, -2147483648);
+console.assertEqual(1 << 32, 1);
+console.assertEqual(1 << -1, -2147483648);
+console.assertEqual(-16 >> 2, -4);
+console.assertEqual(-16 >>> 28, 15);
+console.assertEqual(-1 >>> 0, 4294967295);
+console.assertEqual(4294967295 >> 0, -1);
+
+// Compound assignment and update operators use the same semantics.
+let n = maxSafe;
+n++;
+n++;
+console.assertEqual(n, 9007199254740992);
+let bits = 1;
+bits <<= 31;
+console.assertEqual(bits, -2147483648);
+bits >>>= 0;
+console.assertEqual(bits, 2147483648);
+
+// Integer arithmetic gives the same results whether or not it fits in 32 bits.
+let maxInt32 = 2147483647;
+let minInt32 = -maxInt32 - 1;
+console.assertEqual(maxInt32 + 1, 2147483648);
+console.assertEqual(minInt32 - 1, -2147483649);
+console.assertEqual(maxInt32 * 3, 6442450941);
+console.assertEqual(maxInt32 * maxInt32, 4611686014132420600);
+console.assertEqual(1 / (0 * -5), -Infinity);
+console.assertEqual(1 / (-4 % 2), -Infinity);
+console.assertEqual(-5 % 3, -2);
+console.assertEqual(7 % -5, 2);
+console.assertEqual((7 / 2) * 2, 7);
+let counter = maxInt32;
+counter++;
+console.assertEqual(counter, 2147483648);
+counter = minInt32;
+counter--;
+console.assertEqual(counter, -2147483649);
+console.assert(maxInt32 + 1 > maxInt32);
+console.assert(minInt32 < minInt32 + 1);
+console.assertEqual(-maxInt32 - 1 === minInt32, true);
+console.assertEqual(minInt32 / -1, 2147483648);
+console.assertEqual(1 / (0 / -3), -Infinity);
+console.assertEqual(-12 / 4, -3);
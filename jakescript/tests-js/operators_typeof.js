@@ -0,0 +1,17 @@
+console.assertEqual(typeof undefined, "undefined");
+console.assertEqual(typeof null, "object");
+console.assertEqual(typeof true, "boolean");
+console.assertEqual(typeof 42, "number");
+console.assertEqual(typeof "str", "string");
+console.assertEqual(typeof {}, "object");
+console.assertEqual(typeof [], "object");
+console.assertEqual(typeof function () {}, "function");
+console.assertEqual(typeof console.log, "function");
+console.assertEqual(typeof typeof 42, "string");
+
+// Referring to an undeclared variable isn't an error for `typeof`.
+console.assertEqual(typeof notDeclared, "undefined");
+
+let a = 1;
+console.assertEqual(typeof a, "number");
+console.assertEqual(typeof a + "!", "number!");
@@ -35,7 +35,7 @@ console.assertEqual(postfixDecrement, 19);
 
 console.assertEqual((~Infinity), -1);
 console.assertEqual((~(-Infinity)), -1);
-console.assertEqual(~NaN, NaN);
+console.assertEqual(~NaN, -1);
 console.assertEqual((+Infinity), Infinity);
 console.assertEqual(+NaN, NaN);
 console.assertEqual((-Infinity), 0 - Infinity);
@@ -129,7 +129,7 @@ function addNumericString(addend, accumulator) {
         }
         let digitSum = accumulatorDigit + addendDigit + carry;
         accumulator = setCharAt(accumulator, accumulatorIdx, digitSum % 10);
-        carry = digitSum / 10;
+        carry = Math.floor(digitSum / 10);
     }
     if (carry) {
         accumulator = String(carry) + accumulator;
@@ -0,0 +1,38 @@
+// `this` in a strict function called without `new` is `undefined`, rather than the global object.
+function strictWithoutNew() {
+    "use strict";
+    return this;
+}
+console.assertEqual(strictWithoutNew(), undefined);
+
+function sloppyWithoutNew() {
+    return this;
+}
+console.assertEqual(sloppyWithoutNew(), this);
+
+// Functions nested inside strict code inherit its strictness.
+let strictOuter = function () {
+    "use strict";
+    return function () {
+        return this;
+    };
+};
+let strictInner = strictOuter();
+console.assertEqual(strictInner(), undefined);
+
+// The directive only counts as part of the directive prologue.
+function notADirective() {
+    let a = 1;
+    "use strict";
+    return this;
+}
+console.assertEqual(notADirective(), this);
+
+// Strict functions can still be called on a receiver.
+let obj = {
+    method: function () {
+        "use strict";
+        return this;
+    },
+};
+console.assertEqual(obj.method(), obj);
@@ -0,0 +1,56 @@
+// Strings are primitive values, compared by their contents.
+console.assertEqual(typeof "", "string");
+console.assertEqual(typeof "abc", "string");
+console.assertEqual(typeof String(42), "string");
+console.assertEqual("abc" === "abc", true);
+console.assertEqual("ab" + "c" === "abc", true);
+console.assertEqual("abc" === "abd", false);
+console.assertEqual("" ? "truthy" : "falsy", "falsy");
+console.assertEqual("0" ? "truthy" : "falsy", "truthy");
+
+// Properties are looked up on `String.prototype`, as if the string had been boxed.
+console.assertEqual("abc".length, 3);
+console.assertEqual("abc"[0], "a");
+console.assertEqual("abc"[2], "c");
+console.assertEqual("abc"[3], undefined);
+console.assertEqual("abc"["1"], "b");
+console.assertEqual("abc"["01"], undefined);
+console.assertEqual("abc".toString(), "abc");
+console.assertEqual("abc".valueOf(), "abc");
+console.assertEqual("abc".charAt(1), "b");
+console.assertEqual("abc".missing, undefined);
+console.assertEqual("abc".split("b").length, 2);
+
+// Primitives can't have properties of their own, so assignments have no effect...
+let s = "abc";
+s.foo = 1;
+console.assertEqual(s.foo, undefined);
+s.length = 1;
+console.assertEqual(s.length, 3);
+s[0] = "x";
+console.assertEqual(s, "abc");
+
+// ...except in strict mode code, where they're an error.
+function assignInStrictMode() {
+    "use strict";
+    let s = "abc";
+    s.foo = 1;
+}
+let caught;
+try {
+    assignInStrictMode();
+} catch (e) {
+    caught = e;
+}
+console.assertEqual(caught.name, "TypeError");
+
+// `new String()` creates a wrapper object around the primitive string.
+let wrapper = new String("abc");
+console.assertEqual(typeof wrapper, "object");
+console.assertEqual(wrapper.length, 3);
+console.assertEqual(wrapper[1], "b");
+console.assertEqual(wrapper.valueOf(), "abc");
+console.assertEqual(wrapper === "abc", false);
+console.assertEqual(wrapper == "abc", true);
+console.assertEqual(wrapper + "def", "abcdef");
+console.assertEqual(new String("") ? "truthy" : "falsy", "truthy");
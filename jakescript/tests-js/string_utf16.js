@@ -0,0 +1,70 @@
+// Strings are sequences of UTF-16 code units, so characters outside of the BMP take up two.
+console.assertEqual("😀".length, 2);
+console.assertEqual("a😀b".length, 4);
+console.assertEqual("😀"[0].length, 1);
+console.assertEqual("😀"[0] === "😀"[1], false);
+console.assertEqual("😀"[0] + "😀"[1], "😀");
+console.assertEqual("a😀b".charAt(3), "b");
+console.assertEqual("a😀b".substring(1, 3), "😀");
+console.assertEqual("a😀b".substring(2).length, 2);
+console.assertEqual("😀".split("").length, 2);
+console.assertEqual("a😀b😀c".split("😀").length, 3);
+console.assertEqual(["😀", "😀"].join("").length, 4);
+
+console.assertEqual("a😀".charCodeAt(0), 97);
+console.assertEqual("a😀".charCodeAt(1), 0xD83D);
+console.assertEqual("a😀".charCodeAt(2), 0xDE00);
+console.assertEqual("a😀".charCodeAt(3), NaN);
+console.assertEqual("a😀".charCodeAt(-1), NaN);
+console.assertEqual("a😀".charCodeAt(), 97);
+
+console.assertEqual("a😀".codePointAt(0), 97);
+console.assertEqual("a😀".codePointAt(1), 0x1F600);
+console.assertEqual("a😀".codePointAt(2), 0xDE00);
+console.assertEqual("a😀".codePointAt(3), undefined);
+
+console.assertEqual(String.fromCharCode(), "");
+console.assertEqual(String.fromCharCode(72, 105), "Hi");
+console.assertEqual(String.fromCharCode(0xD83D, 0xDE00), "😀");
+console.assertEqual(String.fromCharCode(0x10048), "H");
+console.assertEqual(String.fromCodePoint(72, 0x1F600), "H😀");
+console.assertEqual(String.fromCodePoint(0x1F600).length, 2);
+
+// Unpaired surrogates are preserved, rather than being replaced.
+let lone = String.fromCharCode(0xD83D);
+console.assertEqual(lone.length, 1);
+console.assertEqual(lone.charCodeAt(0), 0xD83D);
+console.assertEqual(lone.codePointAt(0), 0xD83D);
+console.assertEqual(String.fromCodePoint(0xDE00).charCodeAt(0), 0xDE00);
+console.assertEqual(lone + String.fromCharCode(0xDE00), "😀");
+console.assertEqual("😀".substring(0, 1) === lone, true);
+
+function fromCodePointError(codePoint) {
+    try {
+        String.fromCodePoint(codePoint);
+    } catch (e) {
+        return e.name;
+    }
+    return undefined;
+}
+console.assertEqual(fromCodePointError(0x110000), "RangeError");
+console.assertEqual(fromCodePointError(-1), "RangeError");
+console.assertEqual(fromCodePointError(1 / 2), "RangeError");
+console.assertEqual(fromCodePointError(NaN), "RangeError");
+
+// Unpaired surrogates are distinct property keys, and aren't confused with U+FFFD.
+let keyed = {};
+keyed[String.fromCharCode(0xD800)] = 1;
+keyed[String.fromCharCode(0xD801)] = 2;
+keyed["�"] = 3;
+console.assertEqual(keyed[String.fromCharCode(0xD800)], 1);
+console.assertEqual(keyed[String.fromCharCode(0xD801)], 2);
+console.assertEqual(keyed["�"], 3);
+console.assertEqual(keyed[String.fromCharCode(0xDC00)], undefined);
+keyed[lone + String.fromCharCode(0xDE00)] = 4;
+console.assertEqual(keyed["😀"], 4);
+
+// Strings are converted to numbers by their code units.
+console.assertEqual(Number(" 42\n"), 42);
+console.assertEqual(Number(lone), NaN);
+console.assertEqual(Number("4" + lone), NaN);
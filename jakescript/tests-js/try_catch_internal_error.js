@@ -0,0 +1,116 @@
+// Errors raised by the interpreter itself can be caught like any other exception.
+let notAFunction = 42;
+try {
+    notAFunction();
+    console.assertNotReached();
+} catch (e) {
+    console.assertEqual(e.name, "TypeError");
+    console.assertEqual(e.message, "object or primitive is not callable");
+}
+
+try {
+    undefinedFunction();
+    console.assertNotReached();
+} catch (e) {
+    console.assertEqual(e.name, "ReferenceError");
+}
+
+try {
+    console.log(undefinedVariable);
+    console.assertNotReached();
+} catch (e) {
+    console.assertEqual(e.name, "ReferenceError");
+}
+
+const constant = 1;
+try {
+    constant = 2;
+    console.assertNotReached();
+} catch (e) {
+    console.assertEqual(e.name, "TypeError");
+}
+console.assertEqual(constant, 1);
+
+try {
+    RegExp("(");
+    console.assertNotReached();
+} catch (e) {
+    console.assertEqual(e.name, "SyntaxError");
+}
+
+// Errors propagate out of function calls until they're caught.
+function fails() {
+    undefinedFunction();
+    console.assertNotReached();
+}
+function callsFails() {
+    fails();
+    console.assertNotReached();
+}
+let caught;
+try {
+    callsFails();
+} catch (e) {
+    caught = e;
+}
+console.assertEqual(caught.name, "ReferenceError");
+
+// Errors thrown inside loops and nested blocks leave the scope chain intact.
+let outer = "outer";
+try {
+    for (let i = 0; i < 3; i++) {
+        let inner = "inner";
+        {
+            notAFunction();
+        }
+    }
+    console.assertNotReached();
+} catch (e) {
+    console.assertEqual(e.name, "TypeError");
+}
+console.assertEqual(outer, "outer");
+console.assertEqual(typeof inner, "undefined");
+
+// `finally` blocks still run.
+let finallyRan = false;
+try {
+    try {
+        notAFunction();
+    } finally {
+        finallyRan = true;
+    }
+} catch (e) {
+    console.assertEqual(e.name, "TypeError");
+}
+console.assert(finallyRan);
+
+// Unbounded recursion runs out of stack space, rather than crashing the host.
+function recurseForever() {
+    recurseForever();
+}
+try {
+    recurseForever();
+    console.assertNotReached();
+} catch (e) {
+    console.assertEqual(e.name, "RangeError");
+}
+
+// An exception thrown while evaluating the operand of `return` or `throw` isn't
+// replaced by it.
+function callsNotAFunction() {
+    notAFunction();
+}
+function returnsThrown() {
+    return callsNotAFunction();
+}
+try {
+    returnsThrown();
+    console.assertNotReached();
+} catch (e) {
+    console.assertEqual(e.name, "TypeError");
+}
+try {
+    throw callsNotAFunction();
+} catch (e) {
+    console.assertEqual(e.name, "TypeError");
+}
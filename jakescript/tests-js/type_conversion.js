@@ -0,0 +1,116 @@
+// Objects are converted to primitives by calling `valueOf` and `toString`.
+let valueOfCalls = 0;
+let counter = {
+    valueOf: function () {
+        valueOfCalls++;
+        return 42;
+    },
+    toString: function () {
+        return "counter";
+    },
+};
+console.assertEqual(counter + 1, 43);
+console.assertEqual(counter * 2, 84);
+console.assertEqual(+counter, 42);
+console.assertEqual("x" + counter, "x42");
+console.assertEqual(valueOfCalls, 4);
+
+let named = {
+    toString: function () {
+        return "named";
+    },
+};
+console.assertEqual(named + "!", "named!");
+console.assertEqual(String(named), "named");
+let byName = {};
+byName[named] = 1;
+console.assertEqual(byName.named, 1);
+
+// `Symbol.toPrimitive` takes precedence, and receives a hint.
+let hints = [];
+let exotic = {};
+exotic[Symbol.toPrimitive] = function (hint) {
+    hints.push(hint);
+    return hint === "number" ? 7 : "seven";
+};
+console.assertEqual(+exotic, 7);
+console.assertEqual(exotic + "", "seven");
+console.assertEqual(String(exotic), "seven");
+console.assertEqual(hints.join(), "number,default,string");
+console.assertEqual(typeof Symbol.toPrimitive, "symbol");
+
+// Arrays are joined when converted to strings.
+console.assertEqual(String([1, 2]), "1,2");
+console.assertEqual([1, [2, 3]] + "", "1,2,3");
+console.assertEqual([null, undefined, 1].join("-"), "--1");
+console.assertEqual(String([]), "");
+console.assertEqual(String({}), "[object Object]");
+
+// Loose equality converts either operand.
+console.assertEqual("1" == 1, true);
+console.assertEqual(1 == "1", true);
+console.assertEqual(" 1 " == 1, true);
+console.assertEqual("1" == true, true);
+console.assertEqual(null == undefined, true);
+console.assertEqual(null == 0, false);
+console.assertEqual(undefined == false, false);
+console.assertEqual({} == "[object Object]", true);
+console.assertEqual(counter == 42, true);
+console.assertEqual([1, 2] == "1,2", true);
+console.assertEqual("1" === 1, false);
+
+// Relational comparisons convert objects to numbers before comparing them.
+console.assertEqual(counter > 5, true);
+console.assertEqual(counter < 100, true);
+console.assertEqual(counter >= 42, true);
+console.assertEqual(counter <= 41, false);
+console.assertEqual([10] > 9, true);
+console.assertEqual("10" < "9", true);
+console.assertEqual("10" < 9, false);
+console.assertEqual(NaN <= NaN, false);
+console.assertEqual(undefined >= 0, false);
+console.assertEqual(null >= 0, true);
+
+// Strings are parsed as numeric literals.
+console.assertEqual(+"", 0);
+console.assertEqual(+"  12  ", 12);
+console.assertEqual(+"0x1f", 31);
+console.assertEqual(+"0b101", 5);
+console.assertEqual(+"-Infinity", -Infinity);
+console.assertEqual(+"1e3", 1000);
+console.assertEqual(+".5" * 2, 1);
+console.assertEqual(isNaN(+"inf"), true);
+console.assertEqual(isNaN(+"12px"), true);
+console.assertEqual(isNaN(+"-0x10"), true);
+
+// Exceptions thrown while converting propagate to the caller.
+let throwing = {
+    toString: function () {
+        throw new RangeError("no string for you");
+    },
+};
+let caught;
+try {
+    "" + throwing;
+} catch (e) {
+    caught = e;
+}
+console.assertEqual(caught.name, "RangeError");
+console.assertEqual(caught.message, "no string for you");
+
+// It's a TypeError if no method returns a primitive.
+let unconvertible = {
+    valueOf: function () {
+        return {};
+    },
+    toString: function () {
+        return {};
+    },
+};
+caught = undefined;
+try {
+    unconvertible + 1;
+} catch (e) {
+    caught = e;
+}
+console.assertEqual(caught.name, "TypeError");
@@ -0,0 +1,31 @@
+// Lexical declarations are scoped to their block.
+let a = 1;
+{
+    let a = 2;
+    console.assertEqual(a, 2);
+}
+console.assertEqual(a, 1);
+
+{
+    const b = 3;
+    console.assertEqual(b, 3);
+}
+{
+    const b = 4;
+    console.assertEqual(b, 4);
+}
+console.assertEqual(typeof b, "undefined");
+
+// Closures can refer to lexical declarations which appear later in the block, as long as they're
+// only called after the declaration has been initialised.
+function getLater() {
+    return later;
+}
+let later = "later";
+console.assertEqual(getLater(), "later");
+
+// Uninitialised `let` declarations are initialised to `undefined`.
+let c;
+console.assertEqual(c, undefined);
+c = 5;
+console.assertEqual(c, 5);
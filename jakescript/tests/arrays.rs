@@ -0,0 +1,58 @@
+use harness::Backend;
+use jakescript::interpreter::{Interpreter, Reference, Value, Vm};
+
+pub mod harness;
+
+fn exec_to_array(it: &mut Interpreter, source_code: &str, backend: Backend) -> Reference {
+    match harness::eval_in(it, source_code, backend) {
+        Value::Object(obj_ref) => obj_ref,
+        value => panic!("expected an array but was {value:?}"),
+    }
+}
+
+fn is_dense(it: &Interpreter, array_ref: Reference) -> bool {
+    let array = it.vm().heap().resolve(array_ref);
+    let dense = array.as_ref().array_data().unwrap().is_dense();
+    dense
+}
+
+#[test]
+fn arrays_stay_dense_until_they_have_a_hole() {
+    for backend in Backend::ALL {
+        let mut it = Interpreter::new(Vm::new().unwrap());
+        let array_ref = exec_to_array(
+            &mut it,
+            "let arr = [1, 2]; for (let i = 2; i < 100; i += 1) { arr[i] = i; } arr.push(3); arr;",
+            backend,
+        );
+        assert!(is_dense(&it, array_ref));
+
+        // Holes at the end don't need the sparse representation.
+        exec_to_array(&mut it, "arr.length = 200; arr.length = 50; arr;", backend);
+        assert!(is_dense(&it, array_ref));
+
+        exec_to_array(&mut it, "arr[60] = 60; arr;", backend);
+        assert!(!is_dense(&it, array_ref));
+        let array = it.vm().heap().resolve(array_ref);
+        assert_eq!(array.as_ref().array_data().unwrap().len(), 61);
+    }
+}
+
+#[test]
+fn indices_come_before_other_keys() {
+    for backend in Backend::ALL {
+        let mut it = Interpreter::new(Vm::new().unwrap());
+        let array_ref = exec_to_array(
+            &mut it,
+            r#"let arr = ["a"]; arr.name = "arr"; arr[3] = "d"; arr[1] = "b"; arr;"#,
+            backend,
+        );
+        let array = it.vm().heap().resolve(array_ref);
+        let keys: Vec<_> = array
+            .as_ref()
+            .own_property_keys()
+            .map(|key| key.to_string())
+            .collect();
+        assert_eq!(keys, ["0", "1", "3", "length", "name"]);
+    }
+}
@@ -1,6 +1,6 @@
 #![feature(assert_matches)]
 
-use harness::FailureReason;
+use harness::{Backend, FailureReason};
 use jakescript::interpreter::ErrorKind;
 use jakescript::token::{SourceLocation, SourcePosition};
 
@@ -37,18 +37,20 @@ fn assertion_fails_with_detail_msg() {
 }
 
 fn assert_fails(source_code: &str, expected_detail_msg: &str, fail_at: SourcePosition) {
-    let report = harness::exec_source_code(source_code);
-    let err = match report.failure_reason() {
-        Some(FailureReason::Runtime(err)) => err,
-        err => unreachable!("{err:#?}"),
-    };
-    if let ErrorKind::Assertion(err_source) = err.kind() {
-        assert_eq!(err_source.detail_msg(), expected_detail_msg);
-        assert_eq!(
-            err.source_location(),
-            &SourceLocation::new("untitled", fail_at)
-        );
-    } else {
-        unreachable!("{err:#?}",);
+    for backend in Backend::ALL {
+        let report = harness::exec_source_code(source_code, backend);
+        let err = match report.failure_reason() {
+            Some(FailureReason::Runtime(err)) => err,
+            err => unreachable!("{err:#?}"),
+        };
+        if let ErrorKind::Assertion(err_source) = err.kind() {
+            assert_eq!(err_source.detail_msg(), expected_detail_msg);
+            assert_eq!(
+                err.source_location(),
+                &SourceLocation::new("untitled", fail_at)
+            );
+        } else {
+            unreachable!("{err:#?}",);
+        }
     }
 }
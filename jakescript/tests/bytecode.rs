@@ -0,0 +1,192 @@
+#![feature(assert_matches)]
+
+use harness::Backend;
+use jakescript::interpreter::{ExecutionState, Interpreter, Limits, Vm};
+use std::assert_matches::assert_matches;
+
+pub mod harness;
+
+#[test]
+fn completion_value_is_the_last_expression_statement() {
+    let mut it = Interpreter::new(Vm::new().unwrap());
+    assert_eq!(
+        harness::eval_to_string_in(&mut it, "let a = 40; a + 2;", Backend::Bytecode),
+        "42"
+    );
+    assert_eq!(
+        harness::eval_to_string_in(&mut it, "a; if (true) {}", Backend::Bytecode),
+        "undefined"
+    );
+}
+
+#[test]
+fn functions_can_be_called_from_either_backend() {
+    let mut it = Interpreter::new(Vm::new().unwrap());
+    harness::eval_in(
+        &mut it,
+        "function double(x) { return x * 2; }",
+        Backend::Bytecode,
+    );
+    assert_eq!(
+        harness::eval_to_string_in(&mut it, "double(21)", Backend::TreeWalker),
+        "42"
+    );
+
+    harness::eval_in(
+        &mut it,
+        "function half(x) { return x / 2; }",
+        Backend::TreeWalker,
+    );
+    assert_eq!(
+        harness::eval_to_string_in(&mut it, "half(double(21))", Backend::Bytecode),
+        "21"
+    );
+}
+
+#[test]
+fn finally_blocks_run_when_jumping_out_of_try_blocks() {
+    let mut it = Interpreter::new(Vm::new().unwrap());
+    let result = harness::eval_to_string_in(
+        &mut it,
+        r#"
+let log = "";
+function f() {
+    for (let i = 0; i < 3; i += 1) {
+        try {
+            if (i === 0) continue;
+            if (i === 1) break;
+        } finally {
+            log += i;
+        }
+    }
+    try {
+        return "r";
+    } finally {
+        log += "f";
+    }
+}
+let result = f();
+log + result;
+"#,
+        Backend::Bytecode,
+    );
+    assert_eq!(result, "01fr");
+}
+
+#[test]
+fn running_out_of_fuel_stops_an_infinite_loop() {
+    let mut it = Interpreter::new(Vm::new().unwrap());
+    it.vm_mut().set_fuel(Some(1000));
+    harness::eval_in(&mut it, "while (true) {}", Backend::Bytecode);
+    assert_matches!(it.vm().execution_state(), ExecutionState::Interrupt);
+    assert_eq!(it.vm().fuel(), Some(0));
+}
+
+#[test]
+fn exceeding_the_call_depth_throws_a_catchable_range_error() {
+    let limits = Limits::default().with_max_call_depth(10);
+    let mut it = Interpreter::new(Vm::with_limits(limits).unwrap());
+    let result = harness::eval_to_string_in(
+        &mut it,
+        r#"
+function recurse() { recurse(); }
+let name;
+try {
+    recurse();
+} catch (e) {
+    name = e.name;
+}
+name;
+"#,
+        Backend::Bytecode,
+    );
+    assert_eq!(result, "RangeError");
+    assert_matches!(it.vm().execution_state(), ExecutionState::Advance);
+}
+
+#[test]
+fn garbage_is_collected_while_looping() {
+    let mut it = Interpreter::new(Vm::new().unwrap());
+    let source_code = r#"
+let obj;
+for (let i = 0; i < 10000; i += 1) {
+    obj = { index: i };
+}
+obj.index;
+"#;
+    assert_eq!(
+        harness::eval_to_string_in(&mut it, source_code, Backend::Bytecode),
+        "9999"
+    );
+    let stats = it.vm().heap().stats();
+    assert!(stats.collections() > 0);
+    assert!(stats.total_freed() > 5000);
+}
+
+#[test]
+fn closures_share_resolved_variables() {
+    let mut it = Interpreter::new(Vm::new().unwrap());
+    let source_code = r#"
+function counter(start) {
+    let count = start;
+    return {
+        next: function next(step) {
+            if (step === undefined) {
+                return next(1);
+            }
+            count += step;
+            return count;
+        },
+        peek: function () { return count; },
+    };
+}
+let c = counter(10);
+c.next(); c.next(5);
+c.peek();
+"#;
+    assert_eq!(
+        harness::eval_to_string_in(&mut it, source_code, Backend::Bytecode),
+        "16"
+    );
+}
+
+#[test]
+fn vars_are_hoisted_out_of_nested_blocks() {
+    let mut it = Interpreter::new(Vm::new().unwrap());
+    let source_code = r#"
+function f(x, x) {
+    let seen = typeof hoisted;
+    for (let i = 0; i < 2; i += 1) {
+        if (i === 1) {
+            var hoisted = x + i;
+        }
+    }
+    return seen + hoisted;
+}
+f(1, 2);
+"#;
+    assert_eq!(
+        harness::eval_to_string_in(&mut it, source_code, Backend::Bytecode),
+        "undefined3"
+    );
+}
+
+#[test]
+fn reading_a_local_before_its_declaration_throws() {
+    let mut it = Interpreter::new(Vm::new().unwrap());
+    let source_code = r#"
+function f() {
+    try {
+        return early;
+    } catch (e) {
+        return e.name;
+    }
+    let early = 1;
+}
+f();
+"#;
+    assert_eq!(
+        harness::eval_to_string_in(&mut it, source_code, Backend::Bytecode),
+        "ReferenceError"
+    );
+}
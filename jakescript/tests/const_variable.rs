@@ -1,8 +1,7 @@
 #![feature(assert_matches)]
 
-use harness::FailureReason;
-use jakescript::interpreter::{ErrorKind, Value};
-use jakescript::token::{SourceLocation, SourcePosition};
+use harness::Backend;
+use jakescript::interpreter::{ExecutionState, Value};
 use std::assert_matches::assert_matches;
 
 pub mod harness;
@@ -14,8 +13,10 @@ fn declare_const_variable_with_initialiser() {
 const a = 10;
 console.assert(a === 10);
 "##;
-    let report = harness::exec_source_code(source_code);
-    assert_matches!(report.success_value(), Some(Value::Undefined));
+    for backend in Backend::ALL {
+        let report = harness::exec_source_code(source_code, backend);
+        assert_matches!(report.success_value(), Some(Value::Undefined));
+    }
 }
 
 #[test]
@@ -23,19 +24,18 @@ fn set_initialised_const_variable() {
     harness::init();
     let source_code = r##"
 const a = 10;
-a = 20;
+try {
+    a = 20;
+    console.assertNotReached();
+} catch (e) {
+    console.assertEqual(e.name, "TypeError");
+    console.assertEqual(e.message, "`a` was declared as `const`");
+}
+console.assert(a === 10);
 "##;
-    let report = harness::exec_source_code(source_code);
-    let err = match report.failure_reason() {
-        Some(FailureReason::Runtime(err)) => err,
-        err => unreachable!("{err:#?}"),
-    };
-    if let ErrorKind::AssignToConstVariable(_) = err.kind() {
-        assert_eq!(
-            err.source_location(),
-            &SourceLocation::new("untitled", SourcePosition::at(2, 2))
-        );
-    } else {
-        unreachable!("{err:#?}");
+    for backend in Backend::ALL {
+        let report = harness::exec_source_code(source_code, backend);
+        assert_matches!(report.success_value(), Some(Value::Undefined));
+        assert_matches!(report.vm_state(), Some(ExecutionState::Advance));
     }
 }
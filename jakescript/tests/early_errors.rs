@@ -0,0 +1,161 @@
+#![feature(assert_matches)]
+
+use harness::{Backend, FailureReason};
+use jakescript::interpreter::Value;
+use jakescript::parser::{self, EarlyErrorKind};
+use std::assert_matches::assert_matches;
+
+pub mod harness;
+
+#[test]
+fn early_errors() {
+    harness::init();
+    for (source_code, expected) in [
+        (
+            "let a = 1; let a = 2;",
+            EarlyErrorKind::DuplicateDeclaration("a".into()),
+        ),
+        (
+            "const a = 1; var a = 2;",
+            EarlyErrorKind::DuplicateDeclaration("a".into()),
+        ),
+        (
+            "var a = 1; let a = 2;",
+            EarlyErrorKind::DuplicateDeclaration("a".into()),
+        ),
+        (
+            "{ let a = 1; { var a = 2; } }",
+            EarlyErrorKind::DuplicateDeclaration("a".into()),
+        ),
+        (
+            "function f() {} let f = 1;",
+            EarlyErrorKind::DuplicateDeclaration("f".into()),
+        ),
+        (
+            "function f(a) { let a = 1; }",
+            EarlyErrorKind::DuplicateDeclaration("a".into()),
+        ),
+        (
+            "{ function f() {} var f; }",
+            EarlyErrorKind::DuplicateDeclaration("f".into()),
+        ),
+        (
+            "{ var f; function f() {} }",
+            EarlyErrorKind::DuplicateDeclaration("f".into()),
+        ),
+        (
+            "{ function f() {} let f = 1; }",
+            EarlyErrorKind::DuplicateDeclaration("f".into()),
+        ),
+        (
+            "{ let f = 1; function f() {} }",
+            EarlyErrorKind::DuplicateDeclaration("f".into()),
+        ),
+        (
+            r#""use strict"; { function f() {} function f() {} }"#,
+            EarlyErrorKind::DuplicateDeclaration("f".into()),
+        ),
+        (
+            "try {} catch (e) { let e = 1; }",
+            EarlyErrorKind::DuplicateDeclaration("e".into()),
+        ),
+        (
+            "try {} catch (e) { function e() {} }",
+            EarlyErrorKind::DuplicateDeclaration("e".into()),
+        ),
+        (
+            "switch (1) { case 1: let a = 1; break; case 2: let a = 2; }",
+            EarlyErrorKind::DuplicateDeclaration("a".into()),
+        ),
+        (
+            "const a;",
+            EarlyErrorKind::ConstWithoutInitialiser("a".into()),
+        ),
+        ("break;", EarlyErrorKind::BreakOutsideLoopOrSwitch),
+        (
+            "while (true) { function f() { break; } }",
+            EarlyErrorKind::BreakOutsideLoopOrSwitch,
+        ),
+        ("continue;", EarlyErrorKind::ContinueOutsideLoop),
+        (
+            "switch (1) { case 1: continue; }",
+            EarlyErrorKind::ContinueOutsideLoop,
+        ),
+        (
+            "while (true) { break outer; }",
+            EarlyErrorKind::UndefinedLabel("outer".into()),
+        ),
+        ("return 1;", EarlyErrorKind::ReturnOutsideFunction),
+        ("1 = 2;", EarlyErrorKind::InvalidAssignmentTarget),
+        (
+            "let a = 1; a + 1 = 2;",
+            EarlyErrorKind::InvalidAssignmentTarget,
+        ),
+        ("++this;", EarlyErrorKind::InvalidAssignmentTarget),
+        (
+            "let o = { __proto__: null, __proto__: null };",
+            EarlyErrorKind::DuplicateProtoProperty,
+        ),
+    ] {
+        for backend in Backend::ALL {
+            let report = harness::exec_source_code(source_code, backend);
+            let err = match report.failure_reason() {
+                Some(FailureReason::Parse(err)) => err,
+                err => unreachable!("{source_code}: {err:#?}"),
+            };
+            assert_matches!(
+                err.kind(),
+                parser::ErrorKind::Early(actual, _) if actual == &expected,
+                "{source_code}"
+            );
+        }
+    }
+}
+
+#[test]
+fn valid_declarations_and_jumps() {
+    harness::init();
+    let source_code = r##"
+var a = 2;
+let b = 1;
+{
+    let b = 2;
+    function g(c) {
+        var c = 3;
+        return c;
+    }
+}
+try {
+    throw 1;
+} catch (e) {
+    var e = 2;
+    { let e = 3; }
+}
+for (let i = 0; i < 1; i++) {}
+for (let i = 0; i < 1; i++) {
+    continue;
+}
+let i = 0;
+switch (i) {
+    case 0:
+        break;
+}
+while (true) {
+    break
+}
+console.assert(a === 2);
+console.assert(b === 1);
+console.assert((a) = 3);
+"##;
+    for backend in Backend::ALL {
+        let report = harness::exec_source_code(source_code, backend);
+        assert_matches!(report.success_value(), Some(Value::Undefined));
+    }
+}
+
+#[test]
+fn function_declarations_in_blocks_can_be_redeclared_in_sloppy_mode() {
+    harness::init();
+    harness::parse("{ function f() {} function f() {} }");
+    harness::parse("function f() {} var f; function f() {}");
+}
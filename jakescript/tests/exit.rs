@@ -1,5 +1,6 @@
 #![feature(assert_matches)]
 
+use harness::Backend;
 use jakescript::interpreter::{ExecutionState, Value};
 use std::assert_matches::assert_matches;
 
@@ -12,9 +13,11 @@ fn exit_with_end_of_input() {
 let a = 1 + 2;
 console.assert(a === 3);
 "#;
-    let report = harness::exec_source_code(source_code);
-    assert_matches!(report.success_value(), Some(Value::Undefined));
-    assert_matches!(report.vm_state(), Some(ExecutionState::Advance));
+    for backend in Backend::ALL {
+        let report = harness::exec_source_code(source_code, backend);
+        assert_matches!(report.success_value(), Some(Value::Undefined));
+        assert_matches!(report.vm_state(), Some(ExecutionState::Advance));
+    }
 }
 
 #[test]
@@ -25,9 +28,11 @@ let a = 1 + 2;
 console.assert(a === 3);
 exit();
 "#;
-    let report = harness::exec_source_code(source_code);
-    assert_matches!(report.success_value(), Some(Value::Undefined));
-    assert_matches!(report.vm_state(), Some(ExecutionState::Exit));
+    for backend in Backend::ALL {
+        let report = harness::exec_source_code(source_code, backend);
+        assert_matches!(report.success_value(), Some(Value::Undefined));
+        assert_matches!(report.vm_state(), Some(ExecutionState::Exit));
+    }
 }
 
 #[test]
@@ -39,9 +44,11 @@ console.assert(a === 3);
 exit();
 console.assertNotReached();
 "#;
-    let report = harness::exec_source_code(source_code);
-    assert_matches!(report.success_value(), Some(Value::Undefined));
-    assert_matches!(report.vm_state(), Some(ExecutionState::Exit));
+    for backend in Backend::ALL {
+        let report = harness::exec_source_code(source_code, backend);
+        assert_matches!(report.success_value(), Some(Value::Undefined));
+        assert_matches!(report.vm_state(), Some(ExecutionState::Exit));
+    }
 }
 
 #[test]
@@ -60,7 +67,9 @@ while (i < 10) {
 }
 console.assertNotReached();
 "#;
-    let report = harness::exec_source_code(source_code);
-    assert_matches!(report.success_value(), Some(Value::Undefined));
-    assert_matches!(report.vm_state(), Some(ExecutionState::Exit));
+    for backend in Backend::ALL {
+        let report = harness::exec_source_code(source_code, backend);
+        assert_matches!(report.success_value(), Some(Value::Undefined));
+        assert_matches!(report.vm_state(), Some(ExecutionState::Exit));
+    }
 }
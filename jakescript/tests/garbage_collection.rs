@@ -0,0 +1,259 @@
+use harness::Backend;
+use jakescript::interpreter::{Interpreter, Value, Vm};
+use std::panic::{self, AssertUnwindSafe};
+
+pub mod harness;
+
+#[test]
+fn unreachable_objects_are_freed() {
+    for backend in Backend::ALL {
+        let mut it = Interpreter::new(Vm::new().unwrap());
+        it.vm_mut().collect_garbage();
+        let baseline = it.vm().heap().stats().live_objects();
+
+        harness::eval_in(
+            &mut it,
+            r#"
+let obj;
+for (let i = 0; i < 100; i += 1) {
+    obj = { index: i };
+}
+"#,
+            backend,
+        );
+        it.vm_mut().collect_garbage();
+        let stats = it.vm().heap().stats();
+        assert_eq!(stats.live_objects(), baseline + 1);
+        assert!(stats.total_freed() >= 99);
+    }
+}
+
+#[test]
+fn freed_slots_are_reused() {
+    for backend in Backend::ALL {
+        let mut it = Interpreter::new(Vm::new().unwrap());
+        let source_code = r#"
+for (let i = 0; i < 10000; i += 1) {
+    obj = { index: i };
+}
+"#;
+        harness::eval_in(&mut it, "let obj;", backend);
+        harness::eval_in(&mut it, source_code, backend);
+        it.vm_mut().collect_garbage();
+        let live_objects = it.vm().heap().stats().live_objects();
+
+        // Collections happen automatically as the loop runs, so the heap doesn't grow
+        // by anything like the number of objects allocated.
+        harness::eval_in(&mut it, source_code, backend);
+        let stats = it.vm().heap().stats();
+        assert!(stats.collections() > 1);
+        assert!(stats.live_objects() < live_objects + 5000);
+    }
+}
+
+#[test]
+fn gc_function_collects_at_the_next_safe_point() {
+    for backend in Backend::ALL {
+        let mut it = Interpreter::new(Vm::new().unwrap());
+        harness::eval_in(
+            &mut it,
+            r#"
+let obj = {};
+obj = null;
+gc();
+"#,
+            backend,
+        );
+        assert_eq!(it.vm().heap().stats().collections(), 0);
+        harness::eval_in(&mut it, "1;", backend);
+        assert_eq!(it.vm().heap().stats().collections(), 1);
+    }
+}
+
+#[test]
+fn garbage_is_collected_within_function_calls() {
+    for backend in Backend::ALL {
+        let mut it = Interpreter::new(Vm::new().unwrap());
+        harness::eval_in(
+            &mut it,
+            r#"
+function main() {
+    let obj;
+    for (let i = 0; i < 10000; i += 1) {
+        obj = { index: i };
+    }
+    return obj.index;
+}
+console.assertEqual(main(), 9999);
+"#,
+            backend,
+        );
+        let stats = it.vm().heap().stats();
+        assert!(stats.collections() > 1);
+        assert!(stats.live_objects() < 5000);
+    }
+}
+
+#[test]
+fn operands_survive_collections_within_function_calls() {
+    for backend in Backend::ALL {
+        let mut it = Interpreter::new(Vm::new().unwrap());
+        let value = harness::eval_to_string_in(
+            &mut it,
+            r#"
+function churn() {
+    for (let i = 0; i < 1000; i += 1) {
+        ({ index: i });
+    }
+    gc();
+    return 0;
+}
+function pair(a, b) {
+    churn();
+    return [a, b];
+}
+let result = [{ answer: 1 }, churn(), pair({ answer: 2 }, churn())];
+result[0].answer + "," + result[1] + "," + result[2][0].answer + "," + result[2][1];
+"#,
+            backend,
+        );
+        assert_eq!(value, "1,0,2,0");
+        assert!(it.vm().heap().stats().collections() >= 3);
+    }
+}
+
+#[test]
+fn handles_keep_values_alive() {
+    for backend in Backend::ALL {
+        let mut it = Interpreter::new(Vm::new().unwrap());
+        let value = harness::eval_in(&mut it, "({ answer: 42 });", backend);
+        let Value::Object(obj_ref) = value else {
+            panic!("expected an object but was {value:?}");
+        };
+        let handle = it.vm_mut().new_handle(value.clone());
+        it.vm_mut().collect_garbage();
+        assert_eq!(it.vm().handle_value(&handle), &value);
+        let obj = it.vm().heap().resolve(obj_ref);
+        assert!(obj.as_ref().own_property(&"answer".into()).is_some());
+        drop(obj);
+
+        let live_objects = it.vm().heap().stats().live_objects();
+        assert_eq!(it.vm_mut().release_handle(handle), value);
+        it.vm_mut().collect_garbage();
+        assert_eq!(it.vm().heap().stats().live_objects(), live_objects - 1);
+    }
+}
+
+#[test]
+fn references_to_freed_objects_are_caught_after_their_slots_are_reused() {
+    for backend in Backend::ALL {
+        let mut it = Interpreter::new(Vm::new().unwrap());
+        let value = harness::eval_in(&mut it, "({ answer: 42 });", backend);
+        let Value::Object(obj_ref) = value else {
+            panic!("expected an object but was {value:?}");
+        };
+        it.vm_mut().collect_garbage();
+        // Allocate more objects than could have been freed, so every free slot is
+        // reused.
+        harness::eval_in(
+            &mut it,
+            "let objs = []; for (let i = 0; i < 10000; i += 1) { objs.push({}); }",
+            backend,
+        );
+        let result = panic::catch_unwind(AssertUnwindSafe(|| it.vm().heap().resolve(obj_ref)));
+        assert!(result.is_err());
+    }
+}
+
+#[test]
+fn scopes_are_freed_once_nothing_refers_to_them() {
+    for backend in Backend::ALL {
+        let mut it = Interpreter::new(Vm::new().unwrap());
+        harness::eval_in(
+            &mut it,
+            r#"
+function add(a, b) {
+    let sum = a + b;
+    return sum;
+}
+let total = 0;
+for (let i = 0; i < 100000; i += 1) {
+    total = add(total, 1);
+}
+console.assertEqual(total, 100000);
+"#,
+            backend,
+        );
+        // Each iteration creates several scopes, but they're collected as the loop
+        // runs.
+        assert!(it.vm().stack().live_scopes() < 5000);
+        it.vm_mut().collect_garbage();
+        assert!(it.vm().stack().live_scopes() < 10);
+    }
+}
+
+#[test]
+fn scopes_are_freed_while_looping_within_a_function() {
+    for backend in Backend::ALL {
+        let mut it = Interpreter::new(Vm::new().unwrap());
+        harness::eval_in(
+            &mut it,
+            r#"
+function add(a, b) {
+    let sum = a + b;
+    return sum;
+}
+function main() {
+    let total = 0;
+    for (let i = 0; i < 100000; i += 1) {
+        total = add(total, 1);
+    }
+    return total;
+}
+console.assertEqual(main(), 100000);
+"#,
+            backend,
+        );
+        // There's no safe point between `main()` returning and the end of the
+        // script, so these were all collected while `main()` was running.
+        assert!(it.vm().stack().live_scopes() < 5000);
+    }
+}
+
+#[test]
+fn scopes_captured_by_closures_are_kept_alive() {
+    for backend in Backend::ALL {
+        let mut it = Interpreter::new(Vm::new().unwrap());
+        harness::eval_in(
+            &mut it,
+            r#"
+function makeCounter() {
+    let count = 0;
+    return function () {
+        count += 1;
+        return count;
+    };
+}
+let counters = [makeCounter(), makeCounter()];
+"#,
+            backend,
+        );
+        it.vm_mut().collect_garbage();
+        let live_scopes = it.vm().stack().live_scopes();
+        let value = harness::eval_in(
+            &mut it,
+            r#"
+counters[0]();
+counters[0]();
+counters[1]();
+counters[0]();
+"#,
+            backend,
+        );
+        assert_eq!(value, Value::Number(3.into()));
+
+        harness::eval_in(&mut it, "counters = null;", backend);
+        it.vm_mut().collect_garbage();
+        assert!(it.vm().stack().live_scopes() < live_scopes);
+    }
+}
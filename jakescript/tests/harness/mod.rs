@@ -1,5 +1,7 @@
 use ansi_term::{Color, Style};
 use fallible_iterator::FallibleIterator;
+use jakescript::ast::Script;
+use jakescript::bytecode;
 use jakescript::interpreter::{self, Eval, ExecutionState, Interpreter, Vm};
 use jakescript::lexer::Lexer;
 use jakescript::parser::{self, Parser};
@@ -17,7 +19,21 @@ pub fn init() {
     });
 }
 
-pub fn exec_source_file(source_path: &Path) -> TestCaseReport {
+/// Which of the interpreter's backends to evaluate a test case with.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Backend {
+    /// Walk the AST directly.
+    #[default]
+    TreeWalker,
+    /// Compile the AST to bytecode first, and run that instead.
+    Bytecode,
+}
+
+impl Backend {
+    pub const ALL: [Self; 2] = [Self::TreeWalker, Self::Bytecode];
+}
+
+pub fn exec_source_file(source_path: &Path, backend: Backend) -> TestCaseReport {
     let start_loc = SourceLocation::at_start_of(source_path);
     let source_name = source_path.display().to_string();
     let mut buf = match fs::File::open(source_path) {
@@ -27,20 +43,52 @@ pub fn exec_source_file(source_path: &Path) -> TestCaseReport {
     exec(
         source_name,
         Lexer::for_chars_fallible(fallible_iterator::convert(buf.chars()), start_loc),
+        backend,
     )
 }
 
-pub fn exec_source_code(source_code: &str) -> TestCaseReport {
+pub fn exec_source_code(source_code: &str, backend: Backend) -> TestCaseReport {
     let start_loc = SourceLocation::at_start_of("untitled");
     exec(
         "untitled".to_owned(),
         Lexer::for_str(source_code, start_loc),
+        backend,
     )
 }
 
+pub fn parse(source_code: &str) -> Script {
+    let lexer = Lexer::for_str(source_code, SourceLocation::at_start_of("untitled"));
+    Parser::for_lexer(lexer).execute().unwrap()
+}
+
+/// Evaluate `source_code` with an existing interpreter, e.g. one which has
+/// been set up by evaluating some other code first. Unlike
+/// [`exec_source_code()`], failing to parse or compile is a panic.
+pub fn try_eval_in(
+    it: &mut Interpreter,
+    source_code: &str,
+    backend: Backend,
+) -> interpreter::Result {
+    let ast = parse(source_code);
+    match backend {
+        Backend::TreeWalker => ast.eval(it),
+        Backend::Bytecode => bytecode::compile(&ast).unwrap().eval(it),
+    }
+}
+
+pub fn eval_in(it: &mut Interpreter, source_code: &str, backend: Backend) -> interpreter::Value {
+    try_eval_in(it, source_code, backend).unwrap()
+}
+
+pub fn eval_to_string_in(it: &mut Interpreter, source_code: &str, backend: Backend) -> String {
+    let value = eval_in(it, source_code, backend);
+    it.to_string(value).unwrap().to_string_lossy()
+}
+
 fn exec<I: FallibleIterator<Item = char, Error = io::Error>>(
     source_name: String,
     source: Lexer<I>,
+    backend: Backend,
 ) -> TestCaseReport {
     let parser = Parser::for_lexer(source);
     let mut interpreter = Interpreter::new(Vm::new().unwrap());
@@ -52,7 +100,14 @@ fn exec<I: FallibleIterator<Item = char, Error = io::Error>>(
         Err(err) => return TestCaseReport::fail(source_name, started_at.elapsed(), err.into()),
     };
 
-    let result = match ast.eval(&mut interpreter) {
+    let result = match backend {
+        Backend::TreeWalker => ast.eval(&mut interpreter),
+        Backend::Bytecode => match bytecode::compile(&ast) {
+            Ok(script) => script.eval(&mut interpreter),
+            Err(err) => return TestCaseReport::fail(source_name, started_at.elapsed(), err.into()),
+        },
+    };
+    let result = match result {
         Ok(result) => result,
         Err(err) => return TestCaseReport::fail(source_name, started_at.elapsed(), err.into()),
     };
@@ -68,18 +123,17 @@ pub enum TestCaseResult {
 }
 
 impl TestCaseResult {
+    /// An uncaught exception counts as a failure, even though evaluation
+    /// completed successfully.
     pub fn is_pass(&self) -> bool {
         match self {
-            Self::Pass(..) => true,
+            Self::Pass(_, vm_state) => !matches!(vm_state, ExecutionState::Exception(..)),
             Self::Fail(..) => false,
         }
     }
 
     pub fn is_fail(&self) -> bool {
-        match self {
-            Self::Pass(..) => false,
-            Self::Fail(..) => true,
-        }
+        !self.is_pass()
     }
 
     pub fn success_value(&self) -> Option<&interpreter::Value> {
@@ -219,6 +273,8 @@ impl fmt::Display for TestCaseReport {
         )?;
         if let Some(failure_reason) = self.failure_reason() {
             write!(f, ": {failure_reason}",)?;
+        } else if let Some(ExecutionState::Exception(ex)) = self.vm_state() {
+            write!(f, ": uncaught exception: {ex:?}",)?;
         }
         Ok(())
     }
@@ -353,6 +409,7 @@ impl FromIterator<TestCaseReport> for TestSuiteReport {
 pub enum FailureReason {
     Read(io::Error),
     Parse(parser::Error),
+    Compile(bytecode::Error),
     Runtime(interpreter::Error),
 }
 
@@ -361,6 +418,7 @@ impl fmt::Display for FailureReason {
         match self {
             Self::Read(source) => write!(f, "{source}"),
             Self::Parse(source) => write!(f, "{source}"),
+            Self::Compile(source) => write!(f, "{source}"),
             Self::Runtime(source) => write!(f, "{source}"),
         }
     }
@@ -371,6 +429,7 @@ impl std::error::Error for FailureReason {
         Some(match self {
             Self::Read(source) => source,
             Self::Parse(source) => source,
+            Self::Compile(source) => source,
             Self::Runtime(source) => source,
         })
     }
@@ -388,6 +447,12 @@ impl From<parser::Error> for FailureReason {
     }
 }
 
+impl From<bytecode::Error> for FailureReason {
+    fn from(source: bytecode::Error) -> Self {
+        Self::Compile(source)
+    }
+}
+
 impl From<interpreter::Error> for FailureReason {
     fn from(source: interpreter::Error) -> Self {
         Self::Runtime(source)
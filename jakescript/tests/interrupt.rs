@@ -0,0 +1,208 @@
+#![feature(assert_matches)]
+
+use harness::Backend;
+use jakescript::interpreter::{ExecutionState, Interpreter, Vm};
+use std::assert_matches::assert_matches;
+use std::thread;
+use std::time::Duration;
+
+pub mod harness;
+
+#[test]
+fn running_out_of_fuel_stops_an_infinite_loop() {
+    for backend in Backend::ALL {
+        let mut it = Interpreter::new(Vm::new().unwrap());
+        it.vm_mut().set_fuel(Some(1000));
+        harness::eval_in(&mut it, "while (true) {}", backend);
+        assert_matches!(it.vm().execution_state(), ExecutionState::Interrupt);
+        assert_eq!(it.vm().fuel(), Some(0));
+    }
+}
+
+#[test]
+fn fuel_is_consumed_by_function_calls() {
+    for backend in Backend::ALL {
+        let mut it = Interpreter::new(Vm::new().unwrap());
+        harness::eval_in(
+            &mut it,
+            "let calls = 0; function f() { calls += 1; }",
+            backend,
+        );
+        it.vm_mut().set_fuel(Some(2));
+        harness::eval_in(&mut it, "f(); f(); f();", backend);
+        assert_matches!(it.vm().execution_state(), ExecutionState::Interrupt);
+        it.vm_mut().reset_execution_state();
+        it.vm_mut().set_fuel(None);
+        assert_eq!(harness::eval_to_string_in(&mut it, "calls", backend), "2");
+    }
+}
+
+#[test]
+fn running_out_of_fuel_stops_a_catastrophically_backtracking_regexp() {
+    for backend in Backend::ALL {
+        let mut it = Interpreter::new(Vm::new().unwrap());
+        it.vm_mut().set_fuel(Some(1000));
+        harness::eval_in(
+            &mut it,
+            r#"/^(a|a)*$/.test("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa!");"#,
+            backend,
+        );
+        assert_matches!(it.vm().execution_state(), ExecutionState::Interrupt);
+        assert_eq!(it.vm().fuel(), Some(0));
+    }
+}
+
+#[test]
+fn running_out_of_fuel_stops_a_native_join_over_a_huge_array_like() {
+    for backend in Backend::ALL {
+        let mut it = Interpreter::new(Vm::new().unwrap());
+        it.vm_mut().set_fuel(Some(1000));
+        harness::eval_in(
+            &mut it,
+            r#"
+let huge = { length: 1000000000000000, join: Array.prototype.join };
+huge.join("");
+"#,
+            backend,
+        );
+        assert_matches!(it.vm().execution_state(), ExecutionState::Interrupt);
+        assert_eq!(it.vm().fuel(), Some(0));
+    }
+}
+
+#[test]
+fn interrupt_cant_be_caught_but_runs_finally_blocks() {
+    for backend in Backend::ALL {
+        let mut it = Interpreter::new(Vm::new().unwrap());
+        harness::eval_in(
+            &mut it,
+            "let caught = false; let finallyRan = false; function loopForever() { while (true) {} \
+             }",
+            backend,
+        );
+        it.vm_mut().set_fuel(Some(1000));
+        harness::eval_in(
+            &mut it,
+            r#"
+try {
+    try {
+        throw 1;
+    } finally {
+        loopForever();
+    }
+} catch (e) {
+    caught = true;
+} finally {
+    finallyRan = true;
+}
+"#,
+            backend,
+        );
+        assert_matches!(it.vm().execution_state(), ExecutionState::Interrupt);
+        assert!(!it.vm().stack().is_in_call());
+
+        // The VM can be used again once the execution state has been reset.
+        it.vm_mut().reset_execution_state();
+        it.vm_mut().set_fuel(None);
+        assert_eq!(
+            harness::eval_to_string_in(&mut it, "caught", backend),
+            "false"
+        );
+        assert_eq!(
+            harness::eval_to_string_in(&mut it, "finallyRan", backend),
+            "true"
+        );
+    }
+}
+
+#[test]
+fn finally_blocks_run_after_an_interrupt_are_interrupted_again() {
+    for backend in Backend::ALL {
+        let mut it = Interpreter::new(Vm::new().unwrap());
+        let interrupt = it.vm().interrupt_handle();
+        let interrupter = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            interrupt.interrupt();
+        });
+        harness::eval_in(
+            &mut it,
+            r#"
+let steps = [];
+function loopForever() { while (true) {} }
+for (;;) {
+    try {
+        loopForever();
+    } finally {
+        steps.push("finally");
+        try {
+            loopForever();
+        } finally {
+            steps.push("nested finally");
+            break;
+        }
+        steps.push("unreachable");
+    }
+}
+steps.push("unreachable");
+"#,
+            backend,
+        );
+        interrupter.join().unwrap();
+        assert_matches!(it.vm().execution_state(), ExecutionState::Interrupt);
+        assert!(!it.vm().stack().is_in_call());
+
+        it.vm_mut().reset_execution_state();
+        assert_eq!(
+            harness::eval_to_string_in(&mut it, "steps.join()", backend),
+            "finally,nested finally"
+        );
+    }
+}
+
+#[test]
+fn interrupt_handle_stops_the_script_from_another_thread() {
+    for backend in Backend::ALL {
+        let mut it = Interpreter::new(Vm::new().unwrap());
+        let interrupt = it.vm().interrupt_handle();
+        let interrupter = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            interrupt.interrupt();
+        });
+        harness::eval_in(&mut it, "let i = 0; while (true) { i += 1; }", backend);
+        interrupter.join().unwrap();
+        assert_matches!(it.vm().execution_state(), ExecutionState::Interrupt);
+        assert!(!it.vm().interrupt_handle().is_interrupted());
+
+        it.vm_mut().reset_execution_state();
+        assert_eq!(harness::eval_to_string_in(&mut it, "1 + 1", backend), "2");
+    }
+}
+
+#[test]
+fn interrupt_handle_stops_a_native_join_over_a_huge_array() {
+    for backend in Backend::ALL {
+        let mut it = Interpreter::new(Vm::new().unwrap());
+        let interrupt = it.vm().interrupt_handle();
+        let interrupter = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            interrupt.interrupt();
+        });
+        harness::eval_in(
+            &mut it,
+            r#"
+let huge = [];
+huge.length = 4294967295;
+huge.join("");
+"#,
+            backend,
+        );
+        interrupter.join().unwrap();
+        assert_matches!(it.vm().execution_state(), ExecutionState::Interrupt);
+
+        it.vm_mut().reset_execution_state();
+        assert_eq!(
+            harness::eval_to_string_in(&mut it, "huge.length", backend),
+            "4294967295"
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use harness::{TestCaseReport, TestSuiteReport};
+use harness::{Backend, TestCaseReport, TestSuiteReport, TestSuiteSummary};
 use std::process;
 use walkdir::{DirEntry, WalkDir};
 
@@ -6,13 +6,22 @@ pub mod harness;
 
 #[test]
 fn js_tests() -> impl process::Termination {
+    run_js_tests(Backend::TreeWalker)
+}
+
+#[test]
+fn js_tests_bytecode() -> impl process::Termination {
+    run_js_tests(Backend::Bytecode)
+}
+
+fn run_js_tests(backend: Backend) -> TestSuiteSummary {
     harness::init();
     WalkDir::new("tests-js")
         .into_iter()
         .map(Result::unwrap)
         .filter(is_normal_file)
         .filter(has_js_extension)
-        .map(|dir_entry| harness::exec_source_file(dir_entry.path()))
+        .map(|dir_entry| harness::exec_source_file(dir_entry.path(), backend))
         .inspect(TestCaseReport::print_report)
         .collect::<TestSuiteReport>()
         .into_summary()
@@ -0,0 +1,223 @@
+use harness::Backend;
+use jakescript::interpreter::{ErrorKind, Interpreter, Limits, Vm};
+
+pub mod harness;
+
+#[test]
+fn exceeding_the_call_depth_throws_a_range_error() {
+    for backend in Backend::ALL {
+        let limits = Limits::default().with_max_call_depth(10);
+        let mut it = Interpreter::new(Vm::with_limits(limits).unwrap());
+        harness::eval_in(
+            &mut it,
+            r#"
+let depth = 0;
+let name;
+function recurse() {
+    depth += 1;
+    recurse();
+}
+try {
+    recurse();
+} catch (e) {
+    name = e.name;
+}
+"#,
+            backend,
+        );
+        assert_eq!(
+            harness::eval_to_string_in(&mut it, "name", backend),
+            "RangeError"
+        );
+        assert_eq!(harness::eval_to_string_in(&mut it, "depth", backend), "10");
+
+        // The stack is unwound, so functions can be called again afterwards.
+        harness::eval_in(&mut it, "depth = 0;", backend);
+        harness::eval_in(&mut it, "try { recurse(); } catch (e) {}", backend);
+        assert_eq!(harness::eval_to_string_in(&mut it, "depth", backend), "10");
+    }
+}
+
+#[test]
+fn unbounded_recursion_doesnt_overflow_the_native_stack() {
+    for backend in Backend::ALL {
+        // Without a call depth limit, the native stack size limit has to stop the
+        // recursion before the thread (with the default 2 MiB of stack) overflows.
+        let limits = Limits::default().with_max_call_depth(usize::MAX);
+        let mut it = Interpreter::new(Vm::with_limits(limits).unwrap());
+        harness::eval_in(
+            &mut it,
+            r#"
+let name;
+function recurse(n) {
+    return [recurse(n + 1), { n: n }];
+}
+try {
+    recurse(0);
+} catch (e) {
+    name = e.name;
+}
+"#,
+            backend,
+        );
+        assert_eq!(
+            harness::eval_to_string_in(&mut it, "name", backend),
+            "RangeError"
+        );
+    }
+}
+
+#[test]
+fn recursion_through_native_functions_throws_a_range_error() {
+    for backend in Backend::ALL {
+        let mut it = Interpreter::new(Vm::new().unwrap());
+        harness::eval_in(
+            &mut it,
+            r#"
+let nested = [];
+for (let i = 0; i < 200000; i += 1) {
+    nested = [nested];
+}
+let name;
+try {
+    String(nested);
+} catch (e) {
+    name = e.name;
+}
+"#,
+            backend,
+        );
+        assert_eq!(
+            harness::eval_to_string_in(&mut it, "name", backend),
+            "RangeError"
+        );
+        // Arrays which are only a little nested can still be joined afterwards.
+        assert_eq!(
+            harness::eval_to_string_in(&mut it, "String([[1, [2]], 3])", backend),
+            "1,2,3"
+        );
+    }
+}
+
+#[test]
+fn exceeding_the_heap_limit_is_an_uncatchable_error() {
+    for backend in Backend::ALL {
+        let baseline = Vm::new().unwrap().heap().stats().live_objects();
+        let limits = Limits::default().with_max_heap_objects(baseline + 100);
+        let mut it = Interpreter::new(Vm::with_limits(limits).unwrap());
+        let result = harness::try_eval_in(
+            &mut it,
+            r#"
+let objs = [];
+try {
+    for (let i = 0; i < 1000; i += 1) {
+        objs.push({ index: i });
+    }
+} catch (e) {
+    console.assertNotReached();
+}
+"#,
+            backend,
+        );
+        let err = result.unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::OutOfHeapSpace(_)), "{err}");
+        assert!(err.kind().native_error_kind().is_none());
+    }
+}
+
+#[test]
+fn garbage_doesnt_count_towards_the_heap_limit() {
+    for backend in Backend::ALL {
+        let baseline = Vm::new().unwrap().heap().stats().live_objects();
+        let limits = Limits::default().with_max_heap_objects(baseline + 100);
+        let mut it = Interpreter::new(Vm::with_limits(limits).unwrap());
+        harness::eval_in(
+            &mut it,
+            r#"
+let kept = [];
+let sum = 0;
+for (let i = 0; i < 100000; i += 1) {
+    let obj = { index: i };
+    sum += obj.index;
+    if (i % 2000 === 0) {
+        kept.push(obj);
+    }
+}
+"#,
+            backend,
+        );
+        assert_eq!(
+            harness::eval_to_string_in(&mut it, "kept.length", backend),
+            "50"
+        );
+        assert_eq!(
+            harness::eval_to_string_in(&mut it, "sum", backend),
+            "4999950000"
+        );
+    }
+}
+
+#[test]
+fn exceeding_the_string_length_throws_a_range_error() {
+    for backend in Backend::ALL {
+        let limits = Limits::default().with_max_string_length(100);
+        let mut it = Interpreter::new(Vm::with_limits(limits).unwrap());
+        harness::eval_in(
+            &mut it,
+            r#"
+let s = "a";
+let name;
+try {
+    while (true) {
+        s = s + s;
+    }
+} catch (e) {
+    name = e.name;
+}
+"#,
+            backend,
+        );
+        assert_eq!(
+            harness::eval_to_string_in(&mut it, "name", backend),
+            "RangeError"
+        );
+        assert_eq!(
+            harness::eval_to_string_in(&mut it, "s.length", backend),
+            "64"
+        );
+
+        harness::eval_in(
+            &mut it,
+            r#"
+name = undefined;
+try {
+    [s, s].join("");
+} catch (e) {
+    name = e.name;
+}
+"#,
+            backend,
+        );
+        assert_eq!(
+            harness::eval_to_string_in(&mut it, "name", backend),
+            "RangeError"
+        );
+
+        harness::eval_in(
+            &mut it,
+            r#"
+name = undefined;
+try {
+    s.replaceAll("a", "aa");
+} catch (e) {
+    name = e.name;
+}
+"#,
+            backend,
+        );
+        assert_eq!(
+            harness::eval_to_string_in(&mut it, "name", backend),
+            "RangeError"
+        );
+    }
+}
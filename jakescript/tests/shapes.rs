@@ -0,0 +1,193 @@
+#![feature(assert_matches)]
+
+use harness::Backend;
+use jakescript::interpreter::{Eval, InlineCache, Interpreter, Reference, Value, Vm};
+use jakescript::{bytecode, prop_key};
+use std::assert_matches::assert_matches;
+use std::rc::Rc;
+
+pub mod harness;
+
+fn exec_to_object(it: &mut Interpreter, source_code: &str, backend: Backend) -> Reference {
+    match harness::eval_in(it, source_code, backend) {
+        Value::Object(obj_ref) => obj_ref,
+        value => panic!("expected an object but was {value:?}"),
+    }
+}
+
+fn own_property_keys(it: &Interpreter, obj_ref: Reference) -> Vec<String> {
+    let obj = it.vm().heap().resolve(obj_ref);
+    let keys = obj
+        .as_ref()
+        .own_property_keys()
+        .map(|key| key.to_string())
+        .collect();
+    keys
+}
+
+#[test]
+fn objects_with_the_same_keys_share_a_shape() {
+    for backend in Backend::ALL {
+        let mut it = Interpreter::new(Vm::new().unwrap());
+        let a = exec_to_object(&mut it, "let a = { x: 1, y: 2 }; a;", backend);
+        let b = exec_to_object(&mut it, "let b = {}; b.x = 3; b.y = 4; b;", backend);
+        let c = exec_to_object(&mut it, "let c = { y: 5, x: 6 }; c;", backend);
+        let heap = it.vm().heap();
+        let (a, b, c) = (heap.resolve(a), heap.resolve(b), heap.resolve(c));
+        assert!(a.as_ref().shape().is_shared());
+        assert!(Rc::ptr_eq(a.as_ref().shape(), b.as_ref().shape()));
+        assert!(!Rc::ptr_eq(a.as_ref().shape(), c.as_ref().shape()));
+    }
+}
+
+#[test]
+fn keys_are_in_insertion_order() {
+    for backend in Backend::ALL {
+        let mut it = Interpreter::new(Vm::new().unwrap());
+        let obj_ref = exec_to_object(
+            &mut it,
+            "let obj = { b: 1, a: 2, c: 3 }; obj.a = 4; obj;",
+            backend,
+        );
+        assert_eq!(own_property_keys(&it, obj_ref), ["b", "a", "c"]);
+
+        let mut obj = it.vm_mut().heap_mut().resolve_mut(obj_ref);
+        assert_matches!(obj.delete(&prop_key!("a")), Ok(true));
+        assert!(!obj.shape().is_shared());
+        drop(obj);
+        harness::eval_in(&mut it, "obj.a = 5; obj.d = 6;", backend);
+        assert_eq!(own_property_keys(&it, obj_ref), ["b", "c", "a", "d"]);
+        assert_eq!(
+            harness::eval_to_string_in(&mut it, "obj.b + obj.c + obj.a + obj.d;", backend),
+            "15"
+        );
+    }
+}
+
+#[test]
+fn objects_with_many_properties_get_a_shape_of_their_own() {
+    for backend in Backend::ALL {
+        let mut it = Interpreter::new(Vm::new().unwrap());
+        let obj_ref = exec_to_object(
+            &mut it,
+            "let obj = {}; for (let i = 0; i < 100; i += 1) { obj['key' + i] = i; } obj;",
+            backend,
+        );
+        let obj = it.vm().heap().resolve(obj_ref);
+        assert!(!obj.as_ref().shape().is_shared());
+        assert_eq!(obj.as_ref().shape().len(), 100);
+        drop(obj);
+        assert_eq!(
+            harness::eval_to_string_in(&mut it, "obj.key0 + obj.key99;", backend),
+            "99"
+        );
+    }
+}
+
+#[test]
+fn inline_caches_become_polymorphic_then_megamorphic() {
+    let mut it = Interpreter::new(Vm::new().unwrap());
+    let script = bytecode::compile(&harness::parse(
+        r#"
+function getX(obj) {
+    return obj.x;
+}
+let sum = getX({ x: 1 }) + getX({ x: 2 });
+"#,
+    ))
+    .unwrap();
+    script.eval(&mut it).unwrap();
+    let cache = || {
+        script
+            .code()
+            .function(0)
+            .code()
+            .property_cache(0)
+            .inline_cache()
+    };
+    assert_matches!(*cache().borrow(), InlineCache::Monomorphic(_));
+
+    harness::eval_in(&mut it, "sum += getX({ y: 0, x: 3 });", Backend::Bytecode);
+    assert_matches!(*cache().borrow(), InlineCache::Polymorphic(ref entries) if entries.len() == 2);
+
+    harness::eval_in(
+        &mut it,
+        r#"
+sum += getX({ a: 0, x: 4 });
+sum += getX({ b: 0, x: 5 });
+sum += getX({ c: 0, x: 6 });
+sum += getX({ x: 7 });
+"#,
+        Backend::Bytecode,
+    );
+    assert_matches!(*cache().borrow(), InlineCache::Megamorphic);
+    assert_eq!(
+        harness::eval_to_string_in(&mut it, "sum;", Backend::Bytecode),
+        "28"
+    );
+}
+
+#[test]
+fn inline_caches_notice_changes_to_the_prototype() {
+    for backend in Backend::ALL {
+        let mut it = Interpreter::new(Vm::new().unwrap());
+        let source_code = r#"
+function Point() {}
+Point.prototype = { name: "point" };
+function name(obj) {
+    return obj.name;
+}
+let p = new Point();
+let log = name(p);
+Point.prototype.name = "renamed";
+log += "," + name(p);
+Point.prototype.other = 0;
+log += "," + name(p);
+p.name = "own";
+log += "," + name(p) + "," + name(new Point());
+log;
+"#;
+        assert_eq!(
+            harness::eval_to_string_in(&mut it, source_code, backend),
+            "point,renamed,renamed,own,renamed"
+        );
+    }
+}
+
+#[test]
+fn cached_assignments_respect_read_only_properties() {
+    for backend in Backend::ALL {
+        let mut it = Interpreter::new(Vm::new().unwrap());
+        let source_code = r#"
+function setLastIndex(obj, value) {
+    obj.lastIndex = value;
+}
+let plain = { lastIndex: 0 };
+setLastIndex(plain, 1);
+setLastIndex(plain, 2);
+plain.lastIndex;
+"#;
+        assert_eq!(
+            harness::eval_to_string_in(&mut it, source_code, backend),
+            "2"
+        );
+
+        let source_code = r#"
+function setLength(s, value) {
+    "use strict";
+    s.length = value;
+}
+let result;
+try {
+    setLength(new String("abc"), 1);
+} catch (e) {
+    result = e.name;
+}
+result;
+"#;
+        assert_eq!(
+            harness::eval_to_string_in(&mut it, source_code, backend),
+            "TypeError"
+        );
+    }
+}
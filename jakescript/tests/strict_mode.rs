@@ -0,0 +1,167 @@
+#![feature(assert_matches)]
+
+use harness::{Backend, FailureReason};
+use jakescript::interpreter::{ExecutionState, Value};
+use jakescript::parser::{self, EarlyErrorKind};
+use std::assert_matches::assert_matches;
+
+pub mod harness;
+
+#[test]
+fn assign_to_undeclared_variable() {
+    harness::init();
+    let source_code = r##"
+"use strict";
+try {
+    a = 10;
+    console.assertNotReached();
+} catch (e) {
+    console.assertEqual(e.name, "ReferenceError");
+    console.assertEqual(e.message, "`a` is not visible from the current scope");
+}
+"##;
+    for backend in Backend::ALL {
+        let report = harness::exec_source_code(source_code, backend);
+        assert_matches!(report.success_value(), Some(Value::Undefined));
+        assert_matches!(report.vm_state(), Some(ExecutionState::Advance));
+    }
+}
+
+#[test]
+fn assign_to_undeclared_variable_sloppy() {
+    harness::init();
+    let source_code = r##"
+a = 10;
+console.assert(a === 10);
+"##;
+    for backend in Backend::ALL {
+        let report = harness::exec_source_code(source_code, backend);
+        assert_matches!(report.success_value(), Some(Value::Undefined));
+    }
+}
+
+#[test]
+fn assign_to_read_only_property() {
+    harness::init();
+    let source_code = r##"
+"use strict";
+try {
+    Math.PI = 3;
+    console.assertNotReached();
+} catch (e) {
+    console.assertEqual(e.name, "TypeError");
+    console.assertEqual(e.message, "`PI` is read-only");
+}
+"##;
+    for backend in Backend::ALL {
+        let report = harness::exec_source_code(source_code, backend);
+        assert_matches!(report.success_value(), Some(Value::Undefined));
+        assert_matches!(report.vm_state(), Some(ExecutionState::Advance));
+    }
+}
+
+#[test]
+fn assign_to_read_only_property_sloppy() {
+    harness::init();
+    let source_code = r##"
+Math.PI = 3;
+console.assert(Math.PI !== 3);
+"##;
+    for backend in Backend::ALL {
+        let report = harness::exec_source_code(source_code, backend);
+        assert_matches!(report.success_value(), Some(Value::Undefined));
+    }
+}
+
+#[test]
+fn early_errors() {
+    harness::init();
+    for (source_code, expected) in [
+        (
+            r#""use strict"; with ({}) {}"#,
+            EarlyErrorKind::StrictModeWith,
+        ),
+        (
+            r#""use strict"; let a = 010;"#,
+            EarlyErrorKind::StrictModeLegacyOctalLiteral,
+        ),
+        (
+            r#""use strict"; let a = 019;"#,
+            EarlyErrorKind::StrictModeNonOctalDecimalLiteral,
+        ),
+        (
+            r#"function f() { "use strict"; return 08; }"#,
+            EarlyErrorKind::StrictModeNonOctalDecimalLiteral,
+        ),
+        (
+            r#"function f(a, a) { "use strict"; }"#,
+            EarlyErrorKind::StrictModeDuplicateParameter("a".into()),
+        ),
+        (
+            r#""use strict"; eval = 1;"#,
+            EarlyErrorKind::StrictModeEvalOrArguments("eval".into()),
+        ),
+        (
+            r#""use strict"; function f(arguments) {}"#,
+            EarlyErrorKind::StrictModeEvalOrArguments("arguments".into()),
+        ),
+    ] {
+        for backend in Backend::ALL {
+            let report = harness::exec_source_code(source_code, backend);
+            let err = match report.failure_reason() {
+                Some(FailureReason::Parse(err)) => err,
+                err => unreachable!("{source_code}: {err:#?}"),
+            };
+            assert_matches!(
+                err.kind(),
+                parser::ErrorKind::Early(actual, _) if actual == &expected,
+                "{source_code}"
+            );
+        }
+    }
+}
+
+#[test]
+fn early_errors_sloppy() {
+    harness::init();
+    let source_code = r##"
+("use strict");
+function f(a, a) {
+    return a;
+}
+function g() {
+    ("use strict");
+    return 010;
+}
+console.assert(f(1, 2) === 2);
+console.assert(010 === 8);
+console.assert(g() === 8);
+console.assert(08 === 8);
+console.assert(019 === 19);
+console.assert(0778 === 778);
+"##;
+    for backend in Backend::ALL {
+        let report = harness::exec_source_code(source_code, backend);
+        assert_matches!(report.success_value(), Some(Value::Undefined));
+    }
+}
+
+#[test]
+fn strict_mode_reserved_word_as_identifier() {
+    harness::init();
+    let source_code = r##"
+"use strict";
+let static = 1;
+"##;
+    for backend in Backend::ALL {
+        let report = harness::exec_source_code(source_code, backend);
+        let err = match report.failure_reason() {
+            Some(FailureReason::Parse(err)) => err,
+            err => unreachable!("{err:#?}"),
+        };
+        assert_matches!(
+            err.kind(),
+            parser::ErrorKind::Parser(parser::Expected::Identifier(_), _)
+        );
+    }
+}
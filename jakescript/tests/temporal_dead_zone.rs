@@ -0,0 +1,127 @@
+#![feature(assert_matches)]
+
+use harness::Backend;
+use jakescript::interpreter::{ExecutionState, Value};
+use std::assert_matches::assert_matches;
+
+pub mod harness;
+
+#[test]
+fn read_before_initialisation() {
+    harness::init();
+    let source_code = r##"
+try {
+    console.log(a);
+    console.assertNotReached();
+} catch (e) {
+    console.assertEqual(e.name, "ReferenceError");
+    console.assertEqual(e.message, "`a` cannot be accessed before initialisation");
+}
+let a = 10;
+"##;
+    for backend in Backend::ALL {
+        let report = harness::exec_source_code(source_code, backend);
+        assert_matches!(report.success_value(), Some(Value::Undefined));
+        assert_matches!(report.vm_state(), Some(ExecutionState::Advance));
+    }
+}
+
+#[test]
+fn write_before_initialisation() {
+    harness::init();
+    let source_code = r##"
+try {
+    a = 20;
+    console.assertNotReached();
+} catch (e) {
+    console.assertEqual(e.name, "ReferenceError");
+}
+let a = 10;
+console.assert(a === 10);
+"##;
+    for backend in Backend::ALL {
+        let report = harness::exec_source_code(source_code, backend);
+        assert_matches!(report.success_value(), Some(Value::Undefined));
+        assert_matches!(report.vm_state(), Some(ExecutionState::Advance));
+    }
+}
+
+#[test]
+fn shadowed_read_before_initialisation() {
+    harness::init();
+    let source_code = r##"
+let a = 1;
+{
+    try {
+        a;
+        console.assertNotReached();
+    } catch (e) {
+        console.assertEqual(e.name, "ReferenceError");
+    }
+    const a = 2;
+}
+"##;
+    for backend in Backend::ALL {
+        let report = harness::exec_source_code(source_code, backend);
+        assert_matches!(report.success_value(), Some(Value::Undefined));
+        assert_matches!(report.vm_state(), Some(ExecutionState::Advance));
+    }
+}
+
+#[test]
+fn closure_called_before_initialisation() {
+    harness::init();
+    let source_code = r##"
+function f() {
+    return a;
+}
+try {
+    f();
+    console.assertNotReached();
+} catch (e) {
+    console.assertEqual(e.name, "ReferenceError");
+}
+let a = 10;
+"##;
+    for backend in Backend::ALL {
+        let report = harness::exec_source_code(source_code, backend);
+        assert_matches!(report.success_value(), Some(Value::Undefined));
+        assert_matches!(report.vm_state(), Some(ExecutionState::Advance));
+    }
+}
+
+#[test]
+fn typeof_before_initialisation() {
+    harness::init();
+    let source_code = r##"
+try {
+    typeof a;
+    console.assertNotReached();
+} catch (e) {
+    console.assertEqual(e.name, "ReferenceError");
+}
+let a = 10;
+"##;
+    for backend in Backend::ALL {
+        let report = harness::exec_source_code(source_code, backend);
+        assert_matches!(report.success_value(), Some(Value::Undefined));
+        assert_matches!(report.vm_state(), Some(ExecutionState::Advance));
+    }
+}
+
+#[test]
+fn closure_called_after_initialisation() {
+    harness::init();
+    let source_code = r##"
+function f() {
+    return a;
+}
+let a = 10;
+console.assert(f() === 10);
+console.assert(typeof undeclared === "undefined");
+"##;
+    for backend in Backend::ALL {
+        let report = harness::exec_source_code(source_code, backend);
+        assert_matches!(report.success_value(), Some(Value::Undefined));
+    }
+}
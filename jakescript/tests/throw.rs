@@ -1,5 +1,6 @@
 #![feature(assert_matches)]
 
+use harness::Backend;
 use jakescript::interpreter::{ExecutionState, Number, Value};
 use std::assert_matches::assert_matches;
 
@@ -13,12 +14,14 @@ let a = 1 + 2;
 console.assert(a === 3);
 throw 42;
 "#;
-    let report = harness::exec_source_code(source_code);
-    assert_matches!(report.success_value(), Some(Value::Undefined));
-    assert_matches!(
-        report.vm_state(),
-        Some(ExecutionState::Exception(Value::Number(Number::Int(42))))
-    );
+    for backend in Backend::ALL {
+        let report = harness::exec_source_code(source_code, backend);
+        assert_matches!(report.success_value(), Some(Value::Undefined));
+        assert_matches!(
+            report.vm_state(),
+            Some(ExecutionState::Exception(Value::Number(Number::Int(42))))
+        );
+    }
 }
 
 #[test]
@@ -29,12 +32,14 @@ let a = 1 + 2;
 console.assert(a === 3);
 throw a;
 "#;
-    let report = harness::exec_source_code(source_code);
-    assert_matches!(report.success_value(), Some(Value::Undefined));
-    assert_matches!(
-        report.vm_state(),
-        Some(ExecutionState::Exception(Value::Number(Number::Int(3))))
-    );
+    for backend in Backend::ALL {
+        let report = harness::exec_source_code(source_code, backend);
+        assert_matches!(report.success_value(), Some(Value::Undefined));
+        assert_matches!(
+            report.vm_state(),
+            Some(ExecutionState::Exception(Value::Number(Number::Int(3))))
+        );
+    }
 }
 
 #[test]
@@ -45,12 +50,14 @@ let a = 1 + 2;
 console.assert(a === 3);
 throw undefined;
 "#;
-    let report = harness::exec_source_code(source_code);
-    assert_matches!(report.success_value(), Some(Value::Undefined));
-    assert_matches!(
-        report.vm_state(),
-        Some(ExecutionState::Exception(Value::Undefined))
-    );
+    for backend in Backend::ALL {
+        let report = harness::exec_source_code(source_code, backend);
+        assert_matches!(report.success_value(), Some(Value::Undefined));
+        assert_matches!(
+            report.vm_state(),
+            Some(ExecutionState::Exception(Value::Undefined))
+        );
+    }
 }
 
 #[test]
@@ -62,12 +69,14 @@ console.assert(a === 3);
 throw 42;
 console.assertNotReached();
 "#;
-    let report = harness::exec_source_code(source_code);
-    assert_matches!(report.success_value(), Some(Value::Undefined));
-    assert_matches!(
-        report.vm_state(),
-        Some(ExecutionState::Exception(Value::Number(Number::Int(42))))
-    );
+    for backend in Backend::ALL {
+        let report = harness::exec_source_code(source_code, backend);
+        assert_matches!(report.success_value(), Some(Value::Undefined));
+        assert_matches!(
+            report.vm_state(),
+            Some(ExecutionState::Exception(Value::Number(Number::Int(42))))
+        );
+    }
 }
 
 #[test]
@@ -84,10 +93,12 @@ while (i < 10) {
 }
 console.assertNotReached();
 "#;
-    let report = harness::exec_source_code(source_code);
-    assert_matches!(report.success_value(), Some(Value::Undefined));
-    assert_matches!(
-        report.vm_state(),
-        Some(ExecutionState::Exception(Value::Number(Number::Int(3))))
-    );
+    for backend in Backend::ALL {
+        let report = harness::exec_source_code(source_code, backend);
+        assert_matches!(report.success_value(), Some(Value::Undefined));
+        assert_matches!(
+            report.vm_state(),
+            Some(ExecutionState::Exception(Value::Number(Number::Int(3))))
+        );
+    }
 }